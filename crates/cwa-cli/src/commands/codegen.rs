@@ -62,14 +62,34 @@ pub enum CodegenCommands {
         dry_run: bool,
     },
 
+    /// Write the `cwa` server entry of .mcp.json
+    McpConfig {
+        /// Connect to a running server over HTTP instead of launching `cwa mcp stdio`
+        #[arg(long, value_name = "URL", num_args = 0..=1, default_missing_value = cwa_mcp::http::DEFAULT_HTTP_URL)]
+        http: Option<String>,
+        /// Preview without writing files
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Generate all artifacts
     All {
+        /// Point .mcp.json at a running server over HTTP instead of `cwa mcp stdio`
+        #[arg(long, value_name = "URL", num_args = 0..=1, default_missing_value = cwa_mcp::http::DEFAULT_HTTP_URL)]
+        mcp_http: Option<String>,
         /// Preview without writing files
         #[arg(long)]
         dry_run: bool,
     },
 }
 
+fn mcp_transport(http_url: Option<String>) -> cwa_codegen::McpTransport {
+    match http_url {
+        Some(url) => cwa_codegen::McpTransport::Http { url },
+        None => cwa_codegen::McpTransport::Stdio,
+    }
+}
+
 pub async fn execute(cmd: CodegenCommands, project_dir: &Path) -> Result<()> {
     let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
     let pool = cwa_db::init_pool(&redis_url).await?;
@@ -102,8 +122,11 @@ pub async fn execute(cmd: CodegenCommands, project_dir: &Path) -> Result<()> {
         CodegenCommands::ClaudeMd { dry_run } => {
             cmd_claude_md(&pool, &project.id, project_dir, dry_run).await
         }
-        CodegenCommands::All { dry_run } => {
-            cmd_all(&pool, &project.id, project_dir, dry_run).await
+        CodegenCommands::McpConfig { http, dry_run } => {
+            cmd_mcp_config(project_dir, &mcp_transport(http), dry_run)
+        }
+        CodegenCommands::All { mcp_http, dry_run } => {
+            cmd_all(&pool, &project.id, project_dir, &mcp_transport(mcp_http), dry_run).await
         }
     }
 }
@@ -221,7 +244,23 @@ async fn cmd_claude_md(pool: &cwa_db::DbPool, project_id: &str, project_dir: &Pa
     Ok(())
 }
 
-async fn cmd_all(pool: &cwa_db::DbPool, project_id: &str, project_dir: &Path, dry_run: bool) -> Result<()> {
+fn cmd_mcp_config(project_dir: &Path, transport: &cwa_codegen::McpTransport, dry_run: bool) -> Result<()> {
+    if dry_run {
+        println!("{}", cwa_codegen::generate_mcp_config(project_dir, transport)?);
+    } else {
+        let path = cwa_codegen::write_mcp_config(project_dir, transport)?;
+        println!("{} Wrote {}", "✓".green().bold(), path);
+    }
+    Ok(())
+}
+
+async fn cmd_all(
+    pool: &cwa_db::DbPool,
+    project_id: &str,
+    project_dir: &Path,
+    mcp: &cwa_codegen::McpTransport,
+    dry_run: bool,
+) -> Result<()> {
     println!("{}", "Generating all artifacts...".bold());
 
     // Tech-stack-aware agents — .cwa/stack.json takes priority over Redis
@@ -312,7 +351,7 @@ async fn cmd_all(pool: &cwa_db::DbPool, project_id: &str, project_dir: &Path, dr
     if dry_run {
         println!("  .mcp.json");
     } else {
-        cwa_codegen::write_mcp_config(project_dir)?;
+        cwa_codegen::write_mcp_config(project_dir, mcp)?;
         println!("  {} .mcp.json", "✓".green());
     }
 
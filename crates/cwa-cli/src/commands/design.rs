@@ -74,7 +74,8 @@ async fn cmd_from_image(args: FromImageArgs, project_dir: &Path) -> Result<()> {
     }
 
     // 5. Store as semantic memory (optional, non-fatal)
-    match cwa_embedding::MemoryPipeline::default_pipeline() {
+    let embedding = cwa_embedding::EmbeddingConfig::load(project_dir)?;
+    match cwa_embedding::MemoryPipeline::from_config(&embedding) {
         Ok(pipeline) => {
             let summary = format_design_for_embedding(&design_system);
             let result = pipeline.add_memory(
@@ -85,7 +86,10 @@ async fn cmd_from_image(args: FromImageArgs, project_dir: &Path) -> Result<()> {
                 Some("design-system"),
             ).await;
             match result {
-                Ok(r) => println!("{} Stored embedding ({} dims)", "✓".green().bold(), r.embedding_dim),
+                Ok(r) => match r.embedding_dim {
+                    Some(dim) => println!("{} Stored embedding ({} dims)", "✓".green().bold(), dim),
+                    None => println!("{} Stored as memory (embedding pending)", "!".yellow()),
+                },
                 Err(e) => println!("{} Embedding skipped (Qdrant/Ollama unavailable): {}", "!".yellow(), e),
             }
         }
@@ -98,7 +102,7 @@ async fn cmd_from_image(args: FromImageArgs, project_dir: &Path) -> Result<()> {
     match cwa_graph::GraphClient::connect_default().await {
         Ok(graph_client) => {
             let sync_result = cwa_graph::sync::design_sync::sync_design_systems(
-                &graph_client, &pool, &project.id
+                &graph_client, &pool, &project.id, None
             ).await;
             match sync_result {
                 Ok(r) => println!("{} Graph synced ({} nodes, {} relationships)",
@@ -0,0 +1,96 @@
+//! Redis index consistency check, plus detection of legacy board cards.
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Fix detected drift (entity hashes are treated as the source of truth)
+    /// and import legacy board cards as tasks
+    #[arg(long)]
+    pub repair: bool,
+}
+
+pub async fn execute(args: DoctorArgs, _project_dir: &Path) -> Result<()> {
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    let pool = cwa_db::init_pool(&redis_url).await?;
+
+    let project = cwa_core::project::get_default_project(&pool).await?
+        .ok_or_else(|| anyhow::anyhow!("No project found. Run 'cwa init' first."))?;
+
+    check_legacy_cards(&pool, &project.id, args.repair).await?;
+
+    println!("{}", "Checking Redis indexes...".bold());
+    let report = cwa_db::consistency::check_project(&pool, &project.id, args.repair).await?;
+
+    if report.is_consistent() {
+        println!(
+            "{} {} entities checked, all indexes consistent",
+            "✓".green().bold(),
+            report.entities_checked
+        );
+        return Ok(());
+    }
+
+    for issue in &report.issues {
+        println!("  {} {}", "•".yellow(), issue);
+    }
+    println!();
+
+    if report.repaired {
+        println!(
+            "{} Repaired {} issue(s) across {} entities",
+            "✓".green().bold(),
+            report.issues.len(),
+            report.entities_checked
+        );
+    } else {
+        println!(
+            "{} {} issue(s) found across {} entities. Run with {} to fix them.",
+            "!".yellow().bold(),
+            report.issues.len(),
+            report.entities_checked,
+            "--repair".bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// Report (or, with `repair`, import) cards stored on boards from before
+/// boards were views of tasks.
+async fn check_legacy_cards(pool: &cwa_db::DbPool, project_id: &str, repair: bool) -> Result<()> {
+    if !cwa_core::board::has_legacy_cards(pool, project_id).await? {
+        return Ok(());
+    }
+
+    if !repair {
+        println!(
+            "{} Boards hold cards that are not tasks yet. Run with {} (or start {}) to import them.",
+            "!".yellow().bold(),
+            "--repair".bold(),
+            "cwa serve".bold()
+        );
+        println!();
+        return Ok(());
+    }
+
+    let migration = cwa_core::board::migrate_legacy_cards(pool, project_id).await?;
+    println!(
+        "{} Imported {} card(s) from {} board(s) as tasks",
+        "✓".green().bold(),
+        migration.tasks,
+        migration.boards
+    );
+    if !migration.unmapped_columns.is_empty() {
+        println!(
+            "  {} No workflow column for: {} (cards moved to the first column)",
+            "•".yellow(),
+            migration.unmapped_columns.join(", ")
+        );
+    }
+    println!();
+    Ok(())
+}
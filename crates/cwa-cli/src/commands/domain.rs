@@ -124,6 +124,7 @@ pub async fn execute(cmd: DomainCommands, project_dir: &Path) -> Result<()> {
 
                 let obj_id = cwa_core::domain::create_domain_object(
                     &pool,
+                    &project.id,
                     &context.id,
                     &args.name,
                     &args.object_type,
@@ -139,7 +140,8 @@ pub async fn execute(cmd: DomainCommands, project_dir: &Path) -> Result<()> {
                 );
 
                 // Try to embed (graceful failure if Qdrant/Ollama unavailable)
-                match cwa_embedding::DomainObjectPipeline::default_pipeline() {
+                let embedding = cwa_embedding::EmbeddingConfig::load(project_dir)?;
+                match cwa_embedding::DomainObjectPipeline::from_config(&embedding) {
                     Ok(pipeline) => {
                         match pipeline.embed_domain_object(
                             &project.id,
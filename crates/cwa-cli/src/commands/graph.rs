@@ -1,20 +1,30 @@
 //! Knowledge Graph CLI commands.
 
 use anyhow::{Context, Result};
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 use colored::Colorize;
 use std::path::Path;
 use std::time::Duration;
 
 #[derive(Subcommand)]
 pub enum GraphCommands {
-    /// Sync SQLite entities to Neo4j
-    Sync,
+    /// Sync Redis entities to Neo4j
+    Sync {
+        /// Only push entities changed since the last sync
+        #[arg(long)]
+        incremental: bool,
+    },
 
     /// Execute a Cypher query
     Query {
         /// Cypher query string
         query: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: QueryFormat,
+        /// Reject queries that write to the graph (CREATE, MERGE, DELETE, SET, ...)
+        #[arg(long)]
+        read_only: bool,
     },
 
     /// Analyze impact of an entity
@@ -23,6 +33,9 @@ pub enum GraphCommands {
         entity_type: String,
         /// Entity ID
         entity_id: String,
+        /// Maximum number of hops to follow (1-5)
+        #[arg(long, default_value = "3")]
+        depth: u32,
     },
 
     /// Explore graph neighborhood
@@ -40,18 +53,35 @@ pub enum GraphCommands {
     Status,
 }
 
+/// Output format for `cwa graph query`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum QueryFormat {
+    /// Aligned text table
+    #[default]
+    Table,
+    /// Pretty-printed JSON array of rows
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
 pub async fn execute(cmd: GraphCommands, project_dir: &Path) -> Result<()> {
     // Print command header immediately — before any connection attempt that can fail,
     // so the user always sees output even when Redis/Neo4j is unavailable.
     match &cmd {
-        GraphCommands::Sync => println!("{}", "Syncing to Knowledge Graph...".bold()),
+        GraphCommands::Sync { .. } => println!("{}", "Syncing to Knowledge Graph...".bold()),
         GraphCommands::Status => {
             println!("{}", "Knowledge Graph Status".bold());
             println!("{}", "─".repeat(40));
         }
-        GraphCommands::Query { query } => println!("{} {}", "Query:".bold(), query.dimmed()),
-        GraphCommands::Impact { entity_type, entity_id } => {
-            println!("{} {} {}", "Impact analysis for".bold(), entity_type.cyan(), entity_id.yellow());
+        // Only the table format gets a header, so JSON/CSV stay machine-readable.
+        GraphCommands::Query { query, format, .. } => {
+            if *format == QueryFormat::Table {
+                println!("{} {}", "Query:".bold(), query.dimmed());
+            }
+        }
+        GraphCommands::Impact { entity_type, entity_id, depth } => {
+            println!("{} {} {} (depth={})", "Impact analysis for".bold(), entity_type.cyan(), entity_id.yellow(), depth);
             println!("{}", "─".repeat(50));
         }
         GraphCommands::Explore { entity_type, entity_id, depth } => {
@@ -87,10 +117,12 @@ pub async fn execute(cmd: GraphCommands, project_dir: &Path) -> Result<()> {
     .context("Failed to connect to Neo4j at bolt://localhost:7687. Is Neo4j running?")?;
 
     match cmd {
-        GraphCommands::Sync => cmd_sync(&graph_client, &pool, &project.id).await,
-        GraphCommands::Query { query } => cmd_query(&graph_client, &query).await,
-        GraphCommands::Impact { entity_type, entity_id } => {
-            cmd_impact(&graph_client, &entity_type, &entity_id).await
+        GraphCommands::Sync { incremental } => cmd_sync(&graph_client, &pool, &project.id, incremental).await,
+        GraphCommands::Query { query, format, read_only } => {
+            cmd_query(&graph_client, &query, format, read_only).await
+        }
+        GraphCommands::Impact { entity_type, entity_id, depth } => {
+            cmd_impact(&graph_client, &entity_type, &entity_id, depth).await
         }
         GraphCommands::Explore { entity_type, entity_id, depth } => {
             cmd_explore(&graph_client, &entity_type, &entity_id, depth).await
@@ -99,8 +131,8 @@ pub async fn execute(cmd: GraphCommands, project_dir: &Path) -> Result<()> {
     }
 }
 
-/// Run full sync from SQLite to Neo4j.
-async fn cmd_sync(client: &cwa_graph::GraphClient, db: &cwa_db::DbPool, project_id: &str) -> Result<()> {
+/// Run a full or incremental sync from Redis to Neo4j.
+async fn cmd_sync(client: &cwa_graph::GraphClient, db: &cwa_db::DbPool, project_id: &str, incremental: bool) -> Result<()> {
     // Schema initialization — wrap in a timeout so transient neo4rs retries
     // (which back off up to 60s per query) don't silently hang the process.
     println!("  {} Initializing schema...", "→".dimmed());
@@ -112,12 +144,15 @@ async fn cmd_sync(client: &cwa_graph::GraphClient, db: &cwa_db::DbPool, project_
     .map_err(|_| anyhow::anyhow!("Schema initialization timed out after 30s. Is Neo4j responding?"))?
     .context("Failed to initialize Neo4j schema")?;
 
-    // Full sync
-    println!("  {} Syncing entities...", "→".dimmed());
-    let result = tokio::time::timeout(
-        Duration::from_secs(120),
-        cwa_graph::run_full_sync(client, db, project_id),
-    )
+    println!("  {} Syncing {} entities...", "→".dimmed(), if incremental { "changed" } else { "all" });
+    let sync = async {
+        if incremental {
+            cwa_graph::run_incremental_sync(client, db, project_id).await
+        } else {
+            cwa_graph::run_full_sync(client, db, project_id).await
+        }
+    };
+    let result = tokio::time::timeout(Duration::from_secs(120), sync)
     .await
     .map_err(|_| anyhow::anyhow!("Sync timed out after 120s. Neo4j may be unresponsive."))?
     .context("Sync failed")?;
@@ -125,61 +160,161 @@ async fn cmd_sync(client: &cwa_graph::GraphClient, db: &cwa_db::DbPool, project_
     println!("\n{}", "Sync complete:".green().bold());
     println!("  Nodes created/updated: {}", result.nodes_created + result.nodes_updated);
     println!("  Relationships created: {}", result.relationships_created);
+    if incremental {
+        println!("  Nodes unchanged:       {}", result.nodes_unchanged);
+    }
+    println!("  Nodes deleted:         {}", result.nodes_deleted);
 
     Ok(())
 }
 
 /// Execute a raw Cypher query.
-async fn cmd_query(client: &cwa_graph::GraphClient, cypher: &str) -> Result<()> {
-    let results = cwa_graph::queries::search::raw_query(client, cypher).await?;
-
-    if results.is_empty() {
-        println!("{}", "No results.".dimmed());
-    } else {
-        for (i, result) in results.iter().enumerate() {
-            println!("{}: {}", (i + 1).to_string().dimmed(), result);
+async fn cmd_query(client: &cwa_graph::GraphClient, cypher: &str, format: QueryFormat, read_only: bool) -> Result<()> {
+    let result = cwa_graph::queries::search::raw_query(client, cypher, read_only).await?;
+
+    match format {
+        QueryFormat::Json => println!("{}", serde_json::to_string_pretty(&result.rows)?),
+        QueryFormat::Csv => print!("{}", render_csv(&result)),
+        QueryFormat::Table => {
+            if result.rows.is_empty() {
+                println!("{}", "No results.".dimmed());
+            } else {
+                print_table(&result);
+                println!("\n{} row(s).", result.rows.len().to_string().bold());
+            }
         }
     }
 
     Ok(())
 }
 
-/// Show impact analysis for an entity.
-async fn cmd_impact(client: &cwa_graph::GraphClient, entity_type: &str, entity_id: &str) -> Result<()> {
-    println!("{} {} {}", "Impact analysis for".bold(), entity_type.cyan(), entity_id.yellow());
-    println!("{}", "─".repeat(50));
+/// Maximum width of a table cell before it is truncated.
+const MAX_CELL_WIDTH: usize = 60;
+
+/// Render a JSON value as a single cell: strings unquoted, null empty, everything else compact JSON.
+fn cell_text(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
 
-    let nodes = cwa_graph::queries::impact::impact_analysis(client, entity_type, entity_id, 3).await?;
+/// Print query results as an aligned table.
+fn print_table(result: &cwa_graph::queries::cypher::QueryResult) {
+    use unicode_width::UnicodeWidthStr;
+
+    let rows: Vec<Vec<String>> = result
+        .rows
+        .iter()
+        .map(|row| {
+            result
+                .columns
+                .iter()
+                .map(|c| truncate_cell(&cell_text(row.get(c)).replace('\n', " ")))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = result
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| rows.iter().map(|r| r[i].width()).chain([c.width()]).max().unwrap_or(0))
+        .collect();
+
+    let pad = |text: &str, width: usize| format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())));
+
+    let header: Vec<String> = result.columns.iter().zip(&widths).map(|(c, w)| pad(c, *w)).collect();
+    println!("{}", header.join(" │ ").bold());
+    let rule: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+    println!("{}", rule.join("─┼─").dimmed());
+    for row in &rows {
+        let cells: Vec<String> = row.iter().zip(&widths).map(|(c, w)| pad(c, *w)).collect();
+        println!("{}", cells.join(" │ "));
+    }
+}
 
-    if nodes.is_empty() {
+fn truncate_cell(text: &str) -> String {
+    if text.chars().count() <= MAX_CELL_WIDTH {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(MAX_CELL_WIDTH - 1).collect();
+    format!("{}…", truncated)
+}
+
+/// Render query results as RFC 4180 CSV.
+fn render_csv(result: &cwa_graph::queries::cypher::QueryResult) -> String {
+    fn escape(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+
+    let mut out = String::new();
+    let header: Vec<String> = result.columns.iter().map(|c| escape(c)).collect();
+    out.push_str(&header.join(","));
+    out.push('\n');
+    for row in &result.rows {
+        let fields: Vec<String> = result.columns.iter().map(|c| escape(&cell_text(row.get(c)))).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Show impact analysis for an entity as a tree.
+async fn cmd_impact(client: &cwa_graph::GraphClient, entity_type: &str, entity_id: &str, depth: u32) -> Result<()> {
+    let analysis = cwa_graph::queries::impact::impact_analysis(client, entity_type, entity_id, depth).await?;
+
+    if analysis.root.is_none() {
+        println!("{}", "Entity not found in graph.".red());
+        return Ok(());
+    }
+    if analysis.nodes.is_empty() {
         println!("{}", "No related entities found.".dimmed());
         return Ok(());
     }
 
-    for node in &nodes {
-        let label_colored = match node.label.as_str() {
-            "Spec" => node.label.cyan(),
-            "Task" => node.label.green(),
-            "BoundedContext" => node.label.magenta(),
-            "DomainEntity" => node.label.blue(),
-            "Decision" => node.label.yellow(),
-            "Term" => node.label.white(),
-            _ => node.label.normal(),
-        };
-        println!(
-            "  {} [{}] {} ({})",
-            "→".dimmed(),
-            label_colored,
-            node.name,
-            node.relationship.dimmed()
-        );
+    let tree = analysis.render_tree_with(
+        |root| format!("[{}] {}", colored_label(&root.label), root.name.bold()),
+        |node| {
+            format!(
+                "{} [{}] {} {}",
+                node.arrow().dimmed(),
+                colored_label(&node.label),
+                node.name,
+                format!("(depth {}, weight {:.2})", node.depth, node.weight).dimmed()
+            )
+        },
+    );
+    print!("{}", tree);
+
+    println!("\n{}", "By label:".bold());
+    for (label, nodes) in analysis.by_label() {
+        println!("  {} {}", colored_label(label), nodes.len());
     }
 
-    println!("\n{} related entities found.", nodes.len().to_string().bold());
+    println!("\n{} related entities found.", analysis.nodes.len().to_string().bold());
 
     Ok(())
 }
 
+/// Color a graph node label by entity kind.
+fn colored_label(label: &str) -> colored::ColoredString {
+    match label {
+        "Spec" => label.cyan(),
+        "Task" => label.green(),
+        "BoundedContext" => label.magenta(),
+        "DomainEntity" => label.blue(),
+        "Decision" => label.yellow(),
+        "Term" => label.white(),
+        _ => label.normal(),
+    }
+}
+
 /// Explore the neighborhood of an entity.
 async fn cmd_explore(client: &cwa_graph::GraphClient, entity_type: &str, entity_id: &str, depth: u32) -> Result<()> {
     println!("{} {} {} (depth={})", "Exploring".bold(), entity_type.cyan(), entity_id.yellow(), depth);
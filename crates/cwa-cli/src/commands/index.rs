@@ -0,0 +1,57 @@
+//! Codebase indexing for semantic code search.
+
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use std::path::Path;
+
+use super::memory::embedding_progress_bar;
+
+#[derive(Args)]
+pub struct IndexArgs {
+    /// Re-embed every file, not only those changed since the last run
+    #[arg(long)]
+    pub full: bool,
+}
+
+pub async fn execute(args: IndexArgs, project_dir: &Path) -> Result<()> {
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    let pool = cwa_db::init_pool(&redis_url).await?;
+
+    let project = cwa_core::project::get_default_project(&pool).await?
+        .ok_or_else(|| anyhow::anyhow!("No project found. Run 'cwa init' first."))?;
+    let embedding = cwa_embedding::EmbeddingConfig::load(project_dir)?;
+
+    let bar = embedding_progress_bar();
+    let progress = bar.clone();
+    let indexer = cwa_embedding::CodeIndexer::from_config(&embedding)?
+        .with_progress(move |done, total| {
+            progress.set_length(total as u64);
+            progress.set_position(done as u64);
+        });
+    println!(
+        "{} Indexing {} with {} ({} dims)...",
+        "→".dimmed(),
+        project_dir.display(),
+        embedding.embedder().name(),
+        embedding.dimension()
+    );
+
+    let report = indexer.index(&pool, &project.id, project_dir, args.full).await;
+    bar.finish_and_clear();
+    let report = report?;
+
+    println!(
+        "{} Indexed {} file(s) into {} chunk(s); {} unchanged, {} removed",
+        "✓".green().bold(),
+        report.indexed,
+        report.chunks,
+        report.unchanged,
+        report.removed
+    );
+    if report.skipped > 0 {
+        println!("  {}", format!("{} binary, oversized or non-UTF-8 file(s) skipped", report.skipped).dimmed());
+    }
+
+    Ok(())
+}
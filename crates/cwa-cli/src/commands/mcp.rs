@@ -12,6 +12,7 @@ use std::sync::Arc;
 /// Number of tools and resources available in the MCP server.
 const MCP_TOOLS_COUNT: usize = 39;
 const MCP_RESOURCES_COUNT: usize = 12;
+const MCP_PROMPTS_COUNT: usize = 4;
 
 /// Supported software targets for MCP installation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -92,16 +93,83 @@ pub struct InstallArgs {
     pub force: bool,
 }
 
+/// Output format for `cwa mcp audit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum AuditFormat {
+    /// One line per call
+    #[default]
+    Table,
+    /// Pretty-printed JSON array of entries
+    Json,
+    /// One JSON entry per line
+    Jsonl,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+#[derive(Args)]
+pub struct AuditArgs {
+    /// Only tools whose name contains this text
+    #[arg(long)]
+    pub tool: Option<String>,
+
+    /// Only calls from clients whose name contains this text
+    #[arg(long)]
+    pub client: Option<String>,
+
+    /// Only calls that ended with this status
+    #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(cwa_core::audit::model::AuditStatus::all_variants()))]
+    pub status: Option<String>,
+
+    /// Only calls from the last N days
+    #[arg(long)]
+    pub days: Option<i64>,
+
+    /// Maximum number of entries (0 for all)
+    #[arg(long, default_value = "50")]
+    pub limit: usize,
+
+    /// Output format (default: table, or csv with --output)
+    #[arg(short, long, value_enum)]
+    pub format: Option<AuditFormat>,
+
+    /// Write the entries to a file instead of stdout (as json, jsonl or csv)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
 #[derive(Subcommand)]
 pub enum McpCommands {
     /// Run MCP server over stdio
-    Stdio,
+    Stdio {
+        /// Tool profile limiting what the client may call (see `cwa mcp profiles`)
+        #[arg(long)]
+        profile: Option<String>,
+    },
+
+    /// List the tool profiles available to `cwa mcp stdio --profile`
+    Profiles {
+        /// Also list the tools each profile allows
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Browse, filter and export the audit log of MCP tool calls
+    Audit(AuditArgs),
 
     /// Run MCP planner server for Claude Desktop
     Planner,
 
     /// Show MCP server status and available tools/resources
-    Status,
+    Status {
+        /// Write the project's .mcp.json entry for the given transport
+        #[arg(long)]
+        write: bool,
+
+        /// Use the HTTP endpoint of a running `cwa serve` instead of stdio
+        #[arg(long, value_name = "URL", num_args = 0..=1, default_missing_value = cwa_mcp::http::DEFAULT_HTTP_URL)]
+        http: Option<String>,
+    },
 
     /// Install CWA MCP server to supported software
     Install(InstallArgs),
@@ -128,20 +196,49 @@ pub enum McpCommands {
 
 pub async fn execute(cmd: McpCommands, project_dir: &Path) -> Result<()> {
     match cmd {
-        McpCommands::Stdio => {
+        McpCommands::Stdio { profile } => {
+            // Resolve the profile first so a typo fails before connecting
+            let profile = cwa_mcp::ToolProfile::load(project_dir, profile.as_deref())?;
             let redis_url = std::env::var("REDIS_URL")
                 .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
             let pool = Arc::new(cwa_db::init_pool(&redis_url).await?);
+            // Work in the project initialized here unless a request names another
+            let default_project = cwa_core::project::find_project_for_dir(&pool, project_dir)
+                .await?
+                .map(|p| p.id);
             // Running standalone - no broadcast channel (uses HTTP fallback)
-            cwa_mcp::run_stdio_server(pool, None).await?;
+            cwa_mcp::run_stdio_server(pool, None, profile, default_project).await?;
         }
 
         McpCommands::Planner => {
             cwa_mcp::run_planner_stdio(project_dir).await?;
         }
 
-        McpCommands::Status => {
+        McpCommands::Profiles { verbose } => {
+            print_profiles(project_dir, verbose)?;
+        }
+
+        McpCommands::Audit(args) => {
+            let redis_url = std::env::var("REDIS_URL")
+                .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+            let pool = cwa_db::init_pool(&redis_url).await?;
+            let project = cwa_core::project::get_default_project(&pool)
+                .await?
+                .context("No project found. Run 'cwa init' first.")?;
+            show_audit_log(&pool, &project.id, args).await?;
+        }
+
+        McpCommands::Status { write, http } => {
             print_mcp_status();
+            let transport = match http {
+                Some(url) => cwa_codegen::McpTransport::Http { url },
+                None => cwa_codegen::McpTransport::Stdio,
+            };
+            if write {
+                let path = cwa_codegen::write_mcp_config(project_dir, &transport)?;
+                println!("  {} Wrote {} ({})", "✓".green().bold(), path, transport_name(&transport));
+                println!();
+            }
         }
 
         McpCommands::Install(args) => {
@@ -603,11 +700,168 @@ async fn uninstall_mcp_server(
     Ok(())
 }
 
+fn transport_name(transport: &cwa_codegen::McpTransport) -> String {
+    match transport {
+        cwa_codegen::McpTransport::Stdio => "stdio".to_string(),
+        cwa_codegen::McpTransport::Http { url } => url.clone(),
+    }
+}
+
+/// Print or export the MCP audit log.
+async fn show_audit_log(pool: &cwa_db::DbPool, project_id: &str, args: AuditArgs) -> Result<()> {
+    use cwa_core::audit::model::{AuditFilter, AuditStatus};
+
+    let filter = AuditFilter {
+        tool: args.tool,
+        client: args.client,
+        status: args.status.as_deref().and_then(AuditStatus::parse),
+        since: args.days.map(AuditFilter::days_ago),
+        limit: Some(args.limit).filter(|limit| *limit > 0),
+    };
+    let entries = cwa_core::audit::list_entries(pool, project_id, &filter).await?;
+
+    let format = match (args.format, &args.output) {
+        (Some(AuditFormat::Table), Some(_)) => {
+            anyhow::bail!("The table format prints to the terminal only; use --format json, jsonl or csv with --output")
+        }
+        (Some(format), _) => format,
+        (None, Some(_)) => AuditFormat::Csv,
+        (None, None) => AuditFormat::Table,
+    };
+
+    let rendered = match format {
+        AuditFormat::Table => {
+            print_audit_table(&entries);
+            return Ok(());
+        }
+        AuditFormat::Json => format!("{}\n", serde_json::to_string_pretty(&entries)?),
+        AuditFormat::Jsonl => entries
+            .iter()
+            .map(|e| serde_json::to_string(e).map(|line| line + "\n"))
+            .collect::<Result<String, _>>()?,
+        AuditFormat::Csv => render_audit_csv(&entries),
+    };
+
+    match args.output {
+        Some(path) => {
+            fs::write(&path, rendered).with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("{} Exported {} audit entries to {}", "✓".green().bold(), entries.len(), path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+fn print_audit_table(entries: &[cwa_core::audit::model::AuditEntry]) {
+    use cwa_core::audit::model::AuditStatus;
+
+    if entries.is_empty() {
+        println!("{}", "No MCP tool calls recorded.".dimmed());
+        return;
+    }
+
+    println!();
+    for entry in entries {
+        let at = entry.at.get(..19).unwrap_or(&entry.at).replace('T', " ");
+        let status = match entry.status {
+            AuditStatus::Ok => "ok    ".green(),
+            AuditStatus::Error => "error ".red(),
+            AuditStatus::Denied => "denied".yellow(),
+        };
+        let detail = match &entry.error {
+            Some(error) => error.clone(),
+            None => entry.arguments.to_string(),
+        };
+        let detail: String = detail.chars().take(80).collect();
+        println!(
+            "  {} {} {:<32} {:>6}ms  {}",
+            at.dimmed(),
+            status,
+            entry.tool.cyan(),
+            entry.duration_ms,
+            entry.client.as_deref().unwrap_or("-")
+        );
+        println!("      {}", detail.dimmed());
+    }
+    println!();
+    println!("  {} {} entries, newest first", "▸".dimmed(), entries.len());
+    println!();
+}
+
+fn render_audit_csv(entries: &[cwa_core::audit::model::AuditEntry]) -> String {
+    fn escape(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+
+    let mut out = String::from("id,at,tool,status,duration_ms,client,session_id,arguments,error\n");
+    for entry in entries {
+        let fields = [
+            entry.id.clone(),
+            entry.at.clone(),
+            entry.tool.clone(),
+            entry.status.as_str().to_string(),
+            entry.duration_ms.to_string(),
+            entry.client.clone().unwrap_or_default(),
+            entry.session_id.clone().unwrap_or_default(),
+            entry.arguments.to_string(),
+            entry.error.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| escape(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Print the built-in and project tool profiles.
+fn print_profiles(project_dir: &Path, verbose: bool) -> Result<()> {
+    let profiles = cwa_mcp::ToolProfile::list(project_dir)?;
+
+    println!();
+    println!("{} MCP Tool Profiles", "●".green().bold());
+    println!();
+    for profile in &profiles {
+        let origin = if profile.builtin { "built-in" } else { "project" };
+        println!(
+            "  {} {} {}",
+            profile.name.cyan().bold(),
+            format!("({} tools, {})", profile.tools.len(), origin).dimmed(),
+            profile.description
+        );
+        if verbose {
+            for tool in &profile.tools {
+                println!("    {}", tool.dimmed());
+            }
+        }
+    }
+    println!();
+    println!(
+        "  {} Select one with {} or {}; define your own in {}",
+        "▸".dimmed(),
+        "cwa mcp stdio --profile <name>".cyan(),
+        "cwa serve --mcp-profile <name>".cyan(),
+        cwa_mcp::profile::PROFILES_FILE.cyan()
+    );
+    println!();
+
+    Ok(())
+}
+
 fn print_mcp_status() {
     println!();
     println!("{} CWA MCP Server Status", "●".green().bold());
     println!();
-    println!("  {} {} tools | {} resources", "▸".dimmed(), MCP_TOOLS_COUNT.to_string().cyan().bold(), MCP_RESOURCES_COUNT.to_string().cyan().bold());
+    println!(
+        "  {} {} tools | {} resources | {} prompts",
+        "▸".dimmed(),
+        MCP_TOOLS_COUNT.to_string().cyan().bold(),
+        MCP_RESOURCES_COUNT.to_string().cyan().bold(),
+        MCP_PROMPTS_COUNT.to_string().cyan().bold()
+    );
     println!();
 
     // Tools by category
@@ -630,12 +884,15 @@ fn print_mcp_status() {
     println!("    {} {}", "cwa_validate_spec".cyan(), "Validate completeness".dimmed());
     println!();
 
-    println!("  {} {}", "Tasks & Kanban".yellow(), "(7)".dimmed());
+    println!("  {} {}", "Tasks & Kanban".yellow(), "(10)".dimmed());
     println!("    {} {}", "cwa_get_current_task".cyan(), "Current in-progress".dimmed());
     println!("    {} {}", "cwa_list_tasks".cyan(), "List all (filterable)".dimmed());
     println!("    {} {}", "cwa_create_task".cyan(), "Create new".dimmed());
+    println!("    {} {}", "cwa_update_task".cyan(), "Edit fields, assignee, labels".dimmed());
+    println!("    {} {}", "cwa_delete_task".cyan(), "Delete one task".dimmed());
     println!("    {} {}", "cwa_update_task_status".cyan(), "Move between statuses".dimmed());
     println!("    {} {}", "cwa_generate_tasks".cyan(), "Generate from spec".dimmed());
+    println!("    {} {}", "cwa_get_workflow".cyan(), "Columns, transitions, policies".dimmed());
     println!("    {} {}", "cwa_get_wip_status".cyan(), "WIP limits status".dimmed());
     println!("    {} {}", "cwa_set_wip_limit".cyan(), "Set column limit".dimmed());
     println!();
@@ -685,6 +942,26 @@ fn print_mcp_status() {
     println!("    {} Context relationships", "project://context-map".green());
     println!();
 
+    // Prompts
+    println!("{}", "  Prompts".bold().underline());
+    println!();
+    println!("    {} Task with spec criteria and decisions", "start-next-task".magenta());
+    println!("    {} Spec quality review", "review-spec".magenta());
+    println!("    {} Draft an ADR", "write-adr".magenta());
+    println!("    {} Session summary from recent work", "summarize-session".magenta());
+    println!();
+
+    // Profiles
+    println!("{}", "  Tool Profiles".bold().underline());
+    println!();
+    println!("    {} Only tools that read project data", "read-only".magenta());
+    println!("    {} Read, plus specs, domain model, decisions and tasks", "planner".magenta());
+    println!("    {} Read, plus working tasks and recording memory", "implementer".magenta());
+    println!("    {} Every tool (default)", "full".magenta());
+    println!();
+    println!("  {} cwa mcp stdio --profile read-only", "▸".dimmed());
+    println!();
+
     // Configuration
     println!("{}", "  Configuration".bold().underline());
     println!();
@@ -701,6 +978,22 @@ fn print_mcp_status() {
   }}"#
     );
     println!();
+    println!("  {} Claude Code over HTTP, sharing one `cwa serve` (.mcp.json):", "▸".dimmed());
+    println!();
+    println!(
+        r#"  {{
+    "mcpServers": {{
+      "cwa": {{
+        "type": "http",
+        "url": "{}"
+      }}
+    }}
+  }}"#,
+        cwa_mcp::http::DEFAULT_HTTP_URL
+    );
+    println!();
+    println!("  {} Write it with {}", "▸".dimmed(), "cwa mcp status --write [--http [URL]]".cyan());
+    println!();
     println!("  {} Claude Desktop (claude_desktop_config.json):", "▸".dimmed());
     println!();
     println!(
@@ -19,6 +19,13 @@ pub enum MemoryCommands {
     /// Compact memories (remove low-confidence entries)
     Compact(CompactArgs),
 
+    /// Rebuild the project's vectors in Qdrant from Redis
+    ///
+    /// Embeddings come from the provider set in .cwa/embedding.json: ollama
+    /// (the default) or openai. The hashing provider is an offline lexical
+    /// fallback that matches shared words, not meaning.
+    Reindex(ReindexArgs),
+
     /// Sync memory with CLAUDE.md
     Sync,
 
@@ -49,6 +56,13 @@ pub struct AddArgs {
     pub context: Option<String>,
 }
 
+#[derive(Args)]
+pub struct ReindexArgs {
+    /// Only embed memories and observations still waiting for an embedding
+    #[arg(long)]
+    pub pending: bool,
+}
+
 #[derive(Args)]
 pub struct SearchArgs {
     /// Search query
@@ -61,6 +75,23 @@ pub struct SearchArgs {
     /// Use legacy text search instead of semantic search
     #[arg(long)]
     pub legacy: bool,
+
+    /// Fuse semantic and BM25 keyword search across memories, observations,
+    /// glossary terms and domain objects
+    #[arg(long, conflicts_with = "legacy")]
+    pub hybrid: bool,
+
+    /// Weight of the semantic leg in hybrid search (0 disables it)
+    #[arg(long, default_value = "1.0", requires = "hybrid")]
+    pub dense_weight: f64,
+
+    /// Weight of the keyword (BM25) leg in hybrid search (0 disables it)
+    #[arg(long, default_value = "1.0", requires = "hybrid")]
+    pub lexical_weight: f64,
+
+    /// How hybrid search fuses the two legs: rrf or score_average
+    #[arg(long, default_value = "rrf", requires = "hybrid")]
+    pub fusion: cwa_embedding::FusionAlgo,
 }
 
 #[derive(Args)]
@@ -143,27 +174,33 @@ pub async fn execute(cmd: MemoryCommands, project_dir: &Path) -> Result<()> {
 
     let project = cwa_core::project::get_default_project(&pool).await?
         .ok_or_else(|| anyhow::anyhow!("No project found. Run 'cwa init' first."))?;
+    let embedding = cwa_embedding::EmbeddingConfig::load(project_dir)?;
 
     match cmd {
-        MemoryCommands::Add(args) => cmd_add(&pool, &project.id, args).await,
-        MemoryCommands::Search(args) => cmd_search(&pool, &project.id, args).await,
-        MemoryCommands::Import => cmd_import(&pool, &project.id).await,
-        MemoryCommands::Compact(args) => cmd_compact(&pool, &project.id, args).await,
+        MemoryCommands::Add(args) => cmd_add(&pool, &project.id, &embedding, args).await,
+        MemoryCommands::Search(args) => cmd_search(&pool, &project.id, &embedding, args).await,
+        MemoryCommands::Import => cmd_import(&pool, &project.id, &embedding).await,
+        MemoryCommands::Compact(args) => cmd_compact(&pool, &project.id, &embedding, args).await,
+        MemoryCommands::Reindex(args) => cmd_reindex(&pool, &project.id, &embedding, args).await,
         MemoryCommands::Sync => cmd_sync(&pool, &project.id, project_dir).await,
         MemoryCommands::Export(args) => cmd_export(&pool, &project.id, args).await,
-        MemoryCommands::Observe(args) => cmd_observe(&pool, &project.id, args).await,
+        MemoryCommands::Observe(args) => cmd_observe(&pool, &project.id, &embedding, args).await,
         MemoryCommands::Timeline(args) => cmd_timeline(&pool, &project.id, args).await,
         MemoryCommands::Summarize(args) => cmd_summarize(&pool, &project.id, args).await,
     }
 }
 
 /// Add a memory with embedding.
-async fn cmd_add(pool: &cwa_db::DbPool, project_id: &str, args: AddArgs) -> Result<()> {
+async fn cmd_add(
+    pool: &cwa_db::DbPool,
+    project_id: &str,
+    embedding: &cwa_embedding::EmbeddingConfig,
+    args: AddArgs) -> Result<()> {
     let entry_type = cwa_embedding::MemoryType::from_str(&args.entry_type)?;
 
     println!("{}", "Adding memory...".dimmed());
 
-    let pipeline = cwa_embedding::MemoryPipeline::default_pipeline()?;
+    let pipeline = cwa_embedding::MemoryPipeline::from_config(embedding)?;
     let result = pipeline.add_memory(
         pool,
         project_id,
@@ -173,17 +210,21 @@ async fn cmd_add(pool: &cwa_db::DbPool, project_id: &str, args: AddArgs) -> Resu
     ).await?;
 
     println!(
-        "{} Memory added (id: {}, embedding: {} dims)",
+        "{} Memory added (id: {}, {})",
         "✓".green().bold(),
         result.id[..8].dimmed(),
-        result.embedding_dim
+        embedding_label(result.embedding_dim)
     );
 
     Ok(())
 }
 
 /// Semantic search across memories.
-async fn cmd_search(pool: &cwa_db::DbPool, project_id: &str, args: SearchArgs) -> Result<()> {
+async fn cmd_search(
+    pool: &cwa_db::DbPool,
+    project_id: &str,
+    embedding: &cwa_embedding::EmbeddingConfig,
+    args: SearchArgs) -> Result<()> {
     if args.legacy {
         // Use the existing text-based search
         let results = cwa_core::memory::search_memory(pool, project_id, &args.query).await?;
@@ -199,10 +240,14 @@ async fn cmd_search(pool: &cwa_db::DbPool, project_id: &str, args: SearchArgs) -
         return Ok(());
     }
 
+    if args.hybrid {
+        return cmd_hybrid_search(pool, project_id, embedding, &args).await;
+    }
+
     // Semantic search via embeddings
     println!("{}", "Searching...".dimmed());
 
-    let search = cwa_embedding::SemanticSearch::default_search()?;
+    let search = cwa_embedding::SemanticSearch::from_config(embedding)?;
     let results = search.search_project(&args.query, project_id, args.top_k).await?;
 
     if results.is_empty() {
@@ -239,10 +284,10 @@ async fn cmd_search(pool: &cwa_db::DbPool, project_id: &str, args: SearchArgs) -
 }
 
 /// Import legacy memory entries.
-async fn cmd_import(pool: &cwa_db::DbPool, project_id: &str) -> Result<()> {
+async fn cmd_import(pool: &cwa_db::DbPool, project_id: &str, embedding: &cwa_embedding::EmbeddingConfig) -> Result<()> {
     println!("{}", "Importing legacy memories...".bold());
 
-    let pipeline = cwa_embedding::MemoryPipeline::default_pipeline()?;
+    let pipeline = cwa_embedding::MemoryPipeline::from_config(embedding)?;
     let count = pipeline.import_legacy_memories(pool, project_id).await?;
 
     println!(
@@ -255,7 +300,11 @@ async fn cmd_import(pool: &cwa_db::DbPool, project_id: &str) -> Result<()> {
 }
 
 /// Compact memories by removing low-confidence entries.
-async fn cmd_compact(pool: &cwa_db::DbPool, project_id: &str, args: CompactArgs) -> Result<()> {
+async fn cmd_compact(
+    pool: &cwa_db::DbPool,
+    project_id: &str,
+    embedding: &cwa_embedding::EmbeddingConfig,
+    args: CompactArgs) -> Result<()> {
     // Apply decay if specified
     if let Some(factor) = args.decay {
         let decayed = cwa_core::memory::decay_confidence(pool, project_id, factor).await?;
@@ -286,7 +335,7 @@ async fn cmd_compact(pool: &cwa_db::DbPool, project_id: &str, args: CompactArgs)
         args.min_confidence
     );
 
-    match cwa_embedding::MemoryPipeline::default_pipeline() {
+    match cwa_embedding::MemoryPipeline::from_config(embedding) {
         Ok(pipeline) => {
             let removed = pipeline.compact_memories(
                 pool,
@@ -309,6 +358,134 @@ async fn cmd_compact(pool: &cwa_db::DbPool, project_id: &str, args: CompactArgs)
     Ok(())
 }
 
+/// Embed queued entries, or rebuild every vector of the project.
+async fn cmd_reindex(
+    pool: &cwa_db::DbPool,
+    project_id: &str,
+    embedding: &cwa_embedding::EmbeddingConfig,
+    args: ReindexArgs,
+) -> Result<()> {
+    let bar = embedding_progress_bar();
+    let progress = bar.clone();
+    let queue = cwa_embedding::EmbeddingQueue::from_config(embedding)?
+        .with_progress(move |done, total| {
+            progress.set_length(total as u64);
+            progress.set_position(done as u64);
+        });
+    println!(
+        "{} {} ({} dims)",
+        "→".dimmed(),
+        embedding.embedder().name(),
+        embedding.dimension()
+    );
+
+    if args.pending {
+        let pending = queue.pending(pool, project_id).await?;
+        if pending == 0 {
+            println!("{} Nothing is waiting for an embedding", "✓".green().bold());
+            return Ok(());
+        }
+        println!("{} Embedding {} queued item(s)...", "→".dimmed(), pending);
+        let report = queue.backfill(pool, project_id).await;
+        bar.finish_and_clear();
+        let report = report?;
+        println!("{} Embedded {} item(s)", "✓".green().bold(), report.embedded);
+        if report.dropped > 0 {
+            println!("  {}", format!("{} deleted item(s) removed from the queue", report.dropped).dimmed());
+        }
+        return Ok(());
+    }
+
+    println!("{} Rebuilding vectors from Redis...", "→".dimmed());
+    let report = queue.reindex(pool, project_id).await;
+    bar.finish_and_clear();
+    let report = report?;
+    for collection in &report.recreated {
+        println!(
+            "  {} Recreated {} for the new dimension; re-run this in other projects sharing it",
+            "!".yellow(),
+            collection.cyan()
+        );
+    }
+    println!(
+        "{} Reindexed {} memories, {} observations, {} domain objects, {} glossary terms",
+        "✓".green().bold(),
+        report.memories,
+        report.observations,
+        report.domain_objects,
+        report.terms
+    );
+
+    Ok(())
+}
+
+/// Progress bar for batch embedding; the queue sets its length.
+pub(crate) fn embedding_progress_bar() -> indicatif::ProgressBar {
+    let bar = indicatif::ProgressBar::new(0);
+    bar.set_style(
+        indicatif::ProgressStyle::with_template("  {bar:30.cyan/blue} {pos}/{len} embedded ({eta} left)")
+            .unwrap_or_else(|_| indicatif::ProgressStyle::default_bar())
+            .progress_chars("=> "),
+    );
+    bar
+}
+
+/// Hybrid semantic + keyword search across the project's collections.
+async fn cmd_hybrid_search(
+    pool: &cwa_db::DbPool,
+    project_id: &str,
+    embedding: &cwa_embedding::EmbeddingConfig,
+    args: &SearchArgs,
+) -> Result<()> {
+    let embedder = embedding.embedder();
+    let qdrant = embedding.store()?;
+    let collections = cwa_db::queries::lexical::COLLECTIONS.iter().map(|c| c.to_string()).collect();
+    let req = cwa_embedding::HybridSearchRequest::new(&args.query, args.top_k as usize)
+        .with_collections(collections)
+        .with_project(project_id)
+        .with_fusion(args.fusion)
+        .with_weights(args.dense_weight, args.lexical_weight);
+
+    let results = cwa_embedding::hybrid_search(embedder.as_ref(), &qdrant, pool, req).await?;
+    if results.is_empty() {
+        println!("{}", "No results found.".dimmed());
+        return Ok(());
+    }
+
+    println!("{} Found {} results:\n", "✓".green().bold(), results.len());
+    for (i, result) in results.iter().enumerate() {
+        let text = match result.payload.get("path").and_then(|v| v.as_str()) {
+            Some(path) => format!(
+                "{}:{}-{}",
+                path,
+                result.payload.get("start_line").and_then(|v| v.as_u64()).unwrap_or(0),
+                result.payload.get("end_line").and_then(|v| v.as_u64()).unwrap_or(0)
+            ),
+            None => ["content", "title", "term", "name"]
+                .iter()
+                .find_map(|field| result.payload.get(*field).and_then(|v| v.as_str()))
+                .unwrap_or(&result.id)
+                .to_string(),
+        };
+        println!(
+            "  {}. [{}] {} {}",
+            (i + 1).to_string().bold(),
+            result.collection.trim_start_matches("cwa_").cyan(),
+            text,
+            format!("({:.3})", result.score).dimmed(),
+        );
+    }
+
+    Ok(())
+}
+
+fn embedding_label(dim: Option<usize>) -> String {
+    match dim {
+        Some(dim) => format!("embedding: {} dims", dim),
+        None => "embedding pending".to_string(),
+    }
+}
+
 /// Sync memory with CLAUDE.md.
 async fn cmd_sync(pool: &cwa_db::DbPool, project_id: &str, project_dir: &Path) -> Result<()> {
     let summary = cwa_core::memory::get_context_summary(pool, project_id).await?;
@@ -337,7 +514,11 @@ async fn cmd_export(pool: &cwa_db::DbPool, project_id: &str, args: ExportArgs) -
 }
 
 /// Record a structured observation.
-async fn cmd_observe(pool: &cwa_db::DbPool, project_id: &str, args: ObserveArgs) -> Result<()> {
+async fn cmd_observe(
+    pool: &cwa_db::DbPool,
+    project_id: &str,
+    embedding: &cwa_embedding::EmbeddingConfig,
+    args: ObserveArgs) -> Result<()> {
     // Validate observation type
     cwa_core::memory::observation::ObservationType::from_str(&args.obs_type)
         .ok_or_else(|| anyhow::anyhow!(
@@ -349,7 +530,7 @@ async fn cmd_observe(pool: &cwa_db::DbPool, project_id: &str, args: ObserveArgs)
     println!("{} Recording observation...", "→".dimmed());
 
     // Try embedding pipeline first (optional - fallback to DB-only)
-    match cwa_embedding::ObservationPipeline::default_pipeline() {
+    match cwa_embedding::ObservationPipeline::from_config(embedding) {
         Ok(pipeline) => {
             let result = pipeline.add_observation(
                 pool, project_id, &args.obs_type, &args.title,
@@ -359,10 +540,10 @@ async fn cmd_observe(pool: &cwa_db::DbPool, project_id: &str, args: ObserveArgs)
             ).await?;
 
             println!(
-                "{} Observation recorded (id: {}, embedding: {} dims)",
+                "{} Observation recorded (id: {}, {})",
                 "✓".green().bold(),
                 result.id[..8].dimmed(),
-                result.embedding_dim
+                embedding_label(result.embedding_dim)
             );
         }
         Err(_) => {
@@ -375,7 +556,7 @@ async fn cmd_observe(pool: &cwa_db::DbPool, project_id: &str, args: ObserveArgs)
             ).await?;
 
             println!(
-                "{} Observation recorded (id: {}, no embedding)",
+                "{} Observation recorded (id: {}, embedding pending)",
                 "✓".green().bold(),
                 obs.id[..8].dimmed(),
             );
@@ -456,7 +637,7 @@ async fn cmd_summarize(pool: &cwa_db::DbPool, project_id: &str, args: SummarizeA
 
     // Collect all facts from the observations (fetch full details)
     let ids: Vec<&str> = observations.iter().map(|o| o.id.as_str()).collect();
-    let full_observations = cwa_core::memory::get_observations_batch(pool, &ids).await?;
+    let full_observations = cwa_core::memory::get_observations_batch(pool, project_id, &ids).await?;
 
     let mut all_facts: Vec<String> = Vec::new();
     let mut summary_parts: Vec<String> = Vec::new();
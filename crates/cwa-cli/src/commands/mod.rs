@@ -30,13 +30,16 @@ pub mod clean;
 pub mod codegen;
 pub mod context;
 pub mod design;
+pub mod doctor;
 pub mod domain;
 pub mod git;
 pub mod graph;
+pub mod index;
 pub mod infra;
 pub mod init;
 pub mod memory;
 pub mod mcp;
+pub mod plan;
 pub mod serve;
 pub mod spec;
 pub mod stack;
@@ -122,6 +125,10 @@ pub enum Commands {
     #[command(subcommand)]
     Git(git::GitCommands),
 
+    /// Apply a structured plan from the planner
+    #[command(subcommand)]
+    Plan(plan::PlanCommands),
+
     /// Tech stack configuration (.cwa/stack.json)
     #[command(subcommand)]
     Stack(stack::StackCommands),
@@ -131,6 +138,16 @@ pub enum Commands {
 
     /// Update project information and regenerate context files
     Update(update::UpdateArgs),
+
+    /// Check Redis indexes for drift and optionally repair them
+    Doctor(doctor::DoctorArgs),
+
+    /// Index the codebase for semantic code search
+    ///
+    /// Embeddings come from the provider set in .cwa/embedding.json: ollama
+    /// (the default) or openai. The hashing provider is an offline lexical
+    /// fallback that matches shared words, not meaning.
+    Index(index::IndexArgs),
 }
 
 impl Cli {
@@ -140,12 +157,14 @@ impl Cli {
         let project_dir = if let Some(p) = self.project {
             p
         } else if matches!(self.command, Commands::Mcp(_)) {
-            // Only Stdio needs an existing CWA project (Redis connection).
+            // Stdio defaults to the enclosing CWA project, but can also run
+            // outside one and serve every registered project by name.
             // Planner, Install, Uninstall, and Status must work from any CWD
             // (Claude Desktop launches them from "/" or other system directories).
+            let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
             match &self.command {
-                Commands::Mcp(mcp::McpCommands::Stdio) => find_cwa_project()?,
-                _ => std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
+                Commands::Mcp(mcp::McpCommands::Stdio { .. }) => find_cwa_project().unwrap_or(cwd),
+                _ => cwd,
             }
         } else {
             std::env::current_dir()?
@@ -167,9 +186,12 @@ impl Cli {
             Commands::Tokens(cmd) => tokens::execute(cmd, &project_dir).await,
             Commands::Infra(cmd) => infra::execute(cmd, &project_dir).await,
             Commands::Git(cmd) => git::execute(cmd).await,
+            Commands::Plan(cmd) => plan::execute(cmd, &project_dir).await,
             Commands::Stack(cmd) => stack::execute(cmd, &project_dir).await,
             Commands::Clean(args) => clean::execute(args, &project_dir).await,
             Commands::Update(args) => update::execute(args, &project_dir).await,
+            Commands::Doctor(args) => doctor::execute(args, &project_dir).await,
+            Commands::Index(args) => index::execute(args, &project_dir).await,
         }
     }
 }
@@ -0,0 +1,101 @@
+//! Structured plan commands.
+//!
+//! Applies the machine-readable plan emitted by `cwa_plan_software`
+//! (JSON, YAML, or the Markdown planning document itself) to the project.
+
+use anyhow::Result;
+use clap::Subcommand;
+use colored::Colorize;
+use cwa_core::plan::model::{ChangeKind, Plan, PlanChange};
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand)]
+pub enum PlanCommands {
+    /// Create the contexts, objects, decisions, specs and tech stack in a plan
+    Apply {
+        /// Plan file (JSON, YAML, or Markdown with a ```json block)
+        file: PathBuf,
+
+        /// Show what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub async fn execute(cmd: PlanCommands, project_dir: &Path) -> Result<()> {
+    match cmd {
+        PlanCommands::Apply { file, dry_run } => cmd_apply(project_dir, &file, dry_run).await,
+    }
+}
+
+async fn cmd_apply(project_dir: &Path, file: &Path, dry_run: bool) -> Result<()> {
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+    let plan = Plan::parse(&content)?;
+
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    let pool = cwa_db::init_pool(&redis_url).await?;
+
+    let project = cwa_core::project::get_default_project(&pool).await?
+        .ok_or_else(|| anyhow::anyhow!("No project found. Run 'cwa init' first."))?;
+
+    let changes = cwa_core::plan::apply(&pool, &project, &plan, dry_run).await?;
+
+    if dry_run {
+        println!("{} {}", "Plan:".bold(), file.display());
+    }
+    for change in &changes {
+        print_change(change);
+    }
+    println!();
+
+    let pending = changes.iter().filter(|c| c.kind != ChangeKind::Unchanged).count();
+    if pending == 0 {
+        println!("{} Project already matches the plan", "✓".green().bold());
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "{} {} change(s) pending. Run without {} to apply them.",
+            "!".yellow().bold(),
+            pending,
+            "--dry-run".bold()
+        );
+        return Ok(());
+    }
+
+    let stack_changed = changes
+        .iter()
+        .any(|c| c.entity == "tech_stack" && c.kind == ChangeKind::Update);
+    if stack_changed {
+        if let Some(info) = cwa_core::project::get_project_info(&pool, &project.id).await? {
+            crate::commands::stack::write_stack(project_dir, &info.tech_stack)?;
+        }
+    }
+
+    println!("{} Applied {} change(s)", "✓".green().bold(), pending);
+    if stack_changed {
+        println!("{}", "Run 'cwa codegen all' to regenerate agents for this stack.".dimmed());
+    }
+
+    Ok(())
+}
+
+fn print_change(change: &PlanChange) {
+    let symbol = match change.kind {
+        ChangeKind::Create => "+".green().bold(),
+        ChangeKind::Update => "~".yellow().bold(),
+        ChangeKind::Unchanged => "=".dimmed(),
+    };
+    let name = match change.kind {
+        ChangeKind::Unchanged => change.name.dimmed(),
+        _ => change.name.normal(),
+    };
+    let detail = change
+        .detail
+        .as_ref()
+        .map(|d| format!(" ({})", d).dimmed().to_string())
+        .unwrap_or_default();
+    println!("  {} {:<10} {}{}", symbol, change.entity.dimmed(), name, detail);
+}
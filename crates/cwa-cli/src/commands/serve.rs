@@ -5,6 +5,10 @@ use clap::Args;
 use colored::Colorize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+
+/// How often queued embeddings are retried while everything is up.
+const EMBEDDING_BACKFILL_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Args)]
 pub struct ServeArgs {
@@ -12,7 +16,7 @@ pub struct ServeArgs {
     #[arg(long, default_value = "3030")]
     pub port: u16,
 
-    /// Host to bind to
+    /// Host to bind to (keep it local: the MCP endpoint has no authentication)
     #[arg(long, default_value = "127.0.0.1")]
     pub host: String,
 
@@ -23,12 +27,25 @@ pub struct ServeArgs {
     /// Custom log file path
     #[arg(long)]
     pub log_file: Option<PathBuf>,
+
+    /// Keep the Neo4j knowledge graph in sync with every change
+    #[arg(long)]
+    pub graph_sync: bool,
+
+    /// MCP tool profile for clients of the HTTP endpoint (see `cwa mcp profiles`)
+    #[arg(long, value_name = "PROFILE")]
+    pub mcp_profile: Option<String>,
 }
 
 pub async fn execute(args: ServeArgs, project_dir: &Path) -> Result<()> {
+    let mcp_profile = cwa_mcp::ToolProfile::load(project_dir, args.mcp_profile.as_deref())?;
     let redis_url = std::env::var("REDIS_URL")
         .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
     let pool = Arc::new(cwa_db::init_pool(&redis_url).await?);
+    // MCP sessions work in the project initialized here unless a request names another
+    let default_project = cwa_core::project::find_project_for_dir(&pool, project_dir)
+        .await?
+        .map(|p| p.id);
 
     // Create shared broadcast channel for real-time updates
     let tx = cwa_db::create_broadcast_channel();
@@ -54,6 +71,14 @@ pub async fn execute(args: ServeArgs, project_dir: &Path) -> Result<()> {
         args.host,
         args.port
     );
+    println!(
+        "  {}        http://{}:{}{} {}",
+        "MCP".green(),
+        args.host,
+        args.port,
+        cwa_mcp::http::MCP_PATH,
+        format!("(profile: {})", mcp_profile.name()).dimmed()
+    );
     println!();
     println!(
         "  {}",
@@ -69,12 +94,115 @@ pub async fn execute(args: ServeArgs, project_dir: &Path) -> Result<()> {
         println!("  {}    {}", "Logging".yellow(), log_path.display());
     }
 
+    migrate_legacy_cards(&pool).await;
+
+    println!();
+    start_embedding_worker(&pool, project_dir).await;
+
+    if args.graph_sync {
+        start_graph_sync_worker(&pool, &tx).await;
+    }
+
     println!();
     println!("  {}", "Ctrl+C to stop".dimmed());
     println!();
 
     // Run web server only - MCP updates come via HTTP /internal/notify
-    cwa_web::run_server(pool, tx, args.port).await?;
+    cwa_web::run_server(pool, tx, &args.host, args.port, mcp_profile, default_project).await?;
 
     Ok(())
 }
+
+/// Import cards left on boards from before boards showed tasks, so the web
+/// board and `cwa task board` agree.
+async fn migrate_legacy_cards(pool: &cwa_db::DbPool) {
+    let Ok(Some(project)) = cwa_core::project::get_default_project(pool).await else { return };
+    match cwa_core::board::migrate_legacy_cards(pool, &project.id).await {
+        Ok(report) if report.tasks > 0 || report.boards > 0 => {
+            println!();
+            println!(
+                "  {}  imported {} card(s) from {} board(s) as tasks",
+                "Boards".green(),
+                report.tasks,
+                report.boards
+            );
+            if !report.unmapped_columns.is_empty() {
+                println!(
+                    "  {}  no workflow column for: {} (cards moved to the first column)",
+                    "Boards".yellow(),
+                    report.unmapped_columns.join(", ")
+                );
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            println!();
+            println!("  {}  card migration failed: {}", "Boards".red(), e);
+        }
+    }
+}
+
+/// Spawn the worker embedding memories and observations that were stored
+/// while the embedder or Qdrant was unavailable.
+async fn start_embedding_worker(pool: &Arc<cwa_db::DbPool>, project_dir: &Path) {
+    let Ok(Some(project)) = cwa_core::project::get_default_project(pool).await else { return };
+
+    let queue = match cwa_embedding::EmbeddingConfig::load(project_dir)
+        .and_then(|config| cwa_embedding::EmbeddingQueue::from_config(&config))
+    {
+        Ok(queue) => queue,
+        Err(e) => {
+            println!("  {}  backfill disabled: {:#}", "Embeddings".yellow(), e);
+            return;
+        }
+    };
+
+    let pending = queue.pending(pool, &project.id).await.unwrap_or(0);
+    println!(
+        "  {}  backfill every {}s ({} pending)",
+        "Embeddings".green(),
+        EMBEDDING_BACKFILL_INTERVAL.as_secs(),
+        pending
+    );
+    tokio::spawn(cwa_embedding::run_backfill_worker(
+        queue,
+        (**pool).clone(),
+        project.id,
+        EMBEDDING_BACKFILL_INTERVAL,
+    ));
+}
+
+/// Spawn the incremental graph sync worker for the default project.
+///
+/// Failures are reported but never stop the web server.
+async fn start_graph_sync_worker(pool: &Arc<cwa_db::DbPool>, tx: &cwa_db::BroadcastSender) {
+    let project = match cwa_core::project::get_default_project(pool).await {
+        Ok(Some(project)) => project,
+        _ => {
+            println!("  {}  skipped (no project found)", "Graph sync".yellow());
+            return;
+        }
+    };
+
+    let client = match tokio::time::timeout(
+        Duration::from_secs(5),
+        cwa_graph::GraphClient::connect_default(),
+    )
+    .await
+    {
+        Ok(Ok(client)) => client,
+        _ => {
+            println!("  {}  skipped (Neo4j unavailable)", "Graph sync".yellow());
+            return;
+        }
+    };
+
+    println!("  {}  incremental, on every change", "Graph sync".green());
+    tokio::spawn(cwa_graph::sync::worker::run_sync_worker(
+        client,
+        (**pool).clone(),
+        project.id,
+        tx.subscribe(),
+        Duration::from_secs(2),
+    ));
+}
@@ -18,6 +18,15 @@ pub enum SpecCommands {
     /// Add acceptance criteria to an existing specification
     AddCriteria(AddCriteriaArgs),
 
+    /// Declare that a specification depends on another one
+    AddDep(DependencyArgs),
+
+    /// Remove a dependency between two specifications
+    RemoveDep(DependencyArgs),
+
+    /// Show draft and active specs ordered into parallelizable waves
+    Plan,
+
     /// List all specifications
     List,
 
@@ -100,6 +109,15 @@ pub struct AddCriteriaArgs {
     pub criteria: Vec<String>,
 }
 
+#[derive(Args)]
+pub struct DependencyArgs {
+    /// Spec ID or title
+    pub spec: String,
+
+    /// Spec ID or title it depends on
+    pub depends_on: String,
+}
+
 #[derive(Args)]
 pub struct ArchiveArgs {
     /// Spec ID
@@ -140,6 +158,7 @@ pub async fn execute(cmd: SpecCommands, project_dir: &Path) -> Result<()> {
                 &args.priority,
                 criteria,
             ).await?;
+            notify_spec_updated(&pool, &project.id, &spec.id).await;
 
             println!(
                 "{} Created spec: {} ({})",
@@ -197,6 +216,9 @@ pub async fn execute(cmd: SpecCommands, project_dir: &Path) -> Result<()> {
                 &input,
                 &args.priority,
             ).await?;
+            for spec in &specs {
+                notify_spec_updated(&pool, &project.id, &spec.id).await;
+            }
 
             println!(
                 "{} Created {} spec(s):\n",
@@ -225,6 +247,7 @@ pub async fn execute(cmd: SpecCommands, project_dir: &Path) -> Result<()> {
                 &args.spec,
                 &args.criteria,
             ).await?;
+            notify_spec_updated(&pool, &project.id, &spec.id).await;
 
             println!(
                 "{} Added {} criteria to spec '{}' (total: {})",
@@ -235,6 +258,35 @@ pub async fn execute(cmd: SpecCommands, project_dir: &Path) -> Result<()> {
             );
         }
 
+        SpecCommands::AddDep(args) => {
+            let spec = cwa_core::spec::add_dependency(&pool, &project.id, &args.spec, &args.depends_on).await?;
+            notify_spec_updated(&pool, &project.id, &spec.id).await;
+            println!(
+                "{} Spec '{}' now depends on '{}' ({} dependencies)",
+                "✓".green().bold(),
+                spec.title.cyan(),
+                args.depends_on,
+                spec.dependencies.len()
+            );
+        }
+
+        SpecCommands::RemoveDep(args) => {
+            let spec = cwa_core::spec::remove_dependency(&pool, &project.id, &args.spec, &args.depends_on).await?;
+            notify_spec_updated(&pool, &project.id, &spec.id).await;
+            println!(
+                "{} Removed dependency '{}' from spec '{}' ({} remaining)",
+                "✓".green().bold(),
+                args.depends_on,
+                spec.title.cyan(),
+                spec.dependencies.len()
+            );
+        }
+
+        SpecCommands::Plan => {
+            let plan = cwa_core::spec::plan_specs(&pool, &project.id).await?;
+            output::print_spec_plan(&plan);
+        }
+
         SpecCommands::List => {
             let specs = cwa_core::spec::list_specs(&pool, &project.id).await?;
             output::print_specs_table(&specs);
@@ -271,6 +323,7 @@ pub async fn execute(cmd: SpecCommands, project_dir: &Path) -> Result<()> {
 
         SpecCommands::Archive(args) => {
             cwa_core::spec::archive_spec(&pool, &args.spec_id).await?;
+            notify_spec_updated(&pool, &project.id, &args.spec_id).await;
             println!(
                 "{} Archived spec: {}",
                 "✓".green().bold(),
@@ -294,7 +347,11 @@ pub async fn execute(cmd: SpecCommands, project_dir: &Path) -> Result<()> {
                 return Ok(());
             }
 
+            let cleared = cwa_core::spec::list_specs(&pool, &project.id).await?;
             let count = cwa_core::spec::clear_specs(&pool, &project.id).await?;
+            for spec in &cleared {
+                notify_spec_updated(&pool, &project.id, &spec.id).await;
+            }
             println!(
                 "{} Cleared {} spec(s).",
                 "✓".green().bold(),
@@ -305,3 +362,8 @@ pub async fn execute(cmd: SpecCommands, project_dir: &Path) -> Result<()> {
 
     Ok(())
 }
+
+/// Tell the web server and MCP subscribers that a spec of `project_id` changed.
+async fn notify_spec_updated(pool: &cwa_db::DbPool, project_id: &str, spec_id: &str) {
+    cwa_core::WebNotifier::new().with_pool(pool, project_id).notify_spec_updated(spec_id).await;
+}
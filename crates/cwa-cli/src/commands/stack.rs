@@ -6,7 +6,7 @@
 use anyhow::{Context, Result};
 use clap::Subcommand;
 use colored::Colorize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Subcommand)]
 pub enum StackCommands {
@@ -29,6 +29,18 @@ pub async fn execute(cmd: StackCommands, project_dir: &Path) -> Result<()> {
 }
 
 fn cmd_set(project_dir: &Path, technologies: Vec<String>) -> Result<()> {
+    let path = write_stack(project_dir, &technologies)?;
+
+    println!("{} Tech stack saved to {}", "✓".green().bold(), path.display());
+    println!("  Stack: {}", technologies.join(", ").cyan());
+    println!();
+    println!("{}", "Run 'cwa codegen all' to regenerate agents for this stack.".dimmed());
+
+    Ok(())
+}
+
+/// Write `.cwa/stack.json`, returning its path.
+pub fn write_stack(project_dir: &Path, technologies: &[String]) -> Result<PathBuf> {
     let cwa_dir = project_dir.join(".cwa");
     std::fs::create_dir_all(&cwa_dir)
         .context("Failed to create .cwa directory")?;
@@ -41,12 +53,7 @@ fn cmd_set(project_dir: &Path, technologies: Vec<String>) -> Result<()> {
     std::fs::write(&path, &content)
         .context("Failed to write .cwa/stack.json")?;
 
-    println!("{} Tech stack saved to {}", "✓".green().bold(), path.display());
-    println!("  Stack: {}", technologies.join(", ").cyan());
-    println!();
-    println!("{}", "Run 'cwa codegen all' to regenerate agents for this stack.".dimmed());
-
-    Ok(())
+    Ok(path)
 }
 
 fn cmd_show(project_dir: &Path) -> Result<()> {
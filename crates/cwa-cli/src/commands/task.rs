@@ -3,7 +3,10 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 use colored::Colorize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use cwa_core::task::model::{TaskFilter, TaskUpdate};
+use cwa_core::task::workflow::Workflow;
 
 use crate::output;
 
@@ -12,8 +15,8 @@ pub enum TaskCommands {
     /// Create a new task
     New(NewTaskArgs),
 
-    /// List all tasks
-    List,
+    /// List tasks, optionally filtered
+    List(ListTaskArgs),
 
     /// Generate tasks from a spec's acceptance criteria
     Generate(GenerateTaskArgs),
@@ -21,19 +24,66 @@ pub enum TaskCommands {
     /// Move a task to a different status
     Move(MoveTaskArgs),
 
+    /// Edit a task's title, description, priority, effort or due date
+    Edit(EditTaskArgs),
+
+    /// Assign a task to someone (or clear the assignee)
+    Assign(AssignTaskArgs),
+
+    /// Add or remove task labels
+    Label(LabelTaskArgs),
+
+    /// Delete a single task
+    Delete(DeleteTaskArgs),
+
+    /// Mark a task as blocked by another task
+    Block(BlockTaskArgs),
+
+    /// Remove a blocker from a task
+    Unblock(BlockTaskArgs),
+
     /// Display the Kanban board
     Board,
 
     /// Show work-in-progress status
     Wip,
 
+    /// Show a task's status transitions
+    History(HistoryTaskArgs),
+
+    /// Flow metrics: lead/cycle time, throughput, time per column, cumulative flow
+    Metrics(MetricsArgs),
+
     /// Set WIP limit for a column
     WipSet(WipSetArgs),
 
+    /// Show or change the project's workflow (columns, transitions, policies)
+    #[command(subcommand)]
+    Workflow(WorkflowCommands),
+
     /// Clear all tasks for a spec
     Clear(ClearTaskArgs),
 }
 
+#[derive(Subcommand)]
+pub enum WorkflowCommands {
+    /// Show the columns, WIP limits, transitions and policies
+    Show {
+        /// Print the workflow as TOML, ready to edit and pass to 'set'
+        #[arg(long)]
+        toml: bool,
+    },
+
+    /// Replace the workflow with one read from a TOML or JSON file
+    Set {
+        /// Path to the workflow file (.toml or .json)
+        file: PathBuf,
+    },
+
+    /// Restore the default five-column workflow
+    Reset,
+}
+
 #[derive(Args)]
 pub struct ClearTaskArgs {
     /// Spec ID or title (optional - clears all tasks if omitted)
@@ -62,14 +112,97 @@ pub struct NewTaskArgs {
     pub priority: String,
 }
 
+#[derive(Args)]
+pub struct ListTaskArgs {
+    /// Only show tasks with this status
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// Only show tasks assigned to this person
+    #[arg(long)]
+    pub assignee: Option<String>,
+
+    /// Only show tasks carrying this label
+    #[arg(long)]
+    pub label: Option<String>,
+}
+
+#[derive(Args)]
+pub struct EditTaskArgs {
+    /// Task ID
+    pub task_id: String,
+
+    /// New title
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// New description (empty string clears it)
+    #[arg(short, long)]
+    pub description: Option<String>,
+
+    /// New priority (low, medium, high, critical)
+    #[arg(long)]
+    pub priority: Option<String>,
+
+    /// Estimated effort, e.g. "2h" or "3 points" (empty string clears it)
+    #[arg(long)]
+    pub estimate: Option<String>,
+
+    /// Actual effort spent (empty string clears it)
+    #[arg(long)]
+    pub actual: Option<String>,
+
+    /// Due date as YYYY-MM-DD (empty string clears it)
+    #[arg(long)]
+    pub due: Option<String>,
+}
+
+#[derive(Args)]
+pub struct AssignTaskArgs {
+    /// Task ID
+    pub task_id: String,
+
+    /// Assignee name
+    #[arg(required_unless_present = "clear")]
+    pub assignee: Option<String>,
+
+    /// Remove the current assignee
+    #[arg(long, conflicts_with = "assignee")]
+    pub clear: bool,
+}
+
+#[derive(Args)]
+pub struct LabelTaskArgs {
+    /// Task ID
+    pub task_id: String,
+
+    /// Labels to add (repeatable)
+    #[arg(long = "add", short = 'a')]
+    pub add: Vec<String>,
+
+    /// Labels to remove (repeatable)
+    #[arg(long = "remove", short = 'r')]
+    pub remove: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct DeleteTaskArgs {
+    /// Task ID
+    pub task_id: String,
+
+    /// Skip confirmation prompt
+    #[arg(long)]
+    pub confirm: bool,
+}
+
 #[derive(Args)]
 pub struct GenerateTaskArgs {
     /// Spec ID or title
     pub spec: String,
 
-    /// Initial task status (default: backlog)
-    #[arg(long, default_value = "backlog")]
-    pub status: String,
+    /// Initial task status (default: the workflow's first column)
+    #[arg(long)]
+    pub status: Option<String>,
 
     /// Preview tasks without creating them
     #[arg(long)]
@@ -85,13 +218,39 @@ pub struct MoveTaskArgs {
     /// Task ID
     pub task_id: String,
 
-    /// Target status (backlog, todo, in_progress, review, done)
+    /// Target column (see 'cwa task workflow show'; default: backlog, todo, in_progress, review, done)
     pub status: String,
 }
 
+#[derive(Args)]
+pub struct BlockTaskArgs {
+    /// Task ID
+    pub task_id: String,
+
+    /// ID of the blocking task
+    pub blocker_id: String,
+}
+
+#[derive(Args)]
+pub struct HistoryTaskArgs {
+    /// Task ID
+    pub task_id: String,
+}
+
+#[derive(Args)]
+pub struct MetricsArgs {
+    /// Number of trailing days to analyse
+    #[arg(long, default_value = "28")]
+    pub days: i64,
+
+    /// Print the metrics as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
 #[derive(Args)]
 pub struct WipSetArgs {
-    /// Column name (backlog, todo, in_progress, review, done)
+    /// Column name (see 'cwa task workflow show')
     pub column: String,
 
     /// WIP limit (use 0 or "none" to remove limit)
@@ -117,7 +276,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
             ).await?;
 
             // Notify web server for live reload
-            let notifier = cwa_core::WebNotifier::new();
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
             notifier.notify_board_refresh().await;
 
             println!(
@@ -142,7 +301,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
 
             if args.dry_run {
                 // Check existing tasks for skip count
-                let existing_tasks = cwa_core::task::list_tasks_by_spec(&pool, &spec.id).await?;
+                let existing_tasks = cwa_core::task::list_tasks_by_spec(&pool, &project.id, &spec.id).await?;
                 let existing_titles: Vec<&str> = existing_tasks.iter().map(|t| t.title.as_str()).collect();
 
                 let new_criteria: Vec<&String> = spec.acceptance_criteria.iter()
@@ -188,7 +347,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
             // If using prefix, we need to update the spec's criteria temporarily
             // Instead, we'll just create tasks directly with the prefixed titles
             if args.prefix.is_some() {
-                let existing_tasks = cwa_core::task::list_tasks_by_spec(&pool, &spec.id).await?;
+                let existing_tasks = cwa_core::task::list_tasks_by_spec(&pool, &project.id, &spec.id).await?;
                 let existing_titles: Vec<String> = existing_tasks.iter().map(|t| t.title.clone()).collect();
 
                 let mut created = 0;
@@ -209,8 +368,8 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
                         spec.priority.as_str(),
                     ).await?;
 
-                    if args.status != "backlog" {
-                        cwa_core::task::move_task(&pool, &project.id, &task.id, &args.status).await?;
+                    if let Some(status) = args.status.as_deref().filter(|s| *s != task.status) {
+                        cwa_core::task::move_task(&pool, &project.id, &task.id, status).await?;
                     }
 
                     created += 1;
@@ -224,7 +383,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
 
                 // Notify web server for live reload
                 if created > 0 {
-                    let notifier = cwa_core::WebNotifier::new();
+                    let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
                     notifier.notify_board_refresh().await;
                 }
 
@@ -239,7 +398,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
                     &pool,
                     &project.id,
                     &args.spec,
-                    &args.status,
+                    args.status.as_deref(),
                 ).await?;
 
                 if result.created.is_empty() && result.skipped > 0 {
@@ -251,7 +410,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
                 } else {
                     // Notify web server for live reload
                     if !result.created.is_empty() {
-                        let notifier = cwa_core::WebNotifier::new();
+                        let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
                         notifier.notify_board_refresh().await;
                     }
 
@@ -278,7 +437,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
             cwa_core::task::move_task(&pool, &project.id, &args.task_id, &args.status).await?;
 
             // Notify web server for live reload
-            let notifier = cwa_core::WebNotifier::new();
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
             notifier.notify_task_updated(&args.task_id, &args.status).await;
 
             println!(
@@ -289,11 +448,154 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
             );
         }
 
-        TaskCommands::List => {
-            let tasks = cwa_core::task::list_tasks(&pool, &project.id).await?;
+        TaskCommands::Block(args) => {
+            let task = cwa_core::task::add_blocker(&pool, &project.id, &args.task_id, &args.blocker_id).await?;
+
+            // Notify web server for live reload
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+            notifier.notify_board_refresh().await;
+
+            println!(
+                "{} Task {} is now blocked by {} ({} blocker(s))",
+                "✓".green().bold(),
+                task.title.cyan(),
+                args.blocker_id.dimmed(),
+                task.blocked_by.len()
+            );
+        }
+
+        TaskCommands::Unblock(args) => {
+            let task = cwa_core::task::remove_blocker(&pool, &project.id, &args.task_id, &args.blocker_id).await?;
+
+            // Notify web server for live reload
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+            notifier.notify_board_refresh().await;
+
+            println!(
+                "{} Removed blocker {} from task {} ({} remaining)",
+                "✓".green().bold(),
+                args.blocker_id.dimmed(),
+                task.title.cyan(),
+                task.blocked_by.len()
+            );
+        }
+
+        TaskCommands::List(args) => {
+            let filter = TaskFilter {
+                status: args.status,
+                spec_id: None,
+                assignee: args.assignee,
+                label: args.label,
+            };
+            let tasks = cwa_core::task::list_tasks_filtered(&pool, &project.id, &filter).await?;
             output::print_tasks_table(&tasks);
         }
 
+        TaskCommands::Edit(args) => {
+            let update = TaskUpdate {
+                title: args.title,
+                description: args.description,
+                priority: args.priority,
+                estimated_effort: args.estimate,
+                actual_effort: args.actual,
+                due_date: args.due,
+                ..Default::default()
+            };
+            let task = cwa_core::task::update_task(&pool, &project.id, &args.task_id, &update).await?;
+
+            // Notify web server for live reload
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+            notifier.notify_board_refresh().await;
+
+            println!(
+                "{} Updated task: {} ({})",
+                "✓".green().bold(),
+                task.title.cyan(),
+                task.id.dimmed()
+            );
+        }
+
+        TaskCommands::Assign(args) => {
+            let update = TaskUpdate {
+                assignee: Some(args.assignee.unwrap_or_default()),
+                ..Default::default()
+            };
+            let task = cwa_core::task::update_task(&pool, &project.id, &args.task_id, &update).await?;
+
+            // Notify web server for live reload
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+            notifier.notify_board_refresh().await;
+
+            match &task.assignee {
+                Some(assignee) => println!(
+                    "{} Assigned task {} to {}",
+                    "✓".green().bold(),
+                    task.title.cyan(),
+                    assignee.yellow()
+                ),
+                None => println!(
+                    "{} Cleared assignee of task {}",
+                    "✓".green().bold(),
+                    task.title.cyan()
+                ),
+            }
+        }
+
+        TaskCommands::Label(args) => {
+            if args.add.is_empty() && args.remove.is_empty() {
+                anyhow::bail!("Nothing to do. Use --add and/or --remove.");
+            }
+            let update = TaskUpdate {
+                add_labels: args.add,
+                remove_labels: args.remove,
+                ..Default::default()
+            };
+            let task = cwa_core::task::update_task(&pool, &project.id, &args.task_id, &update).await?;
+
+            // Notify web server for live reload
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+            notifier.notify_board_refresh().await;
+
+            let labels = if task.labels.is_empty() {
+                "none".dimmed().to_string()
+            } else {
+                task.labels.join(", ")
+            };
+            println!(
+                "{} Labels for task {}: {}",
+                "✓".green().bold(),
+                task.title.cyan(),
+                labels
+            );
+        }
+
+        TaskCommands::Delete(args) => {
+            let task = cwa_core::task::get_task(&pool, &project.id, &args.task_id).await?;
+
+            if !args.confirm {
+                println!(
+                    "{} This will permanently delete task '{}'. Run with {} to confirm.",
+                    "!".yellow().bold(),
+                    task.title.cyan(),
+                    "--confirm".bold()
+                );
+                return Ok(());
+            }
+
+            let task = cwa_core::task::delete_task(&pool, &project.id, &task.id).await?;
+
+            // Notify web server for live reload
+            let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+            notifier.notify_board_refresh().await;
+
+            println!(
+                "{} Deleted task: {} ({})",
+                "✓".green().bold(),
+                task.title.cyan(),
+                task.id.dimmed()
+            );
+        }
+
         TaskCommands::Board => {
             let board = cwa_core::task::get_board(&pool, &project.id).await?;
             output::print_board(&board);
@@ -304,6 +606,21 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
             output::print_wip(&wip);
         }
 
+        TaskCommands::History(args) => {
+            let task = cwa_core::task::get_task(&pool, &project.id, &args.task_id).await?;
+            let events = cwa_core::task::get_task_history(&pool, &project.id, &task.id).await?;
+            output::print_task_history(&task, &events);
+        }
+
+        TaskCommands::Metrics(args) => {
+            let metrics = cwa_core::task::get_task_metrics(&pool, &project.id, args.days).await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&metrics)?);
+            } else {
+                output::print_task_metrics(&metrics);
+            }
+        }
+
         TaskCommands::WipSet(args) => {
             let limit = match args.limit.to_lowercase().as_str() {
                 "0" | "none" | "null" | "-" => None,
@@ -329,10 +646,50 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
             }
         }
 
+        TaskCommands::Workflow(cmd) => match cmd {
+            WorkflowCommands::Show { toml } => {
+                let workflow = cwa_core::task::get_workflow(&pool, &project.id).await?;
+                if toml {
+                    print!("{}", workflow.to_toml().map_err(|e| anyhow::anyhow!(e))?);
+                } else {
+                    output::print_workflow(&workflow);
+                }
+            }
+
+            WorkflowCommands::Set { file } => {
+                let content = std::fs::read_to_string(&file)?;
+                let workflow: Workflow = if file.extension().is_some_and(|e| e == "json") {
+                    serde_json::from_str(&content)?
+                } else {
+                    Workflow::from_toml(&content)
+                        .map_err(|e| anyhow::anyhow!("Invalid workflow file: {}", e))?
+                };
+                cwa_core::task::set_workflow(&pool, &project.id, &workflow).await?;
+
+                let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+                notifier.notify_board_refresh().await;
+
+                println!(
+                    "{} Workflow updated: {}",
+                    "✓".green().bold(),
+                    workflow.column_names().join(" → ").cyan()
+                );
+            }
+
+            WorkflowCommands::Reset => {
+                cwa_core::task::set_workflow(&pool, &project.id, &Workflow::default()).await?;
+
+                let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
+                notifier.notify_board_refresh().await;
+
+                println!("{} Workflow reset to the default columns", "✓".green().bold());
+            }
+        },
+
         TaskCommands::Clear(args) => {
             if let Some(spec_id) = &args.spec {
                 let spec = cwa_core::spec::get_spec(&pool, &project.id, spec_id).await?;
-                let tasks = cwa_core::task::list_tasks_by_spec(&pool, &spec.id).await?;
+                let tasks = cwa_core::task::list_tasks_by_spec(&pool, &project.id, &spec.id).await?;
 
                 if tasks.is_empty() {
                     println!("{} No tasks to clear for spec '{}'.", "⊙".blue().bold(), spec.title.cyan());
@@ -354,7 +711,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
 
                 // Notify web server for live reload
                 if count > 0 {
-                    let notifier = cwa_core::WebNotifier::new();
+                    let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
                     notifier.notify_board_refresh().await;
                 }
 
@@ -386,7 +743,7 @@ pub async fn execute(cmd: TaskCommands, project_dir: &Path) -> Result<()> {
 
                 // Notify web server for live reload
                 if count > 0 {
-                    let notifier = cwa_core::WebNotifier::new();
+                    let notifier = cwa_core::WebNotifier::new().with_pool(&pool, &project.id);
                     notifier.notify_board_refresh().await;
                 }
 
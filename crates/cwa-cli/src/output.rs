@@ -1,8 +1,11 @@
 //! Terminal output formatting.
 
 use colored::{ColoredString, Colorize};
+use cwa_core::spec::SpecPlan;
 use cwa_core::spec::model::Spec;
+use cwa_core::task::metrics::{TaskEvent, TaskMetrics};
 use cwa_core::task::model::{Board, BoardColumn, Task, WipStatus};
+use cwa_core::task::workflow::{ColumnCategory, Policy, Workflow};
 use cwa_core::domain::model::{BoundedContext, GlossaryTerm, ContextMap};
 use unicode_width::UnicodeWidthStr;
 
@@ -26,6 +29,59 @@ pub fn print_spec(spec: &Spec) {
             println!("  {}. {}", i + 1, criterion);
         }
     }
+
+    if !spec.dependencies.is_empty() {
+        println!();
+        println!("{}", "Depends On".bold());
+        for dep in &spec.dependencies {
+            println!("  {} {}", "→".dimmed(), short_id(dep));
+        }
+    }
+}
+
+/// Print the spec plan as a list of waves.
+pub fn print_spec_plan(plan: &SpecPlan) {
+    if plan.waves.is_empty() {
+        println!("{}", "No draft or active specifications to plan.".dimmed());
+        return;
+    }
+
+    println!("{}", "Spec Plan".bold());
+
+    for (i, wave) in plan.waves.iter().enumerate() {
+        println!();
+        println!(
+            "{} {}",
+            format!("Wave {}", i + 1).cyan().bold(),
+            format!("({} in parallel)", wave.len()).dimmed()
+        );
+        for spec in wave {
+            let status = match spec.status.as_str() {
+                "active" => "active".yellow(),
+                s => s.dimmed(),
+            };
+            println!(
+                "  {} {} {} {}",
+                "●".cyan(),
+                spec.title,
+                format!("[{}]", spec.priority.as_str()).dimmed(),
+                status
+            );
+            if !spec.depends_on.is_empty() {
+                let deps: Vec<&str> = spec.depends_on.iter().map(|d| short_id(d)).collect();
+                println!("      {} {}", "depends on".dimmed(), deps.join(", ").dimmed());
+            }
+            if !spec.waiting_on.is_empty() {
+                let deps: Vec<&str> = spec.waiting_on.iter().map(|d| short_id(d)).collect();
+                println!("      {} {}", "waiting on".yellow(), deps.join(", "));
+            }
+        }
+    }
+}
+
+/// First 8 characters of an ID.
+fn short_id(id: &str) -> &str {
+    &id[..8.min(id.len())]
 }
 
 /// Print specs as a table.
@@ -66,10 +122,10 @@ pub fn print_tasks_table(tasks: &[Task]) {
     }
 
     println!(
-        "{:<10} {:<30} {:<12} {:<10}",
-        "ID", "Title", "Status", "Priority"
+        "{:<10} {:<30} {:<12} {:<10} {:<14} Labels",
+        "ID", "Title", "Status", "Priority", "Assignee"
     );
-    println!("{}", "─".repeat(65));
+    println!("{}", "─".repeat(95));
 
     for task in tasks {
         let status_colored = match task.status.as_str() {
@@ -81,11 +137,13 @@ pub fn print_tasks_table(tasks: &[Task]) {
         };
 
         println!(
-            "{:<10} {:<30} {:<12} {:<10}",
+            "{:<10} {:<30} {:<12} {:<10} {:<14} {}",
             &task.id[..8],
             truncate(&task.title, 28),
             status_colored,
-            task.priority
+            task.priority,
+            truncate(task.assignee.as_deref().unwrap_or("-"), 12),
+            task.labels.join(", ")
         );
     }
 
@@ -146,8 +204,9 @@ fn column_display_name(name: &str, max_width: usize) -> String {
 }
 
 /// Format column header text (plain, for width calculation).
-fn column_header_plain(name: &str, count: usize, wip_limit: Option<i64>, max_width: usize) -> String {
-    let suffix = if let Some(l) = wip_limit {
+fn column_header_plain(col: &BoardColumn, max_width: usize) -> String {
+    let count = col.tasks.len();
+    let suffix = if let Some(l) = col.wip_limit {
         format!(" {}/{}", count, l)
     } else if count > 0 {
         format!(" {}", count)
@@ -156,7 +215,10 @@ fn column_header_plain(name: &str, count: usize, wip_limit: Option<i64>, max_wid
     };
     let suffix_width = UnicodeWidthStr::width(suffix.as_str());
     let name_budget = if max_width > suffix_width { max_width - suffix_width } else { max_width };
-    let display_name = column_display_name(name, name_budget);
+    let display_name = match &col.label {
+        Some(label) => column_display_name(label, name_budget),
+        None => column_display_name(&col.name, name_budget),
+    };
     let full = format!("{}{}", display_name, suffix);
     if UnicodeWidthStr::width(full.as_str()) > max_width {
         truncate_visual(&full, max_width)
@@ -165,22 +227,26 @@ fn column_header_plain(name: &str, count: usize, wip_limit: Option<i64>, max_wid
     }
 }
 
-/// Get a colored header for a column name.
-fn column_header_colored(name: &str, count: usize, wip_limit: Option<i64>, max_width: usize) -> ColoredString {
-    let label = column_header_plain(name, count, wip_limit, max_width);
+/// Get a colored header for a column.
+///
+/// Custom workflow columns are colored by their category.
+fn column_header_colored(col: &BoardColumn, max_width: usize) -> ColoredString {
+    let label = column_header_plain(col, max_width);
 
-    let exceeded = wip_limit.map_or(false, |l| count as i64 > l);
+    let exceeded = col.wip_limit.map_or(false, |l| col.tasks.len() as i64 > l);
 
     if exceeded {
         return label.red().bold();
     }
 
-    match name {
-        "backlog" => label.white().dimmed(),
-        "todo" => label.blue().bold(),
-        "in_progress" => label.yellow().bold(),
-        "review" => label.magenta().bold(),
-        "done" => label.green().bold(),
+    match (col.name.as_str(), col.category) {
+        ("backlog", _) => label.white().dimmed(),
+        ("todo", _) => label.blue().bold(),
+        ("in_progress", _) => label.yellow().bold(),
+        ("review", _) => label.magenta().bold(),
+        ("done", _) => label.green().bold(),
+        (_, ColumnCategory::Active) => label.yellow().bold(),
+        (_, ColumnCategory::Done) => label.green().bold(),
         _ => label.normal(),
     }
 }
@@ -196,9 +262,18 @@ fn priority_indicator(priority: &str) -> ColoredString {
     }
 }
 
+/// Task title as shown on a board card, with the assignee appended.
+fn card_title(task: &Task) -> String {
+    match &task.assignee {
+        Some(assignee) => format!("{} @{}", task.title, assignee),
+        None => task.title.clone(),
+    }
+}
+
 /// Print a task card for the wide board layout.
-fn format_task_card(task: &Task, width: usize) -> String {
+fn format_task_card(task: &Task, width: usize, blocked: bool) -> String {
     let indicator = match task.priority.as_str() {
+        _ if blocked => "⊘ ",
         "critical" => "!!",
         "high" => "! ",
         "medium" => "· ",
@@ -206,7 +281,7 @@ fn format_task_card(task: &Task, width: usize) -> String {
     };
     // 2 chars for indicator + 1 space + title
     let title_width = if width > 4 { width - 3 } else { 1 };
-    let title = truncate_visual(&task.title, title_width);
+    let title = truncate_visual(&card_title(task), title_width);
     format!("{} {}", indicator, pad_right(&title, title_width))
 }
 
@@ -234,7 +309,7 @@ fn print_board_wide(board: &Board, term_w: usize) {
     let visible_columns: Vec<&BoardColumn> = if term_w < 100 {
         // For medium terminals, skip empty backlog/done
         board.columns.iter()
-            .filter(|c| !c.tasks.is_empty() || c.name == "todo" || c.name == "in_progress" || c.name == "review")
+            .filter(|c| !c.tasks.is_empty() || (c.name != "backlog" && c.category != ColumnCategory::Done))
             .collect()
     } else {
         board.columns.iter().collect()
@@ -263,8 +338,8 @@ fn print_board_wide(board: &Board, term_w: usize) {
 
     print!("{}", "│".dimmed());
     for (i, col) in visible_columns.iter().enumerate() {
-        let header = column_header_colored(&col.name, col.tasks.len(), col.wip_limit, col_width);
-        let header_plain = column_header_plain(&col.name, col.tasks.len(), col.wip_limit, col_width);
+        let header = column_header_colored(col, col_width);
+        let header_plain = column_header_plain(col, col_width);
         let header_width = UnicodeWidthStr::width(header_plain.as_str());
         let padding = if col_width > header_width { col_width - header_width } else { 0 };
         let left_pad = padding / 2;
@@ -293,26 +368,33 @@ fn print_board_wide(board: &Board, term_w: usize) {
         print!("{}", "│".dimmed());
         for (ci, col) in visible_columns.iter().enumerate() {
             if let Some(task) = col.tasks.get(i) {
-                let card = format_task_card(task, col_width);
+                let blocked = board.blocked_task_ids.contains(&task.id);
+                let card = format_task_card(task, col_width, blocked);
                 // Apply color based on column
                 let colored_card = match col.name.as_str() {
+                    _ if blocked => {
+                        let title_w = if col_width > 4 { col_width - 3 } else { 1 };
+                        let title = truncate_visual(&card_title(task), title_w);
+                        let padded_title = pad_right(&title, title_w);
+                        format!("{} {}", "⊘ ".red(), padded_title.dimmed())
+                    }
                     "in_progress" => {
                         let ind = priority_indicator(&task.priority);
                         let title_w = if col_width > 4 { col_width - 3 } else { 1 };
-                        let title = truncate_visual(&task.title, title_w);
+                        let title = truncate_visual(&card_title(task), title_w);
                         let padded_title = pad_right(&title, title_w);
                         format!("{} {}", ind, padded_title.yellow())
                     }
                     "done" => {
                         let title_w = if col_width > 4 { col_width - 3 } else { 1 };
-                        let title = truncate_visual(&task.title, title_w);
+                        let title = truncate_visual(&card_title(task), title_w);
                         let padded_title = pad_right(&title, title_w);
                         format!("{} {}", "✓ ".green(), padded_title.green().dimmed())
                     }
                     "review" => {
                         let ind = priority_indicator(&task.priority);
                         let title_w = if col_width > 4 { col_width - 3 } else { 1 };
-                        let title = truncate_visual(&task.title, title_w);
+                        let title = truncate_visual(&card_title(task), title_w);
                         let padded_title = pad_right(&title, title_w);
                         format!("{} {}", ind, padded_title.magenta())
                     }
@@ -343,18 +425,24 @@ fn print_board_wide(board: &Board, term_w: usize) {
     // ── Summary line ──
     let total: usize = board.columns.iter().map(|c| c.tasks.len()).sum();
     let done: usize = board.columns.iter()
-        .find(|c| c.name == "done")
+        .filter(|c| c.category == ColumnCategory::Done)
         .map(|c| c.tasks.len())
-        .unwrap_or(0);
+        .sum();
     if total > 0 {
         let progress = if total > 0 { (done * 100) / total } else { 0 };
+        let blocked = if board.blocked_task_ids.is_empty() {
+            String::new()
+        } else {
+            format!(" {} {} blocked", "·".dimmed(), board.blocked_task_ids.len().to_string().red())
+        };
         println!(
-            " {} {} tasks {} {} done ({}%)",
+            " {} {} tasks {} {} done ({}%){}",
             "■".cyan(),
             total.to_string().bold(),
             "·".dimmed(),
             done.to_string().green(),
-            progress
+            progress,
+            blocked
         );
     }
 }
@@ -369,22 +457,32 @@ fn print_board_compact(board: &Board) {
             continue;
         }
 
-        let header = column_header_colored(&col.name, col.tasks.len(), col.wip_limit, 30);
+        let header = column_header_colored(col, 30);
         println!(" {} {}", "▸".dimmed(), header);
 
         for task in &col.tasks {
-            let indicator = priority_indicator(&task.priority);
+            let blocked = board.blocked_task_ids.contains(&task.id);
+            let indicator = if blocked { "⊘ ".red() } else { priority_indicator(&task.priority) };
             let id_short = if task.id.len() >= 6 { &task.id[..6] } else { &task.id };
             let title_colored: ColoredString = match col.name.as_str() {
+                _ if blocked => task.title.as_str().dimmed(),
                 "in_progress" => task.title.as_str().yellow(),
                 "done" => task.title.as_str().green().dimmed(),
                 "review" => task.title.as_str().magenta(),
                 _ => task.title.as_str().normal(),
             };
+            let mut meta = String::new();
+            if let Some(assignee) = &task.assignee {
+                meta.push_str(&format!(" @{}", assignee));
+            }
+            if !task.labels.is_empty() {
+                meta.push_str(&format!(" [{}]", task.labels.join(", ")));
+            }
             println!(
-                "   {} {} {}",
+                "   {} {}{} {}",
                 indicator,
                 title_colored,
+                meta.cyan(),
                 id_short.dimmed()
             );
         }
@@ -392,6 +490,156 @@ fn print_board_compact(board: &Board) {
     }
 }
 
+/// Format a duration given in hours compactly (`45m`, `12.5h`, `3.2d`).
+fn format_hours(hours: f64) -> String {
+    if hours < 1.0 {
+        format!("{:.0}m", hours * 60.0)
+    } else if hours < 48.0 {
+        format!("{:.1}h", hours)
+    } else {
+        format!("{:.1}d", hours / 24.0)
+    }
+}
+
+/// Print a task's status transitions.
+pub fn print_task_history(task: &Task, events: &[TaskEvent]) {
+    println!("{} {}", task.title.cyan().bold(), format!("({})", task.id).dimmed());
+    println!();
+
+    if events.is_empty() {
+        println!("{}", "No recorded transitions.".dimmed());
+        return;
+    }
+
+    for (i, event) in events.iter().enumerate() {
+        let transition = match &event.from {
+            Some(from) => format!("{} → {}", from, event.to),
+            None => format!("created in {}", event.to),
+        };
+        let stay = events
+            .get(i + 1)
+            .map(|next| (next.at - event.at).num_seconds() as f64 / 3600.0)
+            .map(|h| format!("({} in {})", format_hours(h), event.to).dimmed().to_string())
+            .unwrap_or_default();
+        println!(
+            "  {}  {:<28} {}",
+            event.at.format("%Y-%m-%d %H:%M").to_string().dimmed(),
+            transition,
+            stay
+        );
+    }
+    println!();
+}
+
+/// Bar character and color for a column in the cumulative flow diagram.
+fn flow_style(status: &str) -> (char, colored::Color) {
+    match status {
+        "done" => ('█', colored::Color::Green),
+        "review" => ('▓', colored::Color::Magenta),
+        "in_progress" => ('▒', colored::Color::Yellow),
+        "todo" => ('░', colored::Color::Blue),
+        _ => ('·', colored::Color::White),
+    }
+}
+
+/// Print flow metrics with ASCII charts.
+pub fn print_task_metrics(metrics: &TaskMetrics) {
+    println!("{} {}", "Flow Metrics".bold(), format!("(last {} days)", metrics.window_days).dimmed());
+    println!();
+
+    for (name, stats) in [("Lead time", &metrics.lead_time), ("Cycle time", &metrics.cycle_time)] {
+        if stats.count == 0 {
+            println!("  {:<11} {}", name, "no completed tasks".dimmed());
+            continue;
+        }
+        println!(
+            "  {:<11} {} {}  mean {}  median {}  p85 {}",
+            name,
+            stats.count.to_string().bold(),
+            "tasks".dimmed(),
+            format_hours(stats.mean_hours).cyan(),
+            format_hours(stats.median_hours).cyan(),
+            format_hours(stats.p85_hours).cyan()
+        );
+    }
+    println!();
+
+    // ── Throughput ──
+    println!("{}", "Throughput (completed per week)".bold());
+    let max = metrics.throughput.iter().map(|w| w.completed).max().unwrap_or(0).max(1);
+    for week in &metrics.throughput {
+        let bar = "█".repeat(week.completed * 30 / max);
+        println!(
+            "  {}  {} {}",
+            week.week_start.format("%Y-%m-%d").to_string().dimmed(),
+            bar.green(),
+            week.completed
+        );
+    }
+    println!();
+
+    // ── Time in column ──
+    println!("{}", "Time in column".bold());
+    for st in &metrics.time_in_status {
+        if st.tasks == 0 {
+            println!("  {:<12} {}", st.status, "-".dimmed());
+            continue;
+        }
+        println!(
+            "  {:<12} avg {:>7}  total {:>7}  {}",
+            st.status,
+            format_hours(st.mean_hours).cyan(),
+            format_hours(st.total_hours),
+            format!("({} tasks)", st.tasks).dimmed()
+        );
+    }
+    println!();
+
+    // ── Cumulative flow ──
+    println!("{}", "Cumulative flow".bold());
+    let max_total = metrics
+        .cumulative_flow
+        .iter()
+        .map(|p| p.counts.iter().sum::<usize>())
+        .max()
+        .unwrap_or(0);
+    if max_total == 0 {
+        println!("  {}", "No tasks in this window.".dimmed());
+        return;
+    }
+    let width = term_width().saturating_sub(20).clamp(10, 60);
+    // Done is stacked first so finished work forms the base of the diagram
+    let order: Vec<usize> = (0..metrics.statuses.len()).rev().collect();
+
+    for point in &metrics.cumulative_flow {
+        let mut line = String::new();
+        let mut cumulative = 0;
+        for &i in &order {
+            let before = cumulative * width / max_total;
+            cumulative += point.counts[i];
+            let after = cumulative * width / max_total;
+            let (ch, color) = flow_style(&metrics.statuses[i]);
+            line.push_str(&ch.to_string().repeat(after - before).color(color).to_string());
+        }
+        println!(
+            "  {}  {} {}",
+            point.date.format("%m-%d").to_string().dimmed(),
+            line,
+            point.counts.iter().sum::<usize>().to_string().dimmed()
+        );
+    }
+
+    let legend: Vec<String> = order
+        .iter()
+        .map(|&i| {
+            let (ch, color) = flow_style(&metrics.statuses[i]);
+            format!("{} {}", ch.to_string().color(color), metrics.statuses[i])
+        })
+        .collect();
+    println!("  {}", legend.join("  "));
+    println!();
+}
+
 /// Print WIP status.
 pub fn print_wip(wip: &WipStatus) {
     println!("{}", "WIP Status".bold());
@@ -417,6 +665,56 @@ pub fn print_wip(wip: &WipStatus) {
     }
 }
 
+/// Print a project's workflow.
+pub fn print_workflow(workflow: &Workflow) {
+    println!("{}", "Workflow".bold());
+    println!();
+
+    let policy_list = |policies: &[Policy]| {
+        policies
+            .iter()
+            .map(|p| serde_json::to_value(p).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    for column in &workflow.columns {
+        let category = match column.category {
+            ColumnCategory::Waiting => "waiting".dimmed(),
+            ColumnCategory::Active => "active".yellow(),
+            ColumnCategory::Done => "done".green(),
+        };
+        let limit = column
+            .wip_limit
+            .map_or_else(|| "∞".to_string(), |l| l.to_string());
+        let label = match &column.label {
+            Some(label) => format!(" ({})", label),
+            None => String::new(),
+        };
+        println!(
+            "  {}{} {} {} {}",
+            column.name.cyan().bold(),
+            label,
+            category,
+            "WIP".dimmed(),
+            limit
+        );
+
+        let transitions = match &column.transitions {
+            Some(t) if t.is_empty() => "none".to_string(),
+            Some(t) => t.join(", "),
+            None => "any".to_string(),
+        };
+        println!("      {} {}", "→".dimmed(), transitions);
+        if !column.entry_policies.is_empty() {
+            println!("      {} {}", "entry:".dimmed(), policy_list(&column.entry_policies));
+        }
+        if !column.exit_policies.is_empty() {
+            println!("      {} {}", "exit:".dimmed(), policy_list(&column.exit_policies));
+        }
+    }
+}
+
 /// Print bounded contexts.
 pub fn print_contexts(contexts: &[BoundedContext]) {
     if contexts.is_empty() {
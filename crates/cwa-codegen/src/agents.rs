@@ -22,7 +22,7 @@ pub async fn generate_agent(db: &DbPool, context_id: &str) -> Result<GeneratedAg
     let ctx = cwa_db::queries::domains::get_context(db, context_id).await
         .map_err(|e| anyhow::anyhow!("Context not found: {}", e))?;
 
-    let objects = cwa_db::queries::domains::list_domain_objects(db, context_id).await
+    let objects = cwa_db::queries::domains::list_domain_objects_by_context(db, &ctx.project_id, context_id).await
         .map_err(|e| anyhow::anyhow!("Failed to list domain objects: {}", e))?;
 
     let terms = cwa_db::queries::domains::list_glossary(db, &ctx.project_id).await
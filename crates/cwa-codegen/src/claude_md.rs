@@ -100,7 +100,7 @@ pub async fn generate_claude_md(db: &DbPool, project_id: &str) -> Result<Generat
                 content.push_str(&format!("{}\n\n", desc));
             }
 
-            let objects = cwa_db::queries::domains::list_domain_objects(db, &ctx.id).await
+            let objects = cwa_db::queries::domains::list_domain_objects_by_context(db, project_id, &ctx.id).await
                 .unwrap_or_default();
 
             if !objects.is_empty() {
@@ -446,6 +446,82 @@ cwa codegen all
     }
 }
 
+/// Generate the /write-adr command.
+fn write_adr_command() -> GeneratedCommand {
+    let content = r#"# /write-adr
+
+Record an Architectural Decision Record (ADR) for a design choice.
+
+## Usage
+
+```
+/write-adr <title>
+```
+
+## Steps
+
+1. Call `cwa_list_decisions` to check for existing decisions on the same topic
+   - If one exists, decide whether the new ADR supersedes it
+2. Gather the decision's context from the active spec (`cwa_get_spec`) and the domain model
+3. Draft the ADR:
+   - **Context**: The forces at play and why a decision is needed now
+   - **Decision**: What was decided, stated in full sentences
+   - **Consequences**: What becomes easier or harder as a result
+   - **Alternatives**: Options considered and why they were rejected
+4. Call `cwa_add_decision` with `title`, `context`, and `decision`
+5. Call `cwa_observe` with type `decision` so the choice is searchable in memory
+
+## Example
+
+```
+/write-adr Use Redis streams for task history
+```
+"#;
+
+    GeneratedCommand {
+        filename: "write-adr.md".to_string(),
+        content: content.to_string(),
+        name: "write-adr".to_string(),
+    }
+}
+
+/// Generate the /session-summary command.
+fn session_summary_command() -> GeneratedCommand {
+    let content = r#"# /session-summary
+
+Summarize the current work session and capture what was learned.
+
+## Usage
+
+```
+/session-summary
+```
+
+## Steps
+
+1. Call `cwa_get_context_summary` for the current focus and task counts
+2. Call `cwa_memory_timeline` for the observations recorded during the session
+3. Call `cwa_list_tasks` to see which tasks moved to `review` or `done`
+4. Write a summary with:
+   - **Accomplished**: Tasks completed and specs advanced
+   - **Decisions**: Choices made and their rationale
+   - **Open issues**: Blockers and unanswered questions
+   - **Next steps**: What the next session should start with
+5. Record key insights with `cwa_observe` before ending the session
+
+## When to Use
+
+- Before ending a work session
+- Before handing work over to someone else
+"#;
+
+    GeneratedCommand {
+        filename: "session-summary.md".to_string(),
+        content: content.to_string(),
+        name: "session-summary".to_string(),
+    }
+}
+
 /// Generate all built-in commands.
 pub fn generate_all_commands() -> Vec<GeneratedCommand> {
     vec![
@@ -460,9 +536,16 @@ pub fn generate_all_commands() -> Vec<GeneratedCommand> {
         kanban_command(),
         wip_check_command(),
         sync_command(),
+        write_adr_command(),
+        session_summary_command(),
     ]
 }
 
+/// Get a built-in command by name (e.g. `next-task`).
+pub fn get_command(name: &str) -> Option<GeneratedCommand> {
+    generate_all_commands().into_iter().find(|c| c.name == name)
+}
+
 /// Write generated commands to disk.
 pub fn write_commands(commands: &[GeneratedCommand], output_dir: &Path) -> Result<Vec<String>> {
     std::fs::create_dir_all(output_dir)?;
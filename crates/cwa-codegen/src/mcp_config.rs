@@ -1,47 +1,85 @@
 //! Generates `.mcp.json` configuration for Claude Code / Claude Desktop.
 //!
-//! The generated file points Claude Code at this project's CWA MCP server
-//! running in stdio mode. Placing `.mcp.json` in the project root allows
-//! Claude Code to auto-discover the server when opened in that directory.
+//! The generated entry points Claude Code at this project's CWA MCP server,
+//! either launched in stdio mode or already running over HTTP (`cwa serve`).
+//! Placing `.mcp.json` in the project root allows Claude Code to
+//! auto-discover the server when opened in that directory.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
 use std::path::Path;
 
+/// Name of the server entry in `mcpServers`.
+const SERVER_NAME: &str = "cwa";
+
+const DESCRIPTION: &str = "CWA — Claude Workflow Architect (specs, tasks, domain model, memory)";
+
+/// How Claude Code reaches the CWA MCP server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpTransport {
+    /// Launch `cwa mcp stdio` for the project.
+    Stdio,
+    /// Connect to a running server's Streamable HTTP endpoint.
+    Http { url: String },
+}
+
+/// Build the `mcpServers.cwa` entry for the given project directory.
+pub fn mcp_server_entry(project_dir: &Path, transport: &McpTransport) -> Value {
+    match transport {
+        McpTransport::Stdio => {
+            let project_path = project_dir
+                .canonicalize()
+                .unwrap_or_else(|_| project_dir.to_path_buf())
+                .display()
+                .to_string();
+            json!({
+                "command": "cwa",
+                "args": ["--project", project_path, "mcp", "stdio"],
+                "description": DESCRIPTION
+            })
+        }
+        McpTransport::Http { url } => json!({
+            "type": "http",
+            "url": url,
+            "description": DESCRIPTION
+        }),
+    }
+}
+
 /// Generate the content of `.mcp.json` for the given project directory.
 ///
-/// The file instructs Claude Code to launch `cwa mcp stdio` with the
-/// project path, connecting it to this project's domain model, specs,
-/// tasks, and memory graph.
-pub fn generate_mcp_config(project_dir: &Path) -> Result<String> {
-    let project_path = project_dir
-        .canonicalize()
-        .unwrap_or_else(|_| project_dir.to_path_buf())
-        .display()
-        .to_string();
-
-    // Build the JSON manually for deterministic output and no extra deps
-    let json = format!(
-        r#"{{
-  "mcpServers": {{
-    "cwa": {{
-      "command": "cwa",
-      "args": ["--project", "{project_path}", "mcp", "stdio"],
-      "description": "CWA — Claude Workflow Architect (specs, tasks, domain model, memory)"
-    }}
-  }}
-}}"#,
-        project_path = project_path
-    );
-
-    Ok(json)
+/// With [`McpTransport::Stdio`] the file instructs Claude Code to launch
+/// `cwa mcp stdio` with the project path; with [`McpTransport::Http`] it
+/// points at a shared server instead.
+pub fn generate_mcp_config(project_dir: &Path, transport: &McpTransport) -> Result<String> {
+    let config = json!({
+        "mcpServers": {
+            SERVER_NAME: mcp_server_entry(project_dir, transport)
+        }
+    });
+    Ok(serde_json::to_string_pretty(&config)?)
 }
 
-/// Write `.mcp.json` to the project directory root.
+/// Write the `cwa` entry of `.mcp.json` in the project directory root,
+/// keeping any other servers already configured there.
 ///
 /// Returns the path of the written file.
-pub fn write_mcp_config(project_dir: &Path) -> Result<String> {
-    let content = generate_mcp_config(project_dir)?;
+pub fn write_mcp_config(project_dir: &Path, transport: &McpTransport) -> Result<String> {
     let path = project_dir.join(".mcp.json");
-    std::fs::write(&path, &content)?;
+    let mut config: Value = match std::fs::read_to_string(&path) {
+        Ok(existing) if !existing.trim().is_empty() => serde_json::from_str(&existing)
+            .with_context(|| format!("{} is not valid JSON", path.display()))?,
+        _ => json!({}),
+    };
+
+    if !config.is_object() {
+        anyhow::bail!("{} must contain a JSON object", path.display());
+    }
+    if !config["mcpServers"].is_object() {
+        config["mcpServers"] = json!({});
+    }
+    config["mcpServers"][SERVER_NAME] = mcp_server_entry(project_dir, transport);
+
+    std::fs::write(&path, format!("{}\n", serde_json::to_string_pretty(&config)?))?;
     Ok(path.display().to_string())
 }
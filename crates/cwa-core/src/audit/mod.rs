@@ -0,0 +1,128 @@
+//! Audit log of MCP tool calls.
+//!
+//! Every tool call made through the MCP server is recorded with its
+//! arguments, outcome, duration and calling client, so it is possible to
+//! tell afterwards which agent session created a spec or moved a task.
+
+pub mod model;
+
+use crate::error::CwaResult;
+use cwa_db::DbPool;
+use cwa_db::queries::audit as queries;
+use model::{AuditEntry, AuditFilter};
+
+/// String arguments longer than this are truncated in the log.
+pub const MAX_FIELD_CHARS: usize = 500;
+
+/// Entries read from the log per round trip while filtering.
+const PAGE_SIZE: usize = 200;
+
+/// Record a tool call, returning its entry ID.
+pub async fn record(pool: &DbPool, project_id: &str, entry: &AuditEntry) -> CwaResult<String> {
+    let id = queries::append_audit_entry(pool, project_id, &entry.to_row()).await?;
+    Ok(id)
+}
+
+/// List audit entries matching `filter`, newest first.
+pub async fn list_entries(pool: &DbPool, project_id: &str, filter: &AuditFilter) -> CwaResult<Vec<AuditEntry>> {
+    let limit = filter.limit.unwrap_or(usize::MAX);
+    let mut entries = Vec::new();
+    let mut before: Option<String> = None;
+
+    while entries.len() < limit {
+        let page = queries::list_audit_entries(pool, project_id, before.as_deref(), PAGE_SIZE).await?;
+        let exhausted = page.len() < PAGE_SIZE;
+        before = page.last().map(|row| row.id.clone());
+
+        for entry in page.into_iter().map(AuditEntry::from_row) {
+            if filter.is_too_old(&entry) {
+                return Ok(entries);
+            }
+            if filter.matches(&entry) {
+                entries.push(entry);
+                if entries.len() == limit {
+                    break;
+                }
+            }
+        }
+        if exhausted {
+            break;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Copy tool arguments for the log, truncating long strings so large
+/// fields (spec descriptions, observation narratives, Cypher) don't bloat it.
+pub fn truncate_arguments(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) if s.chars().count() > MAX_FIELD_CHARS => {
+            let kept: String = s.chars().take(MAX_FIELD_CHARS).collect();
+            serde_json::Value::String(format!("{}… ({} chars)", kept, s.chars().count()))
+        }
+        serde_json::Value::Array(items) => items.iter().map(truncate_arguments).collect(),
+        serde_json::Value::Object(fields) => fields
+            .iter()
+            .map(|(k, v)| (k.clone(), truncate_arguments(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::AuditStatus;
+
+    fn entry(tool: &str, client: Option<&str>, status: AuditStatus, at: &str) -> AuditEntry {
+        AuditEntry {
+            id: String::new(),
+            tool: tool.to_string(),
+            arguments: serde_json::Value::Null,
+            status,
+            error: None,
+            duration_ms: 3,
+            client: client.map(str::to_string),
+            session_id: None,
+            at: at.to_string(),
+        }
+    }
+
+    #[test]
+    fn long_strings_are_truncated_at_any_depth() {
+        let long = "x".repeat(MAX_FIELD_CHARS + 10);
+        let args = serde_json::json!({
+            "title": "Short",
+            "criteria": [long.clone()],
+            "nested": { "narrative": long },
+            "limit": 5
+        });
+        let logged = truncate_arguments(&args);
+        assert_eq!(logged["title"], "Short");
+        assert_eq!(logged["limit"], 5);
+        let criterion = logged["criteria"][0].as_str().unwrap();
+        assert!(criterion.ends_with(&format!("… ({} chars)", MAX_FIELD_CHARS + 10)));
+        assert_eq!(logged["nested"]["narrative"], logged["criteria"][0]);
+    }
+
+    #[test]
+    fn filter_matches_tool_client_status_and_age() {
+        let call = entry("cwa_create_task", Some("claude-code 1.0"), AuditStatus::Ok, "2026-03-02T10:00:00+00:00");
+
+        assert!(AuditFilter::default().matches(&call));
+        let by_tool = AuditFilter { tool: Some("TASK".into()), ..Default::default() };
+        assert!(by_tool.matches(&call));
+        let by_client = AuditFilter { client: Some("cursor".into()), ..Default::default() };
+        assert!(!by_client.matches(&call));
+        assert!(!by_client.matches(&entry("cwa_list_tasks", None, AuditStatus::Ok, "")));
+        let denied = AuditFilter { status: Some(AuditStatus::Denied), ..Default::default() };
+        assert!(!denied.matches(&call));
+
+        let since = chrono::DateTime::parse_from_rfc3339("2026-03-02T12:00:00Z").unwrap().to_utc();
+        let recent = AuditFilter { since: Some(since), ..Default::default() };
+        assert!(recent.is_too_old(&call));
+        assert!(!AuditFilter::default().is_too_old(&call));
+    }
+}
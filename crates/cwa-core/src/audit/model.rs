@@ -0,0 +1,129 @@
+//! MCP audit log models.
+
+use serde::{Deserialize, Serialize};
+use cwa_db::queries::audit::AuditRow;
+
+/// How an audited tool call ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    /// The tool ran and returned a result.
+    Ok,
+    /// The tool failed or rejected its arguments.
+    Error,
+    /// The session's tool profile does not allow the tool.
+    Denied,
+}
+
+impl AuditStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::Denied => "denied",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ok" => Some(Self::Ok),
+            "error" => Some(Self::Error),
+            "denied" => Some(Self::Denied),
+            _ => None,
+        }
+    }
+
+    pub fn all_variants() -> &'static [&'static str] {
+        &["ok", "error", "denied"]
+    }
+}
+
+/// A recorded MCP tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Stream entry ID; empty until recorded.
+    pub id: String,
+    pub tool: String,
+    /// Call arguments, with large fields truncated.
+    pub arguments: serde_json::Value,
+    pub status: AuditStatus,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Client name and version from `initialize`.
+    pub client: Option<String>,
+    pub session_id: Option<String>,
+    pub at: String,
+}
+
+impl AuditEntry {
+    /// Create from database row.
+    pub fn from_row(row: AuditRow) -> Self {
+        Self {
+            id: row.id,
+            tool: row.tool,
+            arguments: serde_json::from_str(&row.arguments).unwrap_or(serde_json::Value::Null),
+            status: AuditStatus::parse(&row.status).unwrap_or(AuditStatus::Error),
+            error: row.error,
+            duration_ms: row.duration_ms,
+            client: row.client,
+            session_id: row.session_id,
+            at: row.at,
+        }
+    }
+
+    /// Convert to database row.
+    pub fn to_row(&self) -> AuditRow {
+        AuditRow {
+            id: self.id.clone(),
+            tool: self.tool.clone(),
+            arguments: self.arguments.to_string(),
+            status: self.status.as_str().to_string(),
+            error: self.error.clone(),
+            duration_ms: self.duration_ms,
+            client: self.client.clone(),
+            session_id: self.session_id.clone(),
+            at: self.at.clone(),
+        }
+    }
+}
+
+/// Which audit entries to list.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Tool names containing this text.
+    pub tool: Option<String>,
+    /// Client names containing this text.
+    pub client: Option<String>,
+    pub status: Option<AuditStatus>,
+    /// Only entries recorded at or after this time.
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// At most this many entries; `None` lists them all.
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    /// The `since` bound covering the last `days` days.
+    pub fn days_ago(days: i64) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now() - chrono::Duration::days(days)
+    }
+
+    /// Whether `entry` passes the tool, client and status filters.
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        let contains = |filter: &Option<String>, value: Option<&str>| match filter {
+            Some(filter) => value.is_some_and(|v| v.to_lowercase().contains(&filter.to_lowercase())),
+            None => true,
+        };
+        contains(&self.tool, Some(&entry.tool))
+            && contains(&self.client, entry.client.as_deref())
+            && self.status.is_none_or(|status| status == entry.status)
+    }
+
+    /// Whether `entry` was recorded before `since`, so it and every older
+    /// entry fall outside the filter.
+    pub fn is_too_old(&self, entry: &AuditEntry) -> bool {
+        let Some(since) = self.since else {
+            return false;
+        };
+        chrono::DateTime::parse_from_rfc3339(&entry.at).is_ok_and(|at| at < since)
+    }
+}
@@ -1,255 +1,255 @@
 //! Board domain logic for the web Kanban UI.
+//!
+//! Boards are views over the project's tasks, so a move on any board is a
+//! task move and is immediately visible to `cwa task board` and MCP clients.
 
 pub mod model;
 
-pub use model::{Board, Card, Column, Label, Priority, DEFAULT_COLUMNS};
+pub use model::{Board, CardMigration};
 
 use crate::error::{CwaError, CwaResult};
+use crate::task::{self, model::{Task, TaskFilter, TaskUpdate}};
 use cwa_db::DbPool;
-use cwa_db::queries::boards;
+use cwa_db::queries::boards::{self, BoardRow};
 
-/// Create a new board with default columns.
+/// Create a new board showing the tasks that match `filter`.
 pub async fn create_board(
     pool: &DbPool,
     project_id: &str,
     name: &str,
     description: Option<&str>,
+    filter: &TaskFilter,
 ) -> CwaResult<Board> {
     let board_id = uuid::Uuid::new_v4().to_string();
-    boards::create_board(pool, &board_id, project_id, name, description).await?;
-
-    // Create default columns
-    for (position, (col_name, wip_limit, color)) in DEFAULT_COLUMNS.iter().enumerate() {
-        let col_id = uuid::Uuid::new_v4().to_string();
-        boards::create_column(
-            pool,
-            &col_id,
-            &board_id,
-            col_name,
-            position as i32,
-            Some(color),
-            *wip_limit,
-        ).await?;
-    }
-
+    boards::create_board(pool, &board_id, project_id, name, description, &model::filter_to_row(filter)).await?;
     get_board(pool, &board_id).await
 }
 
-/// Get a full board with columns and cards.
+/// Get a board with the matching tasks laid out in the workflow's columns.
 pub async fn get_board(pool: &DbPool, board_id: &str) -> CwaResult<Board> {
-    let board_row = boards::get_board(pool, board_id).await
+    let row = boards::get_board(pool, board_id).await
         .map_err(|_| CwaError::BoardNotFound(board_id.to_string()))?;
+    board_from_row(pool, row).await
+}
 
-    let column_rows = boards::list_columns(pool, board_id).await?;
-
-    let mut columns = Vec::with_capacity(column_rows.len());
-    for col_row in column_rows {
-        let card_rows = boards::list_cards_in_column(pool, &col_row.id).await?;
-        let mut cards: Vec<Card> = Vec::with_capacity(card_rows.len());
-        for c in card_rows {
-            let labels = boards::get_card_labels(pool, &c.id).await.unwrap_or_default();
-            cards.push(Card {
-                id: c.id,
-                column_id: c.column_id,
-                title: c.title,
-                description: c.description,
-                position: c.position,
-                priority: c.priority.as_deref().and_then(Priority::from_str),
-                due_date: c.due_date,
-                labels: labels.into_iter().map(|l| Label {
-                    id: l.id,
-                    board_id: l.board_id,
-                    name: l.name,
-                    color: l.color,
-                }).collect(),
-                created_at: c.created_at,
-                updated_at: c.updated_at,
-                completed_at: c.completed_at,
-            });
-        }
-
-        columns.push(Column {
-            id: col_row.id,
-            board_id: col_row.board_id,
-            name: col_row.name,
-            position: col_row.position,
-            color: col_row.color,
-            wip_limit: col_row.wip_limit,
-            cards,
-        });
+async fn board_from_row(pool: &DbPool, row: BoardRow) -> CwaResult<Board> {
+    let filter = model::filter_from_row(&row.filter);
+    let mut tasks = task::get_board(pool, &row.project_id).await?;
+    for column in &mut tasks.columns {
+        column.tasks.retain(|t| filter.matches(t));
     }
+    let shown = |id: &String| tasks.columns.iter().any(|c| c.tasks.iter().any(|t| &t.id == id));
+    let blocked_task_ids = tasks.blocked_task_ids.iter().filter(|id| shown(id)).cloned().collect();
 
     Ok(Board {
-        id: board_row.id,
-        project_id: board_row.project_id,
-        name: board_row.name,
-        description: board_row.description,
-        columns,
-        created_at: board_row.created_at,
-        updated_at: board_row.updated_at,
+        id: row.id,
+        project_id: row.project_id,
+        name: row.name,
+        description: row.description,
+        filter,
+        columns: tasks.columns,
+        blocked_task_ids,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
     })
 }
 
-/// List all boards for a project.
+/// List all boards for a project, oldest first.
 pub async fn list_boards(pool: &DbPool, project_id: &str) -> CwaResult<Vec<Board>> {
     let board_rows = boards::list_boards(pool, project_id).await?;
     let mut result = Vec::with_capacity(board_rows.len());
     for row in board_rows {
-        result.push(get_board(pool, &row.id).await?);
+        result.push(board_from_row(pool, row).await?);
     }
     Ok(result)
 }
 
-/// Get or create a default board for a project.
+/// Get the project's board showing every task, creating it if needed.
 pub async fn get_or_create_default_board(pool: &DbPool, project_id: &str) -> CwaResult<Board> {
     let existing = boards::list_boards(pool, project_id).await?;
-    if let Some(first) = existing.into_iter().next() {
-        get_board(pool, &first.id).await
+    if let Some(row) = existing.into_iter().find(|b| b.filter == Default::default()) {
+        board_from_row(pool, row).await
     } else {
-        create_board(pool, project_id, "Default Board", None).await
+        create_board(pool, project_id, "Default Board", None, &TaskFilter::default()).await
     }
 }
 
-/// Create a new card in a column.
-pub async fn create_card(
+/// Create a task from a board, in `column`.
+///
+/// The task gets the board's label and assignee so that it shows up on the
+/// board it was created from. The column, transition and WIP limit are
+/// checked before the task is created, and the task is deleted again if
+/// anything later fails, so a rejected card never lingers in the backlog.
+pub async fn create_board_task(
     pool: &DbPool,
-    column_id: &str,
+    board_id: &str,
+    column: &str,
     title: &str,
     description: Option<&str>,
     priority: Option<&str>,
     due_date: Option<&str>,
-) -> CwaResult<Card> {
-    // Verify column exists and check WIP limit
-    let column = boards::get_column(pool, column_id).await
-        .map_err(|_| CwaError::ColumnNotFound(column_id.to_string()))?;
-
-    if let Some(limit) = column.wip_limit {
-        let current = boards::count_cards_in_column(pool, column_id).await?;
-        if current >= limit {
-            return Err(CwaError::WipLimitExceeded {
-                column: column.name,
-                limit: limit as i64,
-                current: current as i64,
-            });
-        }
+) -> CwaResult<Task> {
+    let board = boards::get_board(pool, board_id).await
+        .map_err(|_| CwaError::BoardNotFound(board_id.to_string()))?;
+    let project_id = board.project_id.as_str();
+    if let Some(due) = due_date {
+        chrono::NaiveDate::parse_from_str(due, "%Y-%m-%d")
+            .map_err(|_| CwaError::validation(format!("Invalid due date '{}'. Use YYYY-MM-DD.", due)))?;
     }
 
-    let card_id = uuid::Uuid::new_v4().to_string();
-    let position = boards::next_card_position(pool, column_id).await?;
+    let workflow = task::get_workflow(pool, project_id).await?;
+    task::check_move(pool, project_id, &workflow, workflow.initial_column(), column).await?;
 
-    boards::create_card(
+    let created = task::create_task(
         pool,
-        &card_id,
-        column_id,
+        project_id,
         title,
         description,
-        position,
-        priority,
-        due_date,
+        board.filter.spec_id.as_deref(),
+        priority.unwrap_or("medium"),
     ).await?;
 
-    let card_row = boards::get_card(pool, &card_id).await?;
-    Ok(Card {
-        id: card_row.id,
-        column_id: card_row.column_id,
-        title: card_row.title,
-        description: card_row.description,
-        position: card_row.position,
-        priority: card_row.priority.as_deref().and_then(Priority::from_str),
-        due_date: card_row.due_date,
-        labels: vec![],
-        created_at: card_row.created_at,
-        updated_at: card_row.updated_at,
-        completed_at: card_row.completed_at,
-    })
+    match finish_board_task(pool, project_id, &board, created.clone(), column, due_date).await {
+        Ok(task) => Ok(task),
+        Err(e) => {
+            // Policies can still reject the move; don't leave the task behind
+            if let Err(cleanup) = task::delete_task(pool, project_id, &created.id).await {
+                tracing::warn!(task_id = %created.id, error = %cleanup, "Failed to delete rejected board task");
+            }
+            Err(e)
+        }
+    }
 }
 
-/// Move a card to a different column.
-pub async fn move_card(
+/// Give a task created from `board` the board's fields and move it to `column`.
+async fn finish_board_task(
     pool: &DbPool,
-    card_id: &str,
-    target_column_id: &str,
-    position: i32,
-) -> CwaResult<Card> {
-    // Verify target column exists and check WIP limit
-    let target_column = boards::get_column(pool, target_column_id).await
-        .map_err(|_| CwaError::ColumnNotFound(target_column_id.to_string()))?;
-
-    if let Some(limit) = target_column.wip_limit {
-        let current = boards::count_cards_in_column(pool, target_column_id).await?;
-        // Don't count the card being moved if it's already in this column
-        let card_row = boards::get_card(pool, card_id).await
-            .map_err(|_| CwaError::CardNotFound(card_id.to_string()))?;
-        let adjustment = if card_row.column_id == target_column_id { 1 } else { 0 };
-        if current - adjustment >= limit {
-            return Err(CwaError::WipLimitExceeded {
-                column: target_column.name,
-                limit: limit as i64,
-                current: (current - adjustment) as i64,
-            });
-        }
+    project_id: &str,
+    board: &BoardRow,
+    created: Task,
+    column: &str,
+    due_date: Option<&str>,
+) -> CwaResult<Task> {
+    let update = TaskUpdate {
+        assignee: board.filter.assignee.clone(),
+        add_labels: board.filter.label.iter().cloned().collect(),
+        due_date: due_date.map(str::to_string),
+        ..Default::default()
+    };
+    let created = if update.is_empty() {
+        created
+    } else {
+        task::update_task(pool, project_id, &created.id, &update).await?
+    };
+
+    if created.status != column {
+        task::move_task(pool, project_id, &created.id, column).await?;
+        return task::get_task(pool, project_id, &created.id).await;
     }
+    Ok(created)
+}
 
-    boards::move_card(pool, card_id, target_column_id, position).await?;
-
-    let card_row = boards::get_card(pool, card_id).await?;
-    let labels = boards::get_card_labels(pool, card_id).await.unwrap_or_default();
-    Ok(Card {
-        id: card_row.id,
-        column_id: card_row.column_id,
-        title: card_row.title,
-        description: card_row.description,
-        position: card_row.position,
-        priority: card_row.priority.as_deref().and_then(Priority::from_str),
-        due_date: card_row.due_date,
-        labels: labels.into_iter().map(|l| Label {
-            id: l.id,
-            board_id: l.board_id,
-            name: l.name,
-            color: l.color,
-        }).collect(),
-        created_at: card_row.created_at,
-        updated_at: card_row.updated_at,
-        completed_at: card_row.completed_at,
-    })
+/// Whether any board of the project still holds cards from before boards
+/// and tasks were unified.
+pub async fn has_legacy_cards(pool: &DbPool, project_id: &str) -> CwaResult<bool> {
+    for row in boards::list_boards(pool, project_id).await? {
+        if !boards::list_legacy_columns(pool, project_id, &row.id).await?.is_empty() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
 }
 
-/// Update a card's details.
-pub async fn update_card(
-    pool: &DbPool,
-    card_id: &str,
-    title: &str,
-    description: Option<&str>,
-    priority: Option<&str>,
-    due_date: Option<&str>,
-) -> CwaResult<Card> {
-    boards::update_card(pool, card_id, title, description, priority, due_date).await?;
-
-    let card_row = boards::get_card(pool, card_id).await
-        .map_err(|_| CwaError::CardNotFound(card_id.to_string()))?;
-    let labels = boards::get_card_labels(pool, card_id).await.unwrap_or_default();
-    Ok(Card {
-        id: card_row.id,
-        column_id: card_row.column_id,
-        title: card_row.title,
-        description: card_row.description,
-        position: card_row.position,
-        priority: card_row.priority.as_deref().and_then(Priority::from_str),
-        due_date: card_row.due_date,
-        labels: labels.into_iter().map(|l| Label {
-            id: l.id,
-            board_id: l.board_id,
-            name: l.name,
-            color: l.color,
-        }).collect(),
-        created_at: card_row.created_at,
-        updated_at: card_row.updated_at,
-        completed_at: card_row.completed_at,
-    })
+/// Import the cards of legacy boards as tasks.
+///
+/// Each card becomes a task with the same ID, in the workflow column matching
+/// its board column. The oldest board keeps showing every task; cards of other
+/// boards get a label named after their board, which becomes the board's
+/// filter. Each board is imported atomically, and boards without legacy data
+/// are skipped, so the migration can safely run more than once.
+pub async fn migrate_legacy_cards(pool: &DbPool, project_id: &str) -> CwaResult<CardMigration> {
+    let workflow = task::get_workflow(pool, project_id).await?;
+    let mut report = CardMigration::default();
+
+    for (i, mut row) in boards::list_boards(pool, project_id).await?.into_iter().enumerate() {
+        let columns = boards::list_legacy_columns(pool, project_id, &row.id).await?;
+        if columns.is_empty() {
+            continue;
+        }
+
+        let board_label = (i > 0 && row.filter == Default::default())
+            .then(|| model::board_label(&row.name));
+        if let Some(ref label) = board_label {
+            row.filter.label = Some(label.clone());
+        }
+
+        let mut tasks = Vec::new();
+        let mut events = Vec::new();
+        for column in &columns {
+            let status = match model::column_status(&workflow, &column.name) {
+                Some(status) => status,
+                None => {
+                    report.unmapped_columns.push(column.name.clone());
+                    workflow.initial_column()
+                }
+            };
+            for card in boards::list_legacy_cards(pool, project_id, &column.id).await? {
+                let mut labels: Vec<String> = boards::list_legacy_card_labels(pool, project_id, &card.id)
+                    .await?
+                    .into_iter()
+                    .map(|l| l.name)
+                    .collect();
+                labels.extend(board_label.clone());
+                let (task, history) = model::card_to_task(&card, project_id, status, labels, &workflow);
+                tasks.push(task);
+                events.extend(history);
+            }
+        }
+
+        boards::import_legacy_board(pool, &row, &tasks, &events).await?;
+        report.boards += 1;
+        report.tasks += tasks.len();
+    }
+
+    report.unmapped_columns.sort();
+    report.unmapped_columns.dedup();
+    Ok(report)
 }
 
-/// Delete a card.
-pub async fn delete_card(pool: &DbPool, card_id: &str) -> CwaResult<()> {
-    boards::delete_card(pool, card_id).await?;
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project;
+    use crate::task::workflow::Policy;
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server at REDIS_URL"]
+    async fn rejected_board_tasks_are_not_created() {
+        let pool = cwa_db::init_pool_from_env().await.unwrap();
+        let name = format!("board-test-{}", uuid::Uuid::new_v4());
+        let project = project::create_project(&pool, &name, None).await.unwrap();
+        let board = create_board(&pool, &project.id, "Board", None, &TaskFilter::default()).await.unwrap();
+
+        task::set_wip_limit(&pool, &project.id, "todo", Some(1)).await.unwrap();
+        create_board_task(&pool, &board.id, "todo", "First", None, None, None).await.unwrap();
+        let full = create_board_task(&pool, &board.id, "todo", "Second", None, None, None).await;
+        assert!(matches!(full, Err(CwaError::WipLimitExceeded { .. })), "{:?}", full);
+
+        // Policies are only checked on the created task, which is then removed
+        let mut workflow = task::get_workflow(&pool, &project.id).await.unwrap();
+        let todo = workflow.columns.iter_mut().find(|c| c.name == "todo").unwrap();
+        todo.wip_limit = None;
+        todo.entry_policies = vec![Policy::RequiresEstimate];
+        task::set_workflow(&pool, &project.id, &workflow).await.unwrap();
+        let rejected = create_board_task(&pool, &board.id, "todo", "Third", None, None, None).await;
+        assert!(matches!(rejected, Err(CwaError::PolicyViolation { .. })), "{:?}", rejected);
+
+        let titles: Vec<String> = task::list_tasks(&pool, &project.id).await.unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        assert_eq!(titles, vec!["First"]);
+
+        task::clear_all_tasks(&pool, &project.id).await.unwrap();
+    }
 }
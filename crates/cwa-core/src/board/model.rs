@@ -1,101 +1,204 @@
-//! Board domain models for the web Kanban UI.
+//! Board domain models.
+//!
+//! A board is a saved view over the project's tasks: it shows the columns of
+//! the project's workflow, holding the tasks that match its filter. A task
+//! shows up on every board whose filter it matches.
 
 use serde::{Deserialize, Serialize};
 
-/// A Kanban board containing columns and cards.
+use cwa_db::queries::boards::{BoardFilterRow, CardRow};
+use cwa_db::queries::tasks::{TaskEventRow, TaskRow};
+
+use crate::task::model::{BoardColumn, TaskFilter};
+use crate::task::workflow::{ColumnCategory, Workflow};
+
+/// A Kanban board: the tasks matching `filter`, by workflow column.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Board {
     pub id: String,
     pub project_id: String,
     pub name: String,
     pub description: Option<String>,
-    pub columns: Vec<Column>,
+    /// Tasks shown on the board; `status` is ignored.
+    pub filter: TaskFilter,
+    pub columns: Vec<BoardColumn>,
+    /// IDs of shown tasks that still have unfinished blockers.
+    pub blocked_task_ids: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
-/// A column within a board.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Column {
-    pub id: String,
-    pub board_id: String,
-    pub name: String,
-    pub position: i32,
-    pub color: Option<String>,
-    pub wip_limit: Option<i32>,
-    pub cards: Vec<Card>,
+impl Board {
+    /// Whether the board shows every task of the project.
+    pub fn is_unfiltered(&self) -> bool {
+        self.filter.label.is_none() && self.filter.assignee.is_none() && self.filter.spec_id.is_none()
+    }
 }
 
-/// A card within a column.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Card {
-    pub id: String,
-    pub column_id: String,
-    pub title: String,
-    pub description: Option<String>,
-    pub position: i32,
-    pub priority: Option<Priority>,
-    pub due_date: Option<String>,
-    pub labels: Vec<Label>,
-    pub created_at: String,
-    pub updated_at: String,
-    pub completed_at: Option<String>,
+pub(crate) fn filter_to_row(filter: &TaskFilter) -> BoardFilterRow {
+    BoardFilterRow {
+        label: filter.label.clone(),
+        assignee: filter.assignee.clone(),
+        spec_id: filter.spec_id.clone(),
+    }
 }
 
-/// Card priority levels.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Priority {
-    Low,
-    Medium,
-    High,
-    Critical,
+pub(crate) fn filter_from_row(row: &BoardFilterRow) -> TaskFilter {
+    TaskFilter {
+        status: None,
+        spec_id: row.spec_id.clone(),
+        assignee: row.assignee.clone(),
+        label: row.label.clone(),
+    }
 }
 
-impl Priority {
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s {
-            "low" => Some(Self::Low),
-            "medium" => Some(Self::Medium),
-            "high" => Some(Self::High),
-            "critical" => Some(Self::Critical),
-            _ => None,
+/// Outcome of importing the cards of legacy boards as tasks.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CardMigration {
+    /// Boards whose cards were imported.
+    pub boards: usize,
+    /// Tasks created from cards.
+    pub tasks: usize,
+    /// Legacy column names that matched no workflow column; their cards were
+    /// put in the workflow's first column.
+    pub unmapped_columns: Vec<String>,
+}
+
+/// Normalize a column name for matching: lowercase, with runs of other
+/// characters turned into a single `_`.
+fn normalize(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('_') {
+            out.push('_');
         }
     }
+    out.trim_matches('_').to_string()
+}
 
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Self::Low => "low",
-            Self::Medium => "medium",
-            Self::High => "high",
-            Self::Critical => "critical",
-        }
+/// Workflow column a legacy board column corresponds to, matched by name or
+/// label ("In Progress" → `in_progress`, "TODO" → `todo`).
+pub fn column_status<'a>(workflow: &'a Workflow, column_name: &str) -> Option<&'a str> {
+    let wanted = normalize(column_name);
+    workflow
+        .columns
+        .iter()
+        .find(|c| normalize(&c.name) == wanted || c.label.as_deref().is_some_and(|l| normalize(l) == wanted))
+        .map(|c| c.name.as_str())
+}
+
+/// Label given to tasks imported from a secondary legacy board, so that the
+/// board keeps showing them.
+pub fn board_label(board_name: &str) -> String {
+    let label = normalize(board_name).replace('_', "-");
+    if label.is_empty() { "board".to_string() } else { label }
+}
+
+/// Turn a legacy card into a task in column `status`, keeping its ID.
+///
+/// Returns the task and its history: creation in the workflow's first
+/// column, then a move to `status` when that is another column.
+pub fn card_to_task(
+    card: &CardRow,
+    project_id: &str,
+    status: &str,
+    labels: Vec<String>,
+    workflow: &Workflow,
+) -> (TaskRow, Vec<TaskEventRow>) {
+    let category = workflow.column(status).map(|c| c.category).unwrap_or_default();
+    let moved_at = card.completed_at.clone().unwrap_or_else(|| card.updated_at.clone());
+    let priority = card
+        .priority
+        .as_deref()
+        .filter(|p| crate::task::PRIORITIES.contains(p))
+        .unwrap_or("medium");
+
+    let row = TaskRow {
+        id: card.id.clone(),
+        project_id: project_id.to_string(),
+        spec_id: None,
+        title: card.title.clone(),
+        description: card.description.clone().filter(|d| !d.trim().is_empty()),
+        status: status.to_string(),
+        priority: priority.to_string(),
+        assignee: None,
+        labels: if labels.is_empty() { None } else { serde_json::to_string(&labels).ok() },
+        estimated_effort: None,
+        actual_effort: None,
+        blocked_by: None,
+        due_date: card.due_date.clone().filter(|d| !d.trim().is_empty()),
+        created_at: card.created_at.clone(),
+        updated_at: card.updated_at.clone(),
+        started_at: (category == ColumnCategory::Active).then(|| moved_at.clone()),
+        completed_at: (category == ColumnCategory::Done).then(|| moved_at.clone()),
+    };
+
+    let initial = workflow.initial_column();
+    let event = |from: Option<&str>, to: &str, at: &str| TaskEventRow {
+        id: String::new(),
+        task_id: card.id.clone(),
+        from: from.map(str::to_string),
+        to: to.to_string(),
+        at: at.to_string(),
+    };
+    let mut events = vec![event(None, initial, &card.created_at)];
+    if status != initial {
+        events.push(event(Some(initial), status, &moved_at));
     }
 
-    pub fn css_color(&self) -> &'static str {
-        match self {
-            Self::Low => "#6b7280",
-            Self::Medium => "#3b82f6",
-            Self::High => "#f59e0b",
-            Self::Critical => "#ef4444",
+    (row, events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(column: &str) -> CardRow {
+        CardRow {
+            id: "c1".to_string(),
+            column_id: column.to_string(),
+            title: "Fix login".to_string(),
+            description: Some(String::new()),
+            position: 0,
+            priority: Some("urgent".to_string()),
+            due_date: Some("2026-04-01".to_string()),
+            created_at: "2026-03-01T00:00:00+00:00".to_string(),
+            updated_at: "2026-03-02T00:00:00+00:00".to_string(),
+            completed_at: Some("2026-03-03T00:00:00+00:00".to_string()),
         }
     }
-}
 
-/// A label that can be attached to cards.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Label {
-    pub id: String,
-    pub board_id: String,
-    pub name: String,
-    pub color: String,
-}
+    #[test]
+    fn legacy_column_names_map_to_workflow_columns() {
+        let wf = Workflow::default();
+        assert_eq!(column_status(&wf, "Backlog"), Some("backlog"));
+        assert_eq!(column_status(&wf, "TODO"), Some("todo"));
+        assert_eq!(column_status(&wf, "In Progress"), Some("in_progress"));
+        assert_eq!(column_status(&wf, " done "), Some("done"));
+        assert_eq!(column_status(&wf, "Blocked"), None);
+        assert_eq!(board_label("Sprint #4 (Team A)"), "sprint-4-team-a");
+    }
 
-/// Default column definitions for a new board.
-pub const DEFAULT_COLUMNS: &[(&str, Option<i32>, &str)] = &[
-    ("Backlog", None, "#6b7280"),
-    ("TODO", Some(5), "#3b82f6"),
-    ("In Progress", Some(2), "#f59e0b"),
-    ("Review", Some(2), "#8b5cf6"),
-    ("Done", None, "#10b981"),
-];
+    #[test]
+    fn cards_become_tasks_with_history() {
+        let wf = Workflow::default();
+        let (row, events) = card_to_task(&card("col"), "p1", "done", vec!["ui".to_string()], &wf);
+
+        assert_eq!(row.id, "c1");
+        assert_eq!(row.priority, "medium");
+        assert_eq!(row.description, None);
+        assert_eq!(row.due_date.as_deref(), Some("2026-04-01"));
+        assert_eq!(row.labels.as_deref(), Some(r#"["ui"]"#));
+        assert_eq!(row.completed_at.as_deref(), Some("2026-03-03T00:00:00+00:00"));
+        let steps: Vec<(Option<&str>, &str)> =
+            events.iter().map(|e| (e.from.as_deref(), e.to.as_str())).collect();
+        assert_eq!(steps, vec![(None, "backlog"), (Some("backlog"), "done")]);
+
+        let (row, events) = card_to_task(&card("col"), "p1", "backlog", Vec::new(), &wf);
+        assert_eq!(row.completed_at, None);
+        assert_eq!(row.labels, None);
+        assert_eq!(events.len(), 1);
+    }
+}
@@ -0,0 +1,163 @@
+//! Dependency graph helpers shared by tasks and specs.
+//!
+//! Graphs are adjacency maps from an item ID to the IDs it depends on.
+
+use std::collections::{HashMap, HashSet};
+
+/// Adjacency map: item ID → IDs of the items it depends on.
+pub type DependencyGraph = HashMap<String, Vec<String>>;
+
+/// Check whether adding the edge `from → to` would close a cycle.
+///
+/// Returns the offending cycle as a list of IDs starting and ending with
+/// `from` (e.g. `[a, b, c, a]`), or `None` if the edge is safe to add.
+pub fn find_cycle(graph: &DependencyGraph, from: &str, to: &str) -> Option<Vec<String>> {
+    if from == to {
+        return Some(vec![from.to_string(), to.to_string()]);
+    }
+
+    // Depth-first search from `to`, looking for a path back to `from`.
+    let mut stack: Vec<(String, Vec<String>)> = vec![(to.to_string(), vec![to.to_string()])];
+    let mut visited: HashSet<String> = HashSet::new();
+
+    while let Some((node, path)) = stack.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        for next in graph.get(&node).into_iter().flatten() {
+            if next == from {
+                let mut cycle = Vec::with_capacity(path.len() + 2);
+                cycle.push(from.to_string());
+                cycle.extend(path.iter().cloned());
+                cycle.push(from.to_string());
+                return Some(cycle);
+            }
+            if !visited.contains(next) {
+                let mut next_path = path.clone();
+                next_path.push(next.clone());
+                stack.push((next.clone(), next_path));
+            }
+        }
+    }
+
+    None
+}
+
+/// Find any cycle in the graph.
+///
+/// Returns the first cycle found (in sorted node order), or `None` if the
+/// graph is acyclic.
+pub fn find_any_cycle(graph: &DependencyGraph) -> Option<Vec<String>> {
+    let mut nodes: Vec<&String> = graph.keys().collect();
+    nodes.sort();
+    for node in nodes {
+        for dep in &graph[node] {
+            if let Some(cycle) = find_cycle(graph, node, dep) {
+                return Some(cycle);
+            }
+        }
+    }
+    None
+}
+
+/// Order the graph's nodes into "waves" of items that can be done in parallel.
+///
+/// Wave 0 holds every node without dependencies, wave 1 every node whose
+/// dependencies are all in wave 0, and so on. Edges pointing to IDs that are
+/// not keys of the graph are treated as already satisfied. Nodes within a
+/// wave are sorted by ID for deterministic output.
+///
+/// Returns the offending cycle as `Err` if the graph is not acyclic.
+pub fn topological_waves(graph: &DependencyGraph) -> Result<Vec<Vec<String>>, Vec<String>> {
+    let mut remaining: HashMap<&str, HashSet<&str>> = graph
+        .iter()
+        .map(|(node, deps)| {
+            let deps = deps
+                .iter()
+                .map(String::as_str)
+                .filter(|d| graph.contains_key(*d) && *d != node)
+                .collect();
+            (node.as_str(), deps)
+        })
+        .collect();
+
+    let mut waves = Vec::new();
+    while !remaining.is_empty() {
+        let mut wave: Vec<String> = remaining
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(node, _)| node.to_string())
+            .collect();
+
+        if wave.is_empty() {
+            let stuck: DependencyGraph = remaining
+                .iter()
+                .map(|(node, deps)| (node.to_string(), deps.iter().map(|d| d.to_string()).collect()))
+                .collect();
+            return Err(find_any_cycle(&stuck).unwrap_or_default());
+        }
+
+        wave.sort();
+        for node in &wave {
+            remaining.remove(node.as_str());
+        }
+        for deps in remaining.values_mut() {
+            for node in &wave {
+                deps.remove(node.as_str());
+            }
+        }
+        waves.push(wave);
+    }
+
+    Ok(waves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str)]) -> DependencyGraph {
+        let mut g = DependencyGraph::new();
+        for (from, to) in edges {
+            g.entry(from.to_string()).or_default().push(to.to_string());
+        }
+        g
+    }
+
+    #[test]
+    fn test_no_cycle() {
+        let g = graph(&[("a", "b"), ("b", "c")]);
+        assert!(find_cycle(&g, "a", "c").is_none());
+        assert!(find_cycle(&g, "d", "a").is_none());
+    }
+
+    #[test]
+    fn test_self_dependency() {
+        let g = DependencyGraph::new();
+        assert_eq!(find_cycle(&g, "a", "a"), Some(vec!["a".to_string(), "a".to_string()]));
+    }
+
+    #[test]
+    fn test_transitive_cycle() {
+        let g = graph(&[("b", "c"), ("c", "a")]);
+        let cycle = find_cycle(&g, "a", "b").unwrap();
+        assert_eq!(cycle, vec!["a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_topological_waves() {
+        let mut g = graph(&[("b", "a"), ("c", "a"), ("d", "b"), ("d", "c"), ("e", "zzz")]);
+        g.insert("a".to_string(), vec![]);
+        let waves = topological_waves(&g).unwrap();
+        assert_eq!(waves, vec![vec!["a", "e"], vec!["b", "c"], vec!["d"]]);
+    }
+
+    #[test]
+    fn test_topological_waves_cycle() {
+        let g = graph(&[("a", "b"), ("b", "c"), ("c", "a"), ("d", "a")]);
+        let cycle = topological_waves(&g).unwrap_err();
+        assert_eq!(cycle.first(), cycle.last());
+        assert_eq!(cycle.len(), 4);
+        assert!(find_any_cycle(&g).is_some());
+    }
+}
@@ -2,7 +2,7 @@
 
 pub mod model;
 
-use crate::error::CwaResult;
+use crate::error::{CwaError, CwaResult};
 use cwa_db::DbPool;
 use cwa_db::queries::domains as queries;
 use model::{BoundedContext, ContextRelationshipType, DomainObject, GlossaryTerm, DomainModel, ContextMap};
@@ -17,13 +17,18 @@ pub async fn create_context(
 ) -> CwaResult<BoundedContext> {
     let id = Uuid::new_v4().to_string();
     queries::create_context(pool, &id, project_id, name, description).await?;
-    let row = queries::get_context(pool, &id).await?;
-    Ok(BoundedContext::from_row(row))
+    get_context(pool, project_id, &id).await
 }
 
-/// Get a bounded context by ID.
-pub async fn get_context(pool: &DbPool, id: &str) -> CwaResult<BoundedContext> {
-    let row = queries::get_context(pool, id).await?;
+/// Get a bounded context of a project by ID.
+///
+/// Contexts of other projects are reported as not found.
+pub async fn get_context(pool: &DbPool, project_id: &str, id: &str) -> CwaResult<BoundedContext> {
+    let row = queries::get_context_in_project(pool, project_id, id).await
+        .map_err(|e| match e {
+            cwa_db::DbError::NotFound(_) => CwaError::ContextNotFound(id.to_string()),
+            e => e.into(),
+        })?;
     Ok(BoundedContext::from_row(row))
 }
 
@@ -33,14 +38,17 @@ pub async fn list_contexts(pool: &DbPool, project_id: &str) -> CwaResult<Vec<Bou
     Ok(rows.into_iter().map(BoundedContext::from_row).collect())
 }
 
-/// Create a domain object. Returns the new object's ID.
+/// Create a domain object in one of the project's contexts. Returns the new
+/// object's ID.
 pub async fn create_domain_object(
     pool: &DbPool,
+    project_id: &str,
     context_id: &str,
     name: &str,
     object_type: &str,
     description: Option<&str>,
 ) -> CwaResult<String> {
+    get_context(pool, project_id, context_id).await?;
     let id = Uuid::new_v4().to_string();
     queries::create_domain_object(pool, &id, context_id, name, object_type, description).await?;
     Ok(id)
@@ -58,8 +66,12 @@ pub async fn get_context_by_name(
 }
 
 /// List domain objects for a context.
-pub async fn list_domain_objects(pool: &DbPool, context_id: &str) -> CwaResult<Vec<DomainObject>> {
-    let rows = queries::list_domain_objects(pool, context_id).await?;
+pub async fn list_domain_objects(
+    pool: &DbPool,
+    project_id: &str,
+    context_id: &str,
+) -> CwaResult<Vec<DomainObject>> {
+    let rows = queries::list_domain_objects_by_context(pool, project_id, context_id).await?;
     Ok(rows.into_iter().map(DomainObject::from_row).collect())
 }
 
@@ -71,6 +83,9 @@ pub async fn add_glossary_term(
     definition: &str,
     context_id: Option<&str>,
 ) -> CwaResult<()> {
+    if let Some(context_id) = context_id {
+        get_context(pool, project_id, context_id).await?;
+    }
     let id = Uuid::new_v4().to_string();
     queries::create_glossary_term(pool, &id, project_id, term, definition, context_id).await?;
     Ok(())
@@ -88,7 +103,7 @@ pub async fn get_domain_model(pool: &DbPool, project_id: &str) -> CwaResult<Doma
 
     let mut contexts_with_objects = Vec::new();
     for context in contexts {
-        let objects = list_domain_objects(pool, &context.id).await?;
+        let objects = list_domain_objects(pool, project_id, &context.id).await?;
         contexts_with_objects.push(model::ContextWithObjects {
             context,
             objects,
@@ -39,6 +39,22 @@ pub enum CwaError {
         current: i64,
     },
 
+    #[error("Task '{task}' is blocked by unfinished task(s): {blockers}")]
+    TaskBlocked { task: String, blockers: String },
+
+    #[error("Task '{task}' does not satisfy the workflow policies for '{column}': {reasons}")]
+    PolicyViolation {
+        task: String,
+        column: String,
+        reasons: String,
+    },
+
+    #[error("Spec '{spec}' depends on unfinished spec(s): {dependencies}")]
+    SpecBlocked { spec: String, dependencies: String },
+
+    #[error("Dependency cycle detected: {0}")]
+    DependencyCycle(String),
+
     #[error("Validation error: {0}")]
     ValidationError(String),
 
@@ -3,13 +3,16 @@
 //! Domain models and business logic for the Claude Workflow Architect.
 
 pub mod analysis;
+pub mod audit;
 pub mod board;
 pub mod decision;
+pub mod dependency;
 pub mod design;
 pub mod domain;
 pub mod error;
 pub mod memory;
 pub mod notifier;
+pub mod plan;
 pub mod project;
 pub mod spec;
 pub mod task;
@@ -8,6 +8,7 @@ use crate::task;
 use crate::spec;
 use crate::decision;
 use cwa_db::DbPool;
+use cwa_db::queries::embeddings as embedding_queries;
 use cwa_db::queries::memory as queries;
 use cwa_db::queries::observations as obs_queries;
 use cwa_db::queries::projects as project_queries;
@@ -15,7 +16,7 @@ use model::{MemoryEntry, Session, ContextSummary};
 use observation::{Observation, ObservationIndex, Summary, ObservationType};
 use uuid::Uuid;
 
-/// Create a memory entry.
+/// Create a memory entry, queued for embedding.
 pub async fn add_memory(
     pool: &DbPool,
     project_id: &str,
@@ -26,6 +27,7 @@ pub async fn add_memory(
 ) -> CwaResult<()> {
     let id = Uuid::new_v4().to_string();
     queries::create_memory_entry(pool, &id, project_id, entry_type, content, importance, session_id).await?;
+    embedding_queries::mark_pending(pool, project_id, "memory", &id).await?;
     Ok(())
 }
 
@@ -86,11 +88,11 @@ pub async fn get_context_summary(pool: &DbPool, project_id: &str) -> CwaResult<C
     // Get task counts by status
     let tasks = task::list_tasks(pool, project_id).await?;
     let task_counts = TaskCounts {
-        backlog: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Backlog).count(),
-        todo: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Todo).count(),
-        in_progress: tasks.iter().filter(|t| t.status == task::model::TaskStatus::InProgress).count(),
-        review: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Review).count(),
-        done: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Done).count(),
+        backlog: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Backlog.as_str()).count(),
+        todo: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Todo.as_str()).count(),
+        in_progress: tasks.iter().filter(|t| t.status == task::model::TaskStatus::InProgress.as_str()).count(),
+        review: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Review.as_str()).count(),
+        done: tasks.iter().filter(|t| t.status == task::model::TaskStatus::Done.as_str()).count(),
     };
 
     // Get recent memory entries
@@ -117,12 +119,24 @@ pub async fn suggest_next_steps(pool: &DbPool, project_id: &str) -> CwaResult<Ve
     // Check if there's a task in progress
     let current_task = task::get_current_task(pool, project_id).await?;
     if current_task.is_none() {
-        // No task in progress, suggest starting one
-        let tasks = cwa_db::queries::tasks::list_tasks_by_status(pool, project_id, "todo").await?;
-        if !tasks.is_empty() {
+        // No task in progress, suggest starting the first unblocked one
+        let all_tasks = task::list_tasks(pool, project_id).await?;
+        let workflow = task::get_workflow(pool, project_id).await?;
+        let blocked = task::blocked_task_ids(&all_tasks, &workflow);
+        let (todo_blocked, todo_ready): (Vec<_>, Vec<_>) = all_tasks
+            .iter()
+            .filter(|t| t.status == task::model::TaskStatus::Todo.as_str())
+            .partition(|t| blocked.contains(&t.id));
+
+        if let Some(next) = todo_ready.first() {
             suggestions.push(format!(
                 "Start working on task: {} ({})",
-                tasks[0].title, tasks[0].id
+                next.title, next.id
+            ));
+        } else if !todo_blocked.is_empty() {
+            suggestions.push(format!(
+                "All {} task(s) in 'todo' are blocked. Finish their blockers first.",
+                todo_blocked.len()
             ));
         } else {
             suggestions.push("No tasks in 'todo' column. Consider moving tasks from backlog.".to_string());
@@ -164,7 +178,7 @@ pub struct TaskCounts {
 
 // --- Observation Functions ---
 
-/// Add a new observation.
+/// Add a new observation without embedding it; it is queued for embedding.
 pub async fn add_observation(
     pool: &DbPool,
     project_id: &str,
@@ -197,6 +211,8 @@ pub async fn add_observation(
         files_mod_json.as_deref(), files_read_json.as_deref(),
         None, None, confidence,
     ).await?;
+    // Embedded later by the backfill worker or `cwa memory reindex`
+    embedding_queries::mark_pending(pool, project_id, "observation", &id).await?;
 
     let row = obs_queries::get_observation(pool, &id).await?
         .ok_or_else(|| crate::error::CwaError::NotFound("Observation just created not found".to_string()))?;
@@ -211,9 +227,13 @@ pub async fn get_observation(pool: &DbPool, id: &str) -> CwaResult<Option<Observ
 }
 
 /// Get multiple observations by IDs.
-pub async fn get_observations_batch(pool: &DbPool, ids: &[&str]) -> CwaResult<Vec<Observation>> {
+pub async fn get_observations_batch(pool: &DbPool, project_id: &str, ids: &[&str]) -> CwaResult<Vec<Observation>> {
     let rows = obs_queries::get_observations_batch(pool, ids).await?;
-    Ok(rows.into_iter().map(Observation::from_row).collect())
+    Ok(rows
+        .into_iter()
+        .filter(|row| row.project_id == project_id)
+        .map(Observation::from_row)
+        .collect())
 }
 
 /// Get timeline of observations (compact index).
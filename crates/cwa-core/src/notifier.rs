@@ -1,8 +1,11 @@
 //! Web server notifier for real-time updates.
 //!
 //! This module provides a shared notifier that CLI and MCP can use
-//! to notify the web server of changes for live reload functionality.
+//! to notify the web server of changes for live reload functionality,
+//! and (given a Redis pool) MCP servers in other processes, which turn the
+//! events into resource notifications for their subscribers.
 
+use cwa_db::{DbPool, WebSocketMessage};
 use std::time::Duration;
 use tracing::{debug, warn};
 
@@ -14,6 +17,8 @@ const DEFAULT_WEB_URL: &str = "http://127.0.0.1:3030";
 pub struct WebNotifier {
     client: reqwest::Client,
     base_url: String,
+    /// Pool and project ID to publish events on Redis with.
+    pool: Option<(DbPool, String)>,
 }
 
 impl WebNotifier {
@@ -31,6 +36,7 @@ impl WebNotifier {
                 .build()
                 .unwrap_or_default(),
             base_url,
+            pool: None,
         }
     }
 
@@ -43,6 +49,46 @@ impl WebNotifier {
                 .build()
                 .unwrap_or_default(),
             base_url: base_url.to_string(),
+            pool: None,
+        }
+    }
+
+    /// Also publish every notification on Redis as a change in
+    /// `project_id`, so MCP servers running in other processes see it.
+    pub fn with_pool(mut self, pool: &DbPool, project_id: &str) -> Self {
+        self.pool = Some((pool.clone(), project_id.to_string()));
+        self
+    }
+
+    /// Notify the web server (and, with a pool, other processes) of a change.
+    pub async fn notify(&self, msg: &WebSocketMessage) {
+        self.publish(msg).await;
+
+        let url = format!("{}/internal/notify", self.base_url);
+        debug!(url = %url, ?msg, "Sending notification");
+        match self.client.post(&url).json(msg).send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!("Notification sent successfully");
+            }
+            Ok(response) => {
+                warn!(status_code = %response.status(), "Notification failed with status");
+            }
+            Err(e) => {
+                debug!(
+                    error = %e,
+                    url = %url,
+                    "Failed to send notification (cwa serve may not be running)"
+                );
+            }
+        }
+    }
+
+    async fn publish(&self, msg: &WebSocketMessage) {
+        let Some((ref pool, ref project_id)) = self.pool else { return };
+        match tokio::time::timeout(Duration::from_secs(2), cwa_db::publish_event(pool, project_id, msg)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => debug!(error = %e, "Failed to publish change event"),
+            Err(_) => debug!("Timed out publishing change event"),
         }
     }
 
@@ -51,6 +97,12 @@ impl WebNotifier {
     /// This sends an HTTP POST to `/internal/notify` which broadcasts
     /// the update to all connected WebSocket clients.
     pub async fn notify_task_updated(&self, task_id: &str, status: &str) {
+        self.publish(&WebSocketMessage::TaskUpdated {
+            task_id: task_id.to_string(),
+            status: status.to_string(),
+        })
+        .await;
+
         let url = format!("{}/internal/notify", self.base_url);
         let payload = serde_json::json!({
             "type": "TaskUpdated",
@@ -85,6 +137,8 @@ impl WebNotifier {
 
     /// Notify the web server to refresh the entire board.
     pub async fn notify_board_refresh(&self) {
+        self.publish(&WebSocketMessage::BoardRefresh).await;
+
         let url = format!("{}/internal/notify", self.base_url);
         let payload = serde_json::json!({
             "type": "BoardRefresh"
@@ -115,6 +169,11 @@ impl WebNotifier {
 
     /// Notify the web server that a spec was updated.
     pub async fn notify_spec_updated(&self, spec_id: &str) {
+        self.publish(&WebSocketMessage::SpecUpdated {
+            spec_id: spec_id.to_string(),
+        })
+        .await;
+
         let url = format!("{}/internal/notify", self.base_url);
         let payload = serde_json::json!({
             "type": "SpecUpdated",
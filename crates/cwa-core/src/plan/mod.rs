@@ -0,0 +1,328 @@
+//! Structured project plans.
+//!
+//! A plan describes contexts, domain objects, glossary terms, decisions,
+//! tech stack and specs in one JSON or YAML document (the machine-readable
+//! part of `cwa_plan_software` output). Applying it creates whatever the
+//! project does not have yet, matching existing items by name, so the same
+//! plan can be applied repeatedly.
+
+pub mod model;
+
+use crate::error::{CwaError, CwaResult};
+use crate::project::model::{Project, ProjectInfo};
+use crate::{decision, domain, project, spec};
+use cwa_db::DbPool;
+use model::{ChangeKind, Plan, PlanChange};
+use std::collections::HashMap;
+
+/// Validate `plan` against the project and apply it, returning one change
+/// per planned item. With `dry_run` nothing is written.
+pub async fn apply(pool: &DbPool, project: &Project, plan: &Plan, dry_run: bool) -> CwaResult<Vec<PlanChange>> {
+    let contexts = domain::list_contexts(pool, &project.id).await?;
+    let specs = spec::list_specs(pool, &project.id).await?;
+
+    let context_names: Vec<String> = contexts.iter().map(|c| c.name.clone()).collect();
+    let spec_titles: Vec<String> = specs.iter().map(|s| s.title.clone()).collect();
+    let spec_dependencies: Vec<(String, String)> = specs
+        .iter()
+        .flat_map(|s| {
+            s.dependencies.iter().filter_map(|id| {
+                let dependency = specs.iter().find(|d| &d.id == id)?;
+                Some((s.title.clone(), dependency.title.clone()))
+            })
+        })
+        .collect();
+    // Checked before any write, so a cyclic plan is never half applied
+    let problems = plan.validate(&context_names, &spec_titles, &spec_dependencies);
+    if !problems.is_empty() {
+        return Err(CwaError::validation(format!("Invalid plan:\n  - {}", problems.join("\n  - "))));
+    }
+
+    let mut changes = Vec::new();
+
+    // Bounded contexts and their domain objects. Keyed by lowercase name;
+    // `None` marks a context that only exists after a real run.
+    let mut context_ids: HashMap<String, Option<String>> = contexts
+        .iter()
+        .map(|c| (c.name.to_lowercase(), Some(c.id.clone())))
+        .collect();
+    for planned in &plan.contexts {
+        let key = planned.name.to_lowercase();
+        let context_id = match context_ids.get(&key).cloned().flatten() {
+            Some(id) => {
+                changes.push(PlanChange::new(ChangeKind::Unchanged, "context", &planned.name));
+                Some(id)
+            }
+            None => {
+                changes.push(PlanChange::new(ChangeKind::Create, "context", &planned.name));
+                if dry_run {
+                    None
+                } else {
+                    let created =
+                        domain::create_context(pool, &project.id, &planned.name, planned.description.as_deref()).await?;
+                    Some(created.id)
+                }
+            }
+        };
+        context_ids.insert(key, context_id.clone());
+
+        let existing_objects = match &context_id {
+            Some(id) => domain::list_domain_objects(pool, &project.id, id).await?,
+            None => Vec::new(),
+        };
+        for object in &planned.objects {
+            let name = format!("{}.{}", planned.name, object.name);
+            if existing_objects.iter().any(|o| o.name.eq_ignore_ascii_case(&object.name)) {
+                changes.push(PlanChange::new(ChangeKind::Unchanged, "object", name));
+                continue;
+            }
+            let object_type = object.object_type.as_deref().unwrap_or("entity");
+            changes.push(PlanChange::new(ChangeKind::Create, "object", name).with_detail(object_type));
+            if let Some(context_id) = &context_id {
+                domain::create_domain_object(pool, &project.id, context_id, &object.name, object_type, object.description.as_deref())
+                    .await?;
+            }
+        }
+    }
+
+    // Glossary terms.
+    let glossary = domain::list_glossary(pool, &project.id).await?;
+    for term in &plan.glossary {
+        if glossary.iter().any(|g| g.term.eq_ignore_ascii_case(&term.term)) {
+            changes.push(PlanChange::new(ChangeKind::Unchanged, "term", &term.term));
+            continue;
+        }
+        changes.push(PlanChange::new(ChangeKind::Create, "term", &term.term));
+        if !dry_run {
+            let context_id = term
+                .context
+                .as_ref()
+                .and_then(|name| context_ids.get(&name.to_lowercase()).cloned().flatten());
+            domain::add_glossary_term(pool, &project.id, &term.term, &term.definition, context_id.as_deref()).await?;
+        }
+    }
+
+    // Architectural decisions.
+    let decisions = decision::list_decisions(pool, &project.id).await?;
+    for planned in &plan.decisions {
+        if decisions.iter().any(|d| d.title.eq_ignore_ascii_case(&planned.title)) {
+            changes.push(PlanChange::new(ChangeKind::Unchanged, "decision", &planned.title));
+            continue;
+        }
+        changes.push(PlanChange::new(ChangeKind::Create, "decision", &planned.title));
+        if !dry_run {
+            decision::create_decision(pool, &project.id, &planned.title, &planned.context, &planned.decision).await?;
+        }
+    }
+
+    // Specs with their criteria, then dependencies once every spec exists.
+    for planned in &plan.specs {
+        let existing = specs.iter().find(|s| s.title.eq_ignore_ascii_case(&planned.title));
+        let priority = planned.priority.as_deref().unwrap_or("medium");
+        let Some(existing) = existing else {
+            changes.push(
+                PlanChange::new(ChangeKind::Create, "spec", &planned.title)
+                    .with_detail(format!("{} criteria", planned.criteria.len())),
+            );
+            if !dry_run {
+                spec::create_spec_with_criteria(
+                    pool,
+                    &project.id,
+                    &planned.title,
+                    planned.description.as_deref(),
+                    priority,
+                    Some(&planned.criteria),
+                )
+                .await?;
+            }
+            continue;
+        };
+
+        let missing: Vec<String> = planned
+            .criteria
+            .iter()
+            .filter(|c| !existing.acceptance_criteria.iter().any(|e| e.trim().eq_ignore_ascii_case(c.trim())))
+            .cloned()
+            .collect();
+        if missing.is_empty() {
+            changes.push(PlanChange::new(ChangeKind::Unchanged, "spec", &planned.title));
+            continue;
+        }
+        changes.push(
+            PlanChange::new(ChangeKind::Update, "spec", &planned.title)
+                .with_detail(format!("+{} criteria", missing.len())),
+        );
+        if !dry_run {
+            spec::add_acceptance_criteria(pool, &project.id, &existing.id, &missing).await?;
+        }
+    }
+
+    let specs = if dry_run { specs } else { spec::list_specs(pool, &project.id).await? };
+    for planned in &plan.specs {
+        let existing = specs.iter().find(|s| s.title.eq_ignore_ascii_case(&planned.title));
+        let missing: Vec<&String> = planned
+            .depends_on
+            .iter()
+            .filter(|title| {
+                let dependency = specs.iter().find(|s| s.title.eq_ignore_ascii_case(title));
+                match (existing, dependency) {
+                    (Some(spec), Some(dep)) => !spec.dependencies.contains(&dep.id),
+                    _ => true,
+                }
+            })
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        let detail = format!("depends on {}", missing.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(", "));
+        changes.push(PlanChange::new(ChangeKind::Update, "spec", &planned.title).with_detail(detail));
+        if !dry_run {
+            let spec = existing.ok_or_else(|| CwaError::SpecNotFound(planned.title.clone()))?;
+            for title in missing {
+                let dependency = specs
+                    .iter()
+                    .find(|s| s.title.eq_ignore_ascii_case(title))
+                    .ok_or_else(|| CwaError::SpecNotFound(title.clone()))?;
+                spec::add_dependency(pool, &project.id, &spec.id, &dependency.id).await?;
+            }
+        }
+    }
+
+    // Tech stack, merged into the project info.
+    if !plan.tech_stack.is_empty() {
+        let info = project::get_project_info(pool, &project.id).await?;
+        let current = info.as_ref().map(|i| i.tech_stack.clone()).unwrap_or_default();
+        let added: Vec<String> = plan
+            .tech_stack
+            .iter()
+            .filter(|t| !current.iter().any(|c| c.eq_ignore_ascii_case(t)))
+            .cloned()
+            .collect();
+        if added.is_empty() {
+            changes.push(PlanChange::new(ChangeKind::Unchanged, "tech_stack", current.join(", ")));
+        } else {
+            changes.push(PlanChange::new(ChangeKind::Update, "tech_stack", added.join(", ")));
+            if !dry_run {
+                let mut info = info.unwrap_or_else(|| {
+                    ProjectInfo::new(
+                        project.name.clone(),
+                        project.description.clone().unwrap_or_default(),
+                        Vec::new(),
+                        Vec::new(),
+                        Vec::new(),
+                    )
+                });
+                info.tech_stack.extend(added);
+                info.updated_at = chrono::Utc::now().to_rfc3339();
+                project::set_project_info(pool, &project.id, &info).await?;
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::model::*;
+    use super::{apply, domain, project};
+
+    const YAML_PLAN: &str = r#"
+tech_stack: [rust, axum]
+contexts:
+  - name: Sessions
+    objects:
+      - name: Session
+        type: aggregate
+glossary:
+  - term: Session
+    definition: A logged-in user's visit
+    context: Sessions
+specs:
+  - title: Create session
+    priority: high
+    criteria: [Returns a token]
+  - title: Expire session
+    criteria: [Sessions expire after 30 minutes]
+    depends_on: [Create session]
+"#;
+
+    #[test]
+    fn parses_json_yaml_and_fenced_markdown() {
+        let yaml = Plan::parse(YAML_PLAN).unwrap();
+        assert_eq!(yaml.tech_stack, vec!["rust", "axum"]);
+        assert_eq!(yaml.contexts[0].objects[0].object_type.as_deref(), Some("aggregate"));
+        assert_eq!(yaml.specs[1].depends_on, vec!["Create session"]);
+
+        let json = Plan::parse(r#"{"specs": [{"title": "A", "criteria": ["x"]}]}"#).unwrap();
+        assert_eq!(json.specs[0].title, "A");
+        assert!(json.contexts.is_empty());
+
+        let markdown = "# Plan\n\nSome prose.\n\n```json\n{\"tech_stack\": [\"go\"]}\n```\n\n```bash\ncwa spec new\n```\n";
+        assert_eq!(Plan::parse(markdown).unwrap().tech_stack, vec!["go"]);
+
+        assert!(Plan::parse("{ not json").is_err());
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let plan = Plan::parse(YAML_PLAN).unwrap();
+        assert!(plan.validate(&[], &[], &[]).is_empty());
+
+        let mut broken = plan.clone();
+        broken.contexts[0].objects[0].object_type = Some("widget".into());
+        broken.glossary[0].context = Some("Billing".into());
+        broken.specs[0].priority = Some("urgent".into());
+        broken.specs[1].criteria.clear();
+        broken.specs[1].depends_on.push("Refresh session".into());
+        broken.specs.push(broken.specs[0].clone());
+        let problems = broken.validate(&[], &[], &[]);
+        assert_eq!(problems.len(), 7, "{:#?}", problems);
+        assert!(problems.iter().any(|p| p.contains("unknown type 'widget'")));
+        assert!(problems.iter().any(|p| p.contains("'Create session' is listed twice")));
+
+        // References to items already in the project are fine.
+        let mut extends = Plan::parse(YAML_PLAN).unwrap();
+        extends.glossary[0].context = Some("Billing".into());
+        extends.specs[1].depends_on.push("Refresh session".into());
+        assert!(extends.validate(&["billing".into()], &["Refresh Session".into()], &[]).is_empty());
+    }
+
+    #[test]
+    fn validate_rejects_dependency_cycles() {
+        let mut cyclic = Plan::parse(YAML_PLAN).unwrap();
+        cyclic.specs[0].depends_on.push("Expire session".into());
+        let problems = cyclic.validate(&[], &[], &[]);
+        assert_eq!(problems.len(), 1, "{:#?}", problems);
+        assert!(problems[0].contains("cycle"), "{}", problems[0]);
+        assert!(problems[0].contains("Create session") && problems[0].contains("Expire session"));
+
+        // A cycle closed through a dependency already in the project
+        let mut extends = Plan::parse(YAML_PLAN).unwrap();
+        extends.specs[0].depends_on.push("Refresh session".into());
+        let existing = ["Refresh session".to_string()];
+        let edges = [("refresh session".to_string(), "Expire Session".to_string())];
+        assert_eq!(extends.validate(&[], &existing, &edges).len(), 1);
+        assert!(extends.validate(&[], &existing, &[]).is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server at REDIS_URL"]
+    async fn applying_twice_creates_nothing_new() {
+        let pool = cwa_db::init_pool_from_env().await.unwrap();
+        let name = format!("plan-test-{}", uuid::Uuid::new_v4());
+        let project = project::create_project(&pool, &name, None).await.unwrap();
+        let plan = Plan::parse(YAML_PLAN).unwrap();
+
+        apply(&pool, &project, &plan, false).await.unwrap();
+        let second = apply(&pool, &project, &plan, false).await.unwrap();
+        let created: Vec<_> = second.iter().filter(|c| c.kind != ChangeKind::Unchanged).collect();
+        assert!(created.is_empty(), "{:#?}", created);
+
+        let model = domain::get_domain_model(&pool, &project.id).await.unwrap();
+        for planned in &plan.contexts {
+            let context = model.contexts.iter().find(|c| c.context.name == planned.name).unwrap();
+            assert_eq!(context.objects.len(), planned.objects.len(), "{}", planned.name);
+        }
+    }
+}
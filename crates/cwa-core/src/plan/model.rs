@@ -0,0 +1,317 @@
+//! Structured plan models.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::dependency::{self, DependencyGraph};
+use crate::error::{CwaError, CwaResult};
+
+/// Domain object types accepted in a plan (see `ObjectType::as_str`).
+pub const OBJECT_TYPES: &[&str] = &[
+    "entity",
+    "value_object",
+    "aggregate",
+    "service",
+    "event",
+    "domain_event",
+    "saga",
+    "port",
+    "adapter",
+];
+
+/// Spec priorities accepted in a plan.
+pub const PRIORITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+/// A machine-readable project plan, as emitted by `cwa_plan_software`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Plan {
+    /// Project name (informational).
+    pub project: Option<String>,
+    pub tech_stack: Vec<String>,
+    pub contexts: Vec<PlanContext>,
+    pub glossary: Vec<PlanTerm>,
+    pub decisions: Vec<PlanDecision>,
+    pub specs: Vec<PlanSpec>,
+}
+
+/// A bounded context with its domain objects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanContext {
+    pub name: String,
+    pub description: Option<String>,
+    pub objects: Vec<PlanObject>,
+}
+
+/// A domain object inside a planned context.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanObject {
+    pub name: String,
+    /// One of [`OBJECT_TYPES`]; defaults to `entity`.
+    #[serde(rename = "type")]
+    pub object_type: Option<String>,
+    pub description: Option<String>,
+}
+
+/// A glossary term.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanTerm {
+    pub term: String,
+    pub definition: String,
+    /// Name of the bounded context the term belongs to.
+    pub context: Option<String>,
+}
+
+/// An architectural decision record.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanDecision {
+    pub title: String,
+    pub context: String,
+    pub decision: String,
+}
+
+/// A specification with its acceptance criteria.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanSpec {
+    pub title: String,
+    pub description: Option<String>,
+    /// One of [`PRIORITIES`]; defaults to `medium`.
+    pub priority: Option<String>,
+    pub criteria: Vec<String>,
+    /// Titles of specs (in this plan or already in the project) this spec
+    /// depends on.
+    pub depends_on: Vec<String>,
+}
+
+impl Plan {
+    /// Parse a plan from JSON, YAML, or a Markdown document containing a
+    /// fenced `json` or `yaml` block.
+    pub fn parse(content: &str) -> CwaResult<Self> {
+        let trimmed = content.trim_start();
+        if trimmed.starts_with('{') {
+            return serde_json::from_str(trimmed)
+                .map_err(|e| CwaError::validation(format!("Invalid JSON plan: {}", e)));
+        }
+        if let Some((lang, block)) = fenced_block(content) {
+            return match lang {
+                "json" => serde_json::from_str(block)
+                    .map_err(|e| CwaError::validation(format!("Invalid JSON plan: {}", e))),
+                _ => serde_yaml::from_str(block)
+                    .map_err(|e| CwaError::validation(format!("Invalid YAML plan: {}", e))),
+            };
+        }
+        serde_yaml::from_str(content)
+            .map_err(|e| CwaError::validation(format!("Invalid YAML plan: {}", e)))
+    }
+
+    /// Check the plan for missing names, duplicates, unknown values,
+    /// dangling references and dependency cycles. Returns every problem found.
+    ///
+    /// `existing_contexts` and `existing_specs` are names already in the
+    /// project, which glossary terms and `depends_on` may also reference.
+    /// `existing_dependencies` are the project's spec dependencies as
+    /// `(spec title, dependency title)` pairs, checked for cycles together
+    /// with the planned ones.
+    pub fn validate(
+        &self,
+        existing_contexts: &[String],
+        existing_specs: &[String],
+        existing_dependencies: &[(String, String)],
+    ) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let mut context_names = HashSet::new();
+        for (i, context) in self.contexts.iter().enumerate() {
+            if context.name.trim().is_empty() {
+                problems.push(format!("contexts[{}]: name is empty", i));
+                continue;
+            }
+            if !context_names.insert(context.name.to_lowercase()) {
+                problems.push(format!("Context '{}' is listed twice", context.name));
+            }
+            let mut object_names = HashSet::new();
+            for object in &context.objects {
+                if object.name.trim().is_empty() {
+                    problems.push(format!("Context '{}': object name is empty", context.name));
+                    continue;
+                }
+                if !object_names.insert(object.name.to_lowercase()) {
+                    problems.push(format!("Object '{}' is listed twice in '{}'", object.name, context.name));
+                }
+                if let Some(kind) = &object.object_type {
+                    if !OBJECT_TYPES.contains(&kind.as_str()) {
+                        problems.push(format!(
+                            "Object '{}': unknown type '{}' (expected one of: {})",
+                            object.name,
+                            kind,
+                            OBJECT_TYPES.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut terms = HashSet::new();
+        for term in &self.glossary {
+            if term.term.trim().is_empty() || term.definition.trim().is_empty() {
+                problems.push(format!("Glossary term '{}' needs a term and a definition", term.term));
+                continue;
+            }
+            if !terms.insert(term.term.to_lowercase()) {
+                problems.push(format!("Glossary term '{}' is listed twice", term.term));
+            }
+            if let Some(context) = &term.context {
+                let known = context_names.contains(&context.to_lowercase())
+                    || existing_contexts.iter().any(|c| c.eq_ignore_ascii_case(context));
+                if !known {
+                    problems.push(format!("Glossary term '{}': unknown context '{}'", term.term, context));
+                }
+            }
+        }
+
+        let mut decisions = HashSet::new();
+        for decision in &self.decisions {
+            if decision.title.trim().is_empty() || decision.decision.trim().is_empty() {
+                problems.push(format!("Decision '{}' needs a title and a decision", decision.title));
+                continue;
+            }
+            if !decisions.insert(decision.title.to_lowercase()) {
+                problems.push(format!("Decision '{}' is listed twice", decision.title));
+            }
+        }
+
+        let mut spec_titles: HashSet<String> = existing_specs.iter().map(|t| t.to_lowercase()).collect();
+        let mut planned = HashSet::new();
+        for spec in &self.specs {
+            if spec.title.trim().is_empty() {
+                problems.push("Spec title is empty".to_string());
+                continue;
+            }
+            if !planned.insert(spec.title.to_lowercase()) {
+                problems.push(format!("Spec '{}' is listed twice", spec.title));
+            }
+            spec_titles.insert(spec.title.to_lowercase());
+            if spec.criteria.is_empty() {
+                problems.push(format!("Spec '{}' has no acceptance criteria", spec.title));
+            }
+            if let Some(priority) = &spec.priority {
+                if !PRIORITIES.contains(&priority.as_str()) {
+                    problems.push(format!(
+                        "Spec '{}': unknown priority '{}' (expected one of: {})",
+                        spec.title,
+                        priority,
+                        PRIORITIES.join(", ")
+                    ));
+                }
+            }
+        }
+        for spec in &self.specs {
+            for dependency in &spec.depends_on {
+                if dependency.eq_ignore_ascii_case(&spec.title) {
+                    problems.push(format!("Spec '{}' depends on itself", spec.title));
+                } else if !spec_titles.contains(&dependency.to_lowercase()) {
+                    problems.push(format!("Spec '{}': unknown dependency '{}'", spec.title, dependency));
+                }
+            }
+        }
+
+        if let Some(cycle) = self.find_dependency_cycle(existing_specs, existing_dependencies) {
+            problems.push(format!("Spec dependencies form a cycle: {}", cycle.join(" → ")));
+        }
+
+        problems
+    }
+
+    /// A cycle among the planned and existing spec dependencies, as spec
+    /// titles, if there is one.
+    fn find_dependency_cycle(
+        &self,
+        existing_specs: &[String],
+        existing_dependencies: &[(String, String)],
+    ) -> Option<Vec<String>> {
+        let titles: HashMap<String, &str> = existing_specs
+            .iter()
+            .map(String::as_str)
+            .chain(self.specs.iter().map(|s| s.title.as_str()))
+            .map(|title| (title.to_lowercase(), title))
+            .collect();
+
+        let mut graph = DependencyGraph::new();
+        let planned = self
+            .specs
+            .iter()
+            .flat_map(|s| s.depends_on.iter().map(move |d| (s.title.as_str(), d.as_str())));
+        let existing = existing_dependencies.iter().map(|(s, d)| (s.as_str(), d.as_str()));
+        for (spec, dependency) in existing.chain(planned) {
+            let (spec, dependency) = (spec.to_lowercase(), dependency.to_lowercase());
+            // Self and dangling references are reported on their own
+            if spec == dependency || !titles.contains_key(&dependency) {
+                continue;
+            }
+            let edges = graph.entry(spec).or_default();
+            if !edges.contains(&dependency) {
+                edges.push(dependency);
+            }
+        }
+
+        dependency::find_any_cycle(&graph)
+            .map(|cycle| cycle.iter().map(|key| titles.get(key).map_or(key.clone(), |t| t.to_string())).collect())
+    }
+}
+
+/// The first fenced ```json / ```yaml block in a Markdown document.
+fn fenced_block(content: &str) -> Option<(&'static str, &str)> {
+    for (fence, lang) in [("```json", "json"), ("```yaml", "yaml"), ("```yml", "yaml")] {
+        if let Some(start) = content.find(fence) {
+            let body = &content[start + fence.len()..];
+            let body = body.split_once('\n').map_or("", |(_, rest)| rest);
+            let end = body.find("```").unwrap_or(body.len());
+            return Some((lang, &body[..end]));
+        }
+    }
+    None
+}
+
+/// What applying a plan does to one item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The item does not exist yet and is created.
+    Create,
+    /// The item exists but gains criteria, dependencies or stack entries.
+    Update,
+    /// The item already exists as planned.
+    Unchanged,
+}
+
+/// One item of a plan and what applying it does.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanChange {
+    pub kind: ChangeKind,
+    /// `context`, `object`, `term`, `decision`, `spec` or `tech_stack`.
+    pub entity: &'static str,
+    pub name: String,
+    /// What is added to an existing item.
+    pub detail: Option<String>,
+}
+
+impl PlanChange {
+    pub(crate) fn new(kind: ChangeKind, entity: &'static str, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            entity,
+            name: name.into(),
+            detail: None,
+        }
+    }
+
+    pub(crate) fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
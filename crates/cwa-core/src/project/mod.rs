@@ -7,6 +7,7 @@ use crate::error::{CwaError, CwaResult};
 use cwa_db::DbPool;
 use cwa_db::queries::projects as queries;
 use model::{Project, ProjectInfo};
+use std::path::Path;
 use uuid::Uuid;
 
 /// Create a new project in the database.
@@ -35,6 +36,48 @@ pub async fn list_projects(pool: &DbPool) -> CwaResult<Vec<Project>> {
     Ok(rows.into_iter().map(Project::from_row).collect())
 }
 
+/// Find a project by ID, name (case-insensitive), or unique ID prefix.
+pub async fn find_project(pool: &DbPool, identifier: &str) -> CwaResult<Project> {
+    let projects = list_projects(pool).await?;
+    if let Some(project) = projects
+        .iter()
+        .find(|p| p.id == identifier || p.name.eq_ignore_ascii_case(identifier))
+    {
+        return Ok(project.clone());
+    }
+    let matches: Vec<&Project> = projects.iter().filter(|p| p.id.starts_with(identifier)).collect();
+    match matches.as_slice() {
+        [project] => Ok((*project).clone()),
+        [] => Err(CwaError::ProjectNotFound(identifier.to_string())),
+        _ => Err(CwaError::validation(format!(
+            "Project ID prefix '{}' is ambiguous: {}",
+            identifier,
+            matches.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(", ")
+        ))),
+    }
+}
+
+/// Resolve the project a request targets: the project named by `selector`,
+/// else the project with ID `default_id`, else the default project.
+pub async fn resolve_project(pool: &DbPool, selector: Option<&str>, default_id: Option<&str>) -> CwaResult<Project> {
+    if let Some(selector) = selector.map(str::trim).filter(|s| !s.is_empty()) {
+        return find_project(pool, selector).await;
+    }
+    if let Some(id) = default_id {
+        return get_project(pool, id).await;
+    }
+    get_default_project(pool)
+        .await?
+        .ok_or_else(|| CwaError::ProjectNotFound("no project registered, run 'cwa init' first".to_string()))
+}
+
+/// The project initialized in `dir`, recognized by its constitution path.
+pub async fn find_project_for_dir(pool: &DbPool, dir: &Path) -> CwaResult<Option<Project>> {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let projects = list_projects(pool).await?;
+    Ok(projects.into_iter().find(|p| p.root_dir().is_some_and(|root| root == dir)))
+}
+
 /// Get the project constitution content.
 pub async fn get_constitution(pool: &DbPool, project_id: &str) -> CwaResult<String> {
     let project = queries::get_project(pool, project_id).await?;
@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use cwa_db::queries::projects::ProjectRow;
+use std::path::{Path, PathBuf};
 
 /// A CWA project.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +29,13 @@ impl Project {
             updated_at: row.updated_at,
         }
     }
+
+    /// The project directory, derived from `.cwa/constitution.md`.
+    pub fn root_dir(&self) -> Option<PathBuf> {
+        let constitution = Path::new(self.constitution_path.as_deref()?);
+        let root = constitution.parent()?.parent()?;
+        Some(root.canonicalize().unwrap_or_else(|_| root.to_path_buf()))
+    }
 }
 
 /// Extended project metadata for context management.
@@ -3,10 +3,12 @@
 pub mod model;
 pub mod parser;
 
+use crate::dependency::{self, DependencyGraph};
 use crate::error::{CwaError, CwaResult};
 use cwa_db::DbPool;
 use cwa_db::queries::specs as queries;
 use model::{Spec, SpecStatus, Priority};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Create a new specification.
@@ -44,19 +46,24 @@ pub async fn create_spec_with_criteria(
     Ok(Spec::from_row(row))
 }
 
-/// Get a spec by ID, ID prefix, or title.
+/// Get a spec of a project by ID, ID prefix, or title.
+///
+/// Specs of other projects are reported as not found.
 pub async fn get_spec(pool: &DbPool, project_id: &str, identifier: &str) -> CwaResult<Spec> {
     // Try exact ID first
-    match queries::get_spec(pool, identifier).await {
+    match queries::get_spec_in_project(pool, project_id, identifier).await {
         Ok(row) => return Ok(Spec::from_row(row)),
         Err(cwa_db::DbError::NotFound(_)) => {}
         Err(e) => return Err(e.into()),
     }
     // Try ID prefix match
-    match queries::get_spec_by_id_prefix(pool, identifier).await {
-        Ok(row) => return Ok(Spec::from_row(row)),
-        Err(cwa_db::DbError::NotFound(_)) => {}
-        Err(e) => return Err(e.into()),
+    let prefixed = queries::list_spec_ids_with_prefix(pool, project_id, identifier, 1).await?;
+    if let Some(id) = prefixed.first() {
+        match queries::get_spec_in_project(pool, project_id, id).await {
+            Ok(row) => return Ok(Spec::from_row(row)),
+            Err(cwa_db::DbError::NotFound(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
     }
     // Try by title
     let row = queries::get_spec_by_title(pool, project_id, identifier).await
@@ -70,6 +77,12 @@ pub async fn list_specs(pool: &DbPool, project_id: &str) -> CwaResult<Vec<Spec>>
     Ok(rows.into_iter().map(Spec::from_row).collect())
 }
 
+/// List the IDs of a project's specs that start with `prefix`.
+pub async fn list_ids_with_prefix(pool: &DbPool, project_id: &str, prefix: &str, limit: usize) -> CwaResult<Vec<String>> {
+    let ids = queries::list_spec_ids_with_prefix(pool, project_id, prefix, limit).await?;
+    Ok(ids)
+}
+
 /// Get the active spec for a project.
 pub async fn get_active_spec(pool: &DbPool, project_id: &str) -> CwaResult<Option<Spec>> {
     let row = queries::get_active_spec(pool, project_id).await?;
@@ -77,16 +90,33 @@ pub async fn get_active_spec(pool: &DbPool, project_id: &str) -> CwaResult<Optio
 }
 
 /// Update spec status.
+///
+/// Moving a spec to `active` is refused while any spec it depends on is
+/// still unfinished.
 pub async fn update_status(pool: &DbPool, id: &str, status: &str) -> CwaResult<()> {
     let current = queries::get_spec(pool, id).await?;
     let current_status = SpecStatus::from_str(&current.status);
     let new_status = SpecStatus::from_str(status);
 
-    // Validate transition
-    if !current_status.can_transition_to(&new_status) {
-        return Err(CwaError::InvalidStateTransition {
-            from: current.status,
-            to: status.to_string(),
+    let from = current.status.clone();
+    let spec = Spec::from_row(current);
+    let dependencies = load_dependencies(pool, &spec).await?;
+    let statuses: Vec<SpecStatus> = dependencies.iter().map(|d| d.status).collect();
+    if !current_status.can_transition_to(&new_status, &statuses) {
+        if !current_status.lifecycle_allows(&new_status) {
+            return Err(CwaError::InvalidStateTransition {
+                from,
+                to: status.to_string(),
+            });
+        }
+        let unfinished: Vec<String> = dependencies
+            .iter()
+            .filter(|d| !d.status.is_finished())
+            .map(|d| format!("{} ({})", d.title, d.status.as_str()))
+            .collect();
+        return Err(CwaError::SpecBlocked {
+            spec: spec.title,
+            dependencies: unfinished.join(", "),
         });
     }
 
@@ -94,6 +124,207 @@ pub async fn update_status(pool: &DbPool, id: &str, status: &str) -> CwaResult<(
     Ok(())
 }
 
+/// Load the specs a spec depends on. Dependencies that no longer exist are skipped.
+pub async fn load_dependencies(pool: &DbPool, spec: &Spec) -> CwaResult<Vec<Spec>> {
+    let mut specs = Vec::new();
+    for dep_id in &spec.dependencies {
+        match queries::get_spec_in_project(pool, &spec.project_id, dep_id).await {
+            Ok(row) => specs.push(Spec::from_row(row)),
+            Err(cwa_db::DbError::NotFound(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(specs)
+}
+
+/// Declare that a spec depends on another spec. Rejects dependency cycles.
+pub async fn add_dependency(
+    pool: &DbPool,
+    project_id: &str,
+    identifier: &str,
+    dependency: &str,
+) -> CwaResult<Spec> {
+    let spec = get_spec(pool, project_id, identifier).await?;
+    let dep = get_spec(pool, project_id, dependency).await?;
+
+    let mut dependencies = spec.dependencies.clone();
+    if !dependencies.contains(&dep.id) {
+        dependencies.push(dep.id);
+    }
+    set_dependencies(pool, project_id, &spec.id, &dependencies).await
+}
+
+/// Remove a dependency from a spec.
+pub async fn remove_dependency(
+    pool: &DbPool,
+    project_id: &str,
+    identifier: &str,
+    dependency: &str,
+) -> CwaResult<Spec> {
+    let spec = get_spec(pool, project_id, identifier).await?;
+    let dep = get_spec(pool, project_id, dependency).await?;
+
+    if !spec.dependencies.contains(&dep.id) {
+        return Err(CwaError::validation(format!(
+            "Spec '{}' does not depend on '{}'",
+            spec.title, dep.title
+        )));
+    }
+    let dependencies: Vec<String> = spec.dependencies
+        .into_iter()
+        .filter(|d| *d != dep.id)
+        .collect();
+    set_dependencies(pool, project_id, &spec.id, &dependencies).await
+}
+
+/// Replace the full dependency list of a spec.
+///
+/// Every dependency must be a spec of the same project, and the resulting
+/// dependency graph must stay acyclic.
+pub async fn set_dependencies(
+    pool: &DbPool,
+    project_id: &str,
+    spec_id: &str,
+    dependency_ids: &[String],
+) -> CwaResult<Spec> {
+    let spec = Spec::from_row(queries::get_spec_in_project(pool, project_id, spec_id).await
+        .map_err(|_| CwaError::SpecNotFound(spec_id.to_string()))?);
+    let specs = list_specs(pool, project_id).await?;
+    let titles: HashMap<&str, &str> = specs
+        .iter()
+        .map(|s| (s.id.as_str(), s.title.as_str()))
+        .collect();
+
+    let mut graph: DependencyGraph = specs
+        .iter()
+        .filter(|s| s.id != spec.id)
+        .map(|s| (s.id.clone(), s.dependencies.clone()))
+        .collect();
+
+    let mut dependencies: Vec<String> = Vec::new();
+    for dep_id in dependency_ids {
+        if dependencies.contains(dep_id) {
+            continue;
+        }
+        if !titles.contains_key(dep_id.as_str()) {
+            return Err(CwaError::SpecNotFound(dep_id.clone()));
+        }
+        if let Some(cycle) = dependency::find_cycle(&graph, &spec.id, dep_id) {
+            return Err(CwaError::DependencyCycle(describe_path(&cycle, &titles)));
+        }
+        graph.entry(spec.id.clone()).or_default().push(dep_id.clone());
+        dependencies.push(dep_id.clone());
+    }
+
+    let dependencies_json = if dependencies.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&dependencies)?)
+    };
+    queries::update_spec_dependencies(pool, &spec.id, dependencies_json.as_deref()).await?;
+
+    let row = queries::get_spec_in_project(pool, project_id, &spec.id).await?;
+    Ok(Spec::from_row(row))
+}
+
+/// Validate the whole spec dependency graph of a project.
+///
+/// Fails with [`CwaError::DependencyCycle`] if the stored dependencies
+/// contain a cycle (e.g. after manual edits in Redis).
+pub async fn validate_dependencies(pool: &DbPool, project_id: &str) -> CwaResult<()> {
+    let specs = list_specs(pool, project_id).await?;
+    let titles: HashMap<&str, &str> = specs
+        .iter()
+        .map(|s| (s.id.as_str(), s.title.as_str()))
+        .collect();
+    let graph: DependencyGraph = specs
+        .iter()
+        .map(|s| (s.id.clone(), s.dependencies.clone()))
+        .collect();
+
+    match dependency::find_any_cycle(&graph) {
+        Some(cycle) => Err(CwaError::DependencyCycle(describe_path(&cycle, &titles))),
+        None => Ok(()),
+    }
+}
+
+/// Plan the order of unfinished work.
+///
+/// Draft and active specs are ordered topologically into waves: every spec
+/// in a wave only depends on specs from earlier waves (or on specs outside
+/// the plan), so the specs of one wave can be worked on in parallel.
+pub async fn plan_specs(pool: &DbPool, project_id: &str) -> CwaResult<SpecPlan> {
+    let specs = list_specs(pool, project_id).await?;
+    let titles: HashMap<&str, &str> = specs
+        .iter()
+        .map(|s| (s.id.as_str(), s.title.as_str()))
+        .collect();
+    let statuses: HashMap<&str, SpecStatus> = specs
+        .iter()
+        .map(|s| (s.id.as_str(), s.status))
+        .collect();
+
+    let planned: Vec<&Spec> = specs
+        .iter()
+        .filter(|s| matches!(s.status, SpecStatus::Draft | SpecStatus::Active))
+        .collect();
+    let graph: DependencyGraph = planned
+        .iter()
+        .map(|s| (s.id.clone(), s.dependencies.clone()))
+        .collect();
+
+    let id_waves = dependency::topological_waves(&graph)
+        .map_err(|cycle| CwaError::DependencyCycle(describe_path(&cycle, &titles)))?;
+
+    let by_id: HashMap<&str, &Spec> = planned.iter().map(|s| (s.id.as_str(), *s)).collect();
+    let waves = id_waves
+        .into_iter()
+        .map(|ids| {
+            let mut specs: Vec<PlannedSpec> = ids
+                .iter()
+                .filter_map(|id| by_id.get(id.as_str()))
+                .map(|s| PlannedSpec {
+                    id: s.id.clone(),
+                    title: s.title.clone(),
+                    status: s.status,
+                    priority: s.priority,
+                    depends_on: s.dependencies.clone(),
+                    // Dependencies outside the plan that are not finished yet
+                    waiting_on: s.dependencies
+                        .iter()
+                        .filter(|d| !graph.contains_key(*d))
+                        .filter(|d| statuses.get(d.as_str()).is_some_and(|st| !st.is_finished()))
+                        .cloned()
+                        .collect(),
+                })
+                .collect();
+            specs.sort_by(|a, b| priority_rank(b.priority).cmp(&priority_rank(a.priority))
+                .then_with(|| a.title.cmp(&b.title)));
+            specs
+        })
+        .collect();
+
+    Ok(SpecPlan { waves })
+}
+
+fn priority_rank(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 0,
+        Priority::Medium => 1,
+        Priority::High => 2,
+        Priority::Critical => 3,
+    }
+}
+
+/// Render a cycle of spec IDs using their titles.
+fn describe_path(cycle: &[String], titles: &HashMap<&str, &str>) -> String {
+    cycle
+        .iter()
+        .map(|id| titles.get(id.as_str()).copied().unwrap_or(id.as_str()))
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
 /// Archive a spec.
 pub async fn archive_spec(pool: &DbPool, id: &str) -> CwaResult<()> {
     update_status(pool, id, "archived").await
@@ -159,6 +390,35 @@ pub async fn validate_spec(pool: &DbPool, id: &str) -> CwaResult<ValidationResul
         issues.push("Not associated with a bounded context — link with context_id".to_string());
     }
 
+    let dependencies: Vec<String> = spec
+        .dependencies
+        .as_ref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+
+    if !dependencies.is_empty() {
+        let specs: Vec<Spec> = queries::list_specs(pool, &spec.project_id).await?
+            .into_iter()
+            .map(Spec::from_row)
+            .collect();
+        let titles: HashMap<&str, &str> = specs
+            .iter()
+            .map(|s| (s.id.as_str(), s.title.as_str()))
+            .collect();
+        let graph: DependencyGraph = specs
+            .iter()
+            .map(|s| (s.id.clone(), s.dependencies.clone()))
+            .collect();
+
+        for dep_id in &dependencies {
+            if !titles.contains_key(dep_id.as_str()) {
+                issues.push(format!("Depends on a spec that no longer exists: {}", dep_id));
+            } else if let Some(cycle) = dependency::find_cycle(&graph, &spec.id, dep_id) {
+                issues.push(format!("Dependency cycle: {}", describe_path(&cycle, &titles)));
+            }
+        }
+    }
+
     Ok(ValidationResult {
         spec_id: id.to_string(),
         is_valid: issues.is_empty(),
@@ -188,6 +448,25 @@ pub async fn create_specs_from_prompt(
     Ok(specs)
 }
 
+/// Topologically ordered plan of draft and active specs.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpecPlan {
+    /// Each wave only depends on earlier waves.
+    pub waves: Vec<Vec<PlannedSpec>>,
+}
+
+/// A spec placed in a [`SpecPlan`] wave.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PlannedSpec {
+    pub id: String,
+    pub title: String,
+    pub status: SpecStatus,
+    pub priority: Priority,
+    pub depends_on: Vec<String>,
+    /// Unfinished dependencies that are outside the plan (e.g. in review).
+    pub waiting_on: Vec<String>,
+}
+
 /// Result of spec validation.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ValidationResult {
@@ -99,8 +99,21 @@ impl SpecStatus {
         }
     }
 
-    /// Check if transition to another status is valid.
-    pub fn can_transition_to(&self, to: &Self) -> bool {
+    /// Check if transition to another status is valid, given the statuses of
+    /// the specs this spec depends on. Activation is refused while any
+    /// dependency is unfinished.
+    pub fn can_transition_to(&self, to: &Self, dependencies: &[SpecStatus]) -> bool {
+        if !self.lifecycle_allows(to) {
+            return false;
+        }
+        if *to == Self::Active && *self != Self::Active {
+            return dependencies.iter().all(SpecStatus::is_finished);
+        }
+        true
+    }
+
+    /// Whether the lifecycle allows moving to `to`, ignoring dependencies.
+    pub fn lifecycle_allows(&self, to: &Self) -> bool {
         match (self, to) {
             // Normal lifecycle progression
             (Self::Draft, Self::Active) => true,
@@ -116,6 +129,11 @@ impl SpecStatus {
             _ => false,
         }
     }
+
+    /// Whether the spec no longer holds up the specs that depend on it.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Archived)
+    }
 }
 
 /// Priority level.
@@ -149,3 +167,23 @@ impl Priority {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activation_waits_for_dependencies() {
+        use SpecStatus::*;
+        assert!(Draft.can_transition_to(&Active, &[]));
+        assert!(Draft.can_transition_to(&Active, &[Completed, Archived]));
+        assert!(!Draft.can_transition_to(&Active, &[Completed, InReview]));
+        assert!(!Accepted.can_transition_to(&Active, &[Draft]));
+
+        // Other moves, and staying active, ignore dependencies
+        assert!(Active.can_transition_to(&Active, &[Draft]));
+        assert!(Draft.can_transition_to(&Archived, &[Draft]));
+        assert!(!Draft.can_transition_to(&Completed, &[]));
+        assert!(Draft.lifecycle_allows(&Active));
+    }
+}
@@ -0,0 +1,437 @@
+//! Flow metrics computed from task status history.
+//!
+//! Every status transition is appended to a per-task event stream. From those
+//! events this module derives lead time, cycle time, weekly throughput, time
+//! spent per column and a cumulative flow diagram.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use cwa_db::queries::tasks::TaskEventRow;
+
+use super::model::{Task, TaskStatus};
+use super::workflow::Workflow;
+
+/// A single status transition of a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub task_id: String,
+    /// Previous status; `None` when the task was created.
+    pub from: Option<String>,
+    pub to: String,
+    pub at: DateTime<Utc>,
+}
+
+impl TaskEvent {
+    /// Create an event from a stream entry. Entries with an unparseable
+    /// timestamp are dropped.
+    pub fn from_row(row: TaskEventRow) -> Option<Self> {
+        let at = DateTime::parse_from_rfc3339(&row.at).ok()?.with_timezone(&Utc);
+        Some(Self {
+            task_id: row.task_id,
+            from: row.from,
+            to: row.to,
+            at,
+        })
+    }
+}
+
+/// Reconstruct a best-effort history for a task created before status
+/// transitions were recorded, using its `created_at`/`started_at`/
+/// `completed_at` timestamps.
+pub fn synthesize_events(task: &Task) -> Vec<TaskEvent> {
+    let parse = |s: &str| DateTime::parse_from_rfc3339(s).ok().map(|d| d.with_timezone(&Utc));
+    let Some(created) = parse(&task.created_at) else {
+        return Vec::new();
+    };
+
+    let mut events = vec![TaskEvent {
+        task_id: task.id.clone(),
+        from: None,
+        to: TaskStatus::Backlog.as_str().to_string(),
+        at: created,
+    }];
+    let milestones = [
+        (task.started_at.as_deref().and_then(parse), TaskStatus::InProgress),
+        (task.completed_at.as_deref().and_then(parse), TaskStatus::Done),
+    ];
+    for (at, status) in milestones {
+        if let Some(at) = at {
+            let from = events.last().map(|e| e.to.clone());
+            events.push(TaskEvent {
+                task_id: task.id.clone(),
+                from,
+                to: status.as_str().to_string(),
+                at,
+            });
+        }
+    }
+
+    // A reopened task no longer matches its last milestone
+    let current = task.status.as_str();
+    if events.last().is_some_and(|e| e.to != current) {
+        let from = events.last().map(|e| e.to.clone());
+        let at = parse(&task.updated_at).unwrap_or(created);
+        events.push(TaskEvent {
+            task_id: task.id.clone(),
+            from,
+            to: current.to_string(),
+            at,
+        });
+    }
+    events
+}
+
+/// Summary of a set of durations, in hours.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DurationStats {
+    pub count: usize,
+    pub mean_hours: f64,
+    pub median_hours: f64,
+    pub p85_hours: f64,
+}
+
+impl DurationStats {
+    fn from_hours(mut hours: Vec<f64>) -> Self {
+        if hours.is_empty() {
+            return Self::default();
+        }
+        hours.sort_by(|a, b| a.total_cmp(b));
+        let count = hours.len();
+        // Nearest-rank percentile
+        let percentile = |p: f64| hours[((p * count as f64).ceil() as usize).clamp(1, count) - 1];
+        Self {
+            count,
+            mean_hours: hours.iter().sum::<f64>() / count as f64,
+            median_hours: percentile(0.5),
+            p85_hours: percentile(0.85),
+        }
+    }
+}
+
+/// Tasks completed during one week (weeks start on Monday).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyThroughput {
+    pub week_start: NaiveDate,
+    pub completed: usize,
+}
+
+/// Time tasks spent in one column during the window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTime {
+    pub status: String,
+    pub total_hours: f64,
+    /// Mean over the tasks that were in this column during the window.
+    pub mean_hours: f64,
+    pub tasks: usize,
+}
+
+/// Number of tasks in each column at the end of a day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowPoint {
+    pub date: NaiveDate,
+    /// Counts in the order of [`TaskMetrics::statuses`].
+    pub counts: Vec<usize>,
+}
+
+/// Flow metrics for a project over a trailing window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskMetrics {
+    pub generated_at: DateTime<Utc>,
+    pub window_days: i64,
+    /// Column order used by `time_in_status` and `cumulative_flow`.
+    pub statuses: Vec<String>,
+    /// Creation to completion, for tasks completed in the window.
+    pub lead_time: DurationStats,
+    /// First start to completion, for tasks completed in the window.
+    pub cycle_time: DurationStats,
+    pub throughput: Vec<WeeklyThroughput>,
+    pub time_in_status: Vec<StatusTime>,
+    pub cumulative_flow: Vec<FlowPoint>,
+}
+
+/// Compute flow metrics from per-task histories.
+///
+/// `histories` holds each task's events sorted oldest first. Only activity
+/// within the last `window_days` days before `now` is counted. A task is
+/// completed when it sits in a done column of `workflow` and started the
+/// first time it entered an active column.
+pub fn compute_metrics(
+    histories: &[Vec<TaskEvent>],
+    workflow: &Workflow,
+    now: DateTime<Utc>,
+    window_days: i64,
+) -> TaskMetrics {
+    let window_days = window_days.max(1);
+    let window_start = now - Duration::days(window_days);
+    let statuses = workflow.column_names();
+
+    let mut lead_times = Vec::new();
+    let mut cycle_times = Vec::new();
+    let mut completions = Vec::new();
+
+    for events in histories {
+        let (Some(first), Some(last)) = (events.first(), events.last()) else {
+            continue;
+        };
+        if !workflow.is_done(&last.to) || last.at < window_start || last.at > now {
+            continue;
+        }
+        completions.push(last.at);
+        lead_times.push(hours(last.at - first.at));
+        if let Some(started) = events.iter().find(|e| workflow.is_active(&e.to)) {
+            cycle_times.push(hours(last.at - started.at));
+        }
+    }
+
+    TaskMetrics {
+        generated_at: now,
+        window_days,
+        statuses: statuses.iter().map(|s| s.to_string()).collect(),
+        lead_time: DurationStats::from_hours(lead_times),
+        cycle_time: DurationStats::from_hours(cycle_times),
+        throughput: weekly_throughput(&completions, window_start, now),
+        time_in_status: time_in_status(histories, workflow, window_start, now),
+        cumulative_flow: cumulative_flow(histories, &statuses, window_start, now),
+    }
+}
+
+fn hours(d: Duration) -> f64 {
+    d.num_seconds().max(0) as f64 / 3600.0
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn weekly_throughput(
+    completions: &[DateTime<Utc>],
+    window_start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<WeeklyThroughput> {
+    let mut weeks = Vec::new();
+    let mut week = week_start(window_start.date_naive());
+    let last = week_start(now.date_naive());
+    while week <= last {
+        let completed = completions
+            .iter()
+            .filter(|at| week_start(at.date_naive()) == week)
+            .count();
+        weeks.push(WeeklyThroughput { week_start: week, completed });
+        week += Duration::days(7);
+    }
+    weeks
+}
+
+fn time_in_status(
+    histories: &[Vec<TaskEvent>],
+    workflow: &Workflow,
+    window_start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<StatusTime> {
+    workflow
+        .column_names()
+        .into_iter()
+        .filter(|s| !workflow.is_done(s))
+        .map(|status| {
+            let mut total = 0.0;
+            let mut tasks = 0;
+            for events in histories {
+                let mut task_hours = 0.0;
+                let mut visited = false;
+                for (i, event) in events.iter().enumerate() {
+                    if event.to != status {
+                        continue;
+                    }
+                    let end = events.get(i + 1).map_or(now, |next| next.at).min(now);
+                    let start = event.at.max(window_start);
+                    if end > start {
+                        task_hours += hours(end - start);
+                        visited = true;
+                    }
+                }
+                if visited {
+                    total += task_hours;
+                    tasks += 1;
+                }
+            }
+            StatusTime {
+                status: status.to_string(),
+                total_hours: total,
+                mean_hours: if tasks > 0 { total / tasks as f64 } else { 0.0 },
+                tasks,
+            }
+        })
+        .collect()
+}
+
+fn cumulative_flow(
+    histories: &[Vec<TaskEvent>],
+    statuses: &[&str],
+    window_start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<FlowPoint> {
+    let mut points = Vec::new();
+    let mut date = window_start.date_naive();
+    let today = now.date_naive();
+
+    while date <= today {
+        let end_of_day = date
+            .succ_opt()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc())
+            .unwrap_or(now)
+            .min(now);
+
+        let mut counts = vec![0; statuses.len()];
+        for events in histories {
+            let current = events.iter().take_while(|e| e.at < end_of_day).last();
+            if let Some(pos) = current.and_then(|e| statuses.iter().position(|s| *s == e.to)) {
+                counts[pos] += 1;
+            }
+        }
+        points.push(FlowPoint { date, counts });
+        date += Duration::days(1);
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    fn history(task: &str, steps: &[(&str, DateTime<Utc>)]) -> Vec<TaskEvent> {
+        let mut from = None;
+        steps
+            .iter()
+            .map(|(to, at)| {
+                let event = TaskEvent {
+                    task_id: task.to_string(),
+                    from: from.clone(),
+                    to: to.to_string(),
+                    at: *at,
+                };
+                from = Some(to.to_string());
+                event
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lead_and_cycle_time_for_completed_tasks() {
+        let histories = vec![
+            history("a", &[("backlog", at(2, 0)), ("in_progress", at(3, 0)), ("done", at(3, 12))]),
+            history("b", &[("backlog", at(2, 0)), ("todo", at(4, 0)), ("in_progress", at(5, 0)), ("done", at(6, 0))]),
+            history("c", &[("backlog", at(2, 0)), ("in_progress", at(5, 0))]),
+        ];
+        let metrics = compute_metrics(&histories, &Workflow::default(), at(9, 0), 14);
+
+        assert_eq!(metrics.lead_time.count, 2);
+        assert_eq!(metrics.lead_time.mean_hours, (36.0 + 96.0) / 2.0);
+        assert_eq!(metrics.cycle_time.median_hours, 12.0);
+        assert_eq!(metrics.cycle_time.p85_hours, 24.0);
+    }
+
+    #[test]
+    fn throughput_counts_completions_per_week() {
+        // 2026-03-02 is a Monday
+        let histories = vec![
+            history("a", &[("backlog", at(1, 0)), ("done", at(3, 0))]),
+            history("b", &[("backlog", at(1, 0)), ("done", at(4, 0))]),
+            history("c", &[("backlog", at(1, 0)), ("done", at(10, 0))]),
+        ];
+        let metrics = compute_metrics(&histories, &Workflow::default(), at(11, 0), 10);
+        let weeks: Vec<(u32, usize)> = metrics
+            .throughput
+            .iter()
+            .map(|w| (w.week_start.day(), w.completed))
+            .collect();
+
+        assert_eq!(weeks, vec![(23, 0), (2, 2), (9, 1)]);
+    }
+
+    #[test]
+    fn time_in_status_is_clipped_to_window() {
+        let histories = vec![history(
+            "a",
+            &[("backlog", at(1, 0)), ("in_progress", at(8, 0)), ("review", at(9, 0))],
+        )];
+        let metrics = compute_metrics(&histories, &Workflow::default(), at(10, 0), 3);
+        let by_status = |s: &str| metrics.time_in_status.iter().find(|t| t.status == s).unwrap();
+
+        // Window starts on the 7th: one day of backlog is counted
+        assert_eq!(by_status("backlog").total_hours, 24.0);
+        assert_eq!(by_status("in_progress").total_hours, 24.0);
+        assert_eq!(by_status("review").total_hours, 24.0);
+        assert_eq!(by_status("todo").tasks, 0);
+        assert!(metrics.time_in_status.iter().all(|t| t.status != "done"));
+    }
+
+    #[test]
+    fn cumulative_flow_tracks_end_of_day_status() {
+        let histories = vec![
+            history("a", &[("backlog", at(1, 10)), ("in_progress", at(2, 10)), ("done", at(3, 10))]),
+            history("b", &[("backlog", at(2, 10))]),
+        ];
+        let metrics = compute_metrics(&histories, &Workflow::default(), at(3, 12), 2);
+        let counts: Vec<Vec<usize>> = metrics.cumulative_flow.iter().map(|p| p.counts.clone()).collect();
+
+        assert_eq!(counts, vec![
+            vec![1, 0, 0, 0, 0],
+            vec![1, 0, 1, 0, 0],
+            vec![1, 0, 0, 0, 1],
+        ]);
+    }
+
+    #[test]
+    fn custom_workflow_categories_drive_completion() {
+        let workflow = Workflow::from_toml(
+            "[[columns]]\nname = \"ready\"\n\n[[columns]]\nname = \"doing\"\ncategory = \"active\"\n\n[[columns]]\nname = \"shipped\"\ncategory = \"done\"",
+        )
+        .unwrap();
+        let histories = vec![
+            history("a", &[("ready", at(2, 0)), ("doing", at(3, 0)), ("shipped", at(4, 0))]),
+            history("b", &[("ready", at(2, 0)), ("done", at(4, 0))]),
+        ];
+        let metrics = compute_metrics(&histories, &workflow, at(5, 0), 7);
+
+        assert_eq!(metrics.statuses, vec!["ready", "doing", "shipped"]);
+        assert_eq!(metrics.lead_time.count, 1);
+        assert_eq!(metrics.cycle_time.mean_hours, 24.0);
+        assert!(metrics.time_in_status.iter().all(|t| t.status != "shipped"));
+    }
+
+    #[test]
+    fn synthesized_history_follows_timestamps() {
+        let task = Task {
+            id: "t".to_string(),
+            project_id: "p".to_string(),
+            spec_id: None,
+            title: "Old task".to_string(),
+            description: None,
+            status: "done".to_string(),
+            priority: "medium".to_string(),
+            assignee: None,
+            labels: Vec::new(),
+            estimated_effort: None,
+            actual_effort: None,
+            blocked_by: Vec::new(),
+            due_date: None,
+            created_at: at(1, 0).to_rfc3339(),
+            updated_at: at(3, 0).to_rfc3339(),
+            started_at: Some(at(2, 0).to_rfc3339()),
+            completed_at: Some(at(3, 0).to_rfc3339()),
+        };
+        let events = synthesize_events(&task);
+        let steps: Vec<&str> = events.iter().map(|e| e.to.as_str()).collect();
+        assert_eq!(steps, vec!["backlog", "in_progress", "done"]);
+    }
+}
@@ -1,23 +1,62 @@
 //! Task management (Kanban).
 
+pub mod metrics;
 pub mod model;
+pub mod workflow;
 
+use crate::dependency::{self, DependencyGraph};
 use crate::error::{CwaError, CwaResult};
 use cwa_db::DbPool;
 use cwa_db::queries::tasks as queries;
-use model::{Task, TaskStatus, Board, BoardColumn, WipStatus};
+use metrics::{TaskEvent, TaskMetrics};
+use model::{Task, TaskFilter, TaskStatus, TaskUpdate, Board, BoardColumn, WipStatus};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
+use workflow::{Policy, PolicyContext, Workflow};
 
-/// Default Kanban columns.
-/// WIP limits: only in_progress is limited (solo dev mode).
-/// Use `cwa task wip-set` to adjust limits as needed.
-const DEFAULT_COLUMNS: &[(&str, Option<i64>)] = &[
-    ("backlog", None),
-    ("todo", None),           // No limit - queue of ready tasks
-    ("in_progress", Some(1)), // WIP limit of 1 for focused work
-    ("review", Some(3)),      // Allow a few items in review
-    ("done", None),
-];
+/// Get the project's workflow.
+///
+/// Projects that never stored one get the default workflow, with any WIP
+/// limits set before workflows existed carried over.
+pub async fn get_workflow(pool: &DbPool, project_id: &str) -> CwaResult<Workflow> {
+    if let Some(json) = queries::get_workflow(pool, project_id).await? {
+        return Ok(serde_json::from_str(&json)?);
+    }
+
+    let mut workflow = Workflow::default();
+    for (column, limit) in queries::get_all_wip_limits(pool, project_id).await? {
+        if let Some(col) = workflow.columns.iter_mut().find(|c| c.name == column) {
+            col.wip_limit = (limit > 0).then_some(limit);
+        }
+    }
+    Ok(workflow)
+}
+
+/// Replace the project's workflow.
+///
+/// Rejects invalid workflows and workflows that drop a column still holding
+/// tasks; move those tasks first.
+pub async fn set_workflow(pool: &DbPool, project_id: &str, workflow: &Workflow) -> CwaResult<()> {
+    workflow.validate().map_err(CwaError::ValidationError)?;
+
+    let tasks = list_tasks(pool, project_id).await?;
+    let mut orphaned: Vec<&str> = tasks
+        .iter()
+        .map(|t| t.status.as_str())
+        .filter(|s| workflow.column(s).is_none())
+        .collect();
+    orphaned.sort_unstable();
+    orphaned.dedup();
+    if !orphaned.is_empty() {
+        return Err(CwaError::validation(format!(
+            "Tasks are still in column(s) missing from the new workflow: {}. Move them first.",
+            orphaned.join(", ")
+        )));
+    }
+
+    queries::set_workflow(pool, project_id, &serde_json::to_string(workflow)?).await?;
+    Ok(())
+}
 
 /// Create a new task.
 pub async fn create_task(
@@ -29,23 +68,47 @@ pub async fn create_task(
     priority: &str,
 ) -> CwaResult<Task> {
     let id = Uuid::new_v4().to_string();
-
-    queries::create_task(pool, &id, project_id, title, description, spec_id, priority).await?;
-
-    let row = queries::get_task(pool, &id).await?;
-    Ok(Task::from_row(row))
+    let workflow = get_workflow(pool, project_id).await?;
+    let spec_id = match spec_id {
+        Some(identifier) => Some(crate::spec::get_spec(pool, project_id, identifier).await?.id),
+        None => None,
+    };
+
+    queries::create_task(
+        pool,
+        &id,
+        project_id,
+        title,
+        description,
+        spec_id.as_deref(),
+        priority,
+        workflow.initial_column(),
+    ).await?;
+
+    get_task(pool, project_id, &id).await
 }
 
-/// Get a task by ID.
-pub async fn get_task(pool: &DbPool, id: &str) -> CwaResult<Task> {
-    let row = queries::get_task(pool, id).await?;
-    Ok(Task::from_row(row))
+/// Get a task of a project by ID.
+///
+/// Tasks of other projects are reported as not found.
+pub async fn get_task(pool: &DbPool, project_id: &str, task_id: &str) -> CwaResult<Task> {
+    Ok(Task::from_row(get_task_row(pool, project_id, task_id).await?))
 }
 
 /// Get the current in-progress task.
+///
+/// Looks in the `in_progress` column, or in the first active column of a
+/// custom workflow that has none.
 pub async fn get_current_task(pool: &DbPool, project_id: &str) -> CwaResult<Option<Task>> {
-    let row = queries::get_current_task(pool, project_id).await?;
-    Ok(row.map(Task::from_row))
+    let workflow = get_workflow(pool, project_id).await?;
+    let column = workflow
+        .column(TaskStatus::InProgress.as_str())
+        .or_else(|| workflow.columns.iter().find(|c| workflow.is_active(&c.name)));
+    let Some(column) = column else {
+        return Ok(None);
+    };
+    let rows = queries::list_tasks_by_status(pool, project_id, &column.name).await?;
+    Ok(rows.into_iter().next().map(Task::from_row))
 }
 
 /// List all tasks for a project.
@@ -54,82 +117,463 @@ pub async fn list_tasks(pool: &DbPool, project_id: &str) -> CwaResult<Vec<Task>>
     Ok(rows.into_iter().map(Task::from_row).collect())
 }
 
-/// List tasks linked to a specific spec.
-pub async fn list_tasks_by_spec(pool: &DbPool, spec_id: &str) -> CwaResult<Vec<Task>> {
-    let rows = queries::list_tasks_by_spec(pool, spec_id).await?;
+/// List the IDs of a project's tasks that start with `prefix`.
+pub async fn list_ids_with_prefix(pool: &DbPool, project_id: &str, prefix: &str, limit: usize) -> CwaResult<Vec<String>> {
+    let ids = queries::list_task_ids_with_prefix(pool, project_id, prefix, limit).await?;
+    Ok(ids)
+}
+
+/// List the tasks of a project that match a filter.
+pub async fn list_tasks_filtered(pool: &DbPool, project_id: &str, filter: &TaskFilter) -> CwaResult<Vec<Task>> {
+    let tasks = list_tasks(pool, project_id).await?;
+    Ok(tasks.into_iter().filter(|t| filter.matches(t)).collect())
+}
+
+/// List a project's tasks linked to a specific spec.
+pub async fn list_tasks_by_spec(pool: &DbPool, project_id: &str, spec_id: &str) -> CwaResult<Vec<Task>> {
+    let rows = queries::list_tasks_by_spec_in_project(pool, project_id, spec_id).await?;
     Ok(rows.into_iter().map(Task::from_row).collect())
 }
 
 /// Move a task to a new status.
+///
+/// The move must be allowed by the project's workflow, satisfy the exit
+/// policies of the current column and the entry policies of the target
+/// column, and respect the target's WIP limit.
 pub async fn move_task(pool: &DbPool, project_id: &str, task_id: &str, new_status: &str) -> CwaResult<()> {
-    let task = queries::get_task(pool, task_id).await?;
-    let current_status = TaskStatus::from_str(&task.status);
-    let target_status = TaskStatus::from_str(new_status);
+    let workflow = get_workflow(pool, project_id).await?;
+    let task = get_task(pool, project_id, task_id).await?;
+
+    check_move(pool, project_id, &workflow, &task.status, new_status).await?;
+    check_policies(pool, project_id, &workflow, &task, new_status).await?;
+
+    queries::update_task_status(
+        pool,
+        project_id,
+        task_id,
+        new_status,
+        workflow.is_active(new_status),
+        workflow.is_done(new_status),
+    ).await?;
+    Ok(())
+}
 
-    // Validate transition
-    if !current_status.can_transition_to(&target_status) {
+/// Check that the workflow has column `to`, allows moving there from
+/// `from`, and that `to` has room under its WIP limit.
+///
+/// Policies depend on the task itself and are checked separately.
+pub(crate) async fn check_move(
+    pool: &DbPool,
+    project_id: &str,
+    workflow: &Workflow,
+    from: &str,
+    to: &str,
+) -> CwaResult<()> {
+    let Some(column) = workflow.column(to) else {
+        return Err(CwaError::validation(format!(
+            "Unknown column '{}'. Valid columns: {}",
+            to,
+            workflow.column_names().join(", ")
+        )));
+    };
+    if workflow.check_transition(from, to).is_err() {
         return Err(CwaError::InvalidStateTransition {
-            from: task.status,
-            to: new_status.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
         });
     }
 
-    // Check WIP limit
-    if let Some(limit) = queries::get_wip_limit(pool, project_id, new_status).await? {
-        let current_count = queries::count_tasks_by_status(pool, project_id, new_status).await?;
-        if current_count >= limit {
-            return Err(CwaError::WipLimitExceeded {
-                column: new_status.to_string(),
-                limit,
-                current: current_count,
-            });
+    if from != to {
+        if let Some(limit) = column.wip_limit {
+            let current_count = queries::count_tasks_by_status(pool, project_id, to).await?;
+            if current_count >= limit {
+                return Err(CwaError::WipLimitExceeded {
+                    column: to.to_string(),
+                    limit,
+                    current: current_count,
+                });
+            }
         }
     }
-
-    queries::update_task_status(pool, task_id, new_status).await?;
     Ok(())
 }
 
+/// Evaluate the workflow policies that apply to moving `task` to `to`.
+async fn check_policies(
+    pool: &DbPool,
+    project_id: &str,
+    workflow: &Workflow,
+    task: &Task,
+    to: &str,
+) -> CwaResult<()> {
+    let policies = workflow.policies_for(&task.status, to);
+    if policies.is_empty() {
+        return Ok(());
+    }
+
+    let mut ctx = PolicyContext::default();
+    if policies.iter().any(Policy::needs_spec) {
+        if let Some(ref spec_id) = task.spec_id {
+            // A deleted spec fails the policy rather than the whole move
+            match crate::spec::get_spec(pool, project_id, spec_id).await {
+                Ok(spec) => ctx.spec_criteria = Some(spec.acceptance_criteria),
+                Err(CwaError::SpecNotFound(_)) => ctx.spec_missing = true,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    let blockers = if policies.contains(&Policy::NoUnfinishedBlockers) {
+        unfinished_blockers(pool, workflow, task).await?
+    } else {
+        Vec::new()
+    };
+    ctx.unfinished_blockers = blockers
+        .iter()
+        .map(|b| describe_tasks(std::slice::from_ref(b)))
+        .collect();
+
+    let failed: Vec<(Policy, String)> = policies
+        .iter()
+        .filter_map(|p| p.check(task, &ctx).map(|reason| (*p, reason)))
+        .collect();
+
+    match failed.as_slice() {
+        [] => Ok(()),
+        // Keep the dedicated error for the most common policy
+        [(Policy::NoUnfinishedBlockers, _)] => Err(CwaError::TaskBlocked {
+            task: task.title.clone(),
+            blockers: describe_tasks(&blockers),
+        }),
+        _ => Err(CwaError::PolicyViolation {
+            task: task.title.clone(),
+            column: to.to_string(),
+            reasons: failed.into_iter().map(|(_, r)| r).collect::<Vec<_>>().join("; "),
+        }),
+    }
+}
+
+/// Get the status transitions of a task, oldest first.
+///
+/// Tasks created before transitions were recorded get a history
+/// reconstructed from their timestamps.
+pub async fn get_task_history(pool: &DbPool, project_id: &str, task_id: &str) -> CwaResult<Vec<TaskEvent>> {
+    let task = get_task(pool, project_id, task_id).await?;
+    let events: Vec<TaskEvent> = queries::list_task_events(pool, project_id, &task.id).await?
+        .into_iter()
+        .filter_map(TaskEvent::from_row)
+        .collect();
+    if events.is_empty() {
+        return Ok(metrics::synthesize_events(&task));
+    }
+    Ok(events)
+}
+
+/// Compute flow metrics (lead/cycle time, throughput, time per column and
+/// cumulative flow) over the last `window_days` days.
+pub async fn get_task_metrics(pool: &DbPool, project_id: &str, window_days: i64) -> CwaResult<TaskMetrics> {
+    let tasks: HashMap<String, Task> = list_tasks(pool, project_id).await?
+        .into_iter()
+        .map(|t| (t.id.clone(), t))
+        .collect();
+
+    let histories: Vec<Vec<TaskEvent>> = queries::list_project_task_events(pool, project_id).await?
+        .into_iter()
+        .filter_map(|(task_id, rows)| {
+            let task = tasks.get(&task_id)?;
+            let events: Vec<TaskEvent> = rows.into_iter().filter_map(TaskEvent::from_row).collect();
+            Some(if events.is_empty() { metrics::synthesize_events(task) } else { events })
+        })
+        .collect();
+
+    let workflow = get_workflow(pool, project_id).await?;
+    Ok(metrics::compute_metrics(&histories, &workflow, chrono::Utc::now(), window_days))
+}
+
 /// Get the Kanban board for a project.
+///
+/// Within each column, tasks with unfinished blockers are listed after the
+/// tasks that are ready to be worked on.
 pub async fn get_board(pool: &DbPool, project_id: &str) -> CwaResult<Board> {
-    let tasks = queries::list_tasks(pool, project_id).await?;
+    let workflow = get_workflow(pool, project_id).await?;
+    let tasks: Vec<Task> = queries::list_tasks(pool, project_id).await?
+        .into_iter()
+        .map(Task::from_row)
+        .collect();
+    let blocked = blocked_task_ids(&tasks, &workflow);
+
+    let columns = workflow
+        .columns
+        .iter()
+        .map(|column| {
+            let mut column_tasks: Vec<Task> = tasks
+                .iter()
+                .filter(|t| t.status == column.name)
+                .cloned()
+                .collect();
+            column_tasks.sort_by_key(|t| blocked.contains(&t.id));
+
+            BoardColumn {
+                name: column.name.clone(),
+                label: column.label.clone(),
+                category: column.category,
+                wip_limit: column.wip_limit,
+                tasks: column_tasks,
+            }
+        })
+        .collect();
+
+    let mut blocked_task_ids: Vec<String> = blocked.into_iter().collect();
+    blocked_task_ids.sort();
+
+    Ok(Board { columns, blocked_task_ids })
+}
 
-    let mut columns = Vec::new();
-    for (name, default_limit) in DEFAULT_COLUMNS {
-        let wip_limit = queries::get_wip_limit(pool, project_id, name).await?
-            .or(*default_limit);
-
-        let column_tasks: Vec<Task> = tasks
-            .iter()
-            .filter(|t| t.status == *name)
-            .cloned()
-            .map(Task::from_row)
-            .collect();
-
-        columns.push(BoardColumn {
-            name: name.to_string(),
-            wip_limit,
-            tasks: column_tasks,
-        });
+/// IDs of tasks that still have at least one unfinished blocker.
+///
+/// Blockers that no longer exist are ignored.
+pub fn blocked_task_ids(tasks: &[Task], workflow: &Workflow) -> HashSet<String> {
+    let statuses: HashMap<&str, &str> = tasks
+        .iter()
+        .map(|t| (t.id.as_str(), t.status.as_str()))
+        .collect();
+
+    tasks
+        .iter()
+        .filter(|t| {
+            t.blocked_by.iter().any(|b| {
+                statuses.get(b.as_str()).is_some_and(|s| !workflow.is_done(s))
+            })
+        })
+        .map(|t| t.id.clone())
+        .collect()
+}
+
+/// List the blockers of a task that are not in a done column yet.
+pub async fn unfinished_blockers(pool: &DbPool, workflow: &Workflow, task: &Task) -> CwaResult<Vec<Task>> {
+    let mut blockers = Vec::new();
+    for blocker_id in &task.blocked_by {
+        match queries::get_task_in_project(pool, &task.project_id, blocker_id).await {
+            Ok(row) if !workflow.is_done(&row.status) => {
+                blockers.push(Task::from_row(row));
+            }
+            Ok(_) | Err(cwa_db::DbError::NotFound(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(blockers)
+}
+
+/// Add a blocker to a task. Rejects blockers that would create a cycle.
+pub async fn add_blocker(
+    pool: &DbPool,
+    project_id: &str,
+    task_id: &str,
+    blocker_id: &str,
+) -> CwaResult<Task> {
+    let task = get_task(pool, project_id, task_id).await?;
+    let mut blocked_by = task.blocked_by.clone();
+    if !blocked_by.iter().any(|b| b == blocker_id) {
+        blocked_by.push(blocker_id.to_string());
+    }
+    set_blockers(pool, project_id, task_id, &blocked_by).await
+}
+
+/// Remove a blocker from a task.
+pub async fn remove_blocker(
+    pool: &DbPool,
+    project_id: &str,
+    task_id: &str,
+    blocker_id: &str,
+) -> CwaResult<Task> {
+    let task = get_task(pool, project_id, task_id).await?;
+    if !task.blocked_by.iter().any(|b| b == blocker_id) {
+        return Err(CwaError::validation(format!(
+            "Task '{}' is not blocked by '{}'",
+            task.title, blocker_id
+        )));
+    }
+    let blocked_by: Vec<String> = task.blocked_by
+        .into_iter()
+        .filter(|b| b != blocker_id)
+        .collect();
+    set_blockers(pool, project_id, task_id, &blocked_by).await
+}
+
+/// Replace the full list of blockers for a task.
+///
+/// Every blocker must be an existing task of the same project, and the
+/// resulting dependency graph must stay acyclic.
+pub async fn set_blockers(
+    pool: &DbPool,
+    project_id: &str,
+    task_id: &str,
+    blocker_ids: &[String],
+) -> CwaResult<Task> {
+    let task = get_task(pool, project_id, task_id).await?;
+    let tasks: Vec<Task> = queries::list_tasks(pool, project_id).await?
+        .into_iter()
+        .map(Task::from_row)
+        .collect();
+    let titles: HashMap<&str, &str> = tasks
+        .iter()
+        .map(|t| (t.id.as_str(), t.title.as_str()))
+        .collect();
+
+    let mut graph: DependencyGraph = tasks
+        .iter()
+        .filter(|t| t.id != task.id)
+        .map(|t| (t.id.clone(), t.blocked_by.clone()))
+        .collect();
+
+    let mut blocked_by: Vec<String> = Vec::new();
+    for blocker_id in blocker_ids {
+        if blocked_by.contains(blocker_id) {
+            continue;
+        }
+        if !titles.contains_key(blocker_id.as_str()) {
+            return Err(CwaError::TaskNotFound(blocker_id.clone()));
+        }
+        if let Some(cycle) = dependency::find_cycle(&graph, &task.id, blocker_id) {
+            let path: Vec<&str> = cycle
+                .iter()
+                .map(|id| titles.get(id.as_str()).copied().unwrap_or(id.as_str()))
+                .collect();
+            return Err(CwaError::DependencyCycle(path.join(" → ")));
+        }
+        graph.entry(task.id.clone()).or_default().push(blocker_id.clone());
+        blocked_by.push(blocker_id.clone());
+    }
+
+    let blocked_by_json = if blocked_by.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&blocked_by)?)
+    };
+    queries::update_task_blocked_by(pool, project_id, &task.id, blocked_by_json.as_deref()).await?;
+
+    get_task(pool, project_id, &task.id).await
+}
+
+/// Valid task priorities.
+pub(crate) const PRIORITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+/// Edit a task's fields.
+///
+/// Status changes are not accepted here; they go through [`move_task`] so
+/// that transition rules, blockers and WIP limits are enforced.
+pub async fn update_task(
+    pool: &DbPool,
+    project_id: &str,
+    task_id: &str,
+    update: &TaskUpdate,
+) -> CwaResult<Task> {
+    if update.is_empty() {
+        return Err(CwaError::validation("No task fields to update"));
     }
+    if update.title.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        return Err(CwaError::validation("Task title cannot be empty"));
+    }
+    if let Some(ref due) = update.due_date {
+        let due = due.trim();
+        if !due.is_empty() && chrono::NaiveDate::parse_from_str(due, "%Y-%m-%d").is_err() {
+            return Err(CwaError::validation(format!(
+                "Invalid due date '{}'. Use YYYY-MM-DD.",
+                due
+            )));
+        }
+    }
+    if let Some(ref priority) = update.priority {
+        if !PRIORITIES.contains(&priority.as_str()) {
+            return Err(CwaError::validation(format!(
+                "Invalid priority '{}'. Valid priorities: {}",
+                priority,
+                PRIORITIES.join(", ")
+            )));
+        }
+    }
+
+    let mut row = get_task_row(pool, project_id, task_id).await?;
+    let current_labels = Task::from_row(row.clone()).labels;
+
+    if let Some(ref title) = update.title {
+        row.title = title.trim().to_string();
+    }
+    if let Some(ref description) = update.description {
+        row.description = non_empty(description);
+    }
+    if let Some(ref priority) = update.priority {
+        row.priority = priority.clone();
+    }
+    if let Some(ref assignee) = update.assignee {
+        row.assignee = non_empty(assignee);
+    }
+    if update.labels.is_some() || !update.add_labels.is_empty() || !update.remove_labels.is_empty() {
+        let labels = update.apply_labels(&current_labels);
+        row.labels = if labels.is_empty() { None } else { Some(serde_json::to_string(&labels)?) };
+    }
+    if let Some(ref effort) = update.estimated_effort {
+        row.estimated_effort = non_empty(effort);
+    }
+    if let Some(ref effort) = update.actual_effort {
+        row.actual_effort = non_empty(effort);
+    }
+    if let Some(ref due) = update.due_date {
+        row.due_date = non_empty(due);
+    }
+    row.updated_at = chrono::Utc::now().to_rfc3339();
+    queries::update_task(pool, &row).await?;
+
+    match update.blocked_by {
+        Some(ref blockers) => set_blockers(pool, project_id, task_id, blockers).await,
+        None => get_task(pool, project_id, task_id).await,
+    }
+}
 
-    Ok(Board { columns })
+/// Delete a single task. Tasks it was blocking are unblocked.
+///
+/// Returns the deleted task.
+pub async fn delete_task(pool: &DbPool, project_id: &str, task_id: &str) -> CwaResult<Task> {
+    let task = get_task(pool, project_id, task_id).await?;
+    queries::delete_task(pool, project_id, &task.id).await?;
+    Ok(task)
+}
+
+/// Trim a user-supplied value, treating an empty string as "unset".
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+async fn get_task_row(pool: &DbPool, project_id: &str, task_id: &str) -> CwaResult<queries::TaskRow> {
+    queries::get_task_in_project(pool, project_id, task_id).await
+        .map_err(|e| match e {
+            cwa_db::DbError::NotFound(_) => CwaError::TaskNotFound(task_id.to_string()),
+            e => e.into(),
+        })
+}
+
+/// Format tasks as `title (short-id)` for error messages.
+fn describe_tasks(tasks: &[Task]) -> String {
+    tasks
+        .iter()
+        .map(|t| format!("{} ({})", t.title, &t.id[..8.min(t.id.len())]))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Get WIP status for a project.
 pub async fn get_wip_status(pool: &DbPool, project_id: &str) -> CwaResult<WipStatus> {
+    let workflow = get_workflow(pool, project_id).await?;
     let mut columns = Vec::new();
 
-    for (name, default_limit) in DEFAULT_COLUMNS {
-        let wip_limit = queries::get_wip_limit(pool, project_id, name).await?
-            .or(*default_limit);
-        let current_count = queries::count_tasks_by_status(pool, project_id, name).await?;
+    for column in &workflow.columns {
+        let current_count = queries::count_tasks_by_status(pool, project_id, &column.name).await?;
 
         columns.push(model::ColumnWipStatus {
-            name: name.to_string(),
-            limit: wip_limit,
+            name: column.name.clone(),
+            limit: column.wip_limit,
             current: current_count,
-            is_exceeded: wip_limit.map_or(false, |l| current_count > l),
+            is_exceeded: column.wip_limit.map_or(false, |l| current_count > l),
         });
     }
 
@@ -146,12 +590,13 @@ pub struct GenerateResult {
 /// Generate tasks from a spec's acceptance criteria.
 ///
 /// Creates one task per acceptance criterion, skipping criteria that already
-/// have a matching task (by title comparison).
+/// have a matching task (by title comparison). Tasks start in the workflow's
+/// first column unless `initial_status` names another one.
 pub async fn generate_tasks_from_spec(
     pool: &DbPool,
     project_id: &str,
     spec_id: &str,
-    initial_status: &str,
+    initial_status: Option<&str>,
 ) -> CwaResult<GenerateResult> {
     // Fetch the spec
     let spec = crate::spec::get_spec(pool, project_id, spec_id).await?;
@@ -163,7 +608,7 @@ pub async fn generate_tasks_from_spec(
     }
 
     // Fetch existing tasks for this spec to avoid duplicates
-    let existing_tasks = queries::list_tasks_by_spec(pool, &spec.id).await?;
+    let existing_tasks = queries::list_tasks_by_spec_in_project(pool, project_id, &spec.id).await?;
     let existing_titles: Vec<String> = existing_tasks.iter().map(|t| t.title.clone()).collect();
 
     let priority = spec.priority.as_str();
@@ -185,16 +630,14 @@ pub async fn generate_tasks_from_spec(
             priority,
         ).await?;
 
-        // Move to initial status if not "backlog"
-        if initial_status != "backlog" {
-            move_task(pool, project_id, &task.id, initial_status).await?;
+        // Move to the requested column if it isn't the initial one
+        match initial_status {
+            Some(status) if status != task.status => {
+                move_task(pool, project_id, &task.id, status).await?;
+                created.push(get_task(pool, project_id, &task.id).await?);
+            }
+            _ => created.push(task),
         }
-
-        created.push(if initial_status != "backlog" {
-            get_task(pool, &task.id).await?
-        } else {
-            task
-        });
     }
 
     Ok(GenerateResult { created, skipped })
@@ -213,32 +656,57 @@ pub async fn clear_all_tasks(pool: &DbPool, project_id: &str) -> CwaResult<usize
     Ok(count)
 }
 
-/// Initialize default Kanban columns for a project.
+/// Initialize the Kanban workflow for a project.
+///
+/// Stores the default workflow unless the project already has one.
 pub async fn init_kanban_columns(pool: &DbPool, project_id: &str) -> CwaResult<()> {
-    for (i, (name, limit)) in DEFAULT_COLUMNS.iter().enumerate() {
-        queries::set_wip_limit(pool, project_id, name, *limit, i as i32).await?;
+    if queries::get_workflow(pool, project_id).await?.is_none() {
+        let workflow = get_workflow(pool, project_id).await?;
+        set_workflow(pool, project_id, &workflow).await?;
     }
     Ok(())
 }
 
 /// Set WIP limit for a column. Use None to remove limit.
 pub async fn set_wip_limit(pool: &DbPool, project_id: &str, column: &str, limit: Option<i64>) -> CwaResult<()> {
-    // Validate column name
-    let valid_columns: Vec<&str> = DEFAULT_COLUMNS.iter().map(|(name, _)| *name).collect();
-    if !valid_columns.contains(&column) {
+    let mut workflow = get_workflow(pool, project_id).await?;
+    let valid_columns = workflow.column_names().join(", ");
+    let Some(col) = workflow.columns.iter_mut().find(|c| c.name == column) else {
         return Err(CwaError::validation(format!(
             "Invalid column '{}'. Valid columns: {}",
             column,
-            valid_columns.join(", ")
+            valid_columns
         )));
-    }
+    };
+    col.wip_limit = limit;
 
-    // Get column order
-    let order = DEFAULT_COLUMNS
-        .iter()
-        .position(|(name, _)| *name == column)
-        .unwrap() as i32;
+    set_workflow(pool, project_id, &workflow).await
+}
 
-    queries::set_wip_limit(pool, project_id, column, limit, order).await?;
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project;
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server at REDIS_URL"]
+    async fn tasks_are_scoped_to_their_project() {
+        let pool = cwa_db::init_pool_from_env().await.unwrap();
+        let a = project::create_project(&pool, &format!("scope-a-{}", Uuid::new_v4()), None).await.unwrap();
+        let b = project::create_project(&pool, &format!("scope-b-{}", Uuid::new_v4()), None).await.unwrap();
+        let task = create_task(&pool, &b.id, "Belongs to B", None, None, "medium").await.unwrap();
+
+        let not_found = |r: CwaResult<_>| matches!(r, Err(CwaError::TaskNotFound(_)));
+        assert!(not_found(get_task(&pool, &a.id, &task.id).await.map(|_| ())));
+        assert!(not_found(move_task(&pool, &a.id, &task.id, "in_progress").await));
+        assert!(not_found(delete_task(&pool, &a.id, &task.id).await.map(|_| ())));
+        let update = TaskUpdate { title: Some("Hijacked".into()), ..Default::default() };
+        assert!(not_found(update_task(&pool, &a.id, &task.id, &update).await.map(|_| ())));
+
+        let unchanged = get_task(&pool, &b.id, &task.id).await.unwrap();
+        assert_eq!(unchanged.status, task.status);
+        assert_eq!(unchanged.title, "Belongs to B");
+
+        clear_all_tasks(&pool, &b.id).await.unwrap();
+    }
 }
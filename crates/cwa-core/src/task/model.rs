@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use cwa_db::queries::tasks::TaskRow;
 
+use super::workflow::ColumnCategory;
+
 /// A Kanban task.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -11,13 +13,16 @@ pub struct Task {
     pub spec_id: Option<String>,
     pub title: String,
     pub description: Option<String>,
-    pub status: TaskStatus,
+    /// Name of the workflow column the task is in.
+    pub status: String,
     pub priority: String,
     pub assignee: Option<String>,
     pub labels: Vec<String>,
     pub estimated_effort: Option<String>,
     pub actual_effort: Option<String>,
     pub blocked_by: Vec<String>,
+    /// Due date (`YYYY-MM-DD`).
+    pub due_date: Option<String>,
     pub created_at: String,
     pub updated_at: String,
     pub started_at: Option<String>,
@@ -45,13 +50,14 @@ impl Task {
             spec_id: row.spec_id,
             title: row.title,
             description: row.description,
-            status: TaskStatus::from_str(&row.status),
+            status: row.status,
             priority: row.priority,
             assignee: row.assignee,
             labels,
             estimated_effort: row.estimated_effort,
             actual_effort: row.actual_effort,
             blocked_by,
+            due_date: row.due_date,
             created_at: row.created_at,
             updated_at: row.updated_at,
             started_at: row.started_at,
@@ -60,7 +66,10 @@ impl Task {
     }
 }
 
-/// Task status (Kanban column).
+/// Columns of the default workflow.
+///
+/// Projects can define their own columns (see [`super::workflow`]); task
+/// statuses are therefore stored as plain column names.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
@@ -122,12 +131,20 @@ impl TaskStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Board {
     pub columns: Vec<BoardColumn>,
+    /// Tasks that still have unfinished blockers.
+    #[serde(default)]
+    pub blocked_task_ids: Vec<String>,
 }
 
 /// A column on the Kanban board.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BoardColumn {
     pub name: String,
+    /// Display name from the workflow.
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub category: ColumnCategory,
     pub wip_limit: Option<i64>,
     pub tasks: Vec<Task>,
 }
@@ -146,3 +163,149 @@ pub struct ColumnWipStatus {
     pub current: i64,
     pub is_exceeded: bool,
 }
+
+/// Field changes for a task edit. `None` leaves a field untouched; an empty
+/// string clears an optional field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub priority: Option<String>,
+    pub assignee: Option<String>,
+    /// Replaces the full label set.
+    pub labels: Option<Vec<String>>,
+    #[serde(default)]
+    pub add_labels: Vec<String>,
+    #[serde(default)]
+    pub remove_labels: Vec<String>,
+    pub estimated_effort: Option<String>,
+    pub actual_effort: Option<String>,
+    /// Replaces the full list of blockers.
+    pub blocked_by: Option<Vec<String>>,
+    /// Due date (`YYYY-MM-DD`).
+    pub due_date: Option<String>,
+}
+
+impl TaskUpdate {
+    /// Whether the update changes anything.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.priority.is_none()
+            && self.assignee.is_none()
+            && self.labels.is_none()
+            && self.add_labels.is_empty()
+            && self.remove_labels.is_empty()
+            && self.estimated_effort.is_none()
+            && self.actual_effort.is_none()
+            && self.blocked_by.is_none()
+            && self.due_date.is_none()
+    }
+
+    /// Compute the label set after applying this update to `current`.
+    ///
+    /// Labels are trimmed, empty ones dropped and duplicates removed while
+    /// keeping the first occurrence's position.
+    pub fn apply_labels(&self, current: &[String]) -> Vec<String> {
+        let mut labels: Vec<String> = Vec::new();
+        let base = self.labels.as_deref().unwrap_or(current);
+        for label in base.iter().chain(&self.add_labels) {
+            let label = label.trim();
+            if !label.is_empty() && !labels.iter().any(|l| l == label) {
+                labels.push(label.to_string());
+            }
+        }
+        labels.retain(|l| !self.remove_labels.iter().any(|r| r.trim() == l));
+        labels
+    }
+}
+
+/// Criteria for narrowing a task listing. Empty criteria match every task.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskFilter {
+    pub status: Option<String>,
+    pub spec_id: Option<String>,
+    pub assignee: Option<String>,
+    pub label: Option<String>,
+}
+
+impl TaskFilter {
+    /// Check whether a task satisfies every set criterion.
+    ///
+    /// Assignee and label comparisons are case-insensitive.
+    pub fn matches(&self, task: &Task) -> bool {
+        if self.status.as_deref().is_some_and(|s| task.status != s) {
+            return false;
+        }
+        if self.spec_id.is_some() && task.spec_id != self.spec_id {
+            return false;
+        }
+        if let Some(ref assignee) = self.assignee {
+            if !task.assignee.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(assignee)) {
+                return false;
+            }
+        }
+        if let Some(ref label) = self.label {
+            if !task.labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(assignee: Option<&str>, labels: &[&str]) -> Task {
+        Task {
+            id: "t1".to_string(),
+            project_id: "p1".to_string(),
+            spec_id: None,
+            title: "Task".to_string(),
+            description: None,
+            status: "todo".to_string(),
+            priority: "medium".to_string(),
+            assignee: assignee.map(str::to_string),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            estimated_effort: None,
+            actual_effort: None,
+            blocked_by: Vec::new(),
+            due_date: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+            started_at: None,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn apply_labels_adds_removes_and_dedupes() {
+        let current = vec!["bug".to_string(), "ui".to_string()];
+        let update = TaskUpdate {
+            add_labels: vec![" backend ".to_string(), "bug".to_string(), "".to_string()],
+            remove_labels: vec!["ui".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(update.apply_labels(&current), vec!["bug", "backend"]);
+
+        let replace = TaskUpdate { labels: Some(vec!["docs".to_string()]), ..Default::default() };
+        assert_eq!(replace.apply_labels(&current), vec!["docs"]);
+        assert!(!replace.is_empty());
+        assert!(TaskUpdate::default().is_empty());
+    }
+
+    #[test]
+    fn filter_matches_assignee_label_and_status() {
+        let t = task(Some("Alice"), &["bug", "ui"]);
+
+        assert!(TaskFilter::default().matches(&t));
+        assert!(TaskFilter { assignee: Some("alice".into()), ..Default::default() }.matches(&t));
+        assert!(TaskFilter { label: Some("UI".into()), status: Some("todo".into()), ..Default::default() }.matches(&t));
+        assert!(!TaskFilter { assignee: Some("bob".into()), ..Default::default() }.matches(&t));
+        assert!(!TaskFilter { label: Some("docs".into()), ..Default::default() }.matches(&t));
+        assert!(!TaskFilter { status: Some("done".into()), ..Default::default() }.matches(&t));
+        assert!(!TaskFilter { assignee: Some("alice".into()), ..Default::default() }.matches(&task(None, &[])));
+    }
+}
@@ -0,0 +1,396 @@
+//! Per-project Kanban workflows.
+//!
+//! A workflow defines the board columns a task can be in, which moves between
+//! them are allowed, the WIP limit of each column and the policies a task has
+//! to satisfy to enter or leave a column. Projects without a stored workflow
+//! use [`Workflow::default`], the classic five-column board.
+
+use serde::{Deserialize, Serialize};
+
+use super::model::{Task, TaskStatus};
+
+/// What a column means for flow tracking.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnCategory {
+    /// Work not started yet.
+    #[default]
+    Waiting,
+    /// Work in progress; entering it starts the cycle-time clock.
+    Active,
+    /// Finished work; entering it completes the task.
+    Done,
+}
+
+/// A rule a task must satisfy to enter or leave a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Policy {
+    /// The task is linked to a spec.
+    RequiresSpec,
+    /// The task is linked to a spec and its title is one of the spec's
+    /// acceptance criteria (as created by `cwa task generate`).
+    RequiresSpecCriterion,
+    RequiresAssignee,
+    RequiresDescription,
+    RequiresEstimate,
+    RequiresActualEffort,
+    /// Every task blocking this one is in a done column.
+    NoUnfinishedBlockers,
+}
+
+/// Data needed to evaluate policies that look beyond the task itself.
+#[derive(Debug, Clone, Default)]
+pub struct PolicyContext {
+    /// Acceptance criteria of the task's spec, if it has one.
+    pub spec_criteria: Option<Vec<String>>,
+    /// The task links to a spec that no longer exists.
+    pub spec_missing: bool,
+    /// Blockers of the task that are not done yet.
+    pub unfinished_blockers: Vec<String>,
+}
+
+impl Policy {
+    /// Whether evaluating this policy needs the task's spec.
+    pub fn needs_spec(&self) -> bool {
+        matches!(self, Self::RequiresSpec | Self::RequiresSpecCriterion)
+    }
+
+    /// Check the policy, returning why it fails.
+    pub fn check(&self, task: &Task, ctx: &PolicyContext) -> Option<String> {
+        let failed = match self {
+            Self::RequiresSpec => task.spec_id.is_none() || ctx.spec_missing,
+            Self::RequiresSpecCriterion => !ctx.spec_criteria.as_ref().is_some_and(|criteria| {
+                criteria.iter().any(|c| {
+                    task.title == *c || task.title.strip_suffix(c.as_str()).is_some_and(|p| p.ends_with(": "))
+                })
+            }),
+            Self::RequiresAssignee => task.assignee.is_none(),
+            Self::RequiresDescription => task.description.as_deref().is_none_or(|d| d.trim().is_empty()),
+            Self::RequiresEstimate => task.estimated_effort.is_none(),
+            Self::RequiresActualEffort => task.actual_effort.is_none(),
+            Self::NoUnfinishedBlockers => !ctx.unfinished_blockers.is_empty(),
+        };
+        failed.then(|| match self {
+            Self::RequiresSpec | Self::RequiresSpecCriterion if ctx.spec_missing => format!(
+                "linked spec {} no longer exists",
+                task.spec_id.as_deref().unwrap_or_default()
+            ),
+            Self::RequiresSpec => "task must be linked to a spec".to_string(),
+            Self::RequiresSpecCriterion => {
+                "task must match an acceptance criterion of its linked spec".to_string()
+            }
+            Self::RequiresAssignee => "task must have an assignee".to_string(),
+            Self::RequiresDescription => "task must have a description".to_string(),
+            Self::RequiresEstimate => "task must have an estimated effort".to_string(),
+            Self::RequiresActualEffort => "task must record its actual effort".to_string(),
+            Self::NoUnfinishedBlockers => format!(
+                "task is blocked by unfinished task(s): {}",
+                ctx.unfinished_blockers.join(", ")
+            ),
+        })
+    }
+}
+
+/// A column of a workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowColumn {
+    /// Status value stored on tasks (lowercase, `[a-z0-9_]`).
+    pub name: String,
+    /// Display name; defaults to the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub category: ColumnCategory,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wip_limit: Option<i64>,
+    /// Columns a task may move to from here. `None` allows any column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transitions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entry_policies: Vec<Policy>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exit_policies: Vec<Policy>,
+}
+
+impl WorkflowColumn {
+    /// Display name of the column.
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A project's Kanban workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workflow {
+    pub columns: Vec<WorkflowColumn>,
+}
+
+impl Default for Workflow {
+    /// The classic board: backlog → todo → in_progress → review → done, with
+    /// a WIP limit of 1 in progress and 3 in review.
+    fn default() -> Self {
+        let statuses = [
+            (TaskStatus::Backlog, ColumnCategory::Waiting, None),
+            (TaskStatus::Todo, ColumnCategory::Waiting, None),
+            (TaskStatus::InProgress, ColumnCategory::Active, Some(1)),
+            (TaskStatus::Review, ColumnCategory::Active, Some(3)),
+            (TaskStatus::Done, ColumnCategory::Done, None),
+        ];
+        let columns = statuses
+            .iter()
+            .map(|(status, category, wip_limit)| {
+                let transitions = statuses
+                    .iter()
+                    .map(|(to, _, _)| to)
+                    .filter(|to| to != &status && status.can_transition_to(to))
+                    .map(|to| to.as_str().to_string())
+                    .collect();
+                let entry_policies = if matches!(status, TaskStatus::InProgress | TaskStatus::Done) {
+                    vec![Policy::NoUnfinishedBlockers]
+                } else {
+                    Vec::new()
+                };
+                WorkflowColumn {
+                    name: status.as_str().to_string(),
+                    label: None,
+                    category: *category,
+                    wip_limit: *wip_limit,
+                    transitions: Some(transitions),
+                    entry_policies,
+                    exit_policies: Vec::new(),
+                }
+            })
+            .collect();
+        Self { columns }
+    }
+}
+
+impl Workflow {
+    /// Parse a workflow from TOML (`[[columns]]` tables).
+    pub fn from_toml(s: &str) -> Result<Self, String> {
+        toml::from_str(s).map_err(|e| e.to_string())
+    }
+
+    /// Serialize the workflow as TOML.
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Look up a column by name.
+    pub fn column(&self, name: &str) -> Option<&WorkflowColumn> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Names of all columns, in board order.
+    pub fn column_names(&self) -> Vec<&str> {
+        self.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    /// Column new tasks are created in.
+    pub fn initial_column(&self) -> &str {
+        self.columns.first().map_or(TaskStatus::Backlog.as_str(), |c| c.name.as_str())
+    }
+
+    /// Whether `status` is a done column.
+    pub fn is_done(&self, status: &str) -> bool {
+        self.column(status).is_some_and(|c| c.category == ColumnCategory::Done)
+    }
+
+    /// Whether `status` is an active (in progress) column.
+    pub fn is_active(&self, status: &str) -> bool {
+        self.column(status).is_some_and(|c| c.category == ColumnCategory::Active)
+    }
+
+    /// Check that the workflow is well formed.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.columns.is_empty() {
+            return Err("workflow must have at least one column".to_string());
+        }
+        for (i, column) in self.columns.iter().enumerate() {
+            let valid_name = !column.name.is_empty()
+                && column
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid_name {
+                return Err(format!(
+                    "invalid column name '{}': use lowercase letters, digits and '_'",
+                    column.name
+                ));
+            }
+            if self.columns[..i].iter().any(|c| c.name == column.name) {
+                return Err(format!("duplicate column '{}'", column.name));
+            }
+            if column.wip_limit.is_some_and(|l| l < 1) {
+                return Err(format!("WIP limit of '{}' must be at least 1", column.name));
+            }
+            for target in column.transitions.iter().flatten() {
+                if self.column(target).is_none() {
+                    return Err(format!(
+                        "column '{}' allows a transition to unknown column '{}'",
+                        column.name, target
+                    ));
+                }
+            }
+        }
+        if !self.columns.iter().any(|c| c.category == ColumnCategory::Done) {
+            return Err("workflow must have at least one column with category = \"done\"".to_string());
+        }
+        Ok(())
+    }
+
+    /// Check that a task may move from `from` to `to`, ignoring policies.
+    ///
+    /// Tasks sitting in a column the workflow no longer knows may move
+    /// anywhere, so they can always be brought back onto the board.
+    pub fn check_transition(&self, from: &str, to: &str) -> Result<(), String> {
+        if self.column(to).is_none() {
+            return Err(format!(
+                "unknown column '{}'. Valid columns: {}",
+                to,
+                self.column_names().join(", ")
+            ));
+        }
+        if from == to {
+            return Ok(());
+        }
+        match self.column(from).and_then(|c| c.transitions.as_ref()) {
+            Some(allowed) if !allowed.iter().any(|t| t == to) => Err(format!(
+                "cannot move from '{}' to '{}' (allowed: {})",
+                from,
+                to,
+                if allowed.is_empty() { "none".to_string() } else { allowed.join(", ") }
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Policies that apply when moving from `from` to `to`: the exit
+    /// policies of the source column followed by the entry policies of the
+    /// target column.
+    pub fn policies_for(&self, from: &str, to: &str) -> Vec<Policy> {
+        if from == to {
+            return Vec::new();
+        }
+        let mut policies: Vec<Policy> = Vec::new();
+        let exit = self.column(from).map(|c| c.exit_policies.as_slice()).unwrap_or_default();
+        let entry = self.column(to).map(|c| c.entry_policies.as_slice()).unwrap_or_default();
+        for policy in exit.iter().chain(entry) {
+            if !policies.contains(policy) {
+                policies.push(*policy);
+            }
+        }
+        policies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> Task {
+        Task {
+            id: "t1".to_string(),
+            project_id: "p1".to_string(),
+            spec_id: Some("s1".to_string()),
+            title: "Auth: users can log in".to_string(),
+            description: None,
+            status: "todo".to_string(),
+            priority: "medium".to_string(),
+            assignee: None,
+            labels: Vec::new(),
+            estimated_effort: None,
+            actual_effort: None,
+            blocked_by: Vec::new(),
+            due_date: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+            started_at: None,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn default_workflow_matches_builtin_transitions() {
+        let wf = Workflow::default();
+        assert!(wf.validate().is_ok());
+        assert_eq!(wf.initial_column(), "backlog");
+        assert!(wf.check_transition("backlog", "todo").is_ok());
+        assert!(wf.check_transition("review", "in_progress").is_ok());
+        assert!(wf.check_transition("backlog", "done").is_err());
+        assert!(wf.check_transition("todo", "qa").is_err());
+        assert!(wf.is_done("done") && wf.is_active("review") && !wf.is_active("todo"));
+        assert_eq!(wf.policies_for("todo", "in_progress"), vec![Policy::NoUnfinishedBlockers]);
+        assert!(wf.policies_for("in_progress", "review").is_empty());
+    }
+
+    #[test]
+    fn toml_round_trip_and_validation() {
+        let wf = Workflow::from_toml(
+            r#"
+            [[columns]]
+            name = "ready"
+
+            [[columns]]
+            name = "doing"
+            category = "active"
+            wip_limit = 2
+            transitions = ["qa"]
+
+            [[columns]]
+            name = "qa"
+            label = "QA"
+            category = "active"
+            entry_policies = ["requires_spec_criterion"]
+
+            [[columns]]
+            name = "shipped"
+            category = "done"
+            exit_policies = ["requires_actual_effort"]
+            "#,
+        )
+        .unwrap();
+        assert!(wf.validate().is_ok());
+        assert_eq!(wf.column("qa").unwrap().label(), "QA");
+        assert!(wf.check_transition("ready", "shipped").is_ok());
+        assert!(wf.check_transition("doing", "shipped").is_err());
+        assert_eq!(Workflow::from_toml(&wf.to_toml().unwrap()).unwrap(), wf);
+
+        let mut broken = wf.clone();
+        broken.columns[1].transitions = Some(vec!["nowhere".to_string()]);
+        assert!(broken.validate().is_err());
+        broken.columns.retain(|c| c.category != ColumnCategory::Done);
+        assert!(broken.validate().is_err());
+        assert!(Workflow::from_toml("[[columns]]\nname = \"In Progress\"\ncategory = \"done\"").unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn policies_report_unmet_requirements() {
+        let t = task();
+        let ctx = PolicyContext {
+            spec_criteria: Some(vec!["users can log in".to_string()]),
+            ..Default::default()
+        };
+        assert!(Policy::RequiresSpec.check(&t, &ctx).is_none());
+        assert!(Policy::RequiresSpecCriterion.check(&t, &ctx).is_none());
+        assert!(Policy::RequiresAssignee.check(&t, &ctx).is_some());
+        assert!(Policy::RequiresDescription.check(&t, &ctx).is_some());
+
+        let other = PolicyContext { spec_criteria: Some(vec!["admins can log in".to_string()]), ..ctx };
+        assert!(Policy::RequiresSpecCriterion.check(&t, &other).is_some());
+
+        let blocked = PolicyContext { unfinished_blockers: vec!["Setup (abc)".to_string()], ..Default::default() };
+        assert!(Policy::NoUnfinishedBlockers.check(&t, &blocked).unwrap().contains("Setup (abc)"));
+    }
+
+    #[test]
+    fn deleted_spec_fails_spec_policies() {
+        let t = task();
+        let ctx = PolicyContext { spec_missing: true, ..Default::default() };
+        for policy in [Policy::RequiresSpec, Policy::RequiresSpecCriterion] {
+            assert_eq!(policy.check(&t, &ctx).as_deref(), Some("linked spec s1 no longer exists"));
+        }
+        assert!(Policy::RequiresAssignee.check(&t, &ctx).unwrap().contains("assignee"));
+    }
+}
@@ -10,8 +10,10 @@ pub use cwa_redis::RedisPool as DbPool;
 pub use cwa_redis::RedisError as DbError;
 pub use cwa_redis::RedisResult as DbResult;
 pub use cwa_redis::{
-    BroadcastReceiver, BroadcastSender, WebSocketMessage, create_broadcast_channel,
+    BroadcastReceiver, BroadcastSender, ProjectEvent, WebSocketMessage, create_broadcast_channel,
+    publish_event, subscribe_events,
 };
+pub use cwa_redis::consistency;
 
 /// Initialize a database pool from a Redis URL.
 ///
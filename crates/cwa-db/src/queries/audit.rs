@@ -0,0 +1,2 @@
+//! MCP audit log queries — delegates to cwa-redis.
+pub use cwa_redis::queries::audit::*;
@@ -0,0 +1,2 @@
+//! Codebase index queries — delegates to cwa-redis.
+pub use cwa_redis::queries::code_index::*;
@@ -0,0 +1,2 @@
+//! Embedding queue queries — delegates to cwa-redis.
+pub use cwa_redis::queries::embeddings::*;
@@ -0,0 +1,2 @@
+//! Lexical index queries — delegates to cwa-redis.
+pub use cwa_redis::queries::lexical::*;
@@ -1,11 +1,15 @@
 //! Database query implementations.
 
+pub mod audit;
 pub mod boards;
+pub mod code_index;
 pub mod projects;
 pub mod specs;
 pub mod tasks;
 pub mod domains;
 pub mod decisions;
 pub mod design_systems;
+pub mod embeddings;
+pub mod lexical;
 pub mod memory;
 pub mod observations;
@@ -0,0 +1,134 @@
+//! Content-addressed embedding cache.
+//!
+//! [`CachedEmbedder`] wraps another embedder and keeps every vector it
+//! computes in Redis, keyed by the embedder's name and dimension plus the
+//! SHA-256 of the text. Reindexing or backfilling unchanged content then
+//! costs a Redis round trip instead of a model call. The cache is an
+//! optimization only: when Redis fails, texts go straight to the inner
+//! embedder.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use cwa_db::DbPool;
+
+use crate::embedder::Embedder;
+
+/// Embedder that serves repeated texts from the Redis cache.
+pub struct CachedEmbedder {
+    inner: Arc<dyn Embedder>,
+    db: DbPool,
+}
+
+impl CachedEmbedder {
+    /// Cache the vectors of `inner` in `db`.
+    pub fn new(inner: Arc<dyn Embedder>, db: DbPool) -> Self {
+        Self { inner, db }
+    }
+
+    /// Cache namespace: vectors of different models or sizes never mix.
+    fn model_key(&self) -> String {
+        format!("{}@{}", self.inner.name(), self.inner.dimension())
+    }
+}
+
+#[async_trait]
+impl Embedder for CachedEmbedder {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut vectors = self.embed_batch(&[text.to_string()]).await?;
+        Ok(vectors.pop().unwrap_or_default())
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let model = self.model_key();
+        let dimension = self.inner.dimension();
+        let hashes: Vec<String> = texts.iter().map(|t| content_hash(t)).collect();
+
+        let cached = cwa_db::queries::embeddings::get_cached(&self.db, &model, &hashes)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "Embedding cache unavailable");
+                vec![None; texts.len()]
+            });
+        let mut vectors: Vec<Option<Vec<f32>>> = cached
+            .into_iter()
+            .map(|bytes| bytes.and_then(|b| decode_vector(&b, dimension)))
+            .collect();
+
+        let misses: Vec<usize> = (0..texts.len()).filter(|i| vectors[*i].is_none()).collect();
+        debug!(model = %model, hits = texts.len() - misses.len(), misses = misses.len(), "Embedding cache lookup");
+
+        if !misses.is_empty() {
+            let miss_texts: Vec<String> = misses.iter().map(|i| texts[*i].clone()).collect();
+            let fresh = self.inner.embed_batch(&miss_texts).await?;
+
+            let entries: Vec<(String, Vec<u8>)> = misses
+                .iter()
+                .zip(&fresh)
+                .map(|(i, vector)| (hashes[*i].clone(), encode_vector(vector)))
+                .collect();
+            if let Err(e) = cwa_db::queries::embeddings::put_cached(&self.db, &model, &entries).await {
+                warn!(error = %e, "Failed to cache embeddings");
+            }
+
+            for (i, vector) in misses.into_iter().zip(fresh) {
+                vectors[i] = Some(vector);
+            }
+        }
+
+        Ok(vectors.into_iter().map(Option::unwrap_or_default).collect())
+    }
+}
+
+/// Hex SHA-256 of the embedded text.
+pub fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Little-endian `f32`s.
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Inverse of [`encode_vector`]; `None` when the entry has the wrong size.
+fn decode_vector(bytes: &[u8], dimension: usize) -> Option<Vec<f32>> {
+    if bytes.len() != dimension * 4 {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_round_trip_and_hashes_are_stable() {
+        let vector = vec![0.25, -1.5, 3.0e-7, 0.0];
+        assert_eq!(decode_vector(&encode_vector(&vector), 4), Some(vector.clone()));
+        assert_eq!(decode_vector(&encode_vector(&vector), 3), None);
+
+        assert_eq!(content_hash("cwa"), "5352f140a4a7d0aec8db5c1853d2c70a2c27bfed28b273faddbb125832ecc7d6");
+        assert_ne!(content_hash("cwa"), content_hash("cwa "));
+    }
+}
@@ -0,0 +1,570 @@
+//! Codebase indexing into the `cwa_files` collection.
+//!
+//! `cwa index` walks the project (respecting `.gitignore`), splits source
+//! files into chunks at top-level definitions, and stores each chunk in
+//! Qdrant and the lexical index with its path, line range, language and the
+//! file's git blob hash. Files whose blob hash is unchanged since the last
+//! run are skipped, and the chunks of files that were deleted or are now
+//! ignored, too large or binary are removed.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use cwa_db::queries::code_index::{self, IndexedFileRow};
+use cwa_db::queries::lexical;
+use cwa_db::DbPool;
+
+use crate::cache::CachedEmbedder;
+use crate::config::EmbeddingConfig;
+use crate::embedder::Embedder;
+use crate::hybrid::{hybrid_search, HybridSearchRequest};
+use crate::qdrant::{NewPoint, QdrantStore, FILES_COLLECTION};
+use crate::queue::{ProgressFn, BATCH_SIZE};
+
+/// Files larger than this are not indexed (generated code, data, bundles).
+const MAX_FILE_BYTES: usize = 512 * 1024;
+
+/// Chunks are grown up to this many lines.
+const TARGET_LINES: usize = 40;
+
+/// Longer sections are split, at nested definitions or blank lines if possible.
+const MAX_LINES: usize = 120;
+
+/// Characters of a chunk sent to the embedder.
+const MAX_EMBED_CHARS: usize = 6000;
+
+/// Chunks embedded before their files are written, bounding memory use.
+const GROUP_CHUNKS: usize = BATCH_SIZE * 4;
+
+/// Directories skipped even when not ignored.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+/// Indexed languages by file extension.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("py", "python"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("jsx", "javascript"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("go", "go"),
+    ("java", "java"),
+    ("kt", "kotlin"),
+    ("kts", "kotlin"),
+    ("scala", "scala"),
+    ("swift", "swift"),
+    ("cs", "csharp"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cc", "cpp"),
+    ("cpp", "cpp"),
+    ("cxx", "cpp"),
+    ("hpp", "cpp"),
+    ("rb", "ruby"),
+    ("php", "php"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+    ("lua", "lua"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("md", "markdown"),
+];
+
+/// A contiguous range of a source file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeChunk {
+    /// First line, 1-based.
+    pub start_line: usize,
+    /// Last line, inclusive.
+    pub end_line: usize,
+    pub text: String,
+}
+
+/// A file found by [`source_files`].
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// Path relative to the project root, with `/` separators.
+    pub path: String,
+    pub abs_path: PathBuf,
+    pub language: &'static str,
+}
+
+/// Result of indexing a project's code.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CodeIndexReport {
+    /// Source files found.
+    pub files: usize,
+    /// Files (re-)embedded.
+    pub indexed: usize,
+    /// Files whose content did not change.
+    pub unchanged: usize,
+    /// Previously indexed files that no longer exist or are now skipped.
+    pub removed: usize,
+    /// Files too large, binary or not UTF-8.
+    pub skipped: usize,
+    /// Chunks embedded.
+    pub chunks: usize,
+}
+
+/// A code chunk matching a search.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeSearchResult {
+    pub path: String,
+    pub language: String,
+    pub start_line: u64,
+    pub end_line: u64,
+    pub score: f64,
+    pub content: String,
+}
+
+/// Language of a file, from its extension.
+pub fn language_for(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    LANGUAGES.iter().find(|(e, _)| *e == ext).map(|(_, lang)| *lang)
+}
+
+/// Source files of the project at `root`, sorted by path.
+///
+/// Honours `.gitignore` (also outside a git repository) and `.ignore`, and
+/// skips hidden files and dependency or build directories.
+pub fn source_files(root: &Path) -> Vec<SourceFile> {
+    let mut files: Vec<SourceFile> = ignore::WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(|entry| {
+            !(entry.file_type().is_some_and(|t| t.is_dir())
+                && SKIPPED_DIRS.iter().any(|d| entry.file_name() == *d))
+        })
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let language = language_for(entry.path())?;
+            let relative = entry.path().strip_prefix(root).ok()?;
+            let path = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some(SourceFile { path, abs_path: entry.path().to_path_buf(), language })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Git blob hash of a file's content (what `git hash-object` prints).
+pub fn git_blob_hash(content: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Split a source file into chunks at top-level definitions.
+///
+/// Sections start at a definition (`fn`, `class`, `def`, a Markdown
+/// heading, ...) together with the comments and attributes right above it.
+/// Small neighbouring sections are merged up to [`TARGET_LINES`]; longer
+/// ones are split at nested definitions or blank lines, and at
+/// [`MAX_LINES`] at the latest.
+pub fn chunk_source(language: &str, source: &str) -> Vec<CodeChunk> {
+    let lines: Vec<&str> = source.lines().collect();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let mut starts = vec![0];
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        if !line.starts_with(char::is_whitespace) && starts_definition(language, line) {
+            let mut start = i;
+            while start > 0 && is_lead_in(language, lines[start - 1]) {
+                start -= 1;
+            }
+            if start > *starts.last().unwrap_or(&0) {
+                starts.push(start);
+            }
+        }
+    }
+
+    let mut pieces = Vec::new();
+    for (n, start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(lines.len());
+        let mut piece_start = *start;
+        for (i, line) in lines.iter().enumerate().take(end).skip(start + 1) {
+            let len = i - piece_start;
+            let soft_cut = len >= TARGET_LINES
+                && (line.trim().is_empty() || starts_definition(language, line.trim_start()));
+            if soft_cut || len >= MAX_LINES {
+                pieces.push((piece_start, i));
+                piece_start = i;
+            }
+        }
+        pieces.push((piece_start, end));
+    }
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in pieces {
+        match merged.last_mut() {
+            Some(last) if end - last.0 <= TARGET_LINES => last.1 = end,
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+        .into_iter()
+        .filter_map(|(start, mut end)| {
+            while end > start && lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+            let first = (start..end).find(|i| !lines[*i].trim().is_empty())?;
+            Some(CodeChunk {
+                start_line: first + 1,
+                end_line: end,
+                text: lines[first..end].join("\n"),
+            })
+        })
+        .collect()
+}
+
+/// Whether a line (without its indentation) begins a definition.
+fn starts_definition(language: &str, line: &str) -> bool {
+    let keywords: &[&str] = match language {
+        "rust" => &["fn ", "pub ", "pub(", "impl", "struct ", "enum ", "trait ", "mod ", "type ", "const ", "static ", "async fn ", "unsafe ", "macro_rules!"],
+        "python" => &["def ", "async def ", "class "],
+        "javascript" | "typescript" => &["function ", "async function ", "class ", "export ", "const ", "let ", "interface ", "type ", "enum ", "abstract class "],
+        "go" => &["func ", "type ", "var ", "const "],
+        "c" | "cpp" => &["struct ", "class ", "namespace ", "template", "typedef ", "enum ", "static ", "void ", "int ", "char ", "bool ", "unsigned ", "const "],
+        "ruby" => &["def ", "class ", "module "],
+        "elixir" => &["defmodule ", "def ", "defp ", "defmacro "],
+        "lua" => &["function ", "local function "],
+        "shell" => &["function "],
+        "markdown" => &["#"],
+        _ => &["public ", "private ", "protected ", "internal ", "class ", "interface ", "enum ", "record ", "struct ", "object ", "trait ", "fun ", "func ", "def ", "function ", "static ", "abstract ", "final ", "extension ", "namespace "],
+    };
+    keywords.iter().any(|k| line.starts_with(k))
+        || (language == "shell" && line.trim_end().ends_with("() {"))
+}
+
+/// Whether a line is a comment, attribute or decorator that belongs to the
+/// definition below it.
+fn is_lead_in(language: &str, line: &str) -> bool {
+    let line = line.trim_start();
+    if line.is_empty() {
+        return false;
+    }
+    match language {
+        "markdown" => false,
+        "python" | "ruby" | "elixir" | "shell" => line.starts_with('#') || line.starts_with('@'),
+        "lua" => line.starts_with("--"),
+        _ => ["//", "/*", "*", "#[", "#!", "@"].iter().any(|p| line.starts_with(p)),
+    }
+}
+
+/// Stable point ID of a chunk.
+fn chunk_id(project_id: &str, path: &str, index: usize) -> String {
+    let digest = Sha256::digest(format!("{}\0{}\0{}", project_id, path, index).as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_random_bytes(bytes).into_uuid().to_string()
+}
+
+/// Text embedded for a chunk: its path, then the code.
+fn embedding_text(path: &str, chunk: &CodeChunk) -> String {
+    let mut text = format!("{}\n{}", path, chunk.text);
+    if text.len() > MAX_EMBED_CHARS {
+        let mut cut = MAX_EMBED_CHARS;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        text.truncate(cut);
+    }
+    text
+}
+
+/// A changed file waiting to be embedded.
+struct PendingFile {
+    file: SourceFile,
+    blob: String,
+    chunks: Vec<CodeChunk>,
+}
+
+/// Indexes a project's source files.
+pub struct CodeIndexer {
+    embedder: Arc<dyn Embedder>,
+    qdrant: QdrantStore,
+    progress: Option<ProgressFn>,
+}
+
+impl CodeIndexer {
+    /// Create an indexer with the given clients.
+    pub fn new(embedder: Arc<dyn Embedder>, qdrant: QdrantStore) -> Self {
+        Self { embedder, qdrant, progress: None }
+    }
+
+    /// Create an indexer for the given embedding configuration.
+    pub fn from_config(config: &EmbeddingConfig) -> Result<Self> {
+        Ok(Self::new(config.embedder(), config.store()?))
+    }
+
+    /// Call `progress` with `(chunks embedded, chunks to embed)` after each batch.
+    pub fn with_progress(mut self, progress: impl Fn(usize, usize) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Index the source files under `root`.
+    ///
+    /// Only files whose git blob hash changed since the last run are
+    /// embedded, unless `full` is set. Work is saved file by file, so an
+    /// interrupted run resumes where it stopped.
+    pub async fn index(&self, db: &DbPool, project_id: &str, root: &Path, full: bool) -> Result<CodeIndexReport> {
+        let files = source_files(root);
+        let previous = code_index::list_indexed_files(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read code index: {}", e))?;
+
+        let mut report = CodeIndexReport { files: files.len(), ..Default::default() };
+        // Paths whose stored chunks stay valid; every other indexed path is removed
+        let mut kept = HashSet::new();
+        let mut pending = Vec::new();
+        for file in files {
+            let content = match std::fs::read(&file.abs_path) {
+                Ok(content) => content,
+                Err(e) => {
+                    // Possibly transient, so what was indexed before is kept
+                    warn!(path = %file.path, error = %e, "Skipping unreadable file");
+                    kept.insert(file.path.clone());
+                    report.skipped += 1;
+                    continue;
+                }
+            };
+            if content.len() > MAX_FILE_BYTES || content.contains(&0) {
+                report.skipped += 1;
+                continue;
+            }
+
+            let blob = git_blob_hash(&content);
+            if !full && previous.get(&file.path).is_some_and(|row| row.blob == blob) {
+                kept.insert(file.path.clone());
+                report.unchanged += 1;
+                continue;
+            }
+            let Ok(source) = String::from_utf8(content) else {
+                report.skipped += 1;
+                continue;
+            };
+            kept.insert(file.path.clone());
+            let chunks = chunk_source(file.language, &source);
+            pending.push(PendingFile { file, blob, chunks });
+        }
+
+        for (path, row) in previous.iter().filter(|(path, _)| !kept.contains(*path)) {
+            self.qdrant.delete_matching(FILES_COLLECTION, project_id, "path", path).await?;
+            self.remove_lexical(db, project_id, path, 0, row.chunks).await?;
+            code_index::remove_indexed_file(db, project_id, path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to update code index: {}", e))?;
+            report.removed += 1;
+        }
+
+        let total: usize = pending.iter().map(|p| p.chunks.len()).sum();
+        let embedder = CachedEmbedder::new(self.embedder.clone(), db.clone());
+        let mut group = Vec::new();
+        let mut group_chunks = 0;
+        let mut pending = pending.into_iter().peekable();
+        while let Some(file) = pending.next() {
+            group_chunks += file.chunks.len();
+            group.push(file);
+            if group_chunks >= GROUP_CHUNKS || pending.peek().is_none() {
+                self.store_group(&embedder, db, project_id, &group, &previous, &mut report, total)
+                    .await
+                    .with_context(|| format!("Indexed {} file(s) before failing", report.indexed))?;
+                group.clear();
+                group_chunks = 0;
+            }
+        }
+
+        info!(
+            project_id,
+            files = report.files,
+            indexed = report.indexed,
+            removed = report.removed,
+            chunks = report.chunks,
+            "Indexed codebase"
+        );
+        Ok(report)
+    }
+
+    /// Embed a group of files and replace their stored chunks.
+    #[allow(clippy::too_many_arguments)]
+    async fn store_group(
+        &self,
+        embedder: &CachedEmbedder,
+        db: &DbPool,
+        project_id: &str,
+        group: &[PendingFile],
+        previous: &HashMap<String, IndexedFileRow>,
+        report: &mut CodeIndexReport,
+        total: usize,
+    ) -> Result<()> {
+        let texts: Vec<String> = group
+            .iter()
+            .flat_map(|p| p.chunks.iter().map(|chunk| embedding_text(&p.file.path, chunk)))
+            .collect();
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(BATCH_SIZE) {
+            vectors.extend(embedder.embed_batch(batch).await.context("Failed to embed code chunks")?);
+            if let Some(progress) = &self.progress {
+                progress(report.chunks + vectors.len(), total);
+            }
+        }
+        if vectors.len() != texts.len() {
+            anyhow::bail!("Embedder returned {} vectors for {} code chunks", vectors.len(), texts.len());
+        }
+
+        let indexed_at = chrono::Utc::now().to_rfc3339();
+        let mut vectors = vectors.into_iter();
+        for pending in group {
+            let path = &pending.file.path;
+            if let Some(old) = previous.get(path) {
+                self.qdrant.delete_matching(FILES_COLLECTION, project_id, "path", path).await?;
+                self.remove_lexical(db, project_id, path, pending.chunks.len(), old.chunks).await?;
+            }
+
+            let mut points: Vec<NewPoint> = Vec::with_capacity(pending.chunks.len());
+            for (index, chunk) in pending.chunks.iter().enumerate() {
+                let id = chunk_id(project_id, path, index);
+                let payload = serde_json::json!({
+                    "id": id,
+                    "project_id": project_id,
+                    "path": path,
+                    "language": pending.file.language,
+                    "start_line": chunk.start_line,
+                    "end_line": chunk.end_line,
+                    "blob": pending.blob,
+                    "content": chunk.text,
+                });
+                lexical::index_document(db, project_id, lexical::FILES, &id, &format!("{}\n{}", path, chunk.text), &payload)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to update lexical index: {}", e))?;
+                let vector = vectors.next().context("Embedder returned fewer vectors than code chunks")?;
+                points.push((id, vector, payload));
+            }
+            self.qdrant.upsert_batch(FILES_COLLECTION, points).await?;
+
+            let row = IndexedFileRow {
+                blob: pending.blob.clone(),
+                language: pending.file.language.to_string(),
+                chunks: pending.chunks.len(),
+                indexed_at: indexed_at.clone(),
+            };
+            code_index::set_indexed_file(db, project_id, path, &row)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to update code index: {}", e))?;
+            report.indexed += 1;
+            report.chunks += pending.chunks.len();
+        }
+        Ok(())
+    }
+
+    /// Remove chunks `from..to` of a file from the lexical index.
+    async fn remove_lexical(&self, db: &DbPool, project_id: &str, path: &str, from: usize, to: usize) -> Result<()> {
+        for index in from..to {
+            lexical::remove_document(db, project_id, lexical::FILES, &chunk_id(project_id, path, index))
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to update lexical index: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Search indexed code by meaning and keywords, optionally limited to one
+/// language or to paths under `path_prefix`.
+#[allow(clippy::too_many_arguments)]
+pub async fn search_code(
+    embedder: &dyn Embedder,
+    qdrant: &QdrantStore,
+    db: &DbPool,
+    project_id: &str,
+    query: &str,
+    top_k: usize,
+    language: Option<&str>,
+    path_prefix: Option<&str>,
+) -> Result<Vec<CodeSearchResult>> {
+    let filtered = language.is_some() || path_prefix.is_some();
+    let fetch_k = if filtered { top_k * 5 } else { top_k };
+    let req = HybridSearchRequest::new(query, fetch_k)
+        .with_collections(vec![FILES_COLLECTION.to_string()])
+        .with_project(project_id);
+
+    let field = |payload: &serde_json::Value, name: &str| payload.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let line = |payload: &serde_json::Value, name: &str| payload.get(name).and_then(|v| v.as_u64()).unwrap_or(0);
+
+    Ok(hybrid_search(embedder, qdrant, db, req)
+        .await?
+        .into_iter()
+        .map(|r| CodeSearchResult {
+            path: field(&r.payload, "path"),
+            language: field(&r.payload, "language"),
+            start_line: line(&r.payload, "start_line"),
+            end_line: line(&r.payload, "end_line"),
+            score: r.score,
+            content: field(&r.payload, "content"),
+        })
+        .filter(|r| language.is_none_or(|l| r.language == l))
+        .filter(|r| path_prefix.is_none_or(|p| r.path.starts_with(p)))
+        .take(top_k)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blob_hash_matches_git() {
+        // `printf 'hello\n' | git hash-object --stdin`
+        assert_eq!(git_blob_hash(b"hello\n"), "ce013625030ba8dba906f756967f9e9ca394464a");
+    }
+
+    #[test]
+    fn chunks_follow_definitions_and_keep_their_comments() {
+        let mut source = String::from("use std::fmt;\n\n/// Adds.\n#[inline]\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n");
+        source.push_str("struct Big {\n");
+        for i in 0..60 {
+            source.push_str(&format!("    field_{}: u32,\n", i));
+        }
+        source.push_str("}\n");
+
+        let chunks = chunk_source("rust", &source);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 7));
+        assert!(chunks[0].text.contains("/// Adds.\n#[inline]\nfn add"));
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (9, 70));
+        assert!(chunks[1].text.starts_with("struct Big {"));
+    }
+
+    #[test]
+    fn long_sections_are_split() {
+        let body: String = (0..300).map(|i| format!("    x{} = {}\n", i, i)).collect();
+        let chunks = chunk_source("python", &format!("def main():\n{}", body));
+        assert!(chunks.len() >= 3);
+        assert!(chunks.iter().all(|c| c.end_line - c.start_line < MAX_LINES));
+        assert_eq!(chunks.last().unwrap().end_line, 301);
+    }
+
+    #[test]
+    fn languages_come_from_the_extension() {
+        assert_eq!(language_for(Path::new("src/main.rs")), Some("rust"));
+        assert_eq!(language_for(Path::new("web/App.TSX")), Some("typescript"));
+        assert_eq!(language_for(Path::new("Cargo.lock")), None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Embedding provider configuration.
+//!
+//! Read from `.cwa/embedding.json`; every field is optional and the file
+//! itself may be absent, in which case CWA embeds with Ollama and
+//! nomic-embed-text (768 dimensions):
+//!
+//! ```json
+//! {
+//!   "provider": "openai",
+//!   "model": "text-embedding-3-small",
+//!   "dimension": 1536,
+//!   "url": "http://localhost:8080",
+//!   "api_key_env": "OPENAI_API_KEY"
+//! }
+//! ```
+//!
+//! `provider` is `ollama`, `openai` (any OpenAI-compatible server) or
+//! `hashing`, a lexical fallback that needs no model (`local` is accepted as
+//! an older name for it). Qdrant collections are created with `dimension`,
+//! so changing it requires re-creating them.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::embedder::Embedder;
+use crate::hashing::{HashingEmbedder, DEFAULT_HASHING_DIM};
+use crate::ollama::{OllamaClient, DEFAULT_MODEL, DEFAULT_OLLAMA_URL, EMBEDDING_DIM};
+use crate::openai::{OpenAiClient, DEFAULT_OPENAI_DIM, DEFAULT_OPENAI_MODEL, DEFAULT_OPENAI_URL};
+use crate::qdrant::QdrantStore;
+
+/// Project file configuring the embedding provider, relative to the project root.
+pub const CONFIG_FILE: &str = ".cwa/embedding.json";
+
+/// Environment variable holding the API key when `api_key_env` is not set.
+pub const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// Dimensions of common embedding models, used when `dimension` is not set.
+const KNOWN_DIMENSIONS: &[(&str, usize)] = &[
+    ("nomic-embed-text", 768),
+    ("mxbai-embed-large", 1024),
+    ("all-minilm", 384),
+    ("snowflake-arctic-embed", 1024),
+    ("bge-m3", 1024),
+    ("text-embedding-3-small", 1536),
+    ("text-embedding-3-large", 3072),
+    ("text-embedding-ada-002", 1536),
+];
+
+/// Where embeddings come from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// Ollama's `/api/embeddings`.
+    #[default]
+    Ollama,
+    /// Any server implementing OpenAI's `/v1/embeddings`.
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// In-process feature hashing of words and trigrams: lexical, not
+    /// semantic, similarity, for offline use when no model is available.
+    #[serde(alias = "local")]
+    Hashing,
+}
+
+impl Provider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ollama => "ollama",
+            Self::OpenAi => "openai",
+            Self::Hashing => "hashing",
+        }
+    }
+}
+
+/// Contents of [`CONFIG_FILE`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingConfig {
+    pub provider: Provider,
+    /// Model name; defaults per provider.
+    pub model: Option<String>,
+    /// Vector size; defaults to the model's known size.
+    pub dimension: Option<usize>,
+    /// Server URL; defaults to `OLLAMA_URL` / `OPENAI_BASE_URL` or the
+    /// provider's standard address.
+    pub url: Option<String>,
+    /// Environment variable holding the API key (OpenAI-compatible only).
+    pub api_key_env: Option<String>,
+}
+
+impl EmbeddingConfig {
+    /// Load the configuration of the project at `project_dir`, or the
+    /// defaults if it has none.
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let path = project_dir.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// The configured model, or the provider's default.
+    pub fn model(&self) -> String {
+        match (&self.model, self.provider) {
+            (Some(model), _) => model.clone(),
+            (None, Provider::Ollama) => DEFAULT_MODEL.to_string(),
+            (None, Provider::OpenAi) => DEFAULT_OPENAI_MODEL.to_string(),
+            (None, Provider::Hashing) => "hashing".to_string(),
+        }
+    }
+
+    /// The configured vector size, or the model's known size.
+    pub fn dimension(&self) -> usize {
+        if let Some(dimension) = self.dimension {
+            return dimension;
+        }
+        let model = self.model();
+        let base = model.split(':').next().unwrap_or(&model);
+        KNOWN_DIMENSIONS
+            .iter()
+            .find(|(name, _)| *name == base)
+            .map(|(_, dim)| *dim)
+            .unwrap_or(match self.provider {
+                Provider::Ollama => EMBEDDING_DIM,
+                Provider::OpenAi => DEFAULT_OPENAI_DIM,
+                Provider::Hashing => DEFAULT_HASHING_DIM,
+            })
+    }
+
+    /// The configured server URL, or the provider's default.
+    pub fn url(&self) -> Option<String> {
+        let (env, default) = match self.provider {
+            Provider::Ollama => ("OLLAMA_URL", DEFAULT_OLLAMA_URL),
+            Provider::OpenAi => ("OPENAI_BASE_URL", DEFAULT_OPENAI_URL),
+            Provider::Hashing => return None,
+        };
+        Some(
+            self.url
+                .clone()
+                .or_else(|| std::env::var(env).ok())
+                .unwrap_or_else(|| default.to_string()),
+        )
+    }
+
+    /// Build the configured embedder.
+    pub fn embedder(&self) -> Arc<dyn Embedder> {
+        let url = self.url().unwrap_or_default();
+        match self.provider {
+            Provider::Ollama => Arc::new(OllamaClient::new(&url, &self.model()).with_dimension(self.dimension())),
+            Provider::OpenAi => {
+                let env = self.api_key_env.as_deref().unwrap_or(DEFAULT_API_KEY_ENV);
+                let api_key = std::env::var(env).ok().filter(|k| !k.is_empty());
+                Arc::new(OpenAiClient::new(&url, &self.model(), api_key, self.dimension()))
+            }
+            Provider::Hashing => Arc::new(HashingEmbedder::new(self.dimension())),
+        }
+    }
+
+    /// A Qdrant store whose collections match the configured dimension.
+    pub fn store(&self) -> Result<QdrantStore> {
+        Ok(QdrantStore::default_store()?.with_dimension(self.dimension()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_follow_the_provider_and_model() {
+        let config = EmbeddingConfig::default();
+        assert_eq!(config.provider, Provider::Ollama);
+        assert_eq!(config.model(), "nomic-embed-text");
+        assert_eq!(config.dimension(), 768);
+
+        let config: EmbeddingConfig = serde_json::from_str(r#"{"provider": "openai"}"#).unwrap();
+        assert_eq!(config.model(), "text-embedding-3-small");
+        assert_eq!(config.dimension(), 1536);
+        assert_eq!(config.embedder().name(), "openai/text-embedding-3-small");
+
+        let config: EmbeddingConfig =
+            serde_json::from_str(r#"{"provider": "ollama", "model": "mxbai-embed-large:latest"}"#).unwrap();
+        assert_eq!(config.dimension(), 1024);
+
+        let config: EmbeddingConfig = serde_json::from_str(r#"{"provider": "hashing", "dimension": 256}"#).unwrap();
+        assert_eq!(config.embedder().dimension(), 256);
+        assert!(config.url().is_none());
+
+        // Configs written before the provider was renamed keep working
+        let config: EmbeddingConfig = serde_json::from_str(r#"{"provider": "local"}"#).unwrap();
+        assert_eq!(config.provider, Provider::Hashing);
+        assert_eq!(config.dimension(), 384);
+
+        assert!(serde_json::from_str::<EmbeddingConfig>(r#"{"provider": "cohere"}"#).is_err());
+    }
+}
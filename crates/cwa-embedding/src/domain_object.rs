@@ -3,14 +3,16 @@
 //! Embeds domain objects into Qdrant for semantic search across the domain model.
 
 use anyhow::{Context, Result};
+use std::sync::Arc;
 use tracing::{debug, info};
 
-use crate::ollama::OllamaClient;
+use crate::config::EmbeddingConfig;
+use crate::embedder::Embedder;
 use crate::qdrant::{QdrantStore, DOMAIN_OBJECTS_COLLECTION};
 
 /// Pipeline for embedding domain objects.
 pub struct DomainObjectPipeline {
-    ollama: OllamaClient,
+    embedder: Arc<dyn Embedder>,
     qdrant: QdrantStore,
 }
 
@@ -27,15 +29,20 @@ pub struct DomainObjectSearchResult {
 
 impl DomainObjectPipeline {
     /// Create a new pipeline with the given clients.
-    pub fn new(ollama: OllamaClient, qdrant: QdrantStore) -> Self {
-        Self { ollama, qdrant }
+    pub fn new(embedder: Arc<dyn Embedder>, qdrant: QdrantStore) -> Self {
+        Self { embedder, qdrant }
     }
 
-    /// Create a pipeline with default client configurations.
+    /// Create a pipeline with the default configuration (Ollama, nomic-embed-text).
     pub fn default_pipeline() -> Result<Self> {
+        Self::from_config(&EmbeddingConfig::default())
+    }
+
+    /// Create a pipeline for the given embedding configuration.
+    pub fn from_config(config: &EmbeddingConfig) -> Result<Self> {
         Ok(Self {
-            ollama: OllamaClient::default_client(),
-            qdrant: QdrantStore::default_store()?,
+            embedder: config.embedder(),
+            qdrant: config.store()?,
         })
     }
 
@@ -51,9 +58,9 @@ impl DomainObjectPipeline {
         context_name: &str,
         description: &str,
     ) -> Result<usize> {
-        let embed_text = format!("{} ({} in {}): {}", name, object_type, context_name, description);
+        let embed_text = crate::queue::domain_object_text(name, object_type, context_name, description);
 
-        let embedding = self.ollama.embed(&embed_text).await
+        let embedding = self.embedder.embed(&embed_text).await
             .context("Failed to generate domain object embedding")?;
         let dim = embedding.len();
 
@@ -82,7 +89,7 @@ impl DomainObjectPipeline {
         project_id: &str,
         top_k: u64,
     ) -> Result<Vec<DomainObjectSearchResult>> {
-        let query_vector = self.ollama.embed(query).await
+        let query_vector = self.embedder.embed(query).await
             .context("Failed to embed domain object search query")?;
 
         debug!(query, dim = query_vector.len(), "Generated domain object query embedding");
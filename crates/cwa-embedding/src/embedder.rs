@@ -0,0 +1,54 @@
+//! Embedding provider abstraction.
+//!
+//! Every pipeline embeds text through an [`Embedder`], so the provider
+//! (Ollama, an OpenAI-compatible server, or the hashing fallback) is chosen
+//! by `.cwa/embedding.json` rather than hard-wired.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+
+/// Requests in flight at once when embedding a batch.
+pub const EMBED_CONCURRENCY: usize = 4;
+
+/// Turns text into a fixed-size vector.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Provider and model, e.g. `ollama/nomic-embed-text`.
+    fn name(&self) -> String;
+
+    /// Length of the vectors this embedder produces.
+    fn dimension(&self) -> usize;
+
+    /// Generate an embedding vector for the given text.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Generate one vector per text, in order.
+    ///
+    /// The default sends up to [`EMBED_CONCURRENCY`] single requests at a
+    /// time; providers with a batch endpoint override it.
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        futures::stream::iter(0..texts.len())
+            .map(|i| self.embed(&texts[i]))
+            .buffered(EMBED_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+}
+
+/// Fail with a clear message when a provider returns vectors of a size other
+/// than the configured one.
+pub(crate) fn check_dimension(embedder: &dyn Embedder, vector: &[f32]) -> Result<()> {
+    if vector.len() != embedder.dimension() {
+        anyhow::bail!(
+            "{} returned {}-dimensional vectors but the configured dimension is {}. \
+             Set \"dimension\": {} in {}",
+            embedder.name(),
+            vector.len(),
+            embedder.dimension(),
+            vector.len(),
+            crate::config::CONFIG_FILE
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Lexical hashing fallback for when no embedding model is available.
+//!
+//! This is not a language model. It hashes words and character trigrams
+//! into a fixed number of buckets ("feature hashing") and L2-normalizes the
+//! result, so texts are close only when they share words or word fragments;
+//! synonyms and paraphrases are not matched. In exchange it is
+//! deterministic, instant, and works offline, in CI, and on machines
+//! without Ollama.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::embedder::Embedder;
+
+/// Default vector size of hashed embeddings.
+pub const DEFAULT_HASHING_DIM: usize = 384;
+
+/// Weight of a character trigram relative to a whole word.
+const TRIGRAM_WEIGHT: f32 = 0.5;
+
+/// Feature used for text without words, so its vector is never all zeros
+/// (a zero vector has no cosine similarity to anything).
+const EMPTY_FEATURE: &[u8] = b"\0empty";
+
+/// Feature-hashing embedder.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimension: usize,
+}
+
+impl HashingEmbedder {
+    /// Create an embedder producing vectors of `dimension` values.
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension: dimension.max(1) }
+    }
+
+    /// Embed synchronously.
+    ///
+    /// Text without any word, such as an empty or whitespace-only string,
+    /// gets a fixed placeholder unit vector.
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimension];
+
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+        {
+            self.add_feature(&mut vector, word.as_bytes(), 1.0);
+
+            let padded: Vec<char> = format!("^{}$", word).chars().collect();
+            for trigram in padded.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                self.add_feature(&mut vector, trigram.as_bytes(), TRIGRAM_WEIGHT);
+            }
+        }
+
+        let mut norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            // No words, or features that cancelled out exactly
+            vector.iter_mut().for_each(|v| *v = 0.0);
+            self.add_feature(&mut vector, EMPTY_FEATURE, 1.0);
+            norm = 1.0;
+        }
+        vector.iter_mut().for_each(|v| *v /= norm);
+        vector
+    }
+
+    fn add_feature(&self, vector: &mut [f32], feature: &[u8], weight: f32) {
+        let hash = fnv1a(feature);
+        let index = (hash % self.dimension as u64) as usize;
+        // The top bit picks the sign so that collisions tend to cancel out.
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[index] += sign * weight;
+    }
+}
+
+/// 64-bit FNV-1a, stable across platforms and releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    fn name(&self) -> String {
+        "hashing".to_string()
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.embed_text(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn vectors_are_normalized_and_deterministic() {
+        let embedder = HashingEmbedder::new(DEFAULT_HASHING_DIM);
+        let a = embedder.embed_text("User sessions expire after 30 minutes");
+        assert_eq!(a.len(), DEFAULT_HASHING_DIM);
+        assert!((cosine(&a, &a) - 1.0).abs() < 1e-5);
+        assert_eq!(a, embedder.embed_text("user SESSIONS expire after 30 minutes!"));
+    }
+
+    #[test]
+    fn text_without_words_gets_a_unit_placeholder() {
+        let embedder = HashingEmbedder::new(DEFAULT_HASHING_DIM);
+        let empty = embedder.embed_text("");
+        assert!((cosine(&empty, &empty) - 1.0).abs() < 1e-5);
+        assert_eq!(empty, embedder.embed_text("  \n\t "));
+        assert_eq!(empty, embedder.embed_text("?!"));
+    }
+
+    #[test]
+    fn related_text_scores_higher() {
+        let embedder = HashingEmbedder::new(DEFAULT_HASHING_DIM);
+        let query = embedder.embed_text("session expiry");
+        let related = embedder.embed_text("Sessions expire after 30 minutes of inactivity");
+        let unrelated = embedder.embed_text("Invoices are rendered as PDF");
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+    }
+}
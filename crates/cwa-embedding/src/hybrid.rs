@@ -1,15 +1,20 @@
-//! Hybrid vector search combining dense similarity and keyword filtering.
+//! Hybrid search fusing dense similarity with a BM25 lexical index.
 //!
-//! Uses Reciprocal Rank Fusion (RRF) to merge ranked results from:
+//! Every requested collection is searched by two legs:
 //! - Dense vector search (semantic similarity via Qdrant)
-//! - Keyword text filter (exact substring match on payload fields)
+//! - BM25 over the project's lexical index in Redis (exact identifiers,
+//!   error strings, glossary terms), for the collections that have one
+//!
+//! The ranked lists are fused with Reciprocal Rank Fusion (RRF) or a
+//! weighted average of normalized scores. Each leg has a weight, so callers
+//! can lean towards exact matches or meaning; a weight of 0 disables a leg.
 //!
 //! ## RRF Formula
 //! ```text
-//! score(d) = Σ  1 / (k + rank(d, list_i))
+//! score(d) = Σ  w_i / (k + rank(d, list_i))
 //!           i
 //! ```
-//! where `k = 60` (standard default).
+//! where `k = 60` (standard default) and `w_i` is the weight of list `i`'s leg.
 //!
 //! ## Multi-collection search
 //! Results are fetched from all requested collections and fused into a
@@ -17,10 +22,13 @@
 
 use anyhow::Result;
 use std::collections::HashMap;
-use tracing::debug;
+use tracing::{debug, warn};
+
+use cwa_db::queries::lexical;
+use cwa_db::DbPool;
 
-use crate::ollama::OllamaClient;
-use crate::qdrant::{QdrantStore, VectorSearchResult};
+use crate::embedder::Embedder;
+use crate::qdrant::QdrantStore;
 
 /// RRF constant (standard value from the original paper).
 const RRF_K: f64 = 60.0;
@@ -30,26 +38,42 @@ const RRF_K: f64 = 60.0;
 pub struct HybridSearchResult {
     /// Entity ID from the payload.
     pub id: String,
-    /// The Qdrant collection this result came from.
+    /// The collection this result came from.
     pub collection: String,
-    /// Final RRF-fused score (higher is more relevant).
+    /// Final fused score (higher is more relevant).
     pub score: f64,
-    /// Full payload from Qdrant.
+    /// Full payload of the matching entity.
     pub payload: serde_json::Value,
 }
 
 /// Algorithm used to fuse ranked lists from multiple search strategies.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub enum FusionAlgo {
     /// Reciprocal Rank Fusion — robust, order-based fusion.
+    #[default]
     Rrf,
-    /// Simple average of normalized scores.
+    /// Weighted average of normalized scores.
     ScoreAverage,
 }
 
-impl Default for FusionAlgo {
-    fn default() -> Self {
-        Self::Rrf
+impl FusionAlgo {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rrf => "rrf",
+            Self::ScoreAverage => "score_average",
+        }
+    }
+}
+
+impl std::str::FromStr for FusionAlgo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rrf" => Ok(Self::Rrf),
+            "score_average" | "average" => Ok(Self::ScoreAverage),
+            other => Err(format!("Unknown fusion '{}': expected rrf or score_average", other)),
+        }
     }
 }
 
@@ -63,9 +87,14 @@ pub struct HybridSearchRequest<'a> {
     /// Collections to search. If empty, searches all known collections.
     pub collections: Vec<String>,
     /// Project to filter results by (matches payload `project_id` field).
+    /// The lexical leg only runs when it is set.
     pub project_id: Option<&'a str>,
     /// Fusion algorithm to use.
     pub fusion: FusionAlgo,
+    /// Weight of the dense (semantic) leg; 0 disables it.
+    pub dense_weight: f64,
+    /// Weight of the lexical (BM25) leg; 0 disables it.
+    pub lexical_weight: f64,
 }
 
 impl<'a> HybridSearchRequest<'a> {
@@ -77,6 +106,8 @@ impl<'a> HybridSearchRequest<'a> {
             collections: Vec::new(),
             project_id: None,
             fusion: FusionAlgo::Rrf,
+            dense_weight: 1.0,
+            lexical_weight: 1.0,
         }
     }
 
@@ -89,210 +120,244 @@ impl<'a> HybridSearchRequest<'a> {
         self.project_id = Some(project_id);
         self
     }
+
+    pub fn with_fusion(mut self, fusion: FusionAlgo) -> Self {
+        self.fusion = fusion;
+        self
+    }
+
+    /// Set the weights of the dense and lexical legs (negative counts as 0).
+    pub fn with_weights(mut self, dense: f64, lexical: f64) -> Self {
+        self.dense_weight = dense.max(0.0);
+        self.lexical_weight = lexical.max(0.0);
+        self
+    }
 }
 
-/// Perform hybrid search across multiple Qdrant collections.
+/// One ranked list: a leg searched in one collection.
+struct Leg {
+    collection: String,
+    weight: f64,
+    hits: Vec<Hit>,
+}
+
+struct Hit {
+    id: String,
+    score: f64,
+    payload: serde_json::Value,
+}
+
+/// Perform hybrid search across multiple collections.
 ///
-/// 1. Embeds the query via Ollama (dense vector)
-/// 2. Runs dense similarity search in every requested collection
-/// 3. Fuses ranked results using RRF
+/// 1. Embeds the query and runs dense similarity search in every requested
+///    collection (skipped, with a warning, if the embedder is down and the
+///    lexical leg can still answer)
+/// 2. Runs BM25 over the project's lexical index for every requested
+///    collection that has one
+/// 3. Fuses the weighted ranked lists with `req.fusion`
 /// 4. Returns the top-k de-duplicated results
 pub async fn hybrid_search(
-    ollama: &OllamaClient,
+    embedder: &dyn Embedder,
     qdrant: &QdrantStore,
+    db: &DbPool,
     req: HybridSearchRequest<'_>,
 ) -> Result<Vec<HybridSearchResult>> {
-    // Generate dense query embedding
-    let query_vec = ollama.embed(req.query).await?;
-    debug!(query = req.query, dim = query_vec.len(), "Embedded hybrid search query");
-
-    let fetch_k = (req.top_k * 3).max(20) as u64;
-
-    // Collect ranked results per collection
-    let mut all_ranked: Vec<Vec<VectorSearchResult>> = Vec::new();
+    let fetch_k = (req.top_k * 3).max(20);
+    let lexical_project = req.project_id.filter(|_| req.lexical_weight > 0.0);
+    let mut legs: Vec<Leg> = Vec::new();
+
+    if req.dense_weight > 0.0 {
+        match embedder.embed(req.query).await {
+            Ok(query_vec) => {
+                debug!(query = req.query, dim = query_vec.len(), "Embedded hybrid search query");
+                for collection in &req.collections {
+                    let results = if let Some(pid) = req.project_id {
+                        qdrant
+                            .search_filtered(collection, query_vec.clone(), fetch_k as u64, pid)
+                            .await
+                            .unwrap_or_default()
+                    } else {
+                        qdrant
+                            .search(collection, query_vec.clone(), fetch_k as u64)
+                            .await
+                            .unwrap_or_default()
+                    };
+                    debug!(collection, count = results.len(), "Dense search results");
+
+                    legs.push(Leg {
+                        collection: collection.clone(),
+                        weight: req.dense_weight,
+                        hits: results
+                            .into_iter()
+                            .map(|r| Hit {
+                                id: entity_id(&r.payload, &r.id),
+                                score: r.score as f64,
+                                payload: r.payload,
+                            })
+                            .collect(),
+                    });
+                }
+            }
+            Err(e) if lexical_project.is_some() => {
+                warn!(error = %format!("{:#}", e), "Embedder unavailable, using lexical search only");
+            }
+            Err(e) => return Err(e),
+        }
+    }
 
-    for collection in &req.collections {
-        let results = if let Some(pid) = req.project_id {
-            qdrant
-                .search_filtered(collection, query_vec.clone(), fetch_k, pid)
-                .await
-                .unwrap_or_default()
-        } else {
-            qdrant
-                .search(collection, query_vec.clone(), fetch_k)
+    if let Some(pid) = lexical_project {
+        for collection in req.collections.iter().filter(|c| lexical::COLLECTIONS.contains(&c.as_str())) {
+            let hits = lexical::search(db, pid, collection, req.query, fetch_k)
                 .await
-                .unwrap_or_default()
-        };
-
-        debug!(
-            collection,
-            count = results.len(),
-            "Dense search results"
-        );
-
-        // Also run keyword filter search and interleave
-        let keyword_results = keyword_filter(&results, req.query);
-        let merged = interleave_by_score(results, keyword_results);
-
-        all_ranked.push(merged);
+                .map_err(|e| anyhow::anyhow!("Lexical search failed: {}", e))?;
+            debug!(collection, count = hits.len(), "Lexical search results");
+
+            // BM25 scores are unbounded; scale them so the best match is 1.
+            let max = hits.iter().map(|h| h.score).fold(0.0, f64::max);
+            legs.push(Leg {
+                collection: collection.clone(),
+                weight: req.lexical_weight,
+                hits: hits
+                    .into_iter()
+                    .map(|h| Hit {
+                        score: if max > 0.0 { h.score / max } else { 0.0 },
+                        id: h.id,
+                        payload: h.payload,
+                    })
+                    .collect(),
+            });
+        }
     }
 
-    // Apply fusion
-    let fused = match req.fusion {
-        FusionAlgo::Rrf => rrf_fuse(&all_ranked, &req.collections, req.top_k),
-        FusionAlgo::ScoreAverage => score_average_fuse(&all_ranked, &req.collections, req.top_k),
-    };
-
-    Ok(fused)
+    Ok(match req.fusion {
+        FusionAlgo::Rrf => rrf_fuse(&legs, req.top_k),
+        FusionAlgo::ScoreAverage => score_average_fuse(&legs, req.top_k),
+    })
 }
 
-/// Filter results whose payload contains the query as a substring (case-insensitive).
-/// Returns a re-ranked subset boosted by keyword match.
-fn keyword_filter(results: &[VectorSearchResult], query: &str) -> Vec<VectorSearchResult> {
-    let q = query.to_lowercase();
-    results
-        .iter()
-        .filter(|r| {
-            r.payload
-                .as_object()
-                .map(|obj| {
-                    obj.values().any(|v| {
-                        v.as_str()
-                            .map(|s| s.to_lowercase().contains(&q))
-                            .unwrap_or(false)
-                    })
-                })
-                .unwrap_or(false)
-        })
-        .cloned()
-        .collect()
+/// The entity ID from a payload, falling back to the point ID.
+fn entity_id(payload: &serde_json::Value, point_id: &str) -> String {
+    payload
+        .get("id")
+        .and_then(|v| v.as_str())
+        .unwrap_or(point_id)
+        .to_string()
 }
 
-/// Interleave two ranked lists, removing duplicates (IDs matched via payload `id` field).
-fn interleave_by_score(
-    primary: Vec<VectorSearchResult>,
-    secondary: Vec<VectorSearchResult>,
-) -> Vec<VectorSearchResult> {
-    let mut seen: HashMap<String, bool> = HashMap::new();
-    let mut result = Vec::with_capacity(primary.len());
-
-    let extract_id = |r: &VectorSearchResult| -> String {
-        r.payload
-            .get("id")
-            .and_then(|v| v.as_str())
-            .unwrap_or(&r.id)
-            .to_string()
-    };
-
-    // Boost keyword hits by bumping score
-    let secondary_ids: std::collections::HashSet<String> =
-        secondary.iter().map(extract_id).collect();
-
-    for mut r in primary {
-        let id = extract_id(&r);
-        if secondary_ids.contains(&id) {
-            r.score = (r.score * 1.2).min(1.0); // keyword boost
+/// Weighted Reciprocal Rank Fusion across all legs.
+fn rrf_fuse(legs: &[Leg], top_k: usize) -> Vec<HybridSearchResult> {
+    let mut scores: HashMap<&str, HybridSearchResult> = HashMap::new();
+
+    for leg in legs {
+        for (rank, hit) in leg.hits.iter().enumerate() {
+            let rrf_score = leg.weight / (RRF_K + (rank as f64 + 1.0));
+            scores
+                .entry(hit.id.as_str())
+                .or_insert_with(|| new_result(leg, hit))
+                .score += rrf_score;
         }
-        seen.insert(id, true);
-        result.push(r);
     }
 
-    // Append secondary results not already in primary
-    for r in secondary {
-        let id = extract_id(&r);
-        if !seen.contains_key(&id) {
-            result.push(r);
+    ranked(scores.into_values().collect(), top_k)
+}
+
+/// Weighted average of scores across the legs of each entity's collection.
+///
+/// A leg that did not return the entity contributes 0, so a document found
+/// by both legs outranks one that only one of them found.
+fn score_average_fuse(legs: &[Leg], top_k: usize) -> Vec<HybridSearchResult> {
+    let mut weights: HashMap<&str, f64> = HashMap::new();
+    for leg in legs {
+        *weights.entry(leg.collection.as_str()).or_default() += leg.weight;
+    }
+
+    let mut scores: HashMap<&str, HybridSearchResult> = HashMap::new();
+    for leg in legs {
+        let total = weights[leg.collection.as_str()];
+        for hit in &leg.hits {
+            scores
+                .entry(hit.id.as_str())
+                .or_insert_with(|| new_result(leg, hit))
+                .score += leg.weight * hit.score / total;
         }
     }
 
-    // Re-sort by boosted score
-    result.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    result
+    ranked(scores.into_values().collect(), top_k)
 }
 
-/// Reciprocal Rank Fusion across multiple ranked lists.
-fn rrf_fuse(
-    ranked_lists: &[Vec<VectorSearchResult>],
-    collections: &[String],
-    top_k: usize,
-) -> Vec<HybridSearchResult> {
-    let mut scores: HashMap<String, (f64, usize, serde_json::Value)> = HashMap::new();
-
-    for (list_idx, list) in ranked_lists.iter().enumerate() {
-        for (rank, item) in list.iter().enumerate() {
-            let id = item
-                .payload
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or(&item.id)
-                .to_string();
-
-            let rrf_score = 1.0 / (RRF_K + (rank as f64 + 1.0));
-
-            let entry = scores
-                .entry(id)
-                .or_insert((0.0, list_idx, item.payload.clone()));
-            entry.0 += rrf_score;
-        }
+fn new_result(leg: &Leg, hit: &Hit) -> HybridSearchResult {
+    HybridSearchResult {
+        id: hit.id.clone(),
+        collection: leg.collection.clone(),
+        score: 0.0,
+        payload: hit.payload.clone(),
     }
+}
 
-    let mut results: Vec<HybridSearchResult> = scores
-        .into_iter()
-        .map(|(id, (score, col_idx, payload))| HybridSearchResult {
-            id,
-            collection: collections
-                .get(col_idx)
-                .cloned()
-                .unwrap_or_default(),
-            score,
-            payload,
-        })
-        .collect();
-
-    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+fn ranked(mut results: Vec<HybridSearchResult>, top_k: usize) -> Vec<HybridSearchResult> {
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
     results.truncate(top_k);
     results
 }
 
-/// Simple score-average fusion.
-fn score_average_fuse(
-    ranked_lists: &[Vec<VectorSearchResult>],
-    collections: &[String],
-    top_k: usize,
-) -> Vec<HybridSearchResult> {
-    let mut scores: HashMap<String, (f64, usize, usize, serde_json::Value)> = HashMap::new();
-
-    for (list_idx, list) in ranked_lists.iter().enumerate() {
-        for item in list {
-            let id = item
-                .payload
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or(&item.id)
-                .to_string();
-
-            let entry = scores
-                .entry(id)
-                .or_insert((0.0, 0, list_idx, item.payload.clone()));
-            entry.0 += item.score as f64;
-            entry.1 += 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(collection: &str, weight: f64, hits: &[(&str, f64)]) -> Leg {
+        Leg {
+            collection: collection.to_string(),
+            weight,
+            hits: hits
+                .iter()
+                .map(|(id, score)| Hit { id: id.to_string(), score: *score, payload: serde_json::json!({ "id": id }) })
+                .collect(),
         }
     }
 
-    let mut results: Vec<HybridSearchResult> = scores
-        .into_iter()
-        .map(|(id, (total, count, col_idx, payload))| HybridSearchResult {
-            id,
-            collection: collections
-                .get(col_idx)
-                .cloned()
-                .unwrap_or_default(),
-            score: total / count as f64,
-            payload,
-        })
-        .collect();
-
-    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    results.truncate(top_k);
-    results
+    fn ids(results: &[HybridSearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn weights_decide_which_leg_wins() {
+        let dense = [("semantic", 0.9), ("both", 0.8)];
+        let lexical = [("exact", 1.0), ("both", 0.5)];
+
+        let even = rrf_fuse(&[leg("m", 1.0, &dense), leg("m", 1.0, &lexical)], 10);
+        assert_eq!(even[0].id, "both");
+
+        let lexical_heavy = rrf_fuse(&[leg("m", 0.5, &dense), leg("m", 2.0, &lexical)], 10);
+        assert_eq!(ids(&lexical_heavy)[1..], ["exact", "semantic"]);
+
+        let dense_heavy = rrf_fuse(&[leg("m", 2.0, &dense), leg("m", 0.5, &lexical)], 10);
+        assert_eq!(ids(&dense_heavy)[1..], ["semantic", "exact"]);
+
+        let dense_only = rrf_fuse(&[leg("m", 1.0, &dense)], 1);
+        assert_eq!(ids(&dense_only), ["semantic"]);
+    }
+
+    #[test]
+    fn score_average_counts_missing_legs_as_zero() {
+        let results = score_average_fuse(
+            &[leg("m", 1.0, &[("a", 0.9), ("b", 0.6)]), leg("m", 1.0, &[("b", 1.0)]), leg("files", 1.0, &[("f", 0.7)])],
+            10,
+        );
+        assert_eq!(ids(&results), ["b", "f", "a"]);
+        assert!((results[0].score - 0.8).abs() < 1e-9);
+        assert!((results[2].score - 0.45).abs() < 1e-9);
+        assert_eq!(results[1].collection, "files");
+    }
+
+    #[test]
+    fn fusion_parses_from_its_name() {
+        assert_eq!("rrf".parse::<FusionAlgo>(), Ok(FusionAlgo::Rrf));
+        assert_eq!(FusionAlgo::ScoreAverage.as_str().parse::<FusionAlgo>(), Ok(FusionAlgo::ScoreAverage));
+        assert!("max".parse::<FusionAlgo>().is_err());
+    }
 }
@@ -1,20 +1,35 @@
 //! # CWA Embedding
 //!
-//! Vector embeddings via Ollama and semantic search via Qdrant for CWA.
+//! Vector embeddings (Ollama, OpenAI-compatible servers, or an offline
+//! lexical hashing fallback) and semantic search via Qdrant for CWA.
 //!
 //! Provides memory indexing, embedding generation, and similarity search.
 
+pub mod cache;
+pub mod code;
+pub mod config;
+pub mod embedder;
+pub mod hashing;
 pub mod hybrid;
 pub mod ollama;
+pub mod openai;
 pub mod qdrant;
+pub mod queue;
 pub mod memory;
 pub mod search;
 pub mod observation;
 pub mod domain_object;
 
 pub use hybrid::{FusionAlgo, HybridSearchRequest, HybridSearchResult, hybrid_search};
+pub use cache::CachedEmbedder;
+pub use code::{CodeIndexReport, CodeIndexer, CodeSearchResult, search_code};
+pub use config::{EmbeddingConfig, Provider};
+pub use embedder::Embedder;
+pub use hashing::HashingEmbedder;
 pub use ollama::OllamaClient;
+pub use openai::OpenAiClient;
 pub use qdrant::{QdrantStore, FILES_COLLECTION};
+pub use queue::{BackfillReport, EmbeddingQueue, ProgressFn, ReindexReport, run_backfill_worker};
 pub use memory::{MemoryPipeline, MemoryType, AddMemoryResult};
 pub use search::{SemanticSearch, SemanticSearchResult};
 pub use observation::{ObservationPipeline, AddObservationResult, ObservationSearchResult};
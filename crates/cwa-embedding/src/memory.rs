@@ -1,15 +1,19 @@
 //! Memory indexing pipeline.
 //!
-//! Handles storing memories with embeddings - creates the SQLite record,
-//! generates an embedding via Ollama, and upserts into Qdrant.
+//! Handles storing memories with embeddings - creates the Redis record,
+//! generates an embedding via the configured embedder, and upserts into Qdrant.
+//! Memories that cannot be embedded yet are queued (see [`crate::queue`]).
 
 use anyhow::{Context, Result};
-use tracing::{debug, info};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use cwa_db::DbPool;
-use crate::ollama::OllamaClient;
+use crate::config::EmbeddingConfig;
+use crate::embedder::Embedder;
 use crate::qdrant::{QdrantStore, MEMORIES_COLLECTION};
+use crate::queue::{self, EmbeddingQueue};
 
 /// Memory entry types.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -49,7 +53,7 @@ impl MemoryType {
 
 /// Pipeline for adding memories with embeddings.
 pub struct MemoryPipeline {
-    ollama: OllamaClient,
+    embedder: Arc<dyn Embedder>,
     qdrant: QdrantStore,
 }
 
@@ -57,24 +61,33 @@ pub struct MemoryPipeline {
 #[derive(Debug)]
 pub struct AddMemoryResult {
     pub id: String,
-    pub embedding_dim: usize,
+    /// Vector size, or `None` if the memory was queued for embedding.
+    pub embedding_dim: Option<usize>,
 }
 
 impl MemoryPipeline {
     /// Create a new memory pipeline with the given clients.
-    pub fn new(ollama: OllamaClient, qdrant: QdrantStore) -> Self {
-        Self { ollama, qdrant }
+    pub fn new(embedder: Arc<dyn Embedder>, qdrant: QdrantStore) -> Self {
+        Self { embedder, qdrant }
     }
 
-    /// Create a pipeline with default client configurations.
+    /// Create a pipeline with the default configuration (Ollama, nomic-embed-text).
     pub fn default_pipeline() -> Result<Self> {
+        Self::from_config(&EmbeddingConfig::default())
+    }
+
+    /// Create a pipeline for the given embedding configuration.
+    pub fn from_config(config: &EmbeddingConfig) -> Result<Self> {
         Ok(Self {
-            ollama: OllamaClient::default_client(),
-            qdrant: QdrantStore::default_store()?,
+            embedder: config.embedder(),
+            qdrant: config.store()?,
         })
     }
 
-    /// Add a memory: store in SQLite, embed, and upsert to Qdrant.
+    /// Add a memory: store in Redis, then embed and upsert to Qdrant.
+    ///
+    /// The memory is kept even when embedding fails; it stays queued as
+    /// `embedding_pending` and `embedding_dim` is `None`.
     pub async fn add_memory(
         &self,
         db: &DbPool,
@@ -85,31 +98,23 @@ impl MemoryPipeline {
     ) -> Result<AddMemoryResult> {
         let id = Uuid::new_v4().to_string();
 
-        // Generate embedding
-        let embedding = self.ollama.embed(content).await
-            .context("Failed to generate embedding")?;
-        let dim = embedding.len();
-
-        // Store in SQLite
-        let embedding_id = format!("qdrant:{}", id);
-        store_memory_in_db(db, &id, project_id, content, entry_type, context, &embedding_id).await?;
-
-        // Upsert to Qdrant
-        let payload = serde_json::json!({
-            "id": id,
-            "project_id": project_id,
-            "content": content,
-            "entry_type": entry_type.as_str(),
-            "context": context.unwrap_or(""),
-            "created_at": chrono::Utc::now().to_rfc3339(),
-        });
-
-        self.qdrant.upsert(MEMORIES_COLLECTION, &id, embedding, payload).await
-            .context("Failed to upsert to Qdrant")?;
+        store_memory_in_db(db, &id, project_id, content, entry_type, context).await?;
+        queue::mark_pending(db, project_id, queue::MEMORY, &id).await?;
+
+        let row = cwa_db::queries::memory::get_memory_entry(db, project_id, &id).await
+            .map_err(|e| anyhow::anyhow!("Failed to load memory: {}", e))?
+            .context("Memory just created not found")?;
+        let embedding_dim = match self.queue().index_memory(db, &row).await {
+            Ok(dim) => Some(dim),
+            Err(e) => {
+                warn!(id = %id, error = %format!("{:#}", e), "Memory queued for embedding");
+                None
+            }
+        };
 
-        info!(id = %id, entry_type = entry_type.as_str(), dim, "Memory added");
+        info!(id = %id, entry_type = entry_type.as_str(), dim = ?embedding_dim, "Memory added");
 
-        Ok(AddMemoryResult { id, embedding_dim: dim })
+        Ok(AddMemoryResult { id, embedding_dim })
     }
 
     /// Import existing memory entries from the old `memory` table to the new `memories` table with embeddings.
@@ -129,7 +134,7 @@ impl MemoryPipeline {
             }
 
             // Generate embedding for the content
-            let embedding = match self.ollama.embed(&entry.content).await {
+            let embedding = match self.embedder.embed(&entry.content).await {
                 Ok(e) => e,
                 Err(e) => {
                     debug!(id = %entry.id, error = %e, "Skipping memory (embedding failed)");
@@ -139,10 +144,9 @@ impl MemoryPipeline {
 
             // Store in memories table
             let memory_id = Uuid::new_v4().to_string();
-            let embedding_id = format!("qdrant:{}", memory_id);
             store_memory_in_db(
                 db, &memory_id, project_id, &entry.content,
-                MemoryType::Fact, entry.context.as_deref(), &embedding_id,
+                MemoryType::Fact, entry.context.as_deref(),
             ).await?;
 
             // Upsert to Qdrant
@@ -182,9 +186,13 @@ impl MemoryPipeline {
         Ok(removed.len())
     }
 
-    /// Get a reference to the Ollama client.
-    pub fn ollama(&self) -> &OllamaClient {
-        &self.ollama
+    /// Get a reference to the embedder.
+    pub fn embedder(&self) -> &dyn Embedder {
+        self.embedder.as_ref()
+    }
+
+    fn queue(&self) -> EmbeddingQueue {
+        EmbeddingQueue::new(self.embedder.clone(), self.qdrant.clone())
     }
 
     /// Get a reference to the Qdrant store.
@@ -200,18 +208,18 @@ struct LegacyMemoryEntry {
     context: Option<String>,
 }
 
-/// Store a memory in the new `memories` table (Redis backend).
+/// Store a memory in the new `memories` table (Redis backend). The context
+/// is kept as the entry's tags so a reindex can restore it.
 async fn store_memory_in_db(
     db: &DbPool,
     id: &str,
     project_id: &str,
     content: &str,
     entry_type: MemoryType,
-    _context: Option<&str>,
-    _embedding_id: &str,
+    context: Option<&str>,
 ) -> Result<()> {
     cwa_db::queries::memory::create_memory_entry(
-        db, id, project_id, entry_type.as_str(), content, "normal", None,
+        db, id, project_id, entry_type.as_str(), content, "normal", context,
     ).await
     .map_err(|e| anyhow::anyhow!("Failed to store memory: {}", e))
 }
@@ -1,19 +1,23 @@
 //! Observation embedding pipeline.
 //!
-//! Handles storing observations with embeddings - creates the SQLite record,
-//! generates an embedding via Ollama, and upserts into Qdrant.
+//! Handles storing observations with embeddings - creates the Redis record,
+//! generates an embedding via the configured embedder, and upserts into Qdrant.
+//! Observations that cannot be embedded yet are queued (see [`crate::queue`]).
 
 use anyhow::{Context, Result};
-use tracing::{debug, info};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use cwa_db::DbPool;
-use crate::ollama::OllamaClient;
+use crate::config::EmbeddingConfig;
+use crate::embedder::Embedder;
 use crate::qdrant::{QdrantStore, OBSERVATIONS_COLLECTION};
+use crate::queue::{self, EmbeddingQueue};
 
 /// Pipeline for adding observations with embeddings.
 pub struct ObservationPipeline {
-    ollama: OllamaClient,
+    embedder: Arc<dyn Embedder>,
     qdrant: QdrantStore,
 }
 
@@ -21,7 +25,8 @@ pub struct ObservationPipeline {
 #[derive(Debug)]
 pub struct AddObservationResult {
     pub id: String,
-    pub embedding_dim: usize,
+    /// Vector size, or `None` if the observation was queued for embedding.
+    pub embedding_dim: Option<usize>,
 }
 
 /// A search result from observation vector search.
@@ -36,19 +41,28 @@ pub struct ObservationSearchResult {
 
 impl ObservationPipeline {
     /// Create a new observation pipeline with the given clients.
-    pub fn new(ollama: OllamaClient, qdrant: QdrantStore) -> Self {
-        Self { ollama, qdrant }
+    pub fn new(embedder: Arc<dyn Embedder>, qdrant: QdrantStore) -> Self {
+        Self { embedder, qdrant }
     }
 
-    /// Create a pipeline with default client configurations.
+    /// Create a pipeline with the default configuration (Ollama, nomic-embed-text).
     pub fn default_pipeline() -> Result<Self> {
+        Self::from_config(&EmbeddingConfig::default())
+    }
+
+    /// Create a pipeline for the given embedding configuration.
+    pub fn from_config(config: &EmbeddingConfig) -> Result<Self> {
         Ok(Self {
-            ollama: OllamaClient::default_client(),
-            qdrant: QdrantStore::default_store()?,
+            embedder: config.embedder(),
+            qdrant: config.store()?,
         })
     }
 
-    /// Add an observation: store in SQLite, embed, and upsert to Qdrant.
+    /// Add an observation: store in Redis, then embed and upsert to Qdrant.
+    ///
+    /// The observation is kept even when embedding fails; it stays queued
+    /// as `embedding_pending` and `embedding_dim` is `None`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn add_observation(
         &self,
         db: &DbPool,
@@ -65,57 +79,36 @@ impl ObservationPipeline {
     ) -> Result<AddObservationResult> {
         let id = Uuid::new_v4().to_string();
 
-        // Build embedding text from title + narrative + facts
-        let mut embed_text = title.to_string();
-        if let Some(n) = narrative {
-            embed_text.push_str(". ");
-            embed_text.push_str(n);
-        }
-        if !facts.is_empty() {
-            embed_text.push_str(". ");
-            embed_text.push_str(&facts.join(", "));
-        }
-
-        // Generate embedding
-        let embedding = self.ollama.embed(&embed_text).await
-            .context("Failed to generate observation embedding")?;
-        let dim = embedding.len();
-
         // Serialize JSON arrays
         let facts_json = if facts.is_empty() { None } else { Some(serde_json::to_string(facts)?) };
         let concepts_json = if concepts.is_empty() { None } else { Some(serde_json::to_string(concepts)?) };
         let files_mod_json = if files_modified.is_empty() { None } else { Some(serde_json::to_string(files_modified)?) };
         let files_read_json = if files_read.is_empty() { None } else { Some(serde_json::to_string(files_read)?) };
 
-        // Store in SQLite
-        let embedding_id = format!("qdrant:{}", id);
+        // Store first, so the observation survives an unavailable embedder
         cwa_db::queries::observations::create_observation(
             db, &id, project_id, session_id, obs_type, title, narrative,
             facts_json.as_deref(), concepts_json.as_deref(),
             files_mod_json.as_deref(), files_read_json.as_deref(),
             None, None, confidence,
         ).await.map_err(|e| anyhow::anyhow!("Failed to store observation: {}", e))?;
+        queue::mark_pending(db, project_id, queue::OBSERVATION, &id).await?;
+
+        let row = cwa_db::queries::observations::get_observation(db, &id).await
+            .map_err(|e| anyhow::anyhow!("Failed to load observation: {}", e))?
+            .context("Observation just created not found")?;
+        let queue = EmbeddingQueue::new(self.embedder.clone(), self.qdrant.clone());
+        let embedding_dim = match queue.index_observation(db, &row).await {
+            Ok(dim) => Some(dim),
+            Err(e) => {
+                warn!(id = %id, error = %format!("{:#}", e), "Observation queued for embedding");
+                None
+            }
+        };
 
-        // Update embedding ID
-        cwa_db::queries::observations::update_embedding_id(db, &id, &embedding_id).await
-            .map_err(|e| anyhow::anyhow!("Failed to update embedding ID: {}", e))?;
-
-        // Upsert to Qdrant
-        let payload = serde_json::json!({
-            "id": id,
-            "project_id": project_id,
-            "obs_type": obs_type,
-            "title": title,
-            "narrative": narrative.unwrap_or(""),
-            "created_at": chrono::Utc::now().to_rfc3339(),
-        });
-
-        self.qdrant.upsert(OBSERVATIONS_COLLECTION, &id, embedding, payload).await
-            .context("Failed to upsert observation to Qdrant")?;
-
-        info!(id = %id, obs_type, dim, "Observation added");
+        info!(id = %id, obs_type, dim = ?embedding_dim, "Observation added");
 
-        Ok(AddObservationResult { id, embedding_dim: dim })
+        Ok(AddObservationResult { id, embedding_dim })
     }
 
     /// Search observations by semantic similarity.
@@ -125,7 +118,7 @@ impl ObservationPipeline {
         project_id: &str,
         top_k: u64,
     ) -> Result<Vec<ObservationSearchResult>> {
-        let query_vector = self.ollama.embed(query).await
+        let query_vector = self.embedder.embed(query).await
             .context("Failed to embed observation search query")?;
 
         debug!(query, dim = query_vector.len(), "Generated observation query embedding");
@@ -1,12 +1,20 @@
 //! Ollama HTTP client for embedding generation.
 //!
-//! Uses the Ollama API at /api/embeddings to generate vectors
-//! with the nomic-embed-text model (768 dimensions).
+//! Uses the Ollama API at /api/embeddings to generate vectors,
+//! by default with the nomic-embed-text model (768 dimensions). Batches go
+//! to /api/embed, falling back to one request per text on Ollama versions
+//! that predate it.
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tracing::debug;
 
+use crate::embedder::{check_dimension, Embedder, EMBED_CONCURRENCY};
+
 /// Default Ollama API URL.
 pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
 
@@ -16,12 +24,18 @@ pub const DEFAULT_MODEL: &str = "nomic-embed-text";
 /// Expected embedding dimension for nomic-embed-text.
 pub const EMBEDDING_DIM: usize = 768;
 
+/// Texts sent per `/api/embed` request.
+const BATCH_SIZE: usize = 32;
+
 /// Ollama embedding client.
 #[derive(Clone)]
 pub struct OllamaClient {
     base_url: String,
     model: String,
+    dimension: usize,
     client: reqwest::Client,
+    /// Set once the server answers 404 on `/api/embed`.
+    no_batch_endpoint: Arc<AtomicBool>,
 }
 
 #[derive(Serialize)]
@@ -35,6 +49,17 @@ struct EmbeddingResponse {
     embedding: Vec<f32>,
 }
 
+#[derive(Serialize)]
+struct BatchRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
 impl OllamaClient {
     /// Create a new Ollama client with specified URL and model.
     pub fn new(base_url: &str, model: &str) -> Self {
@@ -46,10 +71,18 @@ impl OllamaClient {
         Self {
             base_url: base_url.trim_end_matches('/').to_string(),
             model: model.to_string(),
+            dimension: EMBEDDING_DIM,
             client,
+            no_batch_endpoint: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Set the dimension the model produces (768 for nomic-embed-text).
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.dimension = dimension;
+        self
+    }
+
     /// Create a client with default settings (localhost:11434, nomic-embed-text).
     pub fn default_client() -> Self {
         Self::new(DEFAULT_OLLAMA_URL, DEFAULT_MODEL)
@@ -81,10 +114,75 @@ impl OllamaClient {
             .context("Failed to parse Ollama response")?;
 
         debug!(dim = result.embedding.len(), "Generated embedding");
+        check_dimension(self, &result.embedding)?;
 
         Ok(result.embedding)
     }
 
+    /// Generate embeddings for several texts with one `/api/embed` request.
+    ///
+    /// Returns `None` when the server has no batch endpoint.
+    async fn embed_many(&self, texts: &[String]) -> Result<Option<Vec<Vec<f32>>>> {
+        if self.no_batch_endpoint.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+
+        let response = self.client
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&BatchRequest { model: &self.model, input: texts })
+            .send()
+            .await
+            .context("Failed to connect to Ollama")?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            let body = response.text().await.unwrap_or_default();
+            // A missing model is also a 404, but with an error naming it.
+            if !body.contains("model") {
+                debug!("Ollama has no /api/embed, embedding one text at a time");
+                self.no_batch_endpoint.store(true, Ordering::Relaxed);
+                return Ok(None);
+            }
+            anyhow::bail!("Ollama API error (404 Not Found): {}", body);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Ollama API error ({}): {}", status, body);
+        }
+
+        let result: BatchResponse = response
+            .json()
+            .await
+            .context("Failed to parse Ollama response")?;
+
+        if result.embeddings.len() != texts.len() {
+            anyhow::bail!(
+                "Ollama returned {} embeddings for {} inputs",
+                result.embeddings.len(),
+                texts.len()
+            );
+        }
+        for embedding in &result.embeddings {
+            check_dimension(self, embedding)?;
+        }
+
+        debug!(count = result.embeddings.len(), "Generated embeddings");
+        Ok(Some(result.embeddings))
+    }
+
+    /// Embed one chunk, through `/api/embed` when available.
+    async fn embed_chunk(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if let Some(embeddings) = self.embed_many(texts).await? {
+            return Ok(embeddings);
+        }
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            embeddings.push(OllamaClient::embed(self, text).await?);
+        }
+        Ok(embeddings)
+    }
+
     /// Check if the Ollama service is healthy and the model is available.
     pub async fn health_check(&self) -> Result<bool> {
         let response = self.client
@@ -101,3 +199,28 @@ impl OllamaClient {
         }
     }
 }
+
+#[async_trait]
+impl Embedder for OllamaClient {
+    fn name(&self) -> String {
+        format!("ollama/{}", self.model)
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        OllamaClient::embed(self, text).await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        // Iterate over offsets rather than borrowed chunks so the future stays `Send`.
+        let results: Vec<Vec<Vec<f32>>> = futures::stream::iter((0..texts.len()).step_by(BATCH_SIZE))
+            .map(|start| self.embed_chunk(&texts[start..texts.len().min(start + BATCH_SIZE)]))
+            .buffered(EMBED_CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(results.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,158 @@
+//! Client for OpenAI-compatible embedding servers.
+//!
+//! Works with any server exposing `POST /v1/embeddings`: OpenAI itself,
+//! vLLM, LM Studio, llama.cpp, LocalAI, Text Embeddings Inference, etc.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::embedder::{check_dimension, Embedder, EMBED_CONCURRENCY};
+
+/// Default API URL.
+pub const DEFAULT_OPENAI_URL: &str = "https://api.openai.com";
+
+/// Default embedding model.
+pub const DEFAULT_OPENAI_MODEL: &str = "text-embedding-3-small";
+
+/// Embedding dimension of text-embedding-3-small.
+pub const DEFAULT_OPENAI_DIM: usize = 1536;
+
+/// Texts sent per request when embedding a batch.
+const BATCH_SIZE: usize = 64;
+
+/// OpenAI-compatible embedding client.
+#[derive(Clone)]
+pub struct OpenAiClient {
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    dimension: usize,
+    client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a, I: Serialize + ?Sized> {
+    model: &'a str,
+    input: &'a I,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiClient {
+    /// Create a client for the server at `base_url` (with or without the
+    /// trailing `/v1`).
+    pub fn new(base_url: &str, model: &str, api_key: Option<String>, dimension: usize) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            base_url: base_url.trim_end_matches('/').trim_end_matches("/v1").to_string(),
+            model: model.to_string(),
+            api_key,
+            dimension,
+            client,
+        }
+    }
+
+    /// Generate an embedding vector for the given text.
+    pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let embedding = self.request(text)
+            .await?
+            .into_iter()
+            .next()
+            .map(|d| d.embedding)
+            .context("Embedding response contained no data")?;
+
+        debug!(dim = embedding.len(), "Generated embedding");
+        check_dimension(self, &embedding)?;
+
+        Ok(embedding)
+    }
+
+    /// Generate embeddings for several texts in one request.
+    pub async fn embed_many(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut data = self.request(texts).await?;
+        if data.len() != texts.len() {
+            anyhow::bail!("Embedding API returned {} vectors for {} inputs", data.len(), texts.len());
+        }
+        data.sort_by_key(|d| d.index);
+
+        let embeddings: Vec<Vec<f32>> = data.into_iter().map(|d| d.embedding).collect();
+        for embedding in &embeddings {
+            check_dimension(self, embedding)?;
+        }
+
+        debug!(count = embeddings.len(), "Generated embeddings");
+        Ok(embeddings)
+    }
+
+    async fn request<I: Serialize + ?Sized + Sync>(&self, input: &I) -> Result<Vec<EmbeddingData>> {
+        let mut request = self.client
+            .post(format!("{}/v1/embeddings", self.base_url))
+            .json(&EmbeddingRequest { model: &self.model, input });
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to connect to {}", self.base_url))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Embedding API error ({}): {}", status, body);
+        }
+
+        let result: EmbeddingResponse = response
+            .json()
+            .await
+            .context("Failed to parse embedding response")?;
+        Ok(result.data)
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiClient {
+    fn name(&self) -> String {
+        format!("openai/{}", self.model)
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        OpenAiClient::embed(self, text).await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        // Iterate over offsets rather than borrowed chunks so the future stays `Send`.
+        let results: Vec<Vec<Vec<f32>>> = futures::stream::iter((0..texts.len()).step_by(BATCH_SIZE))
+            .map(|start| self.embed_many(&texts[start..texts.len().min(start + BATCH_SIZE)]))
+            .buffered(EMBED_CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(results.into_iter().flatten().collect())
+    }
+}
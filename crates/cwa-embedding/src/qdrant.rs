@@ -11,6 +11,8 @@ use qdrant_client::qdrant::{
     value::Kind, Value,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use tracing::{debug, info};
 use uuid::Uuid;
 
@@ -42,10 +44,17 @@ pub struct VectorSearchResult {
     pub payload: serde_json::Value,
 }
 
+/// A point to upsert: id, vector and payload.
+pub type NewPoint = (String, Vec<f32>, serde_json::Value);
+
 /// Qdrant vector store client for CWA.
 #[derive(Clone)]
 pub struct QdrantStore {
     client: Qdrant,
+    /// Vector size of the collections.
+    dimension: usize,
+    /// Collections already created or checked against `dimension`.
+    checked: Arc<Mutex<HashSet<String>>>,
 }
 
 impl QdrantStore {
@@ -56,7 +65,22 @@ impl QdrantStore {
             .build()
             .context("Failed to create Qdrant client")?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            dimension: EMBEDDING_DIM,
+            checked: Arc::default(),
+        })
+    }
+
+    /// Use collections of `dimension`-sized vectors (768 by default).
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.dimension = dimension;
+        self
+    }
+
+    /// Vector size of the collections.
+    pub fn dimension(&self) -> usize {
+        self.dimension
     }
 
     /// Create a store with default settings (localhost:6334).
@@ -64,8 +88,15 @@ impl QdrantStore {
         Self::new(DEFAULT_QDRANT_URL)
     }
 
-    /// Ensure the memories collection exists with the correct configuration.
+    /// Ensure a collection exists with the store's dimension.
+    ///
+    /// Fails if the collection already exists with another dimension, which
+    /// happens after switching embedding models.
     pub async fn ensure_collection(&self, collection_name: &str) -> Result<()> {
+        if self.checked.lock().unwrap().contains(collection_name) {
+            return Ok(());
+        }
+
         let exists = self.client
             .collection_exists(collection_name)
             .await
@@ -76,21 +107,45 @@ impl QdrantStore {
                 .create_collection(
                     CreateCollectionBuilder::new(collection_name)
                         .vectors_config(VectorParamsBuilder::new(
-                            EMBEDDING_DIM as u64,
+                            self.dimension as u64,
                             Distance::Cosine,
                         )),
                 )
                 .await
                 .context("Failed to create collection")?;
 
-            info!(collection = collection_name, "Created Qdrant collection");
+            info!(collection = collection_name, dim = self.dimension, "Created Qdrant collection");
         } else {
+            if let Some(found) = self.collection_dimension(collection_name).await? {
+                check_collection_dimension(collection_name, found, self.dimension)?;
+            }
             debug!(collection = collection_name, "Collection already exists");
         }
 
+        self.checked.lock().unwrap().insert(collection_name.to_string());
         Ok(())
     }
 
+    /// Vector size of an existing collection, if it has a single unnamed vector.
+    pub async fn collection_dimension(&self, collection: &str) -> Result<Option<usize>> {
+        use qdrant_client::qdrant::vectors_config::Config;
+
+        let info = self.client
+            .collection_info(collection)
+            .await
+            .context("Failed to get collection info")?;
+
+        let config = info.result
+            .and_then(|r| r.config)
+            .and_then(|c| c.params)
+            .and_then(|p| p.vectors_config)
+            .and_then(|v| v.config);
+        Ok(match config {
+            Some(Config::Params(params)) => Some(params.size as usize),
+            _ => None,
+        })
+    }
+
     /// Initialize all required collections.
     pub async fn init_collections(&self) -> Result<()> {
         self.ensure_collection(MEMORIES_COLLECTION).await?;
@@ -137,6 +192,32 @@ impl QdrantStore {
         Ok(())
     }
 
+    /// Insert or update several points in one request.
+    pub async fn upsert_batch(
+        &self,
+        collection: &str,
+        points: Vec<NewPoint>,
+    ) -> Result<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        self.ensure_collection(collection).await?;
+
+        let count = points.len();
+        let points: Vec<PointStruct> = points
+            .into_iter()
+            .map(|(id, vector, payload)| PointStruct::new(uuid_to_point_id(&id), vector, json_to_payload(&payload)))
+            .collect();
+
+        self.client
+            .upsert_points(UpsertPointsBuilder::new(collection, points))
+            .await
+            .context("Failed to upsert points")?;
+
+        debug!(collection, count, "Upserted vectors");
+        Ok(())
+    }
+
     /// Search for similar vectors in a collection.
     pub async fn search(
         &self,
@@ -144,6 +225,8 @@ impl QdrantStore {
         query_vector: Vec<f32>,
         top_k: u64,
     ) -> Result<Vec<VectorSearchResult>> {
+        self.ensure_collection(collection).await?;
+
         let response = self.client
             .search_points(
                 SearchPointsBuilder::new(collection, query_vector, top_k)
@@ -176,6 +259,8 @@ impl QdrantStore {
         top_k: u64,
         project_id: &str,
     ) -> Result<Vec<VectorSearchResult>> {
+        self.ensure_collection(collection).await?;
+
         let response = self.client
             .search_points(
                 SearchPointsBuilder::new(collection, query_vector, top_k)
@@ -226,6 +311,70 @@ impl QdrantStore {
         Ok(())
     }
 
+    /// Remove a project's points from a collection before re-embedding it.
+    ///
+    /// A collection created for another dimension cannot hold the new
+    /// vectors, so it is dropped entirely (other projects sharing it must be
+    /// re-embedded too). Returns whether the collection was dropped.
+    pub async fn reset_project(&self, collection: &str, project_id: &str) -> Result<bool> {
+        let exists = self.client
+            .collection_exists(collection)
+            .await
+            .context("Failed to check collection")?;
+        if !exists {
+            return Ok(false);
+        }
+
+        let dimension = self.collection_dimension(collection).await?;
+        if dimension.is_some_and(|d| d != self.dimension) {
+            self.client
+                .delete_collection(collection)
+                .await
+                .context("Failed to delete collection")?;
+            self.checked.lock().unwrap().remove(collection);
+            info!(collection, "Dropped Qdrant collection with a stale dimension");
+            return Ok(true);
+        }
+
+        use qdrant_client::qdrant::{Condition, DeletePointsBuilder, Filter};
+
+        self.client
+            .delete_points(
+                DeletePointsBuilder::new(collection)
+                    .points(Filter::must([Condition::matches("project_id", project_id.to_string())]))
+                    .wait(true),
+            )
+            .await
+            .context("Failed to delete project points")?;
+
+        debug!(collection, project_id, "Deleted project vectors");
+        Ok(false)
+    }
+
+    /// Delete a project's points whose payload `field` equals `value`.
+    pub async fn delete_matching(&self, collection: &str, project_id: &str, field: &str, value: &str) -> Result<()> {
+        use qdrant_client::qdrant::{Condition, DeletePointsBuilder, Filter};
+
+        if !self.client.collection_exists(collection).await.context("Failed to check collection")? {
+            return Ok(());
+        }
+
+        self.client
+            .delete_points(
+                DeletePointsBuilder::new(collection)
+                    .points(Filter::must([
+                        Condition::matches("project_id", project_id.to_string()),
+                        Condition::matches(field, value.to_string()),
+                    ]))
+                    .wait(true),
+            )
+            .await
+            .context("Failed to delete points")?;
+
+        debug!(collection, project_id, field, value, "Deleted matching vectors");
+        Ok(())
+    }
+
     /// Get the number of points in a collection.
     pub async fn count(&self, collection: &str) -> Result<u64> {
         let info = self.client
@@ -239,6 +388,23 @@ impl QdrantStore {
     }
 }
 
+/// Fail with a clear message when a collection was created for another
+/// embedding dimension than the configured one.
+fn check_collection_dimension(collection: &str, found: usize, expected: usize) -> Result<()> {
+    if found != expected {
+        anyhow::bail!(
+            "Qdrant collection '{}' stores {}-dimensional vectors but the configured embedder \
+             produces {}. Restore the previous model in {}, or delete the collection and \
+             re-embed to switch models.",
+            collection,
+            found,
+            expected,
+            crate::config::CONFIG_FILE
+        );
+    }
+    Ok(())
+}
+
 /// Convert a string ID to a UUID-based point ID for Qdrant.
 fn uuid_to_point_id(id: &str) -> String {
     // If the id is already a valid UUID, use it directly
@@ -323,3 +489,16 @@ fn payload_to_json(payload: &std::collections::HashMap<String, Value>) -> serde_
 
     serde_json::Value::Object(map)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimension_mismatch_names_the_collection() {
+        assert!(check_collection_dimension(MEMORIES_COLLECTION, 768, 768).is_ok());
+        let err = check_collection_dimension(MEMORIES_COLLECTION, 768, 1536).unwrap_err().to_string();
+        assert!(err.contains("'cwa_memories' stores 768-dimensional vectors"), "{}", err);
+        assert!(err.contains("produces 1536"), "{}", err);
+    }
+}
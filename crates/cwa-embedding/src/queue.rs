@@ -0,0 +1,428 @@
+//! Embedding queue: backfill and reindex from Redis.
+//!
+//! Memories and observations are written to Redis before they are
+//! embedded, and stay queued as `embedding_pending` until their vector is
+//! in Qdrant. [`EmbeddingQueue::backfill`] embeds whatever is queued (the
+//! background worker in `cwa serve` calls it with a backoff while the
+//! embedder is down); [`EmbeddingQueue::reindex`] rebuilds a project's
+//! collections from Redis after a model change or a wiped Qdrant volume.
+//! Both embed in batches through the embedding cache, so only new or
+//! changed text reaches the provider.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use cwa_db::queries::lexical::{self, domain_object_payload, memory_payload, observation_payload};
+use cwa_db::queries::memory::MemoryRow;
+use cwa_db::queries::observations::ObservationRow;
+use cwa_db::DbPool;
+
+use crate::cache::CachedEmbedder;
+use crate::config::EmbeddingConfig;
+use crate::embedder::Embedder;
+use crate::qdrant::{NewPoint, QdrantStore, DOMAIN_OBJECTS_COLLECTION, MEMORIES_COLLECTION, OBSERVATIONS_COLLECTION};
+
+/// Queue kind of a memory.
+pub const MEMORY: &str = "memory";
+
+/// Queue kind of an observation.
+pub const OBSERVATION: &str = "observation";
+
+/// Kind of a domain object; domain objects are never queued.
+const DOMAIN_OBJECT: &str = "domain object";
+
+/// Items embedded and upserted together.
+pub const BATCH_SIZE: usize = 64;
+
+/// Longest wait between backfill attempts while the embedder is down.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// Result of embedding the queued entities.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct BackfillReport {
+    /// Entities embedded and removed from the queue.
+    pub embedded: usize,
+    /// Queued entities no longer in Redis, removed from the queue.
+    pub dropped: usize,
+}
+
+/// Result of rebuilding a project's collections.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ReindexReport {
+    pub memories: usize,
+    pub observations: usize,
+    pub domain_objects: usize,
+    /// Glossary terms, which only have a lexical index.
+    pub terms: usize,
+    /// Collections dropped because they were created for another dimension.
+    pub recreated: Vec<String>,
+}
+
+/// Reports `(done, total)` as a backfill or reindex embeds items.
+pub type ProgressFn = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Embeds queued entities and rebuilds collections.
+pub struct EmbeddingQueue {
+    embedder: Arc<dyn Embedder>,
+    qdrant: QdrantStore,
+    progress: Option<ProgressFn>,
+}
+
+/// An entity ready to embed: its text and the point it becomes.
+struct Item {
+    kind: &'static str,
+    collection: &'static str,
+    id: String,
+    project_id: String,
+    text: String,
+    payload: serde_json::Value,
+}
+
+impl EmbeddingQueue {
+    /// Create a queue with the given clients.
+    pub fn new(embedder: Arc<dyn Embedder>, qdrant: QdrantStore) -> Self {
+        Self { embedder, qdrant, progress: None }
+    }
+
+    /// Create a queue for the given embedding configuration.
+    pub fn from_config(config: &EmbeddingConfig) -> Result<Self> {
+        Ok(Self::new(config.embedder(), config.store()?))
+    }
+
+    /// Call `progress` after each batch of a backfill or reindex.
+    pub fn with_progress(mut self, progress: impl Fn(usize, usize) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Number of entities waiting for an embedding.
+    pub async fn pending(&self, db: &DbPool, project_id: &str) -> Result<usize> {
+        cwa_db::queries::embeddings::count_pending(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read embedding queue: {}", e))
+    }
+
+    /// Embed every queued entity of the project.
+    ///
+    /// Works in batches of [`BATCH_SIZE`] and stops at the first failure,
+    /// leaving the rest queued for a later run.
+    pub async fn backfill(&self, db: &DbPool, project_id: &str) -> Result<BackfillReport> {
+        let pending = cwa_db::queries::embeddings::list_pending(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read embedding queue: {}", e))?;
+
+        let mut report = BackfillReport::default();
+        let mut items = Vec::with_capacity(pending.len());
+        for (kind, id) in pending {
+            let item = match kind.as_str() {
+                MEMORY => get_memory(db, project_id, &id).await?.map(|row| memory_item(&row)),
+                OBSERVATION => get_observation(db, &id).await?.map(|row| observation_item(&row)),
+                _ => None,
+            };
+            match item {
+                Some(item) => items.push(item),
+                None => {
+                    clear_pending(db, project_id, &kind, &id).await?;
+                    report.dropped += 1;
+                }
+            }
+        }
+
+        let embedder = self.cached(db);
+        for batch in items.chunks(BATCH_SIZE) {
+            let stored = match self.embed_batch(&embedder, batch).await {
+                Ok(vectors) => self.store(db, batch, vectors).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = stored {
+                return Err(e.context(format!("Embedded {} queued item(s) before failing", report.embedded)));
+            }
+            report.embedded += batch.len();
+            self.report_progress(report.embedded, items.len());
+        }
+
+        if report.embedded > 0 {
+            info!(project_id, embedded = report.embedded, "Embedded queued items");
+        }
+        Ok(report)
+    }
+
+    /// Re-embed every memory, observation and domain object of the project
+    /// from Redis, replacing the project's vectors in Qdrant, and rebuild
+    /// its lexical indexes.
+    ///
+    /// Everything is embedded before the old vectors are deleted, so a
+    /// failure part-way leaves the existing index untouched. Unchanged
+    /// content comes from the embedding cache.
+    pub async fn reindex(&self, db: &DbPool, project_id: &str) -> Result<ReindexReport> {
+        // Fail fast, before embedding anything, if the embedder is down.
+        let probe = self.embedder.embed("cwa").await
+            .with_context(|| format!("Embedder {} is unavailable", self.embedder.name()))?;
+        crate::embedder::check_dimension(self.embedder.as_ref(), &probe)?;
+
+        let mut report = ReindexReport::default();
+        let mut items = Vec::new();
+        // Code chunks are rebuilt by `cwa index`, not from Redis entities.
+        for collection in [lexical::MEMORIES, lexical::OBSERVATIONS, lexical::TERMS, lexical::DOMAIN_OBJECTS] {
+            lexical::clear_index(db, project_id, collection).await.map_err(lexical_error)?;
+        }
+
+        let memory_ids = cwa_db::queries::memory::list_memory_ids(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list memories: {}", e))?;
+        for id in memory_ids {
+            if let Some(row) = get_memory(db, project_id, &id).await? {
+                lexical::index_memory(db, &row).await.map_err(lexical_error)?;
+                items.push(memory_item(&row));
+                report.memories += 1;
+            }
+        }
+
+        let observation_ids = cwa_db::queries::observations::list_observation_ids(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list observations: {}", e))?;
+        for id in observation_ids {
+            if let Some(row) = get_observation(db, &id).await? {
+                lexical::index_observation(db, &row).await.map_err(lexical_error)?;
+                items.push(observation_item(&row));
+                report.observations += 1;
+            }
+        }
+
+        let contexts: HashMap<String, String> = cwa_db::queries::domains::list_contexts(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list contexts: {}", e))?
+            .into_iter()
+            .map(|c| (c.id, c.name))
+            .collect();
+        let objects = cwa_db::queries::domains::list_domain_objects(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list domain objects: {}", e))?;
+        for object in objects {
+            let context_name = contexts.get(&object.context_id).map(String::as_str).unwrap_or("");
+            lexical::index_domain_object(db, project_id, &object, context_name).await.map_err(lexical_error)?;
+            items.push(Item {
+                kind: DOMAIN_OBJECT,
+                collection: DOMAIN_OBJECTS_COLLECTION,
+                id: object.id.clone(),
+                project_id: project_id.to_string(),
+                text: domain_object_text(&object.name, &object.object_type, context_name, object.description.as_deref().unwrap_or("")),
+                payload: domain_object_payload(project_id, &object, context_name),
+            });
+            report.domain_objects += 1;
+        }
+
+        let terms = cwa_db::queries::domains::list_glossary(db, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list glossary: {}", e))?;
+        for term in &terms {
+            lexical::index_term(db, term).await.map_err(lexical_error)?;
+        }
+        report.terms = terms.len();
+
+        let embedder = self.cached(db);
+        let mut vectors = Vec::with_capacity(items.len());
+        for batch in items.chunks(BATCH_SIZE) {
+            vectors.extend(self.embed_batch(&embedder, batch).await?);
+            self.report_progress(vectors.len(), items.len());
+        }
+
+        for collection in [MEMORIES_COLLECTION, OBSERVATIONS_COLLECTION, DOMAIN_OBJECTS_COLLECTION] {
+            if self.qdrant.reset_project(collection, project_id).await? {
+                report.recreated.push(collection.to_string());
+            }
+        }
+
+        let mut vectors = vectors.into_iter();
+        for batch in items.chunks(BATCH_SIZE) {
+            self.store(db, batch, vectors.by_ref().take(batch.len()).collect()).await?;
+        }
+
+        info!(
+            project_id,
+            memories = report.memories,
+            observations = report.observations,
+            domain_objects = report.domain_objects,
+            terms = report.terms,
+            "Reindexed project"
+        );
+        Ok(report)
+    }
+
+    pub(crate) async fn index_memory(&self, db: &DbPool, row: &MemoryRow) -> Result<usize> {
+        self.index_one(db, memory_item(row)).await
+    }
+
+    pub(crate) async fn index_observation(&self, db: &DbPool, row: &ObservationRow) -> Result<usize> {
+        self.index_one(db, observation_item(row)).await
+    }
+
+    async fn index_one(&self, db: &DbPool, item: Item) -> Result<usize> {
+        let items = [item];
+        let vectors = self.embed_batch(&self.cached(db), &items).await?;
+        let dim = vectors.first().map(Vec::len).unwrap_or(0);
+        self.store(db, &items, vectors).await?;
+        Ok(dim)
+    }
+
+    /// The configured embedder behind the Redis embedding cache.
+    fn cached(&self, db: &DbPool) -> CachedEmbedder {
+        CachedEmbedder::new(self.embedder.clone(), db.clone())
+    }
+
+    async fn embed_batch(&self, embedder: &CachedEmbedder, items: &[Item]) -> Result<Vec<Vec<f32>>> {
+        let texts: Vec<String> = items.iter().map(|i| i.text.clone()).collect();
+        embedder.embed_batch(&texts).await.with_context(|| match items {
+            [item] => format!("Failed to embed {} {}", item.kind, item.id),
+            _ => format!("Failed to embed {} items", items.len()),
+        })
+    }
+
+    /// Upsert embedded items and take them off the queue.
+    async fn store(&self, db: &DbPool, items: &[Item], vectors: Vec<Vec<f32>>) -> Result<()> {
+        let mut points: HashMap<&str, Vec<NewPoint>> = HashMap::new();
+        for (item, vector) in items.iter().zip(vectors) {
+            points
+                .entry(item.collection)
+                .or_default()
+                .push((item.id.clone(), vector, item.payload.clone()));
+        }
+        for (collection, points) in points {
+            self.qdrant.upsert_batch(collection, points).await?;
+        }
+
+        for item in items {
+            match item.kind {
+                MEMORY => clear_pending(db, &item.project_id, MEMORY, &item.id).await?,
+                OBSERVATION => {
+                    cwa_db::queries::observations::update_embedding_id(db, &item.id, &format!("qdrant:{}", item.id))
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to update embedding ID: {}", e))?;
+                    clear_pending(db, &item.project_id, OBSERVATION, &item.id).await?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn report_progress(&self, done: usize, total: usize) {
+        if let Some(progress) = &self.progress {
+            progress(done, total);
+        }
+    }
+}
+
+fn memory_item(row: &MemoryRow) -> Item {
+    Item {
+        kind: MEMORY,
+        collection: MEMORIES_COLLECTION,
+        id: row.id.clone(),
+        project_id: row.project_id.clone(),
+        text: row.content.clone(),
+        payload: memory_payload(row),
+    }
+}
+
+fn observation_item(row: &ObservationRow) -> Item {
+    let facts: Vec<String> = row.facts.as_deref()
+        .and_then(|f| serde_json::from_str(f).ok())
+        .unwrap_or_default();
+    Item {
+        kind: OBSERVATION,
+        collection: OBSERVATIONS_COLLECTION,
+        id: row.id.clone(),
+        project_id: row.project_id.clone(),
+        text: observation_text(&row.title, row.narrative.as_deref(), &facts),
+        payload: observation_payload(row),
+    }
+}
+
+/// Embed `project_id`'s queued entities every `interval` until the process
+/// exits. While the embedder or Qdrant is down the wait doubles, up to ten
+/// minutes, and resets after the next successful run.
+pub async fn run_backfill_worker(queue: EmbeddingQueue, db: DbPool, project_id: String, interval: Duration) {
+    info!(project_id = %project_id, "Embedding backfill worker started");
+
+    let mut wait = interval;
+    loop {
+        match queue.backfill(&db, &project_id).await {
+            Ok(report) => {
+                debug!(embedded = report.embedded, dropped = report.dropped, "Embedding backfill finished");
+                wait = interval;
+            }
+            Err(e) => {
+                warn!(error = %format!("{:#}", e), retry_in = wait.as_secs(), "Embedding backfill failed");
+                tokio::time::sleep(wait).await;
+                wait = (wait * 2).min(MAX_BACKOFF);
+                continue;
+            }
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Text embedded for an observation.
+pub(crate) fn observation_text(title: &str, narrative: Option<&str>, facts: &[String]) -> String {
+    let mut text = title.to_string();
+    if let Some(n) = narrative {
+        text.push_str(". ");
+        text.push_str(n);
+    }
+    if !facts.is_empty() {
+        text.push_str(". ");
+        text.push_str(&facts.join(", "));
+    }
+    text
+}
+
+/// Text embedded for a domain object.
+pub(crate) fn domain_object_text(name: &str, object_type: &str, context_name: &str, description: &str) -> String {
+    format!("{} ({} in {}): {}", name, object_type, context_name, description)
+}
+
+pub(crate) async fn mark_pending(db: &DbPool, project_id: &str, kind: &str, id: &str) -> Result<()> {
+    cwa_db::queries::embeddings::mark_pending(db, project_id, kind, id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to queue {} for embedding: {}", kind, e))
+}
+
+async fn clear_pending(db: &DbPool, project_id: &str, kind: &str, id: &str) -> Result<()> {
+    cwa_db::queries::embeddings::clear_pending(db, project_id, kind, id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to update embedding queue: {}", e))
+}
+
+fn lexical_error(e: cwa_db::DbError) -> anyhow::Error {
+    anyhow::anyhow!("Failed to update lexical index: {}", e)
+}
+
+async fn get_memory(db: &DbPool, project_id: &str, id: &str) -> Result<Option<MemoryRow>> {
+    cwa_db::queries::memory::get_memory_entry(db, project_id, id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load memory {}: {}", id, e))
+}
+
+async fn get_observation(db: &DbPool, id: &str) -> Result<Option<ObservationRow>> {
+    cwa_db::queries::observations::get_observation(db, id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load observation {}: {}", id, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observation_text_joins_title_narrative_and_facts() {
+        assert_eq!(observation_text("Fixed login", None, &[]), "Fixed login");
+        assert_eq!(
+            observation_text("Fixed login", Some("Tokens were not refreshed"), &["uses JWT".into(), "1h expiry".into()]),
+            "Fixed login. Tokens were not refreshed. uses JWT, 1h expiry"
+        );
+    }
+}
@@ -1,13 +1,15 @@
 //! Semantic similarity search.
 //!
-//! Converts a text query to an embedding via Ollama, then searches
+//! Converts a text query to an embedding via the configured embedder, then searches
 //! Qdrant for the most similar stored vectors.
 
 use anyhow::{Context, Result};
+use std::sync::Arc;
 use serde::Serialize;
 use tracing::debug;
 
-use crate::ollama::OllamaClient;
+use crate::config::EmbeddingConfig;
+use crate::embedder::Embedder;
 use crate::qdrant::{QdrantStore, MEMORIES_COLLECTION, OBSERVATIONS_COLLECTION};
 
 /// A semantic search result with memory content and similarity score.
@@ -21,23 +23,28 @@ pub struct SemanticSearchResult {
     pub created_at: String,
 }
 
-/// Semantic search engine combining Ollama embeddings with Qdrant vector search.
+/// Semantic search engine combining embeddings with Qdrant vector search.
 pub struct SemanticSearch {
-    ollama: OllamaClient,
+    embedder: Arc<dyn Embedder>,
     qdrant: QdrantStore,
 }
 
 impl SemanticSearch {
     /// Create a new semantic search engine.
-    pub fn new(ollama: OllamaClient, qdrant: QdrantStore) -> Self {
-        Self { ollama, qdrant }
+    pub fn new(embedder: Arc<dyn Embedder>, qdrant: QdrantStore) -> Self {
+        Self { embedder, qdrant }
     }
 
-    /// Create with default client configurations.
+    /// Create a search engine with the default configuration (Ollama, nomic-embed-text).
     pub fn default_search() -> Result<Self> {
+        Self::from_config(&EmbeddingConfig::default())
+    }
+
+    /// Create a search engine for the given embedding configuration.
+    pub fn from_config(config: &EmbeddingConfig) -> Result<Self> {
         Ok(Self {
-            ollama: OllamaClient::default_client(),
-            qdrant: QdrantStore::default_store()?,
+            embedder: config.embedder(),
+            qdrant: config.store()?,
         })
     }
 
@@ -48,7 +55,7 @@ impl SemanticSearch {
         top_k: u64,
     ) -> Result<Vec<SemanticSearchResult>> {
         // Generate embedding for the query
-        let query_vector = self.ollama.embed(query).await
+        let query_vector = self.embedder.embed(query).await
             .context("Failed to embed search query")?;
 
         debug!(query, dim = query_vector.len(), "Generated query embedding");
@@ -80,7 +87,7 @@ impl SemanticSearch {
         project_id: &str,
         top_k: u64,
     ) -> Result<Vec<SemanticSearchResult>> {
-        let query_vector = self.ollama.embed(query).await
+        let query_vector = self.embedder.embed(query).await
             .context("Failed to embed search query")?;
 
         let results = self.qdrant.search_filtered(
@@ -112,7 +119,7 @@ impl SemanticSearch {
         project_id: &str,
         top_k: u64,
     ) -> Result<Vec<SemanticSearchResult>> {
-        let query_vector = self.ollama.embed(query).await
+        let query_vector = self.embedder.embed(query).await
             .context("Failed to embed observation search query")?;
 
         let results = self.qdrant.search_filtered(
@@ -144,7 +151,7 @@ impl SemanticSearch {
         project_id: &str,
         top_k: u64,
     ) -> Result<Vec<SemanticSearchResult>> {
-        let query_vector = self.ollama.embed(query).await
+        let query_vector = self.embedder.embed(query).await
             .context("Failed to embed combined search query")?;
 
         // Search both collections
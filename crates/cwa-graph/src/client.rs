@@ -81,6 +81,29 @@ impl GraphClient {
         Ok(rows)
     }
 
+    /// Execute a Cypher query inside a transaction that is always rolled back.
+    ///
+    /// neo4rs 0.8 cannot open a Bolt READ-mode transaction, so read-only
+    /// callers use this to guarantee that nothing the query writes is ever
+    /// committed, whatever got past the text-level checks.
+    pub async fn query_rolled_back(&self, query: Query) -> Result<Vec<neo4rs::Row>> {
+        let mut txn = self.graph.start_txn().await
+            .context("Failed to start Neo4j transaction")?;
+
+        let rows = async {
+            let mut stream = txn.execute(query).await?;
+            let mut rows = Vec::new();
+            while let Some(row) = stream.next(txn.handle()).await? {
+                rows.push(row);
+            }
+            Ok::<_, neo4rs::Error>(rows)
+        }
+        .await;
+
+        txn.rollback().await.context("Failed to roll back Neo4j transaction")?;
+        rows.context("Neo4j query failed")
+    }
+
     /// Execute a Cypher query and return a single scalar value.
     pub async fn query_scalar<T: DeserializeOwned>(&self, query: Query, field: &str) -> Result<Option<T>> {
         let rows = self.query(query).await?;
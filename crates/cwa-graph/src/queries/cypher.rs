@@ -0,0 +1,597 @@
+//! Raw Cypher support: read-only checks and Bolt → JSON conversion.
+//!
+//! Used by [`super::search::raw_query`] to turn arbitrary query results into
+//! plain JSON rows that can be printed as a table, CSV or JSON.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use neo4rs::{BoltMap, BoltNode, BoltPath, BoltRelation, BoltType, BoltUnboundedRelation};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Clauses that modify the graph or its schema.
+const WRITE_CLAUSES: &[&str] = &["CREATE", "MERGE", "DELETE", "DETACH", "SET", "REMOVE", "DROP"];
+
+/// Procedure namespaces that can write to the graph or run arbitrary Cypher
+/// (e.g. `apoc.cypher.doIt`, `apoc.do.when`).
+const WRITE_NAMESPACES: &[&str] = &[
+    "apoc.cypher.",
+    "apoc.do.",
+    "apoc.periodic.",
+    "apoc.refactor.",
+    "apoc.create.",
+    "apoc.merge.",
+    "apoc.nodes.",
+    "apoc.trigger.",
+    "apoc.schema.",
+    "apoc.load.",
+    "apoc.import.",
+    "apoc.export.",
+    "dbms.",
+];
+
+/// Procedure name segment prefixes that indicate a writing procedure
+/// (e.g. `db.createLabel`, `apoc.graph.deleteNodes`).
+const WRITE_SEGMENT_PREFIXES: &[&str] = &["create", "merge", "delete", "set", "remove", "drop", "add"];
+
+/// Result of a raw Cypher query.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueryResult {
+    /// Column names in RETURN order.
+    pub columns: Vec<String>,
+    /// One JSON object per row, keyed by column name.
+    pub rows: Vec<Map<String, Value>>,
+}
+
+/// Reject queries containing write clauses or writing procedure calls.
+///
+/// String literals, backtick-quoted identifiers, comments and property
+/// accesses (`n.set`) are ignored, so only real clauses are matched.
+pub fn ensure_read_only(cypher: &str) -> Result<()> {
+    let tokens = tokenize(cypher);
+    for (i, token) in tokens.iter().enumerate() {
+        let upper = token.to_ascii_uppercase();
+        if WRITE_CLAUSES.contains(&upper.as_str()) {
+            bail!("Read-only mode: '{}' clauses are not allowed", upper);
+        }
+        if upper == "CALL" {
+            if let Some(procedure) = tokens.get(i + 1) {
+                if is_write_procedure(procedure) {
+                    bail!("Read-only mode: procedure '{}' may write to the graph", procedure);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a procedure name belongs to a writing namespace or has a segment
+/// starting with a write verb.
+fn is_write_procedure(procedure: &str) -> bool {
+    let lower = format!("{}.", procedure.to_ascii_lowercase());
+    WRITE_NAMESPACES.iter().any(|ns| lower.starts_with(ns))
+        || lower
+            .split('.')
+            .any(|part| WRITE_SEGMENT_PREFIXES.iter().any(|prefix| part.starts_with(prefix)))
+}
+
+/// Read a dotted procedure name starting at `i`, unquoting backtick segments
+/// (`` `apoc`.`cypher`.doIt `` → `apoc.cypher.doIt`). Returns the name and
+/// the index just past it.
+fn read_procedure_name(chars: &[char], mut i: usize) -> (String, usize) {
+    let mut name = String::new();
+    while i < chars.len() {
+        match chars[i] {
+            '`' => {
+                let (segment, next) = read_backtick(chars, i);
+                name.push_str(&segment);
+                i = next;
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                name.push(c);
+                i += 1;
+            }
+            c if c.is_whitespace() => {
+                let next = chars[i..].iter().position(|c| !c.is_whitespace()).map(|n| i + n);
+                if name.ends_with('.') || next.is_some_and(|n| chars[n] == '.') {
+                    i = next.unwrap_or(chars.len());
+                } else {
+                    break;
+                }
+            }
+            _ => break,
+        }
+    }
+    (name, i)
+}
+
+/// Read a backtick-quoted identifier whose opening backtick is at `i`.
+///
+/// As in Cypher, a doubled backtick is a literal backtick and backslashes
+/// are not escapes. Returns the unquoted text and the index just past the
+/// closing backtick.
+fn read_backtick(chars: &[char], mut i: usize) -> (String, usize) {
+    let mut text = String::new();
+    i += 1;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            if chars.get(i + 1) == Some(&'`') {
+                text.push('`');
+                i += 2;
+                continue;
+            }
+            return (text, i + 1);
+        }
+        text.push(chars[i]);
+        i += 1;
+    }
+    (text, i)
+}
+
+/// Split a query into words, skipping literals, comments and property names.
+///
+/// Dotted procedure names (`db.index.fulltext.queryNodes`) are kept as one token.
+fn tokenize(cypher: &str) -> Vec<String> {
+    let chars: Vec<char> = cypher.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let after_call = tokens.last().is_some_and(|t: &String| t.eq_ignore_ascii_case("CALL"));
+        if after_call && (c == '`' || c.is_alphabetic() || c == '_') {
+            let (name, next) = read_procedure_name(&chars, i);
+            tokens.push(name);
+            i = next;
+            continue;
+        }
+        match c {
+            '`' => i = read_backtick(&chars, i).1,
+            '\'' | '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                // `n.prop`, `$param`, `:Label` and `{key: ...}` are not clauses
+                let preceded = start > 0 && matches!(chars[start - 1], '.' | '$' | ':');
+                let followed = chars[i..].iter().find(|c| !c.is_whitespace()) == Some(&':');
+                if !preceded && !followed {
+                    let word: String = chars[start..i].iter().collect();
+                    let word = if word.contains('.') {
+                        word.split('.').next().unwrap_or_default().to_string()
+                    } else {
+                        word
+                    };
+                    tokens.push(word);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    tokens
+}
+
+/// Column names of the last top-level RETURN clause, in order.
+///
+/// Neo4rs rows do not preserve column order, so this recovers it from the
+/// query text. Unaliased expressions use the expression text, as Neo4j does.
+/// Returns an empty list if the query has no RETURN clause.
+pub fn return_columns(cypher: &str) -> Vec<String> {
+    let upper = cypher.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+
+    // Find the last RETURN keyword outside literals and brackets.
+    let mut depth = 0i32;
+    let mut quote: Option<u8> = None;
+    let mut return_at = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) => {
+                if b == b'\\' && q != b'`' {
+                    i += 1;
+                } else if b == q {
+                    quote = None;
+                }
+            }
+            None => match b {
+                b'\'' | b'"' | b'`' => quote = Some(b),
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                _ if depth == 0 && is_keyword_at(bytes, i, "RETURN") => return_at = Some(i + "RETURN".len()),
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+
+    let Some(start) = return_at else {
+        return Vec::new();
+    };
+
+    let clause = &cypher[start..];
+    let mut columns = Vec::new();
+    for item in split_top_level(clause) {
+        let item = item.trim();
+        let item = strip_keyword_prefix(item, "DISTINCT").unwrap_or(item).trim();
+        if item.is_empty() || item == "*" {
+            continue;
+        }
+        let name = match find_keyword(item, "AS") {
+            Some(pos) => item[pos + 2..].trim().trim_matches('`').to_string(),
+            None => item.to_string(),
+        };
+        columns.push(name);
+    }
+    columns
+}
+
+/// Split a RETURN clause at top-level commas, stopping at ORDER BY / SKIP / LIMIT / UNION.
+fn split_top_level(clause: &str) -> Vec<&str> {
+    let upper = clause.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<u8> = None;
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) => {
+                if b == b'\\' && q != b'`' {
+                    i += 1;
+                } else if b == q {
+                    quote = None;
+                }
+            }
+            None => match b {
+                b'\'' | b'"' | b'`' => quote = Some(b),
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                b',' if depth == 0 => {
+                    parts.push(&clause[start..i]);
+                    start = i + 1;
+                }
+                b';' if depth == 0 => break,
+                _ if depth == 0
+                    && ["ORDER", "SKIP", "LIMIT", "UNION"].iter().any(|k| is_keyword_at(bytes, i, k)) =>
+                {
+                    break;
+                }
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+    parts.push(&clause[start..i.min(clause.len())]);
+    parts
+}
+
+/// Whether `keyword` starts at `pos` as a whole word (`bytes` must be uppercase).
+fn is_keyword_at(bytes: &[u8], pos: usize, keyword: &str) -> bool {
+    let end = pos + keyword.len();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'.' || b == b'$';
+    end <= bytes.len()
+        && &bytes[pos..end] == keyword.as_bytes()
+        && (pos == 0 || !is_word(bytes[pos - 1]))
+        && (end == bytes.len() || !is_word(bytes[end]))
+}
+
+/// Position of the last whole-word `keyword` in `text` outside literals and brackets.
+fn find_keyword(text: &str, keyword: &str) -> Option<usize> {
+    let upper = text.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+    let mut depth = 0i32;
+    let mut quote: Option<u8> = None;
+    let mut found = None;
+    for (i, &b) in bytes.iter().enumerate() {
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None => match b {
+                b'\'' | b'"' | b'`' => quote = Some(b),
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth -= 1,
+                _ if depth == 0 && is_keyword_at(bytes, i, keyword) => found = Some(i),
+                _ => {}
+            },
+        }
+    }
+    found
+}
+
+fn strip_keyword_prefix<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let upper = text.to_ascii_uppercase();
+    is_keyword_at(upper.as_bytes(), 0, keyword).then(|| &text[keyword.len()..])
+}
+
+/// Convert a Neo4j row into a [`QueryResult`] row, ordering columns as in `columns`.
+///
+/// Columns missing from `columns` (e.g. from `RETURN *`) are appended in
+/// alphabetical order and added to `columns`.
+pub fn row_to_json(row: &neo4rs::Row, columns: &mut Vec<String>) -> Result<Map<String, Value>> {
+    let attributes: BoltMap = row
+        .to_strict()
+        .map_err(|e| anyhow::anyhow!("Failed to decode row: {}", e))?;
+    let mut values = bolt_map_to_json(&attributes);
+
+    let mut extra: Vec<String> = values.keys().filter(|k| !columns.contains(k)).cloned().collect();
+    extra.sort();
+    columns.extend(extra);
+
+    let mut ordered = Map::new();
+    for column in columns.iter() {
+        if let Some(value) = values.remove(column) {
+            ordered.insert(column.clone(), value);
+        }
+    }
+    Ok(ordered)
+}
+
+/// Convert any Bolt value into JSON.
+///
+/// - Nodes become `{identity, labels, properties}`
+/// - Relationships become `{identity, type, start, end, properties}`
+/// - Paths become `{nodes, relationships}` with relationship directions resolved
+/// - Temporal values become ISO-8601 strings, durations `PT<seconds>S`
+/// - Points become `{srid, x, y[, z]}`
+pub fn bolt_to_json(value: &BoltType) -> Value {
+    match value {
+        BoltType::String(s) => Value::String(s.value.clone()),
+        BoltType::Boolean(b) => Value::Bool(b.value),
+        BoltType::Null(_) => Value::Null,
+        BoltType::Integer(i) => Value::from(i.value),
+        BoltType::Float(f) => serde_json::Number::from_f64(f.value)
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(f.value.to_string())),
+        BoltType::List(list) => Value::Array(list.iter().map(bolt_to_json).collect()),
+        BoltType::Map(map) => Value::Object(bolt_map_to_json(map)),
+        BoltType::Bytes(bytes) => Value::Array(bytes.value.iter().map(|b| Value::from(*b)).collect()),
+        BoltType::Node(node) => node_to_json(node),
+        BoltType::Relation(rel) => relation_to_json(rel),
+        BoltType::UnboundedRelation(rel) => unbounded_relation_to_json(rel, None),
+        BoltType::Path(path) => path_to_json(path),
+        BoltType::Point2D(p) => serde_json::json!({
+            "srid": p.sr_id.value,
+            "x": p.x.value,
+            "y": p.y.value
+        }),
+        BoltType::Point3D(p) => serde_json::json!({
+            "srid": p.sr_id.value,
+            "x": p.x.value,
+            "y": p.y.value,
+            "z": p.z.value
+        }),
+        BoltType::Duration(d) => {
+            let duration: std::time::Duration = d.clone().into();
+            let nanos = duration.subsec_nanos();
+            if nanos == 0 {
+                Value::String(format!("PT{}S", duration.as_secs()))
+            } else {
+                Value::String(format!("PT{}.{:09}S", duration.as_secs(), nanos))
+            }
+        }
+        BoltType::Date(d) => NaiveDate::try_from(d)
+            .map(|d| Value::String(d.to_string()))
+            .unwrap_or(Value::Null),
+        BoltType::Time(t) => {
+            let (time, offset): (NaiveTime, FixedOffset) = t.into();
+            Value::String(format!("{}{}", time, offset))
+        }
+        BoltType::LocalTime(t) => Value::String(NaiveTime::from(t).to_string()),
+        BoltType::DateTime(dt) => DateTime::<FixedOffset>::try_from(dt)
+            .map(|dt| Value::String(dt.to_rfc3339()))
+            .unwrap_or(Value::Null),
+        BoltType::LocalDateTime(dt) => NaiveDateTime::try_from(dt)
+            .map(|dt| Value::String(dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string()))
+            .unwrap_or(Value::Null),
+        BoltType::DateTimeZoneId(dt) => DateTime::<FixedOffset>::try_from(dt)
+            .map(|d| Value::String(format!("{}[{}]", d.to_rfc3339(), dt.tz_id())))
+            .unwrap_or(Value::Null),
+    }
+}
+
+fn bolt_map_to_json(map: &BoltMap) -> Map<String, Value> {
+    map.value
+        .iter()
+        .map(|(k, v)| (k.value.clone(), bolt_to_json(v)))
+        .collect()
+}
+
+fn node_to_json(node: &BoltNode) -> Value {
+    let labels: Vec<Value> = node.labels.iter().map(bolt_to_json).collect();
+    serde_json::json!({
+        "identity": node.id.value,
+        "labels": labels,
+        "properties": bolt_map_to_json(&node.properties)
+    })
+}
+
+fn relation_to_json(rel: &BoltRelation) -> Value {
+    serde_json::json!({
+        "identity": rel.id.value,
+        "type": rel.typ.value,
+        "start": rel.start_node_id.value,
+        "end": rel.end_node_id.value,
+        "properties": bolt_map_to_json(&rel.properties)
+    })
+}
+
+fn unbounded_relation_to_json(rel: &BoltUnboundedRelation, ends: Option<(i64, i64)>) -> Value {
+    let mut value = serde_json::json!({
+        "identity": rel.id.value,
+        "type": rel.typ.value,
+        "properties": bolt_map_to_json(&rel.properties)
+    });
+    if let Some((start, end)) = ends {
+        value["start"] = Value::from(start);
+        value["end"] = Value::from(end);
+    }
+    value
+}
+
+/// Convert a path, resolving each relationship's direction from the path indices.
+///
+/// Bolt paths store alternating (relationship, node) indices; a negative
+/// relationship index means the relationship is traversed backwards.
+fn path_to_json(path: &BoltPath) -> Value {
+    let nodes = path.nodes();
+    let rels = path.rels();
+    let indices: Vec<i64> = path.indices().iter().map(|i| i.value).collect();
+
+    let mut relationships = Vec::new();
+    let mut prev = nodes.first().map(|n| n.id.value);
+    for pair in indices.chunks(2) {
+        let [rel_index, node_index] = pair else { break };
+        let Some(rel) = rels.get(rel_index.unsigned_abs() as usize - 1) else { continue };
+        let next = nodes.get(*node_index as usize).map(|n| n.id.value);
+        let ends = match (prev, next) {
+            (Some(p), Some(n)) if *rel_index > 0 => Some((p, n)),
+            (Some(p), Some(n)) => Some((n, p)),
+            _ => None,
+        };
+        relationships.push(unbounded_relation_to_json(rel, ends));
+        prev = next;
+    }
+
+    serde_json::json!({
+        "nodes": nodes.iter().map(node_to_json).collect::<Vec<_>>(),
+        "relationships": relationships
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_rejects_writes() {
+        assert!(ensure_read_only("MATCH (n) RETURN n LIMIT 5").is_ok());
+        assert!(ensure_read_only("MATCH (n) WHERE n.set = 'CREATE' RETURN n.merge").is_ok());
+        assert!(ensure_read_only("CALL db.index.fulltext.queryNodes('spec_search', $q) YIELD node RETURN node").is_ok());
+        assert!(ensure_read_only("// DELETE everything\nMATCH (n) RETURN n").is_ok());
+
+        assert!(ensure_read_only("CREATE (n:Spec {id: '1'})").is_err());
+        assert!(ensure_read_only("match (n) detach delete n").is_err());
+        assert!(ensure_read_only("MATCH (n) SET n.title = 'x'").is_err());
+        assert!(ensure_read_only("MERGE (n:Term {name: 'a'})").is_err());
+        assert!(ensure_read_only("CALL apoc.create.node(['X'], {})").is_err());
+    }
+
+    #[test]
+    fn test_read_only_rejects_write_procedures() {
+        assert!(ensure_read_only("CALL db.labels() YIELD label RETURN label").is_ok());
+        assert!(ensure_read_only("CALL apoc.meta.schema() YIELD value RETURN value").is_ok());
+
+        for query in [
+            "CALL apoc.cypher.doIt('CREATE (n)', {})",
+            "CALL apoc.cypher.runWrite('MATCH (n) DETACH DELETE n', {})",
+            "CALL apoc.do.when(true, 'CREATE (n)', '', {})",
+            "CALL apoc.do.case([true, 'CREATE (n)'], '', {})",
+            "CALL apoc.periodic.iterate('MATCH (n) RETURN n', 'DELETE n', {})",
+            "CALL apoc.refactor.rename.label('A', 'B')",
+            "CALL apoc.merge.node(['X'], {id: 1})",
+            "CALL db.createLabel('X')",
+            "CALL db.createProperty('x')",
+            "CALL apoc.graph.deleteNodes([])",
+            "CALL dbms.security.createUser('x', 'y', false)",
+            "CALL dbms.killQueries([])",
+            "call APOC.CYPHER.DOIT('CREATE (n)', {})",
+            "CALL `apoc`.`cypher`.`doIt`('CREATE (n)', {})",
+            "CALL `apoc.cypher.doIt`('CREATE (n)', {})",
+            "CALL apoc. cypher .doIt('CREATE (n)', {})",
+        ] {
+            assert!(ensure_read_only(query).is_err(), "accepted: {query}");
+        }
+    }
+
+    #[test]
+    fn test_read_only_backticks_have_no_backslash_escapes() {
+        // The backtick identifier ends at the second backtick, so DETACH DELETE is live.
+        assert!(ensure_read_only("MATCH (n:`a\\`) DETACH DELETE n //`) RETURN 1").is_err());
+
+        // A doubled backtick is a literal backtick inside the identifier.
+        assert!(ensure_read_only("MATCH (n:`a``b`) RETURN n").is_ok());
+        assert!(ensure_read_only("MATCH (n:`a``DELETE``b`) RETURN n").is_ok());
+        assert!(ensure_read_only("MATCH (n:`a``b`) DETACH DELETE n").is_err());
+        assert!(ensure_read_only("CALL `db`.`create``Label`('X')").is_err());
+    }
+
+    #[test]
+    fn test_return_columns() {
+        assert_eq!(
+            return_columns("MATCH (s:Spec) RETURN s.id AS id, s.title, count(*) as n ORDER BY n DESC LIMIT 5"),
+            vec!["id", "s.title", "n"]
+        );
+        assert_eq!(
+            return_columns("MATCH (n) WITH n RETURN DISTINCT labels(n)[0] AS label, {a: 1, b: 2} AS `m`"),
+            vec!["label", "m"]
+        );
+        assert_eq!(
+            return_columns("CALL { MATCH (n) RETURN n } RETURN 'a,b' AS text"),
+            vec!["text"]
+        );
+        assert!(return_columns("MATCH (n) DELETE n").is_empty());
+    }
+
+    #[test]
+    fn test_bolt_to_json() {
+        let list = BoltType::from(vec![BoltType::from(1i64), BoltType::from("x"), BoltType::from(true)]);
+        assert_eq!(bolt_to_json(&list), serde_json::json!([1, "x", true]));
+
+        let date = BoltType::from(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(bolt_to_json(&date), serde_json::json!("2024-02-29"));
+
+        let duration = BoltType::from(std::time::Duration::from_secs(90));
+        assert_eq!(bolt_to_json(&duration), serde_json::json!("PT90S"));
+    }
+
+    #[test]
+    fn test_row_to_json() {
+        let mut props = BoltMap::new();
+        props.put("title".into(), "Auth".into());
+        let node = BoltNode::new(7.into(), vec![BoltType::from("Spec")].into(), props);
+
+        let fields: neo4rs::BoltList = vec![BoltType::from("s"), BoltType::from("n")].into();
+        let data: neo4rs::BoltList = vec![BoltType::Node(node), BoltType::from(3i64)].into();
+        let row = neo4rs::Row::new(fields, data);
+
+        let mut columns = vec!["n".to_string()];
+        let json = row_to_json(&row, &mut columns).unwrap();
+        assert_eq!(columns, vec!["n", "s"]);
+        assert_eq!(json.keys().collect::<Vec<_>>(), vec!["n", "s"]);
+        assert_eq!(
+            Value::Object(json),
+            serde_json::json!({
+                "n": 3,
+                "s": {"identity": 7, "labels": ["Spec"], "properties": {"title": "Auth"}}
+            })
+        );
+    }
+}
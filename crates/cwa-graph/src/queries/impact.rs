@@ -2,6 +2,16 @@
 //!
 //! Traverses the graph to find all entities affected by changes
 //! to a given entity (spec, context, task, decision).
+//!
+//! The traversal is breadth-first, one Cypher query per hop, carrying Neo4j
+//! element IDs between hops so every node kind is expanded by an indexed
+//! lookup (terms are keyed by `name`, not `id`). Impact only
+//! propagates along the relationship types (and directions) listed in
+//! [`propagation_weight`]; each hop multiplies the node's weight, so a task
+//! that directly implements a changed spec scores higher than a decision
+//! three hops away.
+
+use std::collections::{BTreeMap, HashMap};
 
 use anyhow::Result;
 use neo4rs::Query;
@@ -9,146 +19,483 @@ use serde::Serialize;
 
 use crate::GraphClient;
 
+/// Hard cap on traversal depth to prevent runaway queries.
+pub const MAX_IMPACT_DEPTH: u32 = 5;
+
+/// Hard cap on the number of affected nodes returned.
+const MAX_IMPACT_NODES: usize = 200;
+
+/// Relationship types that carry impact, in either direction.
+const IMPACT_RELATIONSHIPS: &[&str] = &[
+    "IMPLEMENTS",
+    "DEPENDS_ON",
+    "BLOCKED_BY",
+    "RELATES_TO",
+    "SUPERSEDED_BY",
+    "PART_OF",
+    "DEFINED_IN",
+    "UPSTREAM_OF",
+    "CONFORMIST",
+    "ANTI_CORRUPTION_LAYER",
+    "OPEN_HOST_SERVICE",
+    "CUSTOMER_SUPPLIER",
+    "PARTNERSHIP",
+    "SHARED_KERNEL",
+];
+
+/// The entity whose change is being analyzed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactRoot {
+    pub id: String,
+    pub label: String,
+    pub name: String,
+}
+
 /// A node affected by a change.
 #[derive(Debug, Clone, Serialize)]
 pub struct ImpactNode {
     pub id: String,
     pub label: String,
     pub name: String,
+    /// Relationship type of the last hop (e.g. `IMPLEMENTS`).
     pub relationship: String,
-    pub depth: i64,
+    /// `true` if the last hop's relationship points from the parent to this node.
+    pub outgoing: bool,
+    /// Hop distance from the root.
+    pub depth: u32,
+    /// Product of the relationship weights along the path (0.0–1.0).
+    pub weight: f64,
+    /// Node the impact arrived from (the root for depth 1).
+    pub parent_id: String,
+    /// IDs from the root to this node, both included.
+    pub path: Vec<String>,
 }
 
-/// Analyze the impact of changes to a spec.
-pub async fn spec_impact(client: &GraphClient, spec_id: &str) -> Result<Vec<ImpactNode>> {
-    let query = Query::new(
-        "MATCH (s:Spec {id: $id})
-         OPTIONAL MATCH (s)<-[r1:IMPLEMENTS]-(t:Task)
-         WITH s, collect({id: t.id, label: 'Task', name: t.title, rel: type(r1)}) as tasks
-         OPTIONAL MATCH (s)<-[r2:RELATES_TO]-(d:Decision)
-         WITH s, tasks, collect({id: d.id, label: 'Decision', name: d.title, rel: type(r2)}) as decisions
-         OPTIONAL MATCH (s)<-[r3:DEPENDS_ON]-(dep:Spec)
-         WITH s, tasks, decisions, collect({id: dep.id, label: 'Spec', name: dep.title, rel: type(r3)}) as dependents
-         UNWIND (tasks + decisions + dependents) as impact
-         WITH impact WHERE impact.id IS NOT NULL
-         RETURN impact.id as id, impact.label as label, impact.name as name, impact.rel as relationship
-         "
-            .to_string(),
-    )
-    .param("id", spec_id);
+impl ImpactNode {
+    /// Relationship of the last hop as a Cypher-style arrow, seen from the parent.
+    pub fn arrow(&self) -> String {
+        if self.outgoing {
+            format!("-{}->", self.relationship)
+        } else {
+            format!("<-{}-", self.relationship)
+        }
+    }
+}
 
-    parse_flat_impact_rows(client.query(query).await?)
+/// Result of an impact analysis.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactAnalysis {
+    /// `None` if the entity does not exist in the graph.
+    pub root: Option<ImpactRoot>,
+    pub max_depth: u32,
+    /// Affected nodes in breadth-first order.
+    pub nodes: Vec<ImpactNode>,
 }
 
-/// Analyze the impact of changes to a bounded context.
-pub async fn context_impact(client: &GraphClient, context_id: &str) -> Result<Vec<ImpactNode>> {
-    let query = Query::new(
-        "MATCH (c:BoundedContext {id: $id})
-         OPTIONAL MATCH (c)<-[:PART_OF]-(e:DomainEntity)
-         WITH c, collect({id: e.id, label: 'DomainEntity', name: e.name, rel: 'PART_OF'}) as entities
-         OPTIONAL MATCH (c)<-[:DEFINED_IN]-(t:Term)
-         WITH c, entities, collect({id: t.name, label: 'Term', name: t.name, rel: 'DEFINED_IN'}) as terms
-         OPTIONAL MATCH (c)<-[:UPSTREAM_OF]-(ds:BoundedContext)
-         WITH c, entities, terms, collect({id: ds.id, label: 'BoundedContext', name: ds.name, rel: 'DOWNSTREAM'}) as downstreams
-         UNWIND (entities + terms + downstreams) as impact
-         WITH impact WHERE impact.id IS NOT NULL
-         RETURN impact.id as id, impact.label as label, impact.name as name, impact.rel as relationship
-         "
-            .to_string(),
-    )
-    .param("id", context_id);
+/// A neighbor of a frontier node, as returned by one traversal hop.
+#[derive(Debug, Clone)]
+struct ImpactEdge {
+    /// Neo4j element ID of the frontier node.
+    from_element: String,
+    rel_type: String,
+    outgoing: bool,
+    /// Neo4j element ID of the neighbor.
+    element_id: String,
+    /// Domain key of the neighbor (`id`, or `name` for terms).
+    id: String,
+    label: String,
+    name: String,
+}
 
-    parse_flat_impact_rows(client.query(query).await?)
+/// Weight with which a change propagates across a relationship.
+///
+/// `outgoing` is `true` when the relationship points from the changed node
+/// to its neighbor. Returns `None` if the change does not propagate that way
+/// (e.g. changing a task does not affect the project it belongs to).
+pub fn propagation_weight(rel_type: &str, outgoing: bool) -> Option<f64> {
+    match (rel_type, outgoing) {
+        // A changed spec affects the tasks implementing it, and vice versa (weaker).
+        ("IMPLEMENTS", false) => Some(1.0),
+        ("IMPLEMENTS", true) => Some(0.6),
+        // Tasks blocked by a changed task, specs depending on a changed spec.
+        ("BLOCKED_BY", false) => Some(0.9),
+        ("DEPENDS_ON", false) => Some(0.8),
+        // Domain objects and terms of a changed context.
+        ("PART_OF", false) => Some(0.9),
+        ("DEFINED_IN", false) => Some(0.7),
+        // Downstream contexts of a changed upstream context.
+        ("UPSTREAM_OF" | "CONFORMIST" | "ANTI_CORRUPTION_LAYER" | "OPEN_HOST_SERVICE"
+        | "CUSTOMER_SUPPLIER", true) => Some(0.7),
+        // Symmetric context relationships.
+        ("PARTNERSHIP" | "SHARED_KERNEL", _) => Some(0.7),
+        // Decisions and specs only loosely relate to each other.
+        ("RELATES_TO", _) => Some(0.5),
+        ("SUPERSEDED_BY", _) => Some(0.4),
+        _ => None,
+    }
 }
 
-/// Analyze the impact of changes to a task.
-pub async fn task_impact(client: &GraphClient, task_id: &str) -> Result<Vec<ImpactNode>> {
-    let query = Query::new(
-        "MATCH (t:Task {id: $id})
-         OPTIONAL MATCH (t)-[:IMPLEMENTS]->(s:Spec)
-         WITH t, collect({id: s.id, label: 'Spec', name: s.title, rel: 'IMPLEMENTS'}) as specs
-         OPTIONAL MATCH (t)<-[:BLOCKED_BY]-(blocked:Task)
-         WITH t, specs, collect({id: blocked.id, label: 'Task', name: blocked.title, rel: 'BLOCKS'}) as blockedTasks
-         UNWIND (specs + blockedTasks) as impact
-         WITH impact WHERE impact.id IS NOT NULL
-         RETURN impact.id as id, impact.label as label, impact.name as name, impact.rel as relationship
-         "
-            .to_string(),
-    )
-    .param("id", task_id);
+/// Map a CLI/MCP entity type to its Neo4j label.
+fn entity_label(entity_type: &str) -> Option<&'static str> {
+    match entity_type {
+        "spec" => Some("Spec"),
+        "task" => Some("Task"),
+        "context" => Some("BoundedContext"),
+        "decision" => Some("Decision"),
+        "entity" | "domain_object" => Some("DomainEntity"),
+        _ => None,
+    }
+}
+
+/// Analyze the impact of changes to an entity, up to `max_depth` hops away.
+///
+/// `max_depth` is clamped to `1..=MAX_IMPACT_DEPTH`. Unknown entity types
+/// match any node with the given ID.
+pub async fn impact_analysis(
+    client: &GraphClient,
+    entity_type: &str,
+    entity_id: &str,
+    max_depth: u32,
+) -> Result<ImpactAnalysis> {
+    let max_depth = max_depth.clamp(1, MAX_IMPACT_DEPTH);
+
+    let root_match = match entity_label(entity_type) {
+        Some(label) => format!("MATCH (n:{} {{id: $id}})", label),
+        None => "MATCH (n {id: $id})".to_string(),
+    };
+    let root_query = Query::new(format!(
+        "{}
+         RETURN n.id as id, elementId(n) as element_id, labels(n)[0] as label,
+                COALESCE(n.title, n.name, n.id) as name
+         LIMIT 1",
+        root_match
+    ))
+    .param("id", entity_id);
+
+    let row = client.query(root_query).await?.into_iter().next();
+    let root_element: String = row.as_ref().and_then(|r| r.get("element_id").ok()).unwrap_or_default();
+    let root = row.map(|row| ImpactRoot {
+        id: row.get("id").unwrap_or_default(),
+        label: row.get("label").unwrap_or_default(),
+        name: row.get("name").unwrap_or_default(),
+    });
+
+    let mut analysis = ImpactAnalysis {
+        root,
+        max_depth,
+        nodes: Vec::new(),
+    };
+    let Some(root_id) = analysis.root.as_ref().map(|r| r.id.clone()) else {
+        return Ok(analysis);
+    };
+
+    let mut visited = Visited::default();
+    visited.insert(&root_element, &root_id);
+    let mut frontier = vec![root_element];
+
+    for depth in 1..=max_depth {
+        if frontier.is_empty() || analysis.nodes.len() >= MAX_IMPACT_NODES {
+            break;
+        }
+        let edges = fetch_edges(client, &frontier).await?;
+        frontier = analysis.push_hop(&mut visited, edges, depth);
+    }
+
+    Ok(analysis)
+}
+
+/// Nodes reached so far: element ID → domain key.
+#[derive(Debug, Default)]
+struct Visited(HashMap<String, String>);
 
-    parse_flat_impact_rows(client.query(query).await?)
+impl Visited {
+    /// Record a node; returns `false` if it was already reached.
+    fn insert(&mut self, element_id: &str, id: &str) -> bool {
+        if self.0.contains_key(element_id) {
+            return false;
+        }
+        self.0.insert(element_id.to_string(), id.to_string());
+        true
+    }
+
+    fn contains(&self, element_id: &str) -> bool {
+        self.0.contains_key(element_id)
+    }
+
+    /// Domain key of a reached node.
+    fn id(&self, element_id: &str) -> Option<&str> {
+        self.0.get(element_id).map(String::as_str)
+    }
 }
 
-/// Analyze the impact of changes to a decision.
-pub async fn decision_impact(client: &GraphClient, decision_id: &str) -> Result<Vec<ImpactNode>> {
+/// Fetch every impact-carrying relationship of the frontier nodes, given by
+/// element ID.
+async fn fetch_edges(client: &GraphClient, frontier: &[String]) -> Result<Vec<ImpactEdge>> {
+    let types: Vec<String> = IMPACT_RELATIONSHIPS.iter().map(|t| t.to_string()).collect();
     let query = Query::new(
-        "MATCH (d:Decision {id: $id})
-         OPTIONAL MATCH (d)-[:RELATES_TO]->(s:Spec)
-         WITH d, collect({id: s.id, label: 'Spec', name: s.title, rel: 'RELATES_TO'}) as specs
-         OPTIONAL MATCH (d)<-[:SUPERSEDED_BY]-(old:Decision)
-         WITH d, specs, collect({id: old.id, label: 'Decision', name: old.title, rel: 'SUPERSEDES'}) as superseded
-         OPTIONAL MATCH (d)-[:SUPERSEDED_BY]->(newer:Decision)
-         WITH d, specs, superseded, collect({id: newer.id, label: 'Decision', name: newer.title, rel: 'SUPERSEDED_BY'}) as superseding
-         UNWIND (specs + superseded + superseding) as impact
-         WITH impact WHERE impact.id IS NOT NULL
-         RETURN impact.id as id, impact.label as label, impact.name as name, impact.rel as relationship
-         "
+        "UNWIND $ids AS fid
+         MATCH (f) WHERE elementId(f) = fid
+         MATCH (f)-[r]-(n)
+         WHERE type(r) IN $types
+         RETURN fid as from_element, type(r) as rel_type, startNode(r) = f as outgoing,
+                elementId(n) as element_id, COALESCE(n.id, n.name) as id, labels(n)[0] as label,
+                COALESCE(n.title, n.name, n.id) as name"
             .to_string(),
     )
-    .param("id", decision_id);
+    .param("ids", frontier.to_vec())
+    .param("types", types);
 
-    parse_flat_impact_rows(client.query(query).await?)
+    let mut edges = Vec::new();
+    for row in client.query(query).await? {
+        let id: String = row.get("id").unwrap_or_default();
+        if id.is_empty() {
+            continue;
+        }
+        edges.push(ImpactEdge {
+            from_element: row.get("from_element").unwrap_or_default(),
+            rel_type: row.get("rel_type").unwrap_or_default(),
+            outgoing: row.get("outgoing").unwrap_or_default(),
+            element_id: row.get("element_id").unwrap_or_default(),
+            id,
+            label: row.get("label").unwrap_or_default(),
+            name: row.get("name").unwrap_or_default(),
+        });
+    }
+    Ok(edges)
 }
 
-/// Generic impact analysis - dispatches to type-specific queries.
-pub async fn impact_analysis(client: &GraphClient, entity_type: &str, entity_id: &str, _max_depth: u32) -> Result<Vec<ImpactNode>> {
-    match entity_type {
-        "spec" => spec_impact(client, entity_id).await,
-        "context" => context_impact(client, entity_id).await,
-        "task" => task_impact(client, entity_id).await,
-        "decision" => decision_impact(client, entity_id).await,
-        _ => {
-            // Fallback: generic traversal with variable-length paths
-            let query = Query::new(
-                "MATCH (start {id: $id})
-                 MATCH (start)-[r*1..3]-(connected)
-                 WHERE start <> connected
-                 WITH DISTINCT connected,
-                      [rel IN r | type(rel)][0] as relationship
-                 RETURN connected.id as id, labels(connected)[0] as label,
-                        COALESCE(connected.title, connected.name, connected.id) as name,
-                        relationship
-                 LIMIT 50"
-                    .to_string(),
-            )
-            .param("id", entity_id);
-
-            parse_flat_impact_rows(client.query(query).await?)
+impl ImpactAnalysis {
+    /// Add the nodes reached in one hop and return the next frontier, as
+    /// element IDs.
+    ///
+    /// A node reached by several edges keeps the one with the highest
+    /// accumulated weight, so each node appears once with its shortest,
+    /// strongest path.
+    fn push_hop(&mut self, visited: &mut Visited, edges: Vec<ImpactEdge>, depth: u32) -> Vec<String> {
+        let mut candidates: Vec<(String, ImpactNode)> = edges
+            .into_iter()
+            .filter(|e| !visited.contains(&e.element_id))
+            .filter_map(|e| {
+                let rel_weight = propagation_weight(&e.rel_type, e.outgoing)?;
+                let from_id = visited.id(&e.from_element)?.to_string();
+                let (parent_weight, mut path) = match self.node(&from_id) {
+                    Some(parent) => (parent.weight, parent.path.clone()),
+                    None => (1.0, vec![from_id.clone()]),
+                };
+                path.push(e.id.clone());
+                let node = ImpactNode {
+                    id: e.id,
+                    label: e.label,
+                    name: e.name,
+                    relationship: e.rel_type,
+                    outgoing: e.outgoing,
+                    depth,
+                    weight: parent_weight * rel_weight,
+                    parent_id: from_id,
+                    path,
+                };
+                Some((e.element_id, node))
+            })
+            .collect();
+
+        candidates.sort_by(|(_, a), (_, b)| b.weight.total_cmp(&a.weight).then_with(|| a.id.cmp(&b.id)));
+
+        let mut frontier = Vec::new();
+        for (element_id, node) in candidates {
+            if self.nodes.len() >= MAX_IMPACT_NODES {
+                break;
+            }
+            if visited.insert(&element_id, &node.id) {
+                frontier.push(element_id);
+                self.nodes.push(node);
+            }
+        }
+        frontier
+    }
+
+    /// Look up an affected node by ID.
+    pub fn node(&self, id: &str) -> Option<&ImpactNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Affected nodes grouped by label, strongest first within each group.
+    pub fn by_label(&self) -> BTreeMap<&str, Vec<&ImpactNode>> {
+        let mut groups: BTreeMap<&str, Vec<&ImpactNode>> = BTreeMap::new();
+        for node in &self.nodes {
+            groups.entry(node.label.as_str()).or_default().push(node);
+        }
+        for nodes in groups.values_mut() {
+            nodes.sort_by(|a, b| b.weight.total_cmp(&a.weight).then_with(|| a.name.cmp(&b.name)));
+        }
+        groups
+    }
+
+    /// Children of a node in the impact tree, grouped by label and strongest first.
+    pub fn children(&self, parent_id: &str) -> Vec<&ImpactNode> {
+        let mut children: Vec<&ImpactNode> = self.nodes.iter().filter(|n| n.parent_id == parent_id).collect();
+        children.sort_by(|a, b| {
+            a.label
+                .cmp(&b.label)
+                .then_with(|| b.weight.total_cmp(&a.weight))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        children
+    }
+
+    /// Render the impact tree as plain text.
+    pub fn render_tree(&self) -> String {
+        self.render_tree_with(
+            |root| format!("[{}] {}", root.label, root.name),
+            |node| {
+                format!(
+                    "{} [{}] {} (depth {}, weight {:.2})",
+                    node.arrow(),
+                    node.label,
+                    node.name,
+                    node.depth,
+                    node.weight
+                )
+            },
+        )
+    }
+
+    /// Render the impact tree with custom formatting for the root and each node.
+    pub fn render_tree_with(
+        &self,
+        fmt_root: impl Fn(&ImpactRoot) -> String,
+        fmt_node: impl Fn(&ImpactNode) -> String,
+    ) -> String {
+        let Some(root) = &self.root else {
+            return String::new();
+        };
+        let mut out = fmt_root(root);
+        out.push('\n');
+        self.render_children(&root.id, "", &fmt_node, &mut out);
+        out
+    }
+
+    fn render_children(&self, parent_id: &str, prefix: &str, fmt_node: &impl Fn(&ImpactNode) -> String, out: &mut String) {
+        let children = self.children(parent_id);
+        let count = children.len();
+        for (i, child) in children.into_iter().enumerate() {
+            let last = i + 1 == count;
+            out.push_str(prefix);
+            out.push_str(if last { "└── " } else { "├── " });
+            out.push_str(&fmt_node(child));
+            out.push('\n');
+            let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            self.render_children(&child.id, &child_prefix, fmt_node, out);
         }
     }
 }
 
-/// Parse flat impact rows from Neo4j results.
-fn parse_flat_impact_rows(rows: Vec<neo4rs::Row>) -> Result<Vec<ImpactNode>> {
-    let mut nodes = Vec::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for row in rows {
-        let id: String = row.get("id").unwrap_or_default();
-        let label: String = row.get("label").unwrap_or_default();
-        let name: String = row.get("name").unwrap_or_default();
-        let relationship: String = row.get("relationship").unwrap_or_default();
-
-        if !id.is_empty() {
-            nodes.push(ImpactNode {
-                id,
-                label,
-                name,
-                relationship,
-                depth: 1,
-            });
+    fn edge(from: &str, rel: &str, outgoing: bool, id: &str, label: &str) -> ImpactEdge {
+        ImpactEdge {
+            from_element: format!("e:{}", from),
+            rel_type: rel.to_string(),
+            outgoing,
+            element_id: format!("e:{}", id),
+            id: id.to_string(),
+            label: label.to_string(),
+            name: id.to_uppercase(),
         }
     }
 
-    Ok(nodes)
+    fn analysis(root: &str) -> (ImpactAnalysis, Visited) {
+        let analysis = ImpactAnalysis {
+            root: Some(ImpactRoot {
+                id: root.to_string(),
+                label: "Spec".to_string(),
+                name: root.to_uppercase(),
+            }),
+            max_depth: 3,
+            nodes: Vec::new(),
+        };
+        let mut visited = Visited::default();
+        visited.insert(&format!("e:{}", root), root);
+        (analysis, visited)
+    }
+
+    #[test]
+    fn test_push_hop_tracks_depth_weight_and_path() {
+        let (mut a, mut visited) = analysis("s1");
+        let frontier = a.push_hop(
+            &mut visited,
+            vec![
+                edge("s1", "IMPLEMENTS", false, "t1", "Task"),
+                edge("s1", "RELATES_TO", false, "d1", "Decision"),
+                edge("s1", "BELONGS_TO", true, "p1", "Project"),
+            ],
+            1,
+        );
+        assert_eq!(frontier, vec!["e:t1", "e:d1"]);
+
+        a.push_hop(&mut visited, vec![edge("t1", "BLOCKED_BY", false, "t2", "Task")], 2);
+        let t2 = a.node("t2").unwrap();
+        assert_eq!(t2.depth, 2);
+        assert_eq!(t2.path, vec!["s1", "t1", "t2"]);
+        assert!((t2.weight - 0.9).abs() < 1e-9);
+        assert!(a.node("p1").is_none());
+    }
+
+    #[test]
+    fn test_push_hop_keeps_strongest_path() {
+        let (mut a, mut visited) = analysis("s1");
+        a.push_hop(
+            &mut visited,
+            vec![
+                edge("s1", "IMPLEMENTS", false, "t1", "Task"),
+                edge("s1", "DEPENDS_ON", false, "s2", "Spec"),
+            ],
+            1,
+        );
+        a.push_hop(
+            &mut visited,
+            vec![
+                edge("s2", "IMPLEMENTS", false, "t3", "Task"),
+                edge("t1", "BLOCKED_BY", false, "t3", "Task"),
+            ],
+            2,
+        );
+        assert_eq!(a.node("t3").unwrap().parent_id, "t1");
+        assert_eq!(a.by_label()["Task"].len(), 2);
+    }
+
+    #[test]
+    fn test_push_hop_expands_through_terms() {
+        // Terms have no `id`; their domain key is their name
+        let (mut a, mut visited) = analysis("c1");
+        let frontier = a.push_hop(&mut visited, vec![edge("c1", "DEFINED_IN", false, "Invoice", "Term")], 1);
+        assert_eq!(frontier, vec!["e:Invoice"]);
+
+        a.push_hop(&mut visited, vec![edge("Invoice", "RELATES_TO", true, "d1", "Decision")], 2);
+        let d1 = a.node("d1").unwrap();
+        assert_eq!(d1.parent_id, "Invoice");
+        assert_eq!(d1.path, vec!["c1", "Invoice", "d1"]);
+        assert!((d1.weight - 0.35).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let (mut a, mut visited) = analysis("s1");
+        a.push_hop(
+            &mut visited,
+            vec![
+                edge("s1", "IMPLEMENTS", false, "t1", "Task"),
+                edge("s1", "RELATES_TO", false, "d1", "Decision"),
+            ],
+            1,
+        );
+        a.push_hop(&mut visited, vec![edge("t1", "BLOCKED_BY", false, "t2", "Task")], 2);
+
+        let tree = a.render_tree_with(|r| r.name.clone(), |n| format!("{} {}", n.arrow(), n.name));
+        assert_eq!(
+            tree,
+            "S1\n├── <-RELATES_TO- D1\n└── <-IMPLEMENTS- T1\n    └── <-BLOCKED_BY- T2\n"
+        );
+    }
 }
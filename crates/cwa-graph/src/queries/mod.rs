@@ -1,5 +1,6 @@
 //! Graph traversal and analysis queries.
 
+pub mod cypher;
 pub mod impact;
 pub mod explore;
 pub mod search;
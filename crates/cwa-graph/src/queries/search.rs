@@ -9,6 +9,7 @@ use anyhow::Result;
 use neo4rs::Query;
 use serde::Serialize;
 
+use super::cypher::{self, QueryResult};
 use crate::GraphClient;
 
 /// A search result from the graph.
@@ -95,23 +96,29 @@ pub async fn search_all(client: &GraphClient, query_text: &str, limit: usize) ->
     Ok(results)
 }
 
-/// Execute a raw Cypher query and return results as JSON.
-pub async fn raw_query(client: &GraphClient, cypher: &str) -> Result<Vec<serde_json::Value>> {
+/// Execute a raw Cypher query and return its rows as JSON.
+///
+/// With `read_only`, queries containing write clauses are rejected before
+/// they reach Neo4j, and the rest run in a transaction that is rolled back.
+pub async fn raw_query(client: &GraphClient, cypher: &str, read_only: bool) -> Result<QueryResult> {
     let query = Query::new(cypher.to_string());
-    let rows = client.query(query).await?;
-
-    let mut results = Vec::new();
-    for row in rows {
-        // Convert each row to a JSON value by iterating known field patterns
-        // Neo4rs Row doesn't have a generic "all fields" method,
-        // so we return a simplified representation
-        let value = serde_json::json!({
-            "row": format!("{:?}", row)
-        });
-        results.push(value);
+    let rows = if read_only {
+        cypher::ensure_read_only(cypher)?;
+        client.query_rolled_back(query).await?
+    } else {
+        client.query(query).await?
+    };
+
+    let mut result = QueryResult {
+        columns: cypher::return_columns(cypher),
+        rows: Vec::with_capacity(rows.len()),
+    };
+    for row in &rows {
+        let json = cypher::row_to_json(row, &mut result.columns)?;
+        result.rows.push(json);
     }
 
-    Ok(results)
+    Ok(result)
 }
 
 fn parse_search_results(rows: Vec<neo4rs::Row>) -> Result<Vec<SearchResult>> {
@@ -6,21 +6,34 @@
 //! - (:Decision)-[:SUPERSEDED_BY]->(:Decision)
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use neo4rs::Query;
 use tracing::debug;
 
 use cwa_db::DbPool;
 use crate::GraphClient;
-use super::SyncResult;
+use super::{is_changed_since, SyncResult};
 
-/// Sync all decisions for a project to Neo4j.
-pub async fn sync_decisions(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<SyncResult> {
+/// Sync decisions for a project to Neo4j.
+///
+/// With `since`, only decisions updated after that instant are pushed.
+pub async fn sync_decisions(
+    client: &GraphClient,
+    db: &DbPool,
+    project_id: &str,
+    since: Option<&DateTime<Utc>>,
+) -> Result<SyncResult> {
     let decisions = cwa_db::queries::decisions::list_decisions(db, project_id).await
         .map_err(|e| anyhow::anyhow!("Failed to list decisions: {}", e))?;
 
     let mut result = SyncResult::default();
 
     for decision in &decisions {
+        if !is_changed_since(&decision.updated_at, since) {
+            result.nodes_unchanged += 1;
+            continue;
+        }
+
         // MERGE the Decision node
         let query = Query::new(
             "MERGE (d:Decision {id: $id})
@@ -59,6 +72,16 @@ pub async fn sync_decisions(client: &GraphClient, db: &DbPool, project_id: &str)
         client.execute(proj_rel).await?;
         result.relationships_created += 1;
 
+        // Drop existing spec/supersede links so removed ones don't linger
+        let clear_query = Query::new(
+            "MATCH (d:Decision {id: $decision_id})-[r:RELATES_TO|SUPERSEDED_BY]->()
+             DELETE r"
+                .to_string(),
+        )
+        .param("decision_id", decision.id.as_str());
+
+        client.execute(clear_query).await?;
+
         // RELATES_TO Specs (stored as JSON array of spec IDs)
         if let Some(ref specs_json) = decision.related_specs {
             if let Ok(spec_ids) = serde_json::from_str::<Vec<String>>(specs_json) {
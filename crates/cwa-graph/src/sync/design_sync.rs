@@ -3,21 +3,34 @@
 //! Syncs design system entries from SQLite to Neo4j as DesignSystem nodes.
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use neo4rs::Query;
 use tracing::debug;
 
 use cwa_db::DbPool;
 use crate::GraphClient;
-use super::SyncResult;
+use super::{is_changed_since, SyncResult};
 
-/// Sync all design systems for a project to Neo4j.
-pub async fn sync_design_systems(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<SyncResult> {
+/// Sync design systems for a project to Neo4j.
+///
+/// With `since`, only design systems updated after that instant are pushed.
+pub async fn sync_design_systems(
+    client: &GraphClient,
+    db: &DbPool,
+    project_id: &str,
+    since: Option<&DateTime<Utc>>,
+) -> Result<SyncResult> {
     let design_systems = cwa_db::queries::design_systems::list_design_systems(db, project_id).await
         .map_err(|e| anyhow::anyhow!("Failed to list design systems: {}", e))?;
 
     let mut result = SyncResult::default();
 
     for ds in &design_systems {
+        if !is_changed_since(&ds.updated_at, since) {
+            result.nodes_unchanged += 1;
+            continue;
+        }
+
         // Count colors from JSON
         let colors_count = ds.colors_json.as_deref()
             .and_then(|j| serde_json::from_str::<serde_json::Value>(j).ok())
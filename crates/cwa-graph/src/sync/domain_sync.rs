@@ -7,15 +7,24 @@
 //! - (:BoundedContext)-[:UPSTREAM_OF]->(:BoundedContext)
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use neo4rs::Query;
 use tracing::debug;
 
 use cwa_db::DbPool;
 use crate::GraphClient;
-use super::SyncResult;
+use super::{is_changed_since, SyncResult};
 
-/// Sync all domain entities for a project to Neo4j.
-pub async fn sync_domain(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<SyncResult> {
+/// Sync domain entities for a project to Neo4j.
+///
+/// With `since`, only contexts, objects and terms updated after that
+/// instant are pushed.
+pub async fn sync_domain(
+    client: &GraphClient,
+    db: &DbPool,
+    project_id: &str,
+    since: Option<&DateTime<Utc>>,
+) -> Result<SyncResult> {
     let mut result = SyncResult::default();
 
     // Sync bounded contexts
@@ -23,68 +32,22 @@ pub async fn sync_domain(client: &GraphClient, db: &DbPool, project_id: &str) ->
         .map_err(|e| anyhow::anyhow!("Failed to list contexts: {}", e))?;
 
     for ctx in &contexts {
-        let query = Query::new(
-            "MERGE (c:BoundedContext {id: $id})
-             SET c.name = $name,
-                 c.description = $description,
-                 c.responsibilities = $responsibilities,
-                 c.created_at = $created_at,
-                 c.updated_at = $updated_at"
-                .to_string(),
-        )
-        .param("id", ctx.id.as_str())
-        .param("name", ctx.name.as_str())
-        .param("description", ctx.description.as_deref().unwrap_or(""))
-        .param("responsibilities", ctx.responsibilities.as_deref().unwrap_or(""))
-        .param("created_at", ctx.created_at.as_str())
-        .param("updated_at", ctx.updated_at.as_str());
-
-        client.execute(query).await?;
-        result.nodes_created += 1;
-
-        // BELONGS_TO Project
-        let rel_query = Query::new(
-            "MATCH (c:BoundedContext {id: $ctx_id}), (p:Project {id: $project_id})
-             MERGE (c)-[:BELONGS_TO]->(p)"
-                .to_string(),
-        )
-        .param("ctx_id", ctx.id.as_str())
-        .param("project_id", project_id);
-
-        client.execute(rel_query).await?;
-        result.relationships_created += 1;
-
-        // Upstream context relationships — use relationship_type if present,
-        // otherwise default to UPSTREAM_OF.
-        // Supported DDD patterns: CONFORMIST, ANTI_CORRUPTION_LAYER,
-        // OPEN_HOST_SERVICE, PARTNERSHIP, SHARED_KERNEL, CUSTOMER_SUPPLIER
-        let rel_type = ctx
-            .relationship_type
-            .as_deref()
-            .map(ddd_relationship_type)
-            .unwrap_or("UPSTREAM_OF");
-
-        if let Some(ref upstream_json) = ctx.upstream_contexts {
-            if let Ok(upstream_ids) = serde_json::from_str::<Vec<String>>(upstream_json) {
-                for up_id in &upstream_ids {
-                    let up_query = Query::new(format!(
-                        "MATCH (c:BoundedContext {{id: $ctx_id}}), (up:BoundedContext {{id: $up_id}})
-                         MERGE (up)-[:{rel_type}]->(c)"
-                    ))
-                    .param("ctx_id", ctx.id.as_str())
-                    .param("up_id", up_id.as_str());
-
-                    client.execute(up_query).await?;
-                    result.relationships_created += 1;
-                }
-            }
+        if is_changed_since(&ctx.updated_at, since) {
+            sync_context(client, ctx, project_id, &mut result).await?;
+        } else {
+            result.nodes_unchanged += 1;
         }
 
         // Sync domain objects for this context
-        let objects = cwa_db::queries::domains::list_domain_objects(db, &ctx.id).await
+        let objects = cwa_db::queries::domains::list_domain_objects_by_context(db, project_id, &ctx.id).await
             .map_err(|e| anyhow::anyhow!("Failed to list domain objects: {}", e))?;
 
         for obj in &objects {
+            if !is_changed_since(&obj.updated_at, since) {
+                result.nodes_unchanged += 1;
+                continue;
+            }
+
             // Use a richer node label based on the DDD object type.
             // Core label is always DomainEntity; extended types get their own label.
             let extra_label = domain_object_extra_label(&obj.object_type);
@@ -132,8 +95,6 @@ pub async fn sync_domain(client: &GraphClient, db: &DbPool, project_id: &str) ->
 
             debug!(entity_id = %obj.id, name = %obj.name, object_type = %obj.object_type, "Synced domain entity");
         }
-
-        debug!(context_id = %ctx.id, name = %ctx.name, "Synced bounded context");
     }
 
     // Sync glossary terms
@@ -141,6 +102,11 @@ pub async fn sync_domain(client: &GraphClient, db: &DbPool, project_id: &str) ->
         .map_err(|e| anyhow::anyhow!("Failed to list glossary: {}", e))?;
 
     for term in &terms {
+        if !is_changed_since(&term.updated_at, since) {
+            result.nodes_unchanged += 1;
+            continue;
+        }
+
         let term_query = Query::new(
             "MERGE (t:Term {name: $name})
              SET t.definition = $definition,
@@ -170,6 +136,16 @@ pub async fn sync_domain(client: &GraphClient, db: &DbPool, project_id: &str) ->
         client.execute(proj_rel).await?;
         result.relationships_created += 1;
 
+        // Drop the previous context link in case the term moved
+        let clear_query = Query::new(
+            "MATCH (t:Term {name: $name})-[r:DEFINED_IN]->()
+             DELETE r"
+                .to_string(),
+        )
+        .param("name", term.term.as_str());
+
+        client.execute(clear_query).await?;
+
         // DEFINED_IN BoundedContext (if context_id is set)
         if let Some(ref ctx_id) = term.context_id {
             let ctx_rel = Query::new(
@@ -190,6 +166,85 @@ pub async fn sync_domain(client: &GraphClient, db: &DbPool, project_id: &str) ->
     Ok(result)
 }
 
+/// Upsert a bounded context node with its project and upstream relationships.
+async fn sync_context(
+    client: &GraphClient,
+    ctx: &cwa_db::queries::domains::BoundedContextRow,
+    project_id: &str,
+    result: &mut SyncResult,
+) -> Result<()> {
+    let query = Query::new(
+        "MERGE (c:BoundedContext {id: $id})
+         SET c.name = $name,
+             c.description = $description,
+             c.responsibilities = $responsibilities,
+             c.created_at = $created_at,
+             c.updated_at = $updated_at"
+            .to_string(),
+    )
+    .param("id", ctx.id.as_str())
+    .param("name", ctx.name.as_str())
+    .param("description", ctx.description.as_deref().unwrap_or(""))
+    .param("responsibilities", ctx.responsibilities.as_deref().unwrap_or(""))
+    .param("created_at", ctx.created_at.as_str())
+    .param("updated_at", ctx.updated_at.as_str());
+
+    client.execute(query).await?;
+    result.nodes_created += 1;
+
+    // BELONGS_TO Project
+    let rel_query = Query::new(
+        "MATCH (c:BoundedContext {id: $ctx_id}), (p:Project {id: $project_id})
+         MERGE (c)-[:BELONGS_TO]->(p)"
+            .to_string(),
+    )
+    .param("ctx_id", ctx.id.as_str())
+    .param("project_id", project_id);
+
+    client.execute(rel_query).await?;
+    result.relationships_created += 1;
+
+    // Upstream context relationships — use relationship_type if present,
+    // otherwise default to UPSTREAM_OF.
+    // Supported DDD patterns: CONFORMIST, ANTI_CORRUPTION_LAYER,
+    // OPEN_HOST_SERVICE, PARTNERSHIP, SHARED_KERNEL, CUSTOMER_SUPPLIER
+    let rel_type = ctx
+        .relationship_type
+        .as_deref()
+        .map(ddd_relationship_type)
+        .unwrap_or("UPSTREAM_OF");
+
+    // Drop previous upstream links; the context's own row is the source of truth
+    let clear_query = Query::new(
+        "MATCH (:BoundedContext)-[r]->(c:BoundedContext {id: $ctx_id})
+         DELETE r"
+            .to_string(),
+    )
+    .param("ctx_id", ctx.id.as_str());
+
+    client.execute(clear_query).await?;
+
+    if let Some(ref upstream_json) = ctx.upstream_contexts {
+        if let Ok(upstream_ids) = serde_json::from_str::<Vec<String>>(upstream_json) {
+            for up_id in &upstream_ids {
+                let up_query = Query::new(format!(
+                    "MATCH (c:BoundedContext {{id: $ctx_id}}), (up:BoundedContext {{id: $up_id}})
+                     MERGE (up)-[:{rel_type}]->(c)"
+                ))
+                .param("ctx_id", ctx.id.as_str())
+                .param("up_id", up_id.as_str());
+
+                client.execute(up_query).await?;
+                result.relationships_created += 1;
+            }
+        }
+    }
+
+    debug!(context_id = %ctx.id, name = %ctx.name, "Synced bounded context");
+
+    Ok(())
+}
+
 /// Map a DDD relationship type string to the Neo4j relationship label.
 ///
 /// Supported types (case-insensitive):
@@ -6,21 +6,34 @@
 //! - (:Task)-[:BLOCKED_BY]->(:Task)
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use neo4rs::Query;
 use tracing::debug;
 
 use cwa_db::DbPool;
 use crate::GraphClient;
-use super::SyncResult;
+use super::{is_changed_since, SyncResult};
 
-/// Sync all tasks for a project to Neo4j.
-pub async fn sync_tasks(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<SyncResult> {
+/// Sync tasks for a project to Neo4j.
+///
+/// With `since`, only tasks updated after that instant are pushed.
+pub async fn sync_tasks(
+    client: &GraphClient,
+    db: &DbPool,
+    project_id: &str,
+    since: Option<&DateTime<Utc>>,
+) -> Result<SyncResult> {
     let tasks = cwa_db::queries::tasks::list_tasks(db, project_id).await
         .map_err(|e| anyhow::anyhow!("Failed to list tasks: {}", e))?;
 
     let mut result = SyncResult::default();
 
     for task in &tasks {
+        if !is_changed_since(&task.updated_at, since) {
+            result.nodes_unchanged += 1;
+            continue;
+        }
+
         // MERGE the Task node
         let query = Query::new(
             "MERGE (t:Task {id: $id})
@@ -67,6 +80,16 @@ pub async fn sync_tasks(client: &GraphClient, db: &DbPool, project_id: &str) ->
         client.execute(proj_rel).await?;
         result.relationships_created += 1;
 
+        // Drop existing spec/blocker links so removed ones don't linger
+        let clear_query = Query::new(
+            "MATCH (t:Task {id: $task_id})-[r:IMPLEMENTS|BLOCKED_BY]->()
+             DELETE r"
+                .to_string(),
+        )
+        .param("task_id", task.id.as_str());
+
+        client.execute(clear_query).await?;
+
         // IMPLEMENTS Spec (if spec_id is set)
         if let Some(ref spec_id) = task.spec_id {
             let spec_rel = Query::new(
@@ -7,19 +7,26 @@
 //! - (:Observation)-[:MODIFIES]->(:File)        (for files_modified)
 //! - (:Observation)-[:READS]->(:File)            (for files_read)
 
+use std::collections::HashMap;
+
 use anyhow::Result;
-use neo4rs::Query;
+use chrono::{DateTime, Utc};
+use neo4rs::{BoltType, Query};
 use tracing::debug;
 
 use cwa_db::DbPool;
 use crate::GraphClient;
-use super::SyncResult;
+use super::{is_changed_since, SyncResult};
 
 /// Sync observations from Redis to Neo4j as reasoning memory nodes.
+///
+/// With `since`, only observations recorded after that instant are upserted;
+/// older ones just get their (decaying) confidence refreshed in one batch.
 pub async fn sync_observations(
     client: &GraphClient,
     db: &DbPool,
     project_id: &str,
+    since: Option<&DateTime<Utc>>,
 ) -> Result<SyncResult> {
     let mut result = SyncResult::default();
 
@@ -32,7 +39,18 @@ pub async fn sync_observations(
     .await
     .map_err(|e| anyhow::anyhow!("Failed to list observations: {}", e))?;
 
+    let mut confidence_updates: Vec<BoltType> = Vec::new();
+
     for obs in &observations {
+        if !is_changed_since(&obs.created_at, since) {
+            let mut entry = HashMap::new();
+            entry.insert("id".to_string(), BoltType::from(obs.id.as_str()));
+            entry.insert("confidence".to_string(), BoltType::from(obs.confidence));
+            confidence_updates.push(BoltType::from(entry));
+            result.nodes_unchanged += 1;
+            continue;
+        }
+
         // Upsert Observation node
         let node_query = Query::new(
             "MERGE (o:Observation {id: $id})
@@ -71,6 +89,18 @@ pub async fn sync_observations(
         );
     }
 
+    if !confidence_updates.is_empty() {
+        let refresh_query = Query::new(
+            "UNWIND $updates AS u
+             MATCH (o:Observation {id: u.id})
+             SET o.confidence = u.confidence"
+                .to_string(),
+        )
+        .param("updates", confidence_updates);
+
+        client.execute(refresh_query).await?;
+    }
+
     // For full observations (with files), fetch details individually
     // We limit to recent high-confidence ones to avoid overloading the graph
     let full_obs = cwa_db::queries::observations::list_high_confidence(
@@ -83,6 +113,10 @@ pub async fn sync_observations(
     .map_err(|e| anyhow::anyhow!("Failed to list high-confidence observations: {}", e))?;
 
     for obs in &full_obs {
+        if !is_changed_since(&obs.created_at, since) {
+            continue;
+        }
+
         // Link files modified
         if let Some(ref files_json) = obs.files_modified {
             if let Ok(files) = serde_json::from_str::<Vec<String>>(files_json) {
@@ -1,7 +1,11 @@
-//! SQLite to Neo4j synchronization pipeline.
+//! Redis to Neo4j synchronization pipeline.
 //!
-//! Reads entities from SQLite, syncs them to Neo4j as nodes/relationships,
+//! Reads entities from Redis, syncs them to Neo4j as nodes/relationships,
 //! and tracks sync state to avoid redundant writes.
+//!
+//! A full sync pushes every entity; an incremental sync only pushes entities
+//! whose `updated_at` is newer than the last recorded sync. Both finish by
+//! pruning graph nodes whose Redis source no longer exists.
 
 pub mod spec_sync;
 pub mod domain_sync;
@@ -9,8 +13,11 @@ pub mod kanban_sync;
 pub mod decision_sync;
 pub mod design_sync;
 pub mod memory;
+pub mod prune;
+pub mod worker;
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use neo4rs::Query;
 use redis::AsyncCommands;
 use tracing::info;
@@ -24,6 +31,10 @@ pub struct SyncResult {
     pub nodes_created: usize,
     pub nodes_updated: usize,
     pub relationships_created: usize,
+    /// Entities skipped because they did not change since the last sync.
+    pub nodes_unchanged: usize,
+    /// Graph nodes removed because their Redis source was deleted.
+    pub nodes_deleted: usize,
 }
 
 impl SyncResult {
@@ -31,66 +42,146 @@ impl SyncResult {
         self.nodes_created += other.nodes_created;
         self.nodes_updated += other.nodes_updated;
         self.relationships_created += other.relationships_created;
+        self.nodes_unchanged += other.nodes_unchanged;
+        self.nodes_deleted += other.nodes_deleted;
+    }
+}
+
+/// Observer of a running sync, called after each stage with
+/// `(stages done, total stages, stage name)`.
+pub type SyncProgress<'a> = &'a (dyn Fn(usize, usize, &str) + Send + Sync);
+
+/// Number of stages reported to a [`SyncProgress`].
+const SYNC_STAGES: usize = 8;
+
+/// Whether an entity timestamp is newer than the last sync.
+///
+/// Always `true` for a full sync (`since` is `None`) and for timestamps that
+/// cannot be parsed, so malformed data is re-synced rather than skipped.
+pub fn is_changed_since(timestamp: &str, since: Option<&DateTime<Utc>>) -> bool {
+    let Some(since) = since else {
+        return true;
+    };
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(ts) => ts.with_timezone(&Utc) > *since,
+        Err(_) => true,
     }
 }
 
 /// Run full sync from SQLite to Neo4j for a given project.
 pub async fn run_full_sync(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<SyncResult> {
-    info!(project_id, "Starting full graph sync");
+    run_sync(client, db, project_id, None, &|_, _, _| {}).await
+}
+
+/// Push only the entities changed since the last sync, then prune deleted ones.
+///
+/// Falls back to a full sync if the project was never synced.
+pub async fn run_incremental_sync(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<SyncResult> {
+    let since = last_sync(db, project_id).await?;
+    run_sync(client, db, project_id, since, &|_, _, _| {}).await
+}
+
+/// Run a full or incremental sync, reporting each finished stage to `progress`.
+pub async fn run_sync_with_progress(
+    client: &GraphClient,
+    db: &DbPool,
+    project_id: &str,
+    incremental: bool,
+    progress: SyncProgress<'_>,
+) -> Result<SyncResult> {
+    let since = if incremental { last_sync(db, project_id).await? } else { None };
+    run_sync(client, db, project_id, since, progress).await
+}
 
+async fn last_sync(db: &DbPool, project_id: &str) -> Result<Option<DateTime<Utc>>> {
+    Ok(get_last_sync_time(db, project_id)
+        .await?
+        .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+        .map(|ts| ts.with_timezone(&Utc)))
+}
+
+async fn run_sync(
+    client: &GraphClient,
+    db: &DbPool,
+    project_id: &str,
+    since: Option<DateTime<Utc>>,
+    progress: SyncProgress<'_>,
+) -> Result<SyncResult> {
+    let mode = if since.is_some() { "incremental" } else { "full" };
+    info!(project_id, mode, "Starting graph sync");
+
+    // Taken before reading Redis so changes made during the sync are picked up next time.
+    let started_at = Utc::now();
+    let since = since.as_ref();
     let mut total = SyncResult::default();
 
     // Sync project node first
     sync_project_node(client, db, project_id).await?;
     total.nodes_created += 1;
+    progress(1, SYNC_STAGES, "project");
 
     // Sync specs
-    let spec_result = spec_sync::sync_specs(client, db, project_id).await
+    let spec_result = spec_sync::sync_specs(client, db, project_id, since).await
         .context("Failed to sync specs")?;
     info!(nodes = spec_result.nodes_created + spec_result.nodes_updated, rels = spec_result.relationships_created, "Specs synced");
     total.merge(&spec_result);
+    progress(2, SYNC_STAGES, "specs");
 
     // Sync domain model (contexts + objects + terms)
-    let domain_result = domain_sync::sync_domain(client, db, project_id).await
+    let domain_result = domain_sync::sync_domain(client, db, project_id, since).await
         .context("Failed to sync domain model")?;
     info!(nodes = domain_result.nodes_created + domain_result.nodes_updated, rels = domain_result.relationships_created, "Domain synced");
     total.merge(&domain_result);
+    progress(3, SYNC_STAGES, "domain model");
 
     // Sync tasks
-    let task_result = kanban_sync::sync_tasks(client, db, project_id).await
+    let task_result = kanban_sync::sync_tasks(client, db, project_id, since).await
         .context("Failed to sync tasks")?;
     info!(nodes = task_result.nodes_created + task_result.nodes_updated, rels = task_result.relationships_created, "Tasks synced");
     total.merge(&task_result);
+    progress(4, SYNC_STAGES, "tasks");
 
     // Sync decisions
-    let decision_result = decision_sync::sync_decisions(client, db, project_id).await
+    let decision_result = decision_sync::sync_decisions(client, db, project_id, since).await
         .context("Failed to sync decisions")?;
     info!(nodes = decision_result.nodes_created + decision_result.nodes_updated, rels = decision_result.relationships_created, "Decisions synced");
     total.merge(&decision_result);
+    progress(5, SYNC_STAGES, "decisions");
 
     // Sync design systems
-    let design_result = design_sync::sync_design_systems(client, db, project_id).await
+    let design_result = design_sync::sync_design_systems(client, db, project_id, since).await
         .context("Failed to sync design systems")?;
     info!(nodes = design_result.nodes_created + design_result.nodes_updated, rels = design_result.relationships_created, "Design systems synced");
     total.merge(&design_result);
+    progress(6, SYNC_STAGES, "design systems");
 
     // Sync observations as reasoning memory nodes
-    let memory_result = memory::sync_observations(client, db, project_id).await
+    let memory_result = memory::sync_observations(client, db, project_id, since).await
         .context("Failed to sync observations")?;
     info!(nodes = memory_result.nodes_created, rels = memory_result.relationships_created, "Observations synced");
     total.merge(&memory_result);
+    progress(7, SYNC_STAGES, "observations");
 
-    // Sync state tracking not needed with Redis backend
+    // Remove nodes whose source entity was deleted
+    let deleted = prune::prune_deleted(client, db, project_id).await
+        .context("Failed to prune deleted entities")?;
+    info!(deleted, "Deleted entities pruned");
+    total.nodes_deleted += deleted;
+    progress(8, SYNC_STAGES, "deleted entities");
 
     info!(
+        mode,
         nodes_created = total.nodes_created,
         nodes_updated = total.nodes_updated,
+        nodes_unchanged = total.nodes_unchanged,
+        nodes_deleted = total.nodes_deleted,
         relationships = total.relationships_created,
-        "Full sync complete"
+        "Sync complete"
     );
 
-    // Record sync timestamp so `cwa graph status` can show it
-    save_last_sync_time(db, project_id).await?;
+    // Record sync timestamp so `cwa graph status` can show it and the next
+    // incremental sync knows where to start
+    save_sync_time(db, project_id, &started_at).await?;
 
     Ok(total)
 }
@@ -120,10 +211,13 @@ async fn sync_project_node(client: &GraphClient, db: &DbPool, project_id: &str)
 
 /// Save the current UTC timestamp as the last sync time for a project.
 pub async fn save_last_sync_time(db: &DbPool, project_id: &str) -> Result<()> {
+    save_sync_time(db, project_id, &Utc::now()).await
+}
+
+async fn save_sync_time(db: &DbPool, project_id: &str, at: &DateTime<Utc>) -> Result<()> {
     let mut conn = db.clone();
     let key = format!("graph:sync:{}", project_id);
-    let now = chrono::Utc::now().to_rfc3339();
-    conn.set::<_, _, ()>(&key, &now).await
+    conn.set::<_, _, ()>(&key, at.to_rfc3339()).await
         .map_err(|e| anyhow::anyhow!("Redis error saving sync time: {}", e))?;
     Ok(())
 }
@@ -136,3 +230,20 @@ pub async fn get_last_sync_time(db: &DbPool, project_id: &str) -> Result<Option<
         .map_err(|e| anyhow::anyhow!("Redis error reading sync time: {}", e))?;
     Ok(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_changed_since() {
+        let since = DateTime::parse_from_rfc3339("2026-01-10T12:00:00+00:00").unwrap().with_timezone(&Utc);
+
+        assert!(is_changed_since("2026-01-10T12:00:00.5+00:00", Some(&since)));
+        assert!(is_changed_since("2026-01-10T13:30:00+01:00", Some(&since)));
+        assert!(!is_changed_since("2026-01-10T12:00:00+00:00", Some(&since)));
+        assert!(!is_changed_since("2026-01-09T23:59:59.999999+00:00", Some(&since)));
+        assert!(is_changed_since("not a timestamp", Some(&since)));
+        assert!(is_changed_since("2020-01-01T00:00:00+00:00", None));
+    }
+}
@@ -0,0 +1,130 @@
+//! Removal of graph nodes whose Redis source entity was deleted.
+//!
+//! MERGE-based sync never deletes anything, so after each sync the set of
+//! IDs still present in Redis is compared against the project's nodes in
+//! Neo4j and the leftovers are detached and deleted.
+
+use anyhow::Result;
+use neo4rs::Query;
+use tracing::debug;
+
+use cwa_db::DbPool;
+use crate::GraphClient;
+
+/// Labels owned directly by the project through `BELONGS_TO`.
+const PROJECT_LABELS: &[&str] = &["Spec", "Task", "Decision"];
+
+/// Delete graph nodes for entities that no longer exist in Redis.
+///
+/// Returns the number of nodes removed.
+pub async fn prune_deleted(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<usize> {
+    let mut deleted = 0;
+
+    for label in PROJECT_LABELS {
+        let ids = list_ids(db, project_id, label).await?;
+        deleted += prune_label(client, project_id, label, ids).await?;
+    }
+
+    // Domain entities hang off their context, so prune them before contexts
+    let object_ids = cwa_db::queries::domains::list_domain_objects(db, project_id).await
+        .map_err(|e| anyhow::anyhow!("Failed to list domain objects: {}", e))?
+        .into_iter()
+        .map(|o| o.id)
+        .collect::<Vec<_>>();
+
+    let objects_query = Query::new(
+        "MATCH (e:DomainEntity)-[:PART_OF]->(:BoundedContext)-[:BELONGS_TO]->(:Project {id: $project_id})
+         WHERE NOT e.id IN $ids
+         DETACH DELETE e
+         RETURN count(*) as deleted"
+            .to_string(),
+    )
+    .param("project_id", project_id)
+    .param("ids", object_ids);
+    deleted += count_deleted(client, objects_query).await?;
+
+    let context_ids = cwa_db::queries::domains::list_contexts(db, project_id).await
+        .map_err(|e| anyhow::anyhow!("Failed to list contexts: {}", e))?
+        .into_iter()
+        .map(|c| c.id)
+        .collect::<Vec<_>>();
+    deleted += prune_label(client, project_id, "BoundedContext", context_ids).await?;
+
+    // Terms are keyed by name and may be shared across projects: only drop
+    // this project's link, then the node itself once nothing else owns it.
+    let term_names = cwa_db::queries::domains::list_glossary(db, project_id).await
+        .map_err(|e| anyhow::anyhow!("Failed to list glossary: {}", e))?
+        .into_iter()
+        .map(|t| t.term)
+        .collect::<Vec<_>>();
+
+    let terms_query = Query::new(
+        "MATCH (t:Term)-[r:BELONGS_TO]->(:Project {id: $project_id})
+         WHERE NOT t.name IN $names
+         DELETE r
+         WITH DISTINCT t
+         WHERE NOT (t)-[:BELONGS_TO]->(:Project)
+         DETACH DELETE t
+         RETURN count(*) as deleted"
+            .to_string(),
+    )
+    .param("project_id", project_id)
+    .param("names", term_names);
+    deleted += count_deleted(client, terms_query).await?;
+
+    let observation_ids = cwa_db::queries::observations::list_observation_ids(db, project_id).await
+        .map_err(|e| anyhow::anyhow!("Failed to list observations: {}", e))?;
+
+    let observations_query = Query::new(
+        "MATCH (o:Observation)-[:RECORDED_IN]->(:Project {id: $project_id})
+         WHERE NOT o.id IN $ids
+         DETACH DELETE o
+         RETURN count(*) as deleted"
+            .to_string(),
+    )
+    .param("project_id", project_id)
+    .param("ids", observation_ids);
+    deleted += count_deleted(client, observations_query).await?;
+
+    // Design systems are not pruned: the Redis store does not persist them
+    // yet, so an empty listing would wipe every DesignSystem node.
+
+    debug!(project_id, deleted, "Pruned deleted entities");
+    Ok(deleted)
+}
+
+/// List the Redis IDs for one of the [`PROJECT_LABELS`].
+async fn list_ids(db: &DbPool, project_id: &str, label: &str) -> Result<Vec<String>> {
+    let ids = match label {
+        "Spec" => cwa_db::queries::specs::list_specs(db, project_id).await
+            .map(|rows| rows.into_iter().map(|r| r.id).collect()),
+        "Task" => cwa_db::queries::tasks::list_tasks(db, project_id).await
+            .map(|rows| rows.into_iter().map(|r| r.id).collect()),
+        "Decision" => cwa_db::queries::decisions::list_decisions(db, project_id).await
+            .map(|rows| rows.into_iter().map(|r| r.id).collect()),
+        _ => return Ok(Vec::new()),
+    };
+    ids.map_err(|e| anyhow::anyhow!("Failed to list {} IDs: {}", label, e))
+}
+
+/// Delete `label` nodes belonging to the project whose ID is not in `ids`.
+async fn prune_label(client: &GraphClient, project_id: &str, label: &str, ids: Vec<String>) -> Result<usize> {
+    let query = Query::new(format!(
+        "MATCH (n:{label})-[:BELONGS_TO]->(:Project {{id: $project_id}})
+         WHERE NOT n.id IN $ids
+         DETACH DELETE n
+         RETURN count(*) as deleted"
+    ))
+    .param("project_id", project_id)
+    .param("ids", ids);
+    count_deleted(client, query).await
+}
+
+async fn count_deleted(client: &GraphClient, query: Query) -> Result<usize> {
+    let rows = client.query(query).await?;
+    let deleted = rows
+        .first()
+        .and_then(|row| row.get::<i64>("deleted").ok())
+        .unwrap_or(0);
+    Ok(deleted.max(0) as usize)
+}
@@ -3,21 +3,34 @@
 //! Syncs specs as (:Spec) nodes with relationships to (:Project).
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use neo4rs::Query;
 use tracing::debug;
 
 use cwa_db::DbPool;
 use crate::GraphClient;
-use super::SyncResult;
+use super::{is_changed_since, SyncResult};
 
-/// Sync all specs for a project to Neo4j.
-pub async fn sync_specs(client: &GraphClient, db: &DbPool, project_id: &str) -> Result<SyncResult> {
+/// Sync specs for a project to Neo4j.
+///
+/// With `since`, only specs updated after that instant are pushed.
+pub async fn sync_specs(
+    client: &GraphClient,
+    db: &DbPool,
+    project_id: &str,
+    since: Option<&DateTime<Utc>>,
+) -> Result<SyncResult> {
     let specs = cwa_db::queries::specs::list_specs(db, project_id).await
         .map_err(|e| anyhow::anyhow!("Failed to list specs: {}", e))?;
 
     let mut result = SyncResult::default();
 
     for spec in &specs {
+        if !is_changed_since(&spec.updated_at, since) {
+            result.nodes_unchanged += 1;
+            continue;
+        }
+
         // MERGE the Spec node
         let query = Query::new(
             "MERGE (s:Spec {id: $id})
@@ -54,6 +67,16 @@ pub async fn sync_specs(client: &GraphClient, db: &DbPool, project_id: &str) ->
         client.execute(rel_query).await?;
         result.relationships_created += 1;
 
+        // Drop existing dependencies so removed ones don't linger
+        let clear_query = Query::new(
+            "MATCH (s:Spec {id: $spec_id})-[r:DEPENDS_ON]->()
+             DELETE r"
+                .to_string(),
+        )
+        .param("spec_id", spec.id.as_str());
+
+        client.execute(clear_query).await?;
+
         // Handle spec dependencies (stored as JSON array of spec IDs)
         if let Some(ref deps_json) = spec.dependencies {
             if let Ok(dep_ids) = serde_json::from_str::<Vec<String>>(deps_json) {
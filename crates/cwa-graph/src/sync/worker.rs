@@ -0,0 +1,48 @@
+//! Background worker that keeps the graph in step with Redis writes.
+//!
+//! Listens on the broadcast channel used for WebSocket updates and runs an
+//! incremental sync shortly after each burst of changes.
+
+use std::time::Duration;
+
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tracing::{debug, info, warn};
+
+use cwa_db::{BroadcastReceiver, DbPool};
+use crate::GraphClient;
+
+/// Run incremental syncs for `project_id` whenever a change is broadcast.
+///
+/// Changes arriving within `debounce` of each other are coalesced into a
+/// single sync. Returns when the broadcast channel closes.
+pub async fn run_sync_worker(
+    client: GraphClient,
+    db: DbPool,
+    project_id: String,
+    mut rx: BroadcastReceiver,
+    debounce: Duration,
+) {
+    info!(project_id = %project_id, "Graph sync worker started");
+
+    loop {
+        match rx.recv().await {
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => break,
+        }
+
+        // Wait out the burst, draining anything that arrives meanwhile
+        tokio::time::sleep(debounce).await;
+        while let Ok(_) | Err(TryRecvError::Lagged(_)) = rx.try_recv() {}
+
+        match super::run_incremental_sync(&client, &db, &project_id).await {
+            Ok(result) => debug!(
+                nodes = result.nodes_created + result.nodes_updated,
+                deleted = result.nodes_deleted,
+                "Incremental graph sync finished"
+            ),
+            Err(e) => warn!(error = %e, "Incremental graph sync failed"),
+        }
+    }
+
+    info!(project_id = %project_id, "Graph sync worker stopped");
+}
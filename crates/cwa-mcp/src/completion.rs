@@ -0,0 +1,274 @@
+//! MCP argument completion (`completion/complete`).
+//!
+//! Suggests real spec IDs, task IDs, context names and enum values so
+//! agents stop guessing identifiers. Completes prompt arguments
+//! (`ref/prompt`), resource template parameters (`ref/resource`) and, as a
+//! CWA extension, tool arguments (`ref/tool` with the tool's `name`).
+//! Values come from the project named by the `project` argument already
+//! given in `context.arguments`, else from the session's project.
+
+use cwa_core::memory::observation::ObservationType;
+use cwa_core::plan::model::{OBJECT_TYPES, PRIORITIES};
+use cwa_db::DbPool;
+
+use crate::server::JsonRpcError;
+use crate::session::Session;
+use crate::templates;
+
+/// Most values returned by one completion, as the MCP spec allows.
+pub const MAX_VALUES: usize = 100;
+
+const SPEC_STATUSES: &[&str] = &["draft", "active", "in_review", "accepted", "completed", "archived"];
+const MEMORY_TYPES: &[&str] = &["preference", "decision", "fact", "pattern"];
+
+/// Where the values for an argument come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// Spec IDs, matched by ID prefix or title.
+    Spec,
+    /// Task IDs, matched by ID prefix.
+    Task,
+    /// Bounded context IDs, matched by ID prefix or name.
+    ContextId,
+    /// Bounded context names.
+    ContextName,
+    /// Columns of the project's workflow.
+    Column,
+    /// Names of the registered projects.
+    Project,
+    /// Paths of the fixed `project://` resources.
+    Resource,
+    /// A fixed list of values.
+    Fixed(&'static [&'static str]),
+}
+
+/// The source for `argument` of the referenced prompt, resource template or tool.
+fn source_for(reference: &serde_json::Value, argument: &str) -> Option<Source> {
+    let scoped_resource = reference["uri"].as_str().is_some_and(|uri| uri.starts_with(templates::SCOPED_PREFIX));
+    if argument == "project" && (reference["type"] != "ref/resource" || scoped_resource) {
+        return Some(Source::Project);
+    }
+    match reference["type"].as_str()? {
+        "ref/prompt" => match (reference["name"].as_str()?, argument) {
+            ("start-next-task", "task_id") => Some(Source::Task),
+            ("review-spec", "spec") => Some(Source::Spec),
+            _ => None,
+        },
+        "ref/resource" => {
+            if scoped_resource && argument == "resource" {
+                return Some(Source::Resource);
+            }
+            let template = templates::find(reference["uri"].as_str()?)?;
+            if argument != template.parameter {
+                return None;
+            }
+            match template.uri_template {
+                "project://spec/{id}" => Some(Source::Spec),
+                "project://task/{id}" => Some(Source::Task),
+                _ => Some(Source::ContextName),
+            }
+        }
+        "ref/tool" => {
+            let tool = reference["name"].as_str()?;
+            match argument {
+                "identifier" | "spec_id" | "spec_identifier" | "depends_on" => Some(Source::Spec),
+                "task_id" | "blocker_id" => Some(Source::Task),
+                "context_id" => Some(Source::ContextId),
+                "column" => Some(Source::Column),
+                "status" if tool.contains("spec") => Some(Source::Fixed(SPEC_STATUSES)),
+                "status" => Some(Source::Column),
+                "priority" => Some(Source::Fixed(PRIORITIES)),
+                "object_type" => Some(Source::Fixed(OBJECT_TYPES)),
+                "entry_type" => Some(Source::Fixed(MEMORY_TYPES)),
+                "obs_type" => Some(Source::Fixed(ObservationType::all_variants())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Handle `completion/complete`.
+pub async fn complete(
+    pool: &DbPool,
+    session: &Session,
+    params: Option<serde_json::Value>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let params = params.ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing params".to_string(),
+    })?;
+    let reference = &params["ref"];
+    let argument = params["argument"]["name"].as_str().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing argument name".to_string(),
+    })?;
+    let value = params["argument"]["value"].as_str().unwrap_or_default();
+    let project = params["context"]["arguments"]["project"].as_str();
+
+    // Tools outside the session's profile get no hints either.
+    let hidden_tool = reference["type"] == "ref/tool"
+        && !reference["name"].as_str().is_some_and(|name| session.profile().allows(name));
+    let values = match source_for(reference, argument) {
+        Some(source) if !hidden_tool => values_for(pool, source, project, session.default_project(), value)
+            .await
+            .map_err(|e| JsonRpcError {
+                code: -32603,
+                message: e.to_string(),
+            })?,
+        _ => Vec::new(),
+    };
+
+    Ok(completion_result(values))
+}
+
+/// Candidate values from `source` that match what has been typed so far,
+/// looked up in the project named by `selector` or else `default_project`.
+async fn values_for(
+    pool: &DbPool,
+    source: Source,
+    selector: Option<&str>,
+    default_project: Option<&str>,
+    typed: &str,
+) -> cwa_core::CwaResult<Vec<String>> {
+    match source {
+        Source::Fixed(values) => return Ok(starting_with(values.iter().copied(), typed)),
+        Source::Resource => {
+            let resources = crate::server::get_resources_list().unwrap_or_default();
+            let paths = resources["resources"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|r| r["uri"].as_str()?.strip_prefix("project://"));
+            return Ok(starting_with(paths, typed));
+        }
+        Source::Project => {
+            let projects = cwa_core::project::list_projects(pool).await?;
+            let mut names = starting_with(projects.iter().map(|p| p.name.as_str()), typed);
+            names.sort();
+            return Ok(names);
+        }
+        _ => {}
+    }
+
+    let project = match cwa_core::project::resolve_project(pool, selector, default_project).await {
+        Ok(project) => project,
+        Err(cwa_core::CwaError::ProjectNotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let values = match source {
+        Source::Spec => {
+            let mut ids = cwa_core::spec::list_ids_with_prefix(pool, &project.id, typed, MAX_VALUES + 1).await?;
+            if ids.is_empty() && !typed.is_empty() {
+                // Spec tools also accept titles, so look the text up there.
+                let typed = typed.to_lowercase();
+                ids = cwa_core::spec::list_specs(pool, &project.id)
+                    .await?
+                    .into_iter()
+                    .filter(|s| s.title.to_lowercase().contains(&typed))
+                    .map(|s| s.id)
+                    .collect();
+            }
+            ids
+        }
+        Source::Task => cwa_core::task::list_ids_with_prefix(pool, &project.id, typed, MAX_VALUES + 1).await?,
+        Source::ContextId => {
+            let typed = typed.to_lowercase();
+            cwa_core::domain::list_contexts(pool, &project.id)
+                .await?
+                .into_iter()
+                .filter(|c| c.id.starts_with(&typed) || c.name.to_lowercase().starts_with(&typed))
+                .map(|c| c.id)
+                .collect()
+        }
+        Source::ContextName => {
+            let contexts = cwa_core::domain::list_contexts(pool, &project.id).await?;
+            let mut names = starting_with(contexts.iter().map(|c| c.name.as_str()), typed);
+            names.sort();
+            names
+        }
+        Source::Column => {
+            let workflow = cwa_core::task::get_workflow(pool, &project.id).await?;
+            starting_with(workflow.column_names().into_iter(), typed)
+        }
+        Source::Fixed(_) | Source::Resource | Source::Project => unreachable!("handled above"),
+    };
+    Ok(values)
+}
+
+/// The values that start with `typed`, ignoring case.
+fn starting_with<'a>(values: impl Iterator<Item = &'a str>, typed: &str) -> Vec<String> {
+    let typed = typed.to_lowercase();
+    values
+        .filter(|v| v.to_lowercase().starts_with(&typed))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Wrap values in a completion result, capped at [`MAX_VALUES`].
+///
+/// IDs are looked up with a limit of `MAX_VALUES + 1`, so the full count is
+/// unknown once the cap is exceeded; `total` is then left out and only
+/// `hasMore` is set.
+fn completion_result(mut values: Vec<String>) -> serde_json::Value {
+    if values.len() > MAX_VALUES {
+        values.truncate(MAX_VALUES);
+        return serde_json::json!({
+            "completion": { "values": values, "hasMore": true }
+        });
+    }
+    serde_json::json!({
+        "completion": {
+            "values": values,
+            "total": values.len(),
+            "hasMore": false
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_map_to_their_source() {
+        let tool = |name: &str| serde_json::json!({ "type": "ref/tool", "name": name });
+        assert_eq!(source_for(&tool("cwa_get_spec"), "identifier"), Some(Source::Spec));
+        assert_eq!(source_for(&tool("cwa_add_task_blocker"), "blocker_id"), Some(Source::Task));
+        assert_eq!(source_for(&tool("cwa_create_domain_object"), "context_id"), Some(Source::ContextId));
+        assert_eq!(source_for(&tool("cwa_update_spec_status"), "status"), Some(Source::Fixed(SPEC_STATUSES)));
+        assert_eq!(source_for(&tool("cwa_update_task_status"), "status"), Some(Source::Column));
+        assert_eq!(source_for(&tool("cwa_add_decision"), "title"), None);
+
+        let prompt = serde_json::json!({ "type": "ref/prompt", "name": "review-spec" });
+        assert_eq!(source_for(&prompt, "spec"), Some(Source::Spec));
+
+        let resource = |uri: &str| serde_json::json!({ "type": "ref/resource", "uri": uri });
+        assert_eq!(source_for(&resource("project://context/{name}"), "name"), Some(Source::ContextName));
+        assert_eq!(source_for(&resource("project://task/{id}"), "id"), Some(Source::Task));
+        assert_eq!(source_for(&resource("project://task/{id}"), "name"), None);
+        assert_eq!(source_for(&resource("project://specs"), "id"), None);
+
+        assert_eq!(source_for(&tool("cwa_list_specs"), "project"), Some(Source::Project));
+        assert_eq!(source_for(&resource("project://spec/{id}"), "project"), None);
+        assert_eq!(source_for(&resource("cwa://{project}/spec/{id}"), "project"), Some(Source::Project));
+        assert_eq!(source_for(&resource("cwa://{project}/spec/{id}"), "id"), Some(Source::Spec));
+        assert_eq!(source_for(&resource("cwa://{project}/{resource}"), "resource"), Some(Source::Resource));
+    }
+
+    #[test]
+    fn fixed_values_are_filtered_and_capped() {
+        assert_eq!(starting_with(PRIORITIES.iter().copied(), "C"), vec!["critical"]);
+        assert_eq!(starting_with(OBJECT_TYPES.iter().copied(), "").len(), OBJECT_TYPES.len());
+
+        let many: Vec<String> = (0..150).map(|i| i.to_string()).collect();
+        let result = completion_result(many);
+        assert_eq!(result["completion"]["values"].as_array().unwrap().len(), MAX_VALUES);
+        assert!(result["completion"].get("total").is_none());
+        assert_eq!(result["completion"]["hasMore"], true);
+
+        let few = completion_result(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(few["completion"]["total"], 2);
+        assert_eq!(few["completion"]["hasMore"], false);
+    }
+}
@@ -0,0 +1,372 @@
+//! MCP Streamable HTTP transport.
+//!
+//! Serves the same tools and resources as the stdio server from a single
+//! `/mcp` endpoint, so several clients can share one long-running server.
+//! Clients POST JSON-RPC messages and get each response back either as JSON
+//! or as a one-event SSE stream, depending on their `Accept` header.
+//! `initialize` opens a session whose ID travels in the `Mcp-Session-Id`
+//! header; DELETE ends it. A GET opens the session's SSE stream of
+//! server-initiated notifications (resource updates, tool list changes,
+//! progress). Each POST is handled independently, so slow tool calls don't
+//! block other requests, and `notifications/cancelled` aborts the request
+//! it names. Sessions without an open stream expire after
+//! [`SESSION_IDLE_TIMEOUT`] of inactivity.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::post,
+    Router,
+};
+use cwa_db::{BroadcastSender, DbPool, ProjectEvent};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::profile::ToolProfile;
+use crate::server;
+use crate::session::{self, Session};
+
+/// Path the transport is mounted at.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Header carrying the session ID.
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// How long a session without an open stream lives after its last request.
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// How often expired sessions are looked for.
+const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Endpoint URL of a server started with `cwa serve` on its default port.
+pub const DEFAULT_HTTP_URL: &str = "http://127.0.0.1:3030/mcp";
+
+/// Shared state of the HTTP transport.
+#[derive(Clone)]
+struct McpHttpState {
+    pool: Arc<DbPool>,
+    broadcast_tx: Option<BroadcastSender>,
+    sessions: Arc<Mutex<HashMap<String, HttpSession>>>,
+    /// Change events from every CWA process.
+    events: broadcast::Sender<ProjectEvent>,
+    profile: ToolProfile,
+    /// Project used when a request names none.
+    default_project: Option<String>,
+}
+
+/// A session and the notifications waiting for its SSE stream.
+#[derive(Clone)]
+struct HttpSession {
+    session: Arc<Session>,
+    outgoing: Arc<tokio::sync::Mutex<mpsc::Receiver<String>>>,
+    /// When the client last sent a request.
+    last_seen: Arc<Mutex<Instant>>,
+}
+
+impl HttpSession {
+    fn new(session: Arc<Session>, outgoing: mpsc::Receiver<String>) -> Self {
+        Self {
+            session,
+            outgoing: Arc::new(tokio::sync::Mutex::new(outgoing)),
+            last_seen: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Whether the session has been idle longer than `timeout` at `now`.
+    /// A session with an open stream is never idle.
+    fn expired(&self, now: Instant, timeout: Duration) -> bool {
+        let streaming = self.outgoing.try_lock().is_err();
+        !streaming && now.duration_since(*self.last_seen.lock().unwrap()) > timeout
+    }
+}
+
+/// Drop the sessions idle longer than `timeout`, which also closes their
+/// outboxes and stops their change event forwarding.
+fn expire_sessions(sessions: &mut HashMap<String, HttpSession>, now: Instant, timeout: Duration) {
+    sessions.retain(|_, session| !session.expired(now, timeout));
+}
+
+impl McpHttpState {
+    fn open_session(&self) -> (String, HttpSession) {
+        let (outbox, outgoing) = mpsc::channel(session::OUTBOX_CAPACITY);
+        let session = HttpSession::new(
+            Session::new(outbox, self.profile.clone(), self.default_project.clone()),
+            outgoing,
+        );
+        session.session.forward_events(self.events.subscribe());
+
+        let id = session.session.id().to_string();
+        self.sessions.lock().unwrap().insert(id.clone(), session.clone());
+        (id, session)
+    }
+
+    fn session(&self, headers: &HeaderMap) -> Result<(String, HttpSession), SessionError> {
+        let id = session_from(headers).ok_or(SessionError::Missing)?;
+        match self.sessions.lock().unwrap().get(id) {
+            Some(session) => {
+                *session.last_seen.lock().unwrap() = Instant::now();
+                Ok((id.to_string(), session.clone()))
+            }
+            None => Err(SessionError::Unknown),
+        }
+    }
+}
+
+/// Why a request could not be matched to a session.
+enum SessionError {
+    Missing,
+    Unknown,
+}
+
+impl IntoResponse for SessionError {
+    fn into_response(self) -> Response {
+        match self {
+            SessionError::Missing => error_response(StatusCode::BAD_REQUEST, -32600, "Missing Mcp-Session-Id header"),
+            SessionError::Unknown => error_response(StatusCode::NOT_FOUND, -32600, "Unknown or expired session"),
+        }
+    }
+}
+
+/// Build the router serving the MCP endpoint at [`MCP_PATH`].
+///
+/// The returned router carries its own state, so it can be merged into an
+/// app with any state type. Sessions may only use the tools of `profile`,
+/// and requests that name no project work in `default_project`.
+pub fn router<S>(
+    pool: Arc<DbPool>,
+    broadcast_tx: Option<BroadcastSender>,
+    profile: ToolProfile,
+    default_project: Option<String>,
+) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let state = McpHttpState {
+        pool,
+        broadcast_tx,
+        sessions: Arc::new(Mutex::new(HashMap::new())),
+        events: session::listen_for_changes(),
+        profile,
+        default_project,
+    };
+
+    let sessions = Arc::clone(&state.sessions);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SESSION_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            expire_sessions(&mut sessions.lock().unwrap(), Instant::now(), SESSION_IDLE_TIMEOUT);
+        }
+    });
+
+    Router::new()
+        .route(
+            MCP_PATH,
+            post(handle_post).get(handle_get).delete(handle_delete),
+        )
+        .with_state(state)
+}
+
+/// POST /mcp - Handle one JSON-RPC message.
+async fn handle_post(
+    State(state): State<McpHttpState>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if !origin_allowed(&headers) {
+        return forbidden_origin();
+    }
+
+    let message: serde_json::Value = match serde_json::from_str(&body) {
+        Ok(v) => v,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, -32700, &format!("Parse error: {}", e)),
+    };
+    let method = message.get("method").and_then(|m| m.as_str());
+    let is_request = method.is_some() && message.get("id").is_some_and(|id| !id.is_null());
+
+    let (session_id, session) = if method == Some("initialize") {
+        state.open_session()
+    } else {
+        match state.session(&headers) {
+            Ok(found) => found,
+            Err(e) => return e.into_response(),
+        }
+    };
+
+    // Notifications and responses from the client are acknowledged without a body
+    if !is_request {
+        if method.is_some() {
+            server::handle_message(&state.pool, &state.broadcast_tx, &session.session, &body).await;
+        }
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    // No response means the client cancelled the request
+    let Some(response) = server::handle_message(&state.pool, &state.broadcast_tx, &session.session, &body).await else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let mut resp = if wants_sse(&headers) {
+        let event = Event::default().event("message").data(response);
+        Sse::new(futures::stream::once(async move { Ok::<_, Infallible>(event) })).into_response()
+    } else {
+        json_response(StatusCode::OK, response)
+    };
+    if let Ok(value) = HeaderValue::from_str(&session_id) {
+        resp.headers_mut().insert(SESSION_HEADER, value);
+    }
+    resp
+}
+
+/// GET /mcp - Stream the session's notifications as SSE.
+///
+/// A session has one stream at a time; notifications raised while no stream
+/// is open are delivered when the next one opens.
+async fn handle_get(State(state): State<McpHttpState>, headers: HeaderMap) -> Response {
+    if !origin_allowed(&headers) {
+        return forbidden_origin();
+    }
+    let (_, session) = match state.session(&headers) {
+        Ok(found) => found,
+        Err(e) => return e.into_response(),
+    };
+    let Ok(outgoing) = session.outgoing.try_lock_owned() else {
+        return error_response(StatusCode::CONFLICT, -32600, "A stream is already open for this session");
+    };
+
+    let stream = futures::stream::unfold(outgoing, |mut outgoing| async move {
+        let message = outgoing.recv().await?;
+        Some((Ok::<_, Infallible>(Event::default().event("message").data(message)), outgoing))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// DELETE /mcp - End a session.
+async fn handle_delete(State(state): State<McpHttpState>, headers: HeaderMap) -> Response {
+    if !origin_allowed(&headers) {
+        return forbidden_origin();
+    }
+    match session_from(&headers) {
+        None => SessionError::Missing.into_response(),
+        Some(id) if state.sessions.lock().unwrap().remove(id).is_some() => StatusCode::OK.into_response(),
+        Some(_) => SessionError::Unknown.into_response(),
+    }
+}
+
+// ============================================================
+// HELPERS
+// ============================================================
+
+fn session_from(headers: &HeaderMap) -> Option<&str> {
+    headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok())
+}
+
+/// Whether the client asked for an SSE stream rather than plain JSON.
+fn wants_sse(headers: &HeaderMap) -> bool {
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    accept.contains("text/event-stream") && !accept.contains("application/json")
+}
+
+/// Reject browser requests from non-local pages, which could otherwise
+/// reach the server through DNS rebinding. Requests without an `Origin`
+/// header (non-browser clients) are allowed.
+fn origin_allowed(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    let authority = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let authority = authority.split('/').next().unwrap_or_default();
+    let host = if authority.starts_with('[') {
+        authority.split_once(']').map_or(authority, |(h, _)| h).trim_start_matches('[')
+    } else {
+        authority.split(':').next().unwrap_or_default()
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+fn forbidden_origin() -> Response {
+    error_response(StatusCode::FORBIDDEN, -32600, "Origin not allowed")
+}
+
+fn json_response(status: StatusCode, body: String) -> Response {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap_or_else(|_| status.into_response())
+}
+
+/// A JSON-RPC error without a request ID, for transport-level failures.
+fn error_response(status: StatusCode, code: i32, message: &str) -> Response {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": code, "message": message }
+    });
+    json_response(status, body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_header(name: header::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn only_local_origins_are_allowed() {
+        assert!(origin_allowed(&HeaderMap::new()));
+        for origin in ["http://localhost:3030", "http://127.0.0.1", "http://[::1]:8080", "vscode-webview://localhost"] {
+            assert!(origin_allowed(&with_header(header::ORIGIN, origin)), "{}", origin);
+        }
+        for origin in ["https://evil.example", "http://localhost.evil.example", "http://127.0.0.1.nip.io", "null"] {
+            assert!(!origin_allowed(&with_header(header::ORIGIN, origin)), "{}", origin);
+        }
+    }
+
+    #[tokio::test]
+    async fn idle_sessions_expire_unless_streaming() {
+        let open = |sessions: &mut HashMap<String, HttpSession>| {
+            let (outbox, outgoing) = mpsc::channel(session::OUTBOX_CAPACITY);
+            let session = HttpSession::new(Session::new(outbox, ToolProfile::full(), None), outgoing);
+            sessions.insert(session.session.id().to_string(), session.clone());
+            session
+        };
+        let mut sessions = HashMap::new();
+        let idle = open(&mut sessions);
+        let streaming = open(&mut sessions);
+        let _stream = streaming.outgoing.clone().try_lock_owned().unwrap();
+
+        let timeout = Duration::from_secs(60);
+        expire_sessions(&mut sessions, Instant::now(), timeout);
+        assert_eq!(sessions.len(), 2);
+
+        expire_sessions(&mut sessions, Instant::now() + timeout * 2, timeout);
+        assert!(!sessions.contains_key(idle.session.id()));
+        assert!(sessions.contains_key(streaming.session.id()));
+    }
+
+    #[test]
+    fn sse_only_when_json_is_not_accepted() {
+        assert!(!wants_sse(&HeaderMap::new()));
+        assert!(!wants_sse(&with_header(header::ACCEPT, "application/json, text/event-stream")));
+        assert!(wants_sse(&with_header(header::ACCEPT, "text/event-stream")));
+    }
+}
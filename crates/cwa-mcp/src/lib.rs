@@ -2,22 +2,51 @@
 //!
 //! Model Context Protocol server for Claude Code integration.
 
+pub mod completion;
+pub mod http;
 pub mod planner;
 pub mod planner_template;
+pub mod profile;
+pub mod prompts;
+pub mod schema;
 pub mod server;
+pub mod session;
+pub mod templates;
 
 use cwa_db::{BroadcastSender, DbPool};
 use std::sync::Arc;
 
+pub use profile::ToolProfile;
+
 /// Run the MCP server over stdio.
 ///
 /// If `broadcast_tx` is provided, task updates will be broadcast directly
 /// to WebSocket clients (when running alongside the web server via `cwa serve`).
+/// The client may only use the tools of `profile`. Requests that name no
+/// project work in `default_project` (a project ID), or else the first
+/// project found.
 pub async fn run_stdio_server(
     pool: Arc<DbPool>,
     broadcast_tx: Option<BroadcastSender>,
+    profile: ToolProfile,
+    default_project: Option<String>,
 ) -> anyhow::Result<()> {
-    server::run_stdio(pool, broadcast_tx).await
+    server::run_stdio(pool, broadcast_tx, profile, default_project).await
+}
+
+/// Router serving the MCP Streamable HTTP transport at `/mcp`, for mounting
+/// on the web server. Every session is limited to the tools of `profile`,
+/// and requests that name no project work in `default_project`.
+pub fn http_router<S>(
+    pool: Arc<DbPool>,
+    broadcast_tx: Option<BroadcastSender>,
+    profile: ToolProfile,
+    default_project: Option<String>,
+) -> axum::Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    http::router(pool, broadcast_tx, profile, default_project)
 }
 
 /// Run the MCP planner server over stdio (for Claude Desktop).
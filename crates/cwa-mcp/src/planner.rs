@@ -191,7 +191,7 @@ fn handle_tools_list() -> Result<serde_json::Value, JsonRpcError> {
     Ok(serde_json::json!({
         "tools": [{
             "name": "cwa_plan_software",
-            "description": "Generate a software plan using Domain-Driven Design (DDD) and Specification-Driven Development (SDD) methodologies. Returns executable CWA CLI commands covering: Strategic Design (bounded contexts, subdomains), Architectural Decisions (ADRs), Tech Stack decisions, and Specifications (source of truth with acceptance criteria). The AI asks clarifying questions before generating the plan.",
+            "description": "Generate a software plan using Domain-Driven Design (DDD) and Specification-Driven Development (SDD) methodologies. Returns executable CWA CLI commands covering: Strategic Design (bounded contexts, subdomains), Architectural Decisions (ADRs), Tech Stack decisions, and Specifications (source of truth with acceptance criteria), plus the same plan as a JSON block that `cwa plan apply` can create in one step. The AI asks clarifying questions before generating the plan.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
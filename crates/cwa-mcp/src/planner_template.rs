@@ -119,7 +119,8 @@ pub fn render_planning_document(prompt: &str, existing: Option<ExistingState>) -
             }
         }
 
-        doc.push_str("# Generate ONLY new commands that extend the project. Use cwa spec add-criteria for existing specs.\n\n");
+        doc.push_str("# Generate ONLY new commands that extend the project. Use cwa spec add-criteria for existing specs.\n");
+        doc.push_str("# The CWA PLAN may repeat existing items: `cwa plan apply` skips them and only adds missing criteria.\n\n");
     }
 
     doc
@@ -177,6 +178,9 @@ const HEADER: &str = r#"You are a software architect using Domain-Driven Design
 ### Code Generation
 - `cwa codegen all` — Generate all artifacts
 
+### Structured Plan
+- `cwa plan apply <file> [--dry-run]` — Create everything in a CWA PLAN (JSON/YAML) idempotently; `--dry-run` shows the diff against the project
+
 ## RULES
 
 1. Ask 3-5 clarifying questions first:
@@ -188,7 +192,7 @@ const HEADER: &str = r#"You are a software architect using Domain-Driven Design
 
 2. After answers, create a SINGLE MARKDOWN ARTIFACT titled "CWA Bootstrap — [project-name]".
 
-3. The artifact must be ONLY a ```bash block with CWA commands. No other text outside the code block (except a TECH STACK table at the very end).
+3. The artifact must be ONLY a ```bash block with CWA commands. No other text outside the code block (except the TECH STACK table and the CWA PLAN block at the very end).
 
 4. Include `cwa stack set <technologies>` in Phase 9 BEFORE `cwa codegen all`. Use ONLY the technologies from the TECH STACK table you generate. This ensures tech-stack-aware expert agents are automatically selected.
 
@@ -215,6 +219,14 @@ const HEADER: &str = r#"You are a software architect using Domain-Driven Design
 
 9. After the bash block, include a TECH STACK summary table in markdown with columns: Component | Decision | Rationale.
 
+10. Finish with a `### CWA PLAN` heading and a single ```json block holding the same plan in machine-readable form, so it can be saved and applied with `cwa plan apply plan.json`:
+   - Keys: `tech_stack`, `contexts` (with `objects`), `glossary`, `decisions`, `specs`
+   - Object `type`: aggregate, entity, value_object, service, event, domain_event, saga, port or adapter
+   - Spec `priority`: critical, high, medium or low; every spec needs `criteria`
+   - `depends_on` lists the titles of specs that must be completed first
+   - It must contain exactly the same contexts, objects, terms, decisions, specs and criteria as the bash script
+
+
 "#;
 
 const TEMPLATE_SECTIONS: &str = r#"
@@ -369,11 +381,71 @@ cwa context status
 | Storage | Chrome Storage API (sync) | Cross-device sync, no backend needed |
 | IDs | UUID v4 | Uniqueness without coordination |
 
+### CWA PLAN
+
+```json
+{
+  "project": "session-manager",
+  "tech_stack": ["typescript", "react", "tailwindcss"],
+  "contexts": [
+    {
+      "name": "Session",
+      "description": "Core Domain: Lifecycle management of tab session snapshots",
+      "objects": [
+        { "name": "Session", "type": "aggregate", "description": "Root aggregate representing a named snapshot of browser tabs" },
+        { "name": "SessionMetadata", "type": "value_object", "description": "Immutable metadata: name, creation timestamp, tab count" },
+        { "name": "SessionSaved", "type": "event", "description": "Event emitted when a session is successfully saved" }
+      ]
+    },
+    {
+      "name": "Tab",
+      "description": "Supporting: Capture and representation of browser tabs",
+      "objects": [
+        { "name": "Tab", "type": "entity", "description": "A single browser tab with URL, title, favicon, and pin state" },
+        { "name": "TabCaptureService", "type": "service", "description": "Service that captures current browser tabs into a session snapshot" }
+      ]
+    },
+    {
+      "name": "Tag",
+      "description": "Supporting: Categorization and filtering via colored tags",
+      "objects": [
+        { "name": "Tag", "type": "entity", "description": "A colored label used to categorize sessions" },
+        { "name": "TagColor", "type": "value_object", "description": "Predefined color from the tag palette" }
+      ]
+    }
+  ],
+  "glossary": [
+    { "term": "Session", "definition": "A named snapshot of all currently open browser tabs, including their URLs, titles, favicons, pin states, and ordering", "context": "Session" },
+    { "term": "Restore", "definition": "The action of reopening all tabs from a saved session, preserving their original properties", "context": "Session" }
+  ],
+  "decisions": [
+    { "title": "ADR-001: Chrome Storage API (sync) for persistence", "context": "Sessions must follow the user across devices", "decision": "Use chrome.storage.sync. Alternative rejected: IndexedDB (no sync)" }
+  ],
+  "specs": [
+    {
+      "title": "Session Save",
+      "description": "Save current session capturing all open tabs with metadata",
+      "priority": "critical",
+      "criteria": ["User can save current session with custom name", "System captures URL, title, and favicon of each tab"]
+    },
+    {
+      "title": "Session Restore",
+      "description": "Restore saved sessions by reopening all tabs with original properties",
+      "priority": "critical",
+      "criteria": ["User can restore all tabs from a session", "System preserves original tab order"],
+      "depends_on": ["Session Save"]
+    }
+  ]
+}
+```
+
+(The example plan is abbreviated; yours must list every item from the script.)
+
 ## END OF EXAMPLE
 
 Generate commands for the user's prompt below using DDD/SDD methodology.
 Adapt the structure to the domain complexity — simpler projects need fewer phases.
 ALL data must be REAL (from user answers). ALL commands chained with && in a SINGLE executable script.
-Include a TECH STACK summary table after the bash block.
+Include a TECH STACK summary table after the bash block, then the CWA PLAN json block.
 
 "#;
@@ -0,0 +1,409 @@
+//! Tool permission profiles.
+//!
+//! A profile limits the tools a client of the MCP server can list and call,
+//! so that e.g. a reviewing agent can be handed a server that cannot change
+//! the board. Built-in profiles:
+//!
+//! - `read-only`: tools annotated read-only, except raw graph queries
+//! - `planner`: read-only, plus authoring specs, the domain model, decisions
+//!   and tasks
+//! - `implementer`: read-only, plus working tasks across the board and
+//!   recording memory
+//! - `full`: every tool (the default)
+//!
+//! Projects can define their own profiles, and choose the default one, in
+//! `.cwa/mcp-profiles.json`:
+//!
+//! ```json
+//! {
+//!   "default": "implementer",
+//!   "profiles": {
+//!     "reviewer": {
+//!       "description": "Reads everything, records observations",
+//!       "extends": "read-only",
+//!       "allow": ["cwa_observe", "cwa_memory_*"],
+//!       "deny": ["cwa_graph_query"]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! `allow` and `deny` entries are tool names, or prefixes ending in `*`.
+//! `cwa_graph_query` runs arbitrary Cypher, so no built-in profile but `full`
+//! includes it; a custom profile must allow it by its exact name.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::server;
+
+/// Project file defining custom profiles, relative to the project root.
+pub const PROFILES_FILE: &str = ".cwa/mcp-profiles.json";
+
+/// Profile used when neither `--profile` nor the project picks one.
+pub const DEFAULT_PROFILE: &str = "full";
+
+/// Built-in profiles, in order of increasing access.
+pub const BUILTIN_PROFILES: &[&str] = &["read-only", "planner", "implementer", "full"];
+
+/// Tools the `planner` profile adds to the read-only ones.
+const PLANNER_TOOLS: &[&str] = &[
+    "cwa_create_spec",
+    "cwa_add_acceptance_criteria",
+    "cwa_update_spec_status",
+    "cwa_add_spec_dependency",
+    "cwa_remove_spec_dependency",
+    "cwa_create_context",
+    "cwa_create_domain_object",
+    "cwa_add_glossary_term",
+    "cwa_add_decision",
+    "cwa_generate_tasks",
+    "cwa_create_task",
+    "cwa_observe",
+    "cwa_memory_add",
+    "cwa_memory_get",
+];
+
+/// Tools the `implementer` profile adds to the read-only ones.
+const IMPLEMENTER_TOOLS: &[&str] = &[
+    "cwa_create_task",
+    "cwa_update_task",
+    "cwa_update_task_status",
+    "cwa_add_task_blocker",
+    "cwa_remove_task_blocker",
+    "cwa_add_decision",
+    "cwa_observe",
+    "cwa_memory_add",
+    "cwa_memory_get",
+    "cwa_graph_sync",
+];
+
+/// Tools only `full` and custom profiles naming them exactly may use, even
+/// when annotated read-only.
+const OPT_IN_TOOLS: &[&str] = &["cwa_graph_query"];
+
+/// Profiles can extend each other up to this depth.
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// Contents of [`PROFILES_FILE`].
+#[derive(Debug, Default, Deserialize)]
+struct ProfilesFile {
+    default: Option<String>,
+    #[serde(default)]
+    profiles: HashMap<String, ProfileDef>,
+}
+
+/// A custom profile.
+#[derive(Debug, Default, Deserialize)]
+struct ProfileDef {
+    description: Option<String>,
+    extends: Option<String>,
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+/// The tools a client may use.
+#[derive(Debug, Clone)]
+pub struct ToolProfile {
+    name: String,
+    allowed: BTreeSet<String>,
+}
+
+/// A profile as listed by `cwa mcp profiles`.
+#[derive(Debug, Clone)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub description: String,
+    pub builtin: bool,
+    pub tools: Vec<String>,
+}
+
+impl ToolProfile {
+    /// Every tool.
+    pub fn full() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            allowed: all_tools().into_iter().map(|(name, _)| name).collect(),
+        }
+    }
+
+    /// Load the profile `name`, or the project's default profile, using the
+    /// custom profiles of the project at `project_dir`.
+    pub fn load(project_dir: &Path, name: Option<&str>) -> Result<Self> {
+        let file = read_profiles(project_dir)?;
+        let name = name
+            .or(file.default.as_deref())
+            .unwrap_or(DEFAULT_PROFILE);
+        Ok(Self {
+            name: name.to_string(),
+            allowed: resolve(name, &file.profiles, &all_tools(), 0)?,
+        })
+    }
+
+    /// List the built-in and custom profiles of the project at `project_dir`.
+    pub fn list(project_dir: &Path) -> Result<Vec<ProfileSummary>> {
+        let file = read_profiles(project_dir)?;
+        let tools = all_tools();
+
+        let mut names: Vec<&str> = BUILTIN_PROFILES
+            .iter()
+            .copied()
+            .filter(|n| !file.profiles.contains_key(*n))
+            .collect();
+        let mut custom: Vec<&str> = file.profiles.keys().map(String::as_str).collect();
+        custom.sort();
+        names.extend(custom);
+
+        names
+            .into_iter()
+            .map(|name| {
+                let def = file.profiles.get(name);
+                Ok(ProfileSummary {
+                    name: name.to_string(),
+                    description: def
+                        .and_then(|d| d.description.clone())
+                        .unwrap_or_else(|| builtin_description(name).to_string()),
+                    builtin: def.is_none(),
+                    tools: resolve(name, &file.profiles, &tools, 0)?.into_iter().collect(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the profile lets clients call `tool`.
+    pub fn allows(&self, tool: &str) -> bool {
+        self.allowed.contains(tool)
+    }
+
+    /// Keep only the allowed tools of a `tools/list` result.
+    pub fn filter_tools(&self, mut list: serde_json::Value) -> serde_json::Value {
+        if let Some(tools) = list["tools"].as_array_mut() {
+            tools.retain(|t| t["name"].as_str().is_some_and(|name| self.allows(name)));
+        }
+        list
+    }
+}
+
+fn read_profiles(project_dir: &Path) -> Result<ProfilesFile> {
+    let path = project_dir.join(PROFILES_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ProfilesFile::default()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Every tool with whether it is annotated read-only.
+fn all_tools() -> Vec<(String, bool)> {
+    let Ok(list) = server::get_tools_list() else {
+        return Vec::new();
+    };
+    list["tools"]
+        .as_array()
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|t| {
+                    let name = t["name"].as_str()?.to_string();
+                    Some((name, t["annotations"]["readOnlyHint"] == true))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn builtin_description(name: &str) -> &'static str {
+    match name {
+        "read-only" => "Read project data; change nothing",
+        "planner" => "Read, and author specs, domain model, decisions and tasks",
+        "implementer" => "Read, and work tasks across the board and record memory",
+        _ => "Every tool",
+    }
+}
+
+/// Resolve a profile to the names of the tools it allows.
+fn resolve(
+    name: &str,
+    custom: &HashMap<String, ProfileDef>,
+    tools: &[(String, bool)],
+    depth: usize,
+) -> Result<BTreeSet<String>> {
+    if depth > MAX_EXTENDS_DEPTH {
+        anyhow::bail!("MCP profile '{}' extends itself", name);
+    }
+
+    if let Some(def) = custom.get(name) {
+        let mut allowed = match &def.extends {
+            Some(parent) => resolve(parent, custom, tools, depth + 1)?,
+            None => BTreeSet::new(),
+        };
+        for (tool, _) in tools {
+            let allow = if OPT_IN_TOOLS.contains(&tool.as_str()) {
+                def.allow.iter().any(|p| p == tool)
+            } else {
+                def.allow.iter().any(|p| matches(p, tool))
+            };
+            if allow {
+                allowed.insert(tool.clone());
+            }
+        }
+        allowed.retain(|tool| !def.deny.iter().any(|p| matches(p, tool)));
+        return Ok(allowed);
+    }
+
+    let extra: &[&str] = match name {
+        "full" => return Ok(tools.iter().map(|(tool, _)| tool.clone()).collect()),
+        "read-only" => &[],
+        "planner" => PLANNER_TOOLS,
+        "implementer" => IMPLEMENTER_TOOLS,
+        _ => anyhow::bail!(
+            "Unknown MCP profile '{}'. Use one of {} or define it in {}",
+            name,
+            BUILTIN_PROFILES.join(", "),
+            PROFILES_FILE
+        ),
+    };
+    Ok(tools
+        .iter()
+        .filter(|(tool, read_only)| {
+            (*read_only && !OPT_IN_TOOLS.contains(&tool.as_str())) || extra.contains(&tool.as_str())
+        })
+        .map(|(tool, _)| tool.clone())
+        .collect())
+}
+
+/// Whether a tool name matches an `allow`/`deny` entry.
+fn matches(pattern: &str, tool: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => tool.starts_with(prefix),
+        None => pattern == tool,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, custom: &HashMap<String, ProfileDef>) -> BTreeSet<String> {
+        resolve(name, custom, &all_tools(), 0).unwrap()
+    }
+
+    #[test]
+    fn builtin_profiles_only_widen_read_only() {
+        let none = HashMap::new();
+        let read_only = profile("read-only", &none);
+        assert!(read_only.contains("cwa_list_specs"));
+        assert!(!read_only.contains("cwa_create_task"));
+        assert!(!read_only.contains("cwa_set_wip_limit"));
+
+        let implementer = profile("implementer", &none);
+        assert!(implementer.is_superset(&read_only));
+        assert!(implementer.contains("cwa_update_task_status"));
+        assert!(!implementer.contains("cwa_update_spec_status"));
+
+        let planner = profile("planner", &none);
+        assert!(planner.contains("cwa_update_spec_status"));
+        assert!(!planner.contains("cwa_delete_task"));
+
+        assert_eq!(profile("full", &none).len(), all_tools().len());
+        for tool in PLANNER_TOOLS.iter().chain(IMPLEMENTER_TOOLS) {
+            assert!(all_tools().iter().any(|(name, _)| name == tool), "{}", tool);
+        }
+    }
+
+    #[test]
+    fn read_only_profile_lists_only_reads() {
+        let read_only: Vec<String> = profile("read-only", &HashMap::new()).into_iter().collect();
+        assert_eq!(
+            read_only,
+            [
+                "cwa_cache_status",
+                "cwa_code_search",
+                "cwa_codegen_agents",
+                "cwa_get_context_map",
+                "cwa_get_context_summary",
+                "cwa_get_current_task",
+                "cwa_get_domain_model",
+                "cwa_get_glossary",
+                "cwa_get_next_steps",
+                "cwa_get_project_info",
+                "cwa_get_spec",
+                "cwa_get_tech_stack",
+                "cwa_get_wip_status",
+                "cwa_get_workflow",
+                "cwa_graph_hyperedges",
+                "cwa_graph_impact",
+                "cwa_hybrid_search",
+                "cwa_list_decisions",
+                "cwa_list_projects",
+                "cwa_list_specs",
+                "cwa_list_tasks",
+                "cwa_memory_search_all",
+                "cwa_memory_semantic_search",
+                "cwa_memory_timeline",
+                "cwa_search_memory",
+                "cwa_spec_plan",
+                "cwa_validate_spec",
+            ]
+        );
+    }
+
+    #[test]
+    fn graph_query_needs_explicit_opt_in() {
+        let none = HashMap::new();
+        for name in ["read-only", "planner", "implementer"] {
+            assert!(!profile(name, &none).contains("cwa_graph_query"), "{}", name);
+        }
+        assert!(profile("full", &none).contains("cwa_graph_query"));
+
+        let file: ProfilesFile = serde_json::from_str(r#"{
+            "profiles": {
+                "wildcard": { "extends": "read-only", "allow": ["cwa_*"] },
+                "analyst": { "extends": "read-only", "allow": ["cwa_graph_query"] }
+            }
+        }"#).unwrap();
+        assert!(!profile("wildcard", &file.profiles).contains("cwa_graph_query"));
+        assert!(profile("analyst", &file.profiles).contains("cwa_graph_query"));
+    }
+
+    #[test]
+    fn custom_profiles_extend_allow_and_deny() {
+        let file: ProfilesFile = serde_json::from_str(r#"{
+            "profiles": {
+                "reviewer": { "extends": "read-only", "allow": ["cwa_observe", "cwa_memory_*"], "deny": ["cwa_graph_query"] },
+                "loop": { "extends": "loop" }
+            }
+        }"#).unwrap();
+
+        let reviewer = profile("reviewer", &file.profiles);
+        assert!(reviewer.contains("cwa_observe"));
+        assert!(reviewer.contains("cwa_memory_add"));
+        assert!(reviewer.contains("cwa_list_tasks"));
+        assert!(!reviewer.contains("cwa_graph_query"));
+        assert!(!reviewer.contains("cwa_create_task"));
+
+        assert!(resolve("loop", &file.profiles, &all_tools(), 0).is_err());
+        assert!(resolve("nope", &file.profiles, &all_tools(), 0).is_err());
+    }
+
+    #[test]
+    fn tools_list_is_filtered() {
+        let read_only = ToolProfile {
+            name: "read-only".to_string(),
+            allowed: profile("read-only", &HashMap::new()),
+        };
+        let listed = read_only.filter_tools(server::get_tools_list().unwrap());
+        let tools = listed["tools"].as_array().unwrap();
+        assert!(!tools.is_empty());
+        assert!(tools.iter().all(|t| t["annotations"]["readOnlyHint"] == true));
+    }
+}
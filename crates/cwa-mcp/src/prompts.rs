@@ -0,0 +1,370 @@
+//! MCP prompts: CWA workflows as parameterized prompt templates.
+//!
+//! Each prompt pairs the instructions of a built-in slash command (the same
+//! text `cwa codegen commands` writes to `.claude/commands/`) with live
+//! project data, so clients such as Claude Desktop get the CWA workflows
+//! without any generated files on disk.
+
+use cwa_core::CwaResult;
+use cwa_core::{decision, memory, spec, task};
+use cwa_db::DbPool;
+
+use crate::server::JsonRpcError;
+
+/// An argument a prompt accepts.
+struct PromptArg {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+/// A workflow prompt and the slash command it is built from.
+struct PromptDef {
+    name: &'static str,
+    description: &'static str,
+    command: &'static str,
+    arguments: &'static [PromptArg],
+}
+
+const PROMPTS: &[PromptDef] = &[
+    PromptDef {
+        name: "start-next-task",
+        description: "Start work on a task with its spec's acceptance criteria and related decisions",
+        command: "next-task",
+        arguments: &[PromptArg {
+            name: "task_id",
+            description: "Task to start (defaults to the task in progress)",
+            required: false,
+        }],
+    },
+    PromptDef {
+        name: "review-spec",
+        description: "Review a specification for SDD completeness and quality",
+        command: "spec-review",
+        arguments: &[PromptArg {
+            name: "spec",
+            description: "Spec ID, ID prefix, or title",
+            required: true,
+        }],
+    },
+    PromptDef {
+        name: "write-adr",
+        description: "Draft an Architectural Decision Record in light of existing decisions",
+        command: "write-adr",
+        arguments: &[
+            PromptArg {
+                name: "title",
+                description: "What the decision is about",
+                required: true,
+            },
+            PromptArg {
+                name: "context",
+                description: "Forces and constraints behind the decision",
+                required: false,
+            },
+        ],
+    },
+    PromptDef {
+        name: "summarize-session",
+        description: "Summarize the work session from the board and recent observations",
+        command: "session-summary",
+        arguments: &[PromptArg {
+            name: "days_back",
+            description: "Days of observations to include (default 1)",
+            required: false,
+        }],
+    },
+];
+
+/// Every prompt may target any registered project.
+const PROJECT_ARG: PromptArg = PromptArg {
+    name: "project",
+    description: "Project name or ID (defaults to the server's project)",
+    required: false,
+};
+
+/// Get the list of all available prompts.
+pub fn get_prompts_list() -> Result<serde_json::Value, JsonRpcError> {
+    let prompts: Vec<_> = PROMPTS
+        .iter()
+        .map(|p| {
+            let arguments: Vec<_> = p
+                .arguments
+                .iter()
+                .chain(std::iter::once(&PROJECT_ARG))
+                .map(|a| serde_json::json!({
+                    "name": a.name,
+                    "description": a.description,
+                    "required": a.required
+                }))
+                .collect();
+            serde_json::json!({
+                "name": p.name,
+                "description": p.description,
+                "arguments": arguments
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({ "prompts": prompts }))
+}
+
+/// Render a prompt with the current data of the project named by its
+/// `project` argument, else of `default_project` (a project ID).
+pub async fn get_prompt(
+    pool: &DbPool,
+    default_project: Option<&str>,
+    params: Option<serde_json::Value>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let params = params.ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing params".to_string(),
+    })?;
+
+    let name = params["name"].as_str().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing prompt name".to_string(),
+    })?;
+
+    let prompt = PROMPTS.iter().find(|p| p.name == name).ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: format!("Unknown prompt: {}", name),
+    })?;
+
+    let args = &params["arguments"];
+    for arg in prompt.arguments.iter().filter(|a| a.required) {
+        argument(args, arg.name).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Missing argument: {}", arg.name),
+        })?;
+    }
+
+    let days_back = match argument(args, "days_back") {
+        Some(days) => days.parse::<i64>().map_err(|_| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid days_back: {}", days),
+        })?,
+        None => 1,
+    };
+
+    let project = cwa_core::project::resolve_project(pool, argument(args, "project"), default_project).await
+        .map_err(|e| JsonRpcError {
+            code: -32603,
+            message: e.to_string(),
+        })?;
+
+    let context = match prompt.name {
+        "start-next-task" => task_context(pool, &project.id, argument(args, "task_id")).await,
+        "review-spec" => spec_review_context(pool, &project.id, argument(args, "spec").unwrap_or_default()).await,
+        "write-adr" => adr_context(
+            pool,
+            &project.id,
+            argument(args, "title").unwrap_or_default(),
+            argument(args, "context"),
+        ).await,
+        _ => session_context(pool, &project.id, days_back).await,
+    }
+    .map_err(|e| JsonRpcError {
+        code: -32603,
+        message: e.to_string(),
+    })?;
+
+    let instructions = cwa_codegen::get_command(prompt.command)
+        .map(|c| c.content)
+        .unwrap_or_default();
+
+    Ok(serde_json::json!({
+        "description": prompt.description,
+        "messages": [{
+            "role": "user",
+            "content": {
+                "type": "text",
+                "text": format!("{}\n---\n\n{}", instructions, context)
+            }
+        }]
+    }))
+}
+
+/// A non-empty string argument.
+fn argument<'a>(args: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+    args.get(name)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+// ============================================================
+// PROJECT CONTEXT
+// ============================================================
+
+/// The task to start, its spec's criteria, and the decisions behind it.
+async fn task_context(pool: &DbPool, project_id: &str, task_id: Option<&str>) -> CwaResult<String> {
+    let current = match task_id {
+        Some(id) => Some(task::get_task(pool, project_id, id).await?),
+        None => task::get_current_task(pool, project_id).await?,
+    };
+
+    let Some(current) = current else {
+        let mut output = String::from("## Current Task\n\nNo task is in progress.\n\n## Suggested Next Steps\n\n");
+        for step in memory::suggest_next_steps(pool, project_id).await? {
+            output.push_str(&format!("- {}\n", step));
+        }
+        return Ok(output);
+    };
+
+    let mut output = format!(
+        "## Current Task\n\n**{}** (`{}`)\n\n- Status: {}\n- Priority: {}\n",
+        current.title, current.id, current.status, current.priority
+    );
+    if let Some(due) = &current.due_date {
+        output.push_str(&format!("- Due: {}\n", due));
+    }
+    if !current.blocked_by.is_empty() {
+        output.push_str(&format!("- Blocked by: {}\n", current.blocked_by.join(", ")));
+    }
+    if let Some(description) = &current.description {
+        output.push_str(&format!("\n{}\n", description));
+    }
+
+    let decisions = decision::list_decisions(pool, project_id).await?;
+    let related = match &current.spec_id {
+        Some(spec_id) => {
+            let linked = spec::get_spec(pool, project_id, spec_id).await?;
+            output.push_str(&format!("\n## Spec: {} (`{}`)\n\n", linked.title, linked.id));
+            if let Some(description) = &linked.description {
+                output.push_str(&format!("{}\n\n", description));
+            }
+            output.push_str(&criteria_list(&linked.acceptance_criteria));
+            decisions
+                .into_iter()
+                .filter(|d| d.related_specs.contains(&linked.id))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    if related.is_empty() {
+        let accepted = decision::list_accepted_decisions(pool, project_id).await?;
+        output.push_str(&format!("\n## Accepted Decisions\n\n{}", decision::format_decisions_summary(&accepted)));
+    } else {
+        output.push_str(&format!("\n## Related Decisions\n\n{}", decision::format_decisions_summary(&related)));
+    }
+
+    Ok(output)
+}
+
+/// The spec under review, its dependencies, and automated validation.
+async fn spec_review_context(pool: &DbPool, project_id: &str, identifier: &str) -> CwaResult<String> {
+    let reviewed = spec::get_spec(pool, project_id, identifier).await?;
+    let validation = spec::validate_spec(pool, &reviewed.id).await?;
+
+    let mut output = format!(
+        "## Spec: {} (`{}`)\n\n- Status: {}\n- Priority: {}\n",
+        reviewed.title,
+        reviewed.id,
+        reviewed.status.as_str(),
+        reviewed.priority.as_str()
+    );
+    if let Some(context_id) = &reviewed.context_id {
+        output.push_str(&format!("- Bounded context: {}\n", context_id));
+    }
+    for dependency in spec::load_dependencies(pool, &reviewed).await? {
+        output.push_str(&format!("- Depends on: {} ({})\n", dependency.title, dependency.status.as_str()));
+    }
+    if let Some(description) = &reviewed.description {
+        output.push_str(&format!("\n{}\n", description));
+    }
+
+    output.push('\n');
+    output.push_str(&criteria_list(&reviewed.acceptance_criteria));
+
+    output.push_str("\n## Validation\n\n");
+    if validation.is_valid {
+        output.push_str("Automated validation passed.\n");
+    } else {
+        for issue in &validation.issues {
+            output.push_str(&format!("- {}\n", issue));
+        }
+    }
+
+    Ok(output)
+}
+
+/// The proposed decision alongside the decisions already recorded.
+async fn adr_context(pool: &DbPool, project_id: &str, title: &str, context: Option<&str>) -> CwaResult<String> {
+    let mut output = format!("## Proposed Decision\n\n**{}**\n", title);
+    if let Some(context) = context {
+        output.push_str(&format!("\n{}\n", context));
+    }
+
+    if let Some(active) = spec::get_active_spec(pool, project_id).await? {
+        output.push_str(&format!("\n## Active Spec\n\n**{}** (`{}`)\n", active.title, active.id));
+        if let Some(description) = &active.description {
+            output.push_str(&format!("\n{}\n", description));
+        }
+    }
+
+    let decisions = decision::list_decisions(pool, project_id).await?;
+    output.push_str(&format!("\n## Existing Decisions\n\n{}", decision::format_decisions_summary(&decisions)));
+
+    Ok(output)
+}
+
+/// The board, the active session, and the observations recorded lately.
+async fn session_context(pool: &DbPool, project_id: &str, days_back: i64) -> CwaResult<String> {
+    let mut output = memory::get_context_summary(pool, project_id).await?.to_compact_string();
+
+    if let Some(session) = memory::get_active_session(pool, project_id).await? {
+        output.push_str(&format!("\n## Session\n\n- Started: {}\n", session.started_at));
+        for goal in &session.goals {
+            output.push_str(&format!("- Goal: {}\n", goal));
+        }
+    }
+
+    let observations = memory::get_timeline(pool, project_id, days_back, 50).await?;
+    output.push_str(&format!("\n## Observations (last {} days)\n\n", days_back));
+    if observations.is_empty() {
+        output.push_str("No observations recorded.\n");
+    }
+    for obs in &observations {
+        output.push_str(&format!("- [{}] {} (`{}`)\n", obs.obs_type, obs.title, obs.id));
+    }
+
+    Ok(output)
+}
+
+fn criteria_list(criteria: &[String]) -> String {
+    if criteria.is_empty() {
+        return "## Acceptance Criteria\n\nNo acceptance criteria defined.\n".to_string();
+    }
+    let mut output = String::from("## Acceptance Criteria\n\n");
+    for (i, criterion) in criteria.iter().enumerate() {
+        output.push_str(&format!("{}. {}\n", i + 1, criterion));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_prompt_has_a_command_template() {
+        for prompt in PROMPTS {
+            assert!(cwa_codegen::get_command(prompt.command).is_some(), "{}", prompt.name);
+        }
+        let listed = get_prompts_list().unwrap();
+        assert_eq!(listed["prompts"].as_array().unwrap().len(), PROMPTS.len());
+        assert_eq!(listed["prompts"][1]["arguments"][0]["required"], true);
+        assert_eq!(listed["prompts"][1]["arguments"][1]["name"], "project");
+    }
+
+    #[test]
+    fn blank_arguments_count_as_missing() {
+        let args = serde_json::json!({ "spec": "  ", "title": "Use Redis", "days_back": 3 });
+        assert_eq!(argument(&args, "spec"), None);
+        assert_eq!(argument(&args, "title"), Some("Use Redis"));
+        assert_eq!(argument(&args, "days_back"), None);
+        assert_eq!(argument(&serde_json::Value::Null, "title"), None);
+    }
+}
@@ -0,0 +1,378 @@
+//! Tool result schemas (`outputSchema`) and tool annotations.
+//!
+//! Entity schemas mirror the serialized cwa-core models, so clients can rely
+//! on the `structuredContent` of a tool result: a Spec, Task or Decision comes
+//! back with the same fields everywhere it appears.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// MCP tool annotations: hints for clients deciding which calls to
+/// auto-approve and which to confirm with the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    pub read_only_hint: bool,
+    pub destructive_hint: bool,
+    pub idempotent_hint: bool,
+    /// CWA tools only touch the project's own stores.
+    pub open_world_hint: bool,
+}
+
+impl ToolAnnotations {
+    /// Only reads project data.
+    pub const READ_ONLY: Self = Self::new(true, false, true);
+    /// Records new data without changing existing data.
+    pub const WRITE: Self = Self::new(false, false, false);
+    /// Records new data; repeating the call has no further effect.
+    pub const WRITE_IDEMPOTENT: Self = Self::new(false, false, true);
+    /// Changes or removes existing data; repeating the call has no further
+    /// effect.
+    pub const DESTRUCTIVE: Self = Self::new(false, true, true);
+
+    const fn new(read_only_hint: bool, destructive_hint: bool, idempotent_hint: bool) -> Self {
+        Self {
+            read_only_hint,
+            destructive_hint,
+            idempotent_hint,
+            open_world_hint: false,
+        }
+    }
+}
+
+// ============================================================
+// BUILDING BLOCKS
+// ============================================================
+
+/// An object schema with the given properties, of which `required` must be present.
+pub fn object(properties: Value, required: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required
+    })
+}
+
+pub fn array(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+pub fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+pub fn optional_string() -> Value {
+    json!({ "type": ["string", "null"] })
+}
+
+pub fn integer() -> Value {
+    json!({ "type": "integer" })
+}
+
+pub fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+/// An object whose fields are not described further.
+pub fn any_object() -> Value {
+    json!({ "type": "object" })
+}
+
+pub fn strings() -> Value {
+    array(string())
+}
+
+/// Result of a tool that changes data: `success`, an optional `message`,
+/// and `extra` properties (all required).
+pub fn success(extra: Value) -> Value {
+    let mut properties = json!({
+        "success": boolean(),
+        "message": string()
+    });
+    let mut required = vec!["success".to_string()];
+    if let (Some(props), Value::Object(extra)) = (properties.as_object_mut(), extra) {
+        for (name, schema) in extra {
+            required.push(name.clone());
+            props.insert(name, schema);
+        }
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required
+    })
+}
+
+/// A `{ count, <key>: [items] }` listing.
+pub fn listing(key: &str, items: Value) -> Value {
+    let mut properties = serde_json::Map::new();
+    properties.insert("count".to_string(), integer());
+    properties.insert(key.to_string(), array(items));
+    object(Value::Object(properties), &["count", key])
+}
+
+// ============================================================
+// ENTITIES
+// ============================================================
+
+/// A registered project, as listed by `cwa_list_projects`.
+pub fn project_summary() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "name": string(),
+            "description": optional_string(),
+            "path": optional_string(),
+            "default": boolean()
+        }),
+        &["id", "name", "default"],
+    )
+}
+
+pub fn task() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "project_id": string(),
+            "spec_id": optional_string(),
+            "title": string(),
+            "description": optional_string(),
+            "status": { "type": "string", "description": "Workflow column name" },
+            "priority": string(),
+            "assignee": optional_string(),
+            "labels": strings(),
+            "estimated_effort": optional_string(),
+            "actual_effort": optional_string(),
+            "blocked_by": strings(),
+            "due_date": { "type": ["string", "null"], "description": "YYYY-MM-DD" },
+            "created_at": string(),
+            "updated_at": string(),
+            "started_at": optional_string(),
+            "completed_at": optional_string()
+        }),
+        &["id", "title", "status", "priority"],
+    )
+}
+
+/// The task in progress, or only a `message` when there is none.
+pub fn current_task() -> Value {
+    let mut schema = task();
+    schema["properties"]["message"] = string();
+    schema["required"] = json!([]);
+    schema
+}
+
+/// A task as shown in listings.
+pub fn task_summary() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "title": string(),
+            "status": string(),
+            "priority": string(),
+            "assignee": optional_string(),
+            "labels": strings(),
+            "estimated_effort": optional_string(),
+            "actual_effort": optional_string(),
+            "spec_id": optional_string(),
+            "blocked_by": strings()
+        }),
+        &["id", "title", "status", "priority"],
+    )
+}
+
+fn spec_status() -> Value {
+    json!({
+        "type": "string",
+        "enum": ["draft", "active", "in_review", "accepted", "completed", "archived"]
+    })
+}
+
+fn priority() -> Value {
+    json!({ "type": "string", "enum": ["low", "medium", "high", "critical"] })
+}
+
+pub fn spec() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "project_id": string(),
+            "title": string(),
+            "description": optional_string(),
+            "status": spec_status(),
+            "priority": priority(),
+            "acceptance_criteria": strings(),
+            "dependencies": { "type": "array", "items": string(), "description": "IDs of specs this spec depends on" },
+            "context_id": optional_string(),
+            "created_at": string(),
+            "updated_at": string(),
+            "archived_at": optional_string()
+        }),
+        &["id", "title", "status", "priority", "acceptance_criteria", "dependencies"],
+    )
+}
+
+/// A spec as shown in listings.
+pub fn spec_summary() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "title": string(),
+            "status": spec_status(),
+            "priority": priority(),
+            "criteria_count": integer(),
+            "dependencies": strings()
+        }),
+        &["id", "title", "status", "priority"],
+    )
+}
+
+pub fn decision() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "project_id": string(),
+            "title": string(),
+            "status": { "type": "string", "enum": ["proposed", "accepted", "deprecated", "superseded"] },
+            "context": string(),
+            "decision": string(),
+            "consequences": strings(),
+            "alternatives": array(object(
+                json!({
+                    "title": string(),
+                    "description": string(),
+                    "reason_rejected": string()
+                }),
+                &["title"],
+            )),
+            "related_specs": strings(),
+            "superseded_by": optional_string(),
+            "created_at": string(),
+            "updated_at": string()
+        }),
+        &["id", "title", "status", "context", "decision"],
+    )
+}
+
+pub fn glossary_term() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "project_id": string(),
+            "context_id": optional_string(),
+            "term": string(),
+            "definition": string(),
+            "aliases": strings(),
+            "created_at": string(),
+            "updated_at": string()
+        }),
+        &["id", "term", "definition"],
+    )
+}
+
+pub fn memory_entry() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "project_id": string(),
+            "session_id": optional_string(),
+            "entry_type": string(),
+            "content": string(),
+            "importance": string(),
+            "tags": strings(),
+            "related_entity_type": optional_string(),
+            "related_entity_id": optional_string(),
+            "created_at": string()
+        }),
+        &["id", "entry_type", "content"],
+    )
+}
+
+/// An observation as listed in the timeline.
+pub fn observation_index() -> Value {
+    object(
+        json!({
+            "id": string(),
+            "obs_type": string(),
+            "title": string(),
+            "confidence": { "type": "number" },
+            "created_at": string()
+        }),
+        &["id", "obs_type", "title"],
+    )
+}
+
+/// A code chunk found by `cwa_code_search`.
+pub fn code_chunk() -> Value {
+    object(
+        json!({
+            "path": string(),
+            "language": string(),
+            "start_line": integer(),
+            "end_line": integer(),
+            "score": { "type": "number" },
+            "content": string()
+        }),
+        &["path", "start_line", "end_line", "content"],
+    )
+}
+
+pub fn wip_status() -> Value {
+    object(
+        json!({
+            "columns": array(object(
+                json!({
+                    "name": string(),
+                    "limit": { "type": ["integer", "null"] },
+                    "current": integer(),
+                    "is_exceeded": boolean()
+                }),
+                &["name", "current", "is_exceeded"],
+            ))
+        }),
+        &["columns"],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_requires_its_extra_properties() {
+        let schema = success(json!({ "id": string() }));
+        assert_eq!(schema["required"], json!(["success", "id"]));
+        assert_eq!(schema["properties"]["id"]["type"], "string");
+        assert!(schema["properties"].get("message").is_some());
+    }
+
+    #[test]
+    fn every_tool_declares_its_output_and_annotations() {
+        let tools = crate::server::get_tools_list().unwrap();
+        for tool in tools["tools"].as_array().unwrap() {
+            let name = tool["name"].as_str().unwrap();
+            let output = &tool["outputSchema"];
+            assert_eq!(output["type"], "object", "{}", name);
+            for field in output["required"].as_array().unwrap() {
+                let field = field.as_str().unwrap();
+                assert!(output["properties"].get(field).is_some(), "{} requires undeclared {}", name, field);
+            }
+
+            let read_only = tool["annotations"]["readOnlyHint"].as_bool().unwrap();
+            if name.starts_with("cwa_get_") || name.starts_with("cwa_list_") {
+                assert!(read_only, "{} should be read-only", name);
+            }
+            if name.contains("_update_") || name.contains("_delete_") || name.contains("_remove_") {
+                assert_eq!(tool["annotations"]["destructiveHint"], true, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn current_task_allows_the_no_task_message() {
+        let schema = current_task();
+        assert_eq!(schema["required"], json!([]));
+        assert!(schema["properties"].get("message").is_some());
+        assert!(schema["properties"].get("blocked_by").is_some());
+    }
+}
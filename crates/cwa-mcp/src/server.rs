@@ -3,10 +3,20 @@
 //! Note: This is a simplified implementation. Full rmcp integration
 //! would require the actual rmcp crate which may have different APIs.
 
+use cwa_core::audit::model::{AuditEntry, AuditStatus};
 use cwa_db::{BroadcastSender, DbPool, WebSocketMessage};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+use crate::completion;
+use crate::profile::ToolProfile;
+use crate::prompts;
+use crate::schema::{self, ToolAnnotations};
+use crate::session::{self, Progress, Registration, Session};
+use crate::templates;
 
 /// JSON-RPC request structure.
 #[derive(Debug, Deserialize)]
@@ -42,6 +52,10 @@ struct Tool {
     description: String,
     #[serde(rename = "inputSchema")]
     input_schema: serde_json::Value,
+    /// Schema of the result's `structuredContent`.
+    #[serde(rename = "outputSchema")]
+    output_schema: serde_json::Value,
+    annotations: ToolAnnotations,
 }
 
 /// Resource definition.
@@ -57,74 +71,168 @@ struct Resource {
 /// Run the MCP server over stdio.
 ///
 /// If `broadcast_tx` is provided, task updates will be broadcast directly
-/// to WebSocket clients (when running alongside the web server).
+/// to WebSocket clients (when running alongside the web server). The client
+/// may only use the tools of `profile`, and works in `default_project` (a
+/// project ID) unless a request names another project.
 pub async fn run_stdio(
     pool: Arc<DbPool>,
     broadcast_tx: Option<BroadcastSender>,
+    profile: ToolProfile,
+    default_project: Option<String>,
 ) -> anyhow::Result<()> {
     let stdin = BufReader::new(tokio::io::stdin());
     let mut stdout = tokio::io::stdout();
     let mut lines = stdin.lines();
 
-    while let Some(line) = lines.next_line().await? {
-        if line.trim().is_empty() {
-            continue;
-        }
-
-        let request: JsonRpcRequest = match serde_json::from_str(&line) {
-            Ok(r) => r,
-            Err(e) => {
-                let response = JsonRpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    id: None,
-                    result: None,
-                    error: Some(JsonRpcError {
-                        code: -32700,
-                        message: format!("Parse error: {}", e),
-                    }),
-                };
-                let output = format!("{}\n", serde_json::to_string(&response)?);
-                stdout.write_all(output.as_bytes()).await?;
+    // Responses and notifications for the client arrive on the session's outbox
+    let (outbox, mut outgoing) = mpsc::channel(session::OUTBOX_CAPACITY);
+    let session = Session::new(outbox, profile, default_project);
+    session.forward_events(session::listen_for_changes().subscribe());
+
+    // In-flight handlers, awaited on EOF so piped requests still get replies
+    let mut handlers = JoinSet::new();
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                // Register the message in arrival order, then handle it on its
+                // own task so a slow tool call doesn't hold up the others;
+                // responses carry the request ID.
+                let incoming = receive(&session, &line);
+                let (pool, broadcast_tx, session) = (Arc::clone(&pool), broadcast_tx.clone(), Arc::clone(&session));
+                handlers.spawn(async move {
+                    if let Some(response) = handle_incoming(&pool, &broadcast_tx, &session, incoming).await {
+                        session.send(response).await;
+                    }
+                });
+            }
+            Some(output) = outgoing.recv() => {
+                stdout.write_all(format!("{}\n", output).as_bytes()).await?;
                 stdout.flush().await?;
-                continue;
             }
-        };
-
-        // JSON-RPC 2.0: notifications (no id) must not receive responses
-        if request.id.is_none() {
-            continue;
+            Some(_) = handlers.join_next(), if !handlers.is_empty() => {}
         }
+    }
 
-        let response = handle_request(&pool, &broadcast_tx, request).await;
-        let output = format!("{}\n", serde_json::to_string(&response)?);
-        stdout.write_all(output.as_bytes()).await?;
-        stdout.flush().await?;
+    // stdin closed: let running requests finish, writing what they send
+    // meanwhile so none waits on a full outbox, then flush the rest
+    while !handlers.is_empty() {
+        tokio::select! {
+            _ = handlers.join_next() => {}
+            Some(output) = outgoing.recv() => {
+                stdout.write_all(format!("{}\n", output).as_bytes()).await?;
+            }
+        }
+    }
+    while let Ok(output) = outgoing.try_recv() {
+        stdout.write_all(format!("{}\n", output).as_bytes()).await?;
     }
+    stdout.flush().await?;
 
     Ok(())
 }
 
+/// A message read from the client.
+enum Incoming {
+    /// A request, already registered as in flight.
+    Request(JsonRpcRequest, Registration),
+    /// A notification, already acted on.
+    Notification,
+    /// Input that is not JSON-RPC.
+    Invalid(JsonRpcResponse),
+}
+
+/// Parse one raw JSON-RPC message and register it with the session.
+///
+/// Requests are registered as in flight and `notifications/cancelled` is
+/// applied right away, so transports that call this in the order messages
+/// arrive never lose a cancellation to a handler that has not started yet.
+fn receive(session: &Session, message: &str) -> Incoming {
+    match serde_json::from_str::<JsonRpcRequest>(message) {
+        Ok(request) => match request.id.clone() {
+            Some(id) => Incoming::Request(request, session.register(&id)),
+            None => {
+                if request.method == "notifications/cancelled" {
+                    if let Some(id) = request.params.as_ref().and_then(|p| p.get("requestId")) {
+                        session.cancel(id);
+                    }
+                }
+                Incoming::Notification
+            }
+        },
+        Err(e) => Incoming::Invalid(JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            result: None,
+            error: Some(JsonRpcError {
+                code: -32700,
+                message: format!("Parse error: {}", e),
+            }),
+        }),
+    }
+}
+
+/// Handle a message from [`receive`], returning the serialized response.
+///
+/// Notifications (no `id`) get no response, as JSON-RPC 2.0 requires;
+/// unparseable input gets a parse error. A request cancelled by
+/// `notifications/cancelled` before it finishes gets no response either.
+async fn handle_incoming(
+    pool: &DbPool,
+    broadcast_tx: &Option<BroadcastSender>,
+    session: &Session,
+    incoming: Incoming,
+) -> Option<String> {
+    let response = match incoming {
+        Incoming::Request(request, registration) => {
+            session.run(registration, handle_request(pool, broadcast_tx, session, request)).await?
+        }
+        Incoming::Notification => return None,
+        Incoming::Invalid(response) => response,
+    };
+    serde_json::to_string(&response).ok()
+}
+
+/// Handle one raw JSON-RPC message, returning the serialized response.
+///
+/// Shared by every transport; see [`handle_incoming`].
+pub async fn handle_message(
+    pool: &DbPool,
+    broadcast_tx: &Option<BroadcastSender>,
+    session: &Session,
+    message: &str,
+) -> Option<String> {
+    handle_incoming(pool, broadcast_tx, session, receive(session, message)).await
+}
+
 async fn handle_request(
     pool: &DbPool,
     broadcast_tx: &Option<BroadcastSender>,
+    session: &Session,
     request: JsonRpcRequest,
 ) -> JsonRpcResponse {
     let result = match request.method.as_str() {
-        "initialize" => handle_initialize(),
-        "tools/list" => get_tools_list(),
-        "tools/call" => {
-            // Per MCP spec, tool execution failures should be returned as
-            // successful JSON-RPC responses with isError: true, not as
-            // JSON-RPC errors. Only protocol errors (-32602, -32601) stay
-            // as JSON-RPC errors.
-            match call_tool(pool, broadcast_tx, request.params).await {
-                Ok(v) => Ok(v),
-                Err(e) if e.code == -32603 => Ok(tool_error(&e.message)),
-                Err(e) => Err(e),
-            }
+        "initialize" => {
+            session.set_client(&request.params);
+            handle_initialize()
         }
+        "tools/list" => get_tools_list().map(|list| session.profile().filter_tools(list)),
+        "tools/call" => call_tool(pool, broadcast_tx, session, request.params).await,
         "resources/list" => get_resources_list(),
-        "resources/read" => read_resource(pool, request.params).await,
+        "resources/read" => read_resource(pool, session.default_project(), request.params).await,
+        "resources/templates/list" => templates::get_resource_templates_list(),
+        "resources/subscribe" => subscribe_resource(pool, session, &request.params).await,
+        "resources/unsubscribe" => resource_uri(&request.params).map(|(uri, _, _)| {
+            session.unsubscribe(uri);
+            serde_json::json!({})
+        }),
+        "prompts/list" => prompts::get_prompts_list(),
+        "prompts/get" => prompts::get_prompt(pool, session.default_project(), request.params).await,
+        "completion/complete" => completion::complete(pool, session, request.params).await,
         _ => Err(JsonRpcError {
             code: -32601,
             message: format!("Method not found: {}", request.method),
@@ -159,15 +267,96 @@ fn handle_initialize() -> Result<serde_json::Value, JsonRpcError> {
                 "listChanged": true
             },
             "resources": {
+                "subscribe": true,
                 "listChanged": true
-            }
+            },
+            "prompts": {
+                "listChanged": false
+            },
+            "completions": {}
         }
     }))
 }
 
+/// The `uri` parameter of a subscription request, if it names a resource,
+/// with the project it names (for `cwa://{project}/...` URIs) and its
+/// `project://` form.
+fn resource_uri(params: &Option<serde_json::Value>) -> Result<(&str, Option<String>, String), JsonRpcError> {
+    let uri = params
+        .as_ref()
+        .and_then(|p| p.get("uri"))
+        .and_then(|u| u.as_str())
+        .ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: "Missing uri".to_string(),
+        })?;
+    let (selector, local_uri) = match scoped_uri(uri) {
+        Some((project, local)) => (Some(project), local),
+        None => (None, uri.to_string()),
+    };
+    let known = get_resources_list()?["resources"]
+        .as_array()
+        .is_some_and(|resources| resources.iter().any(|r| r["uri"] == local_uri.as_str()));
+    if !known {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("Unknown resource: {}", uri),
+        });
+    }
+    Ok((uri, selector, local_uri))
+}
+
+/// Handle `resources/subscribe`: follow changes to the resource in the
+/// project it reads.
+async fn subscribe_resource(
+    pool: &DbPool,
+    session: &Session,
+    params: &Option<serde_json::Value>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let (uri, selector, local_uri) = resource_uri(params)?;
+    let project = cwa_core::project::resolve_project(pool, selector.as_deref(), session.default_project())
+        .await
+        .map_err(|e| JsonRpcError {
+            code: match e {
+                cwa_core::CwaError::ProjectNotFound(_) if selector.is_some() => -32602,
+                _ => -32603,
+            },
+            message: e.to_string(),
+        })?;
+    session.subscribe(uri, &project.id, &local_uri);
+    Ok(serde_json::json!({}))
+}
+
+/// Whether `cwa_graph_query` may run writing Cypher. Queries are read-only
+/// unless explicitly allowed by whoever started the server.
+fn graph_writes_allowed() -> bool {
+    std::env::var("CWA_GRAPH_ALLOW_WRITES").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+fn graph_query_annotations() -> ToolAnnotations {
+    if graph_writes_allowed() {
+        ToolAnnotations {
+            idempotent_hint: false,
+            ..ToolAnnotations::DESTRUCTIVE
+        }
+    } else {
+        ToolAnnotations::READ_ONLY
+    }
+}
+
 /// Get the list of all available tools (for reuse by planner).
 pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
-    let tools = vec![
+    let mut tools = vec![
+        Tool {
+            name: "cwa_list_projects".to_string(),
+            description: "List the CWA projects registered in this server's Redis. Pass a project's name or ID as the `project` argument of any other tool to work in it instead of the default project.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+            output_schema: schema::listing("projects", schema::project_summary()),
+            annotations: ToolAnnotations::READ_ONLY,
+        },
         Tool {
             name: "cwa_get_project_info".to_string(),
             description: "Get project metadata including name, description, tech stack, features, and constraints. Run 'cwa update' to modify this information.".to_string(),
@@ -175,6 +364,20 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "name": schema::string(),
+                    "description": schema::optional_string(),
+                    "tech_stack": schema::strings(),
+                    "main_features": schema::strings(),
+                    "constraints": schema::strings(),
+                    "updated_at": schema::string(),
+                    "message": schema::string(),
+                    "hint": schema::string()
+                }),
+                &["name"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_get_current_task".to_string(),
@@ -183,6 +386,8 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::current_task(),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_get_spec".to_string(),
@@ -197,6 +402,8 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["identifier"]
             }),
+            output_schema: schema::spec(),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_get_context_summary".to_string(),
@@ -205,6 +412,13 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "text": schema::string()
+                }),
+                &["text"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_get_domain_model".to_string(),
@@ -213,10 +427,18 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "contexts": schema::array(schema::any_object()),
+                    "glossary": schema::array(schema::glossary_term())
+                }),
+                &["contexts", "glossary"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_update_task_status".to_string(),
-            description: "Update a task's status".to_string(),
+            description: "Move a task to another column of the project's workflow. Rejects moves the workflow does not allow, unmet entry/exit policies and exceeded WIP limits; see cwa_get_workflow.".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -226,11 +448,59 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                     },
                     "status": {
                         "type": "string",
-                        "description": "New status (backlog, todo, in_progress, review, done)"
+                        "description": "Target column name (default workflow: backlog, todo, in_progress, review, done)"
                     }
                 },
                 "required": ["task_id", "status"]
             }),
+            output_schema: schema::success(serde_json::json!({})),
+            annotations: ToolAnnotations::DESTRUCTIVE,
+        },
+        Tool {
+            name: "cwa_add_task_blocker".to_string(),
+            description: "Mark a task as blocked by another task. Blocked tasks cannot move to in_progress or done until their blockers are done. Rejects dependency cycles.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {
+                        "type": "string",
+                        "description": "ID of the task that is blocked"
+                    },
+                    "blocker_id": {
+                        "type": "string",
+                        "description": "ID of the task it waits on"
+                    }
+                },
+                "required": ["task_id", "blocker_id"]
+            }),
+            output_schema: schema::success(serde_json::json!({
+                "task_id": schema::string(),
+                "blocked_by": schema::strings()
+            })),
+            annotations: ToolAnnotations::WRITE_IDEMPOTENT,
+        },
+        Tool {
+            name: "cwa_remove_task_blocker".to_string(),
+            description: "Remove a blocker from a task".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {
+                        "type": "string",
+                        "description": "ID of the blocked task"
+                    },
+                    "blocker_id": {
+                        "type": "string",
+                        "description": "ID of the blocker to remove"
+                    }
+                },
+                "required": ["task_id", "blocker_id"]
+            }),
+            output_schema: schema::success(serde_json::json!({
+                "task_id": schema::string(),
+                "blocked_by": schema::strings()
+            })),
+            annotations: ToolAnnotations::DESTRUCTIVE,
         },
         Tool {
             name: "cwa_add_decision".to_string(),
@@ -253,6 +523,10 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["title", "context", "decision"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string()
+            })),
+            annotations: ToolAnnotations::WRITE,
         },
         Tool {
             name: "cwa_get_next_steps".to_string(),
@@ -261,6 +535,13 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "steps": schema::strings()
+                }),
+                &["steps"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_search_memory".to_string(),
@@ -275,11 +556,13 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["query"]
             }),
+            output_schema: schema::listing("results", schema::memory_entry()),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         // Graph tools
         Tool {
             name: "cwa_graph_query".to_string(),
-            description: "Execute a Cypher query against the Knowledge Graph".to_string(),
+            description: "Execute a read-only Cypher query against the Knowledge Graph. Returns column names and rows as JSON (nodes as {identity, labels, properties}). Queries with CREATE, MERGE, DELETE, SET, REMOVE or DROP are rejected unless the server runs with CWA_GRAPH_ALLOW_WRITES=1".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -290,10 +573,19 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["cypher"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "columns": schema::strings(),
+                    "count": schema::integer(),
+                    "rows": schema::array(schema::any_object())
+                }),
+                &["columns", "count", "rows"],
+            ),
+            annotations: graph_query_annotations(),
         },
         Tool {
             name: "cwa_graph_impact".to_string(),
-            description: "Analyze the impact of changes to an entity in the Knowledge Graph".to_string(),
+            description: "Analyze the transitive impact of changes to an entity in the Knowledge Graph. Returns every affected node with its hop distance, path and relationship-weighted score, grouped by label and rendered as a tree".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -304,18 +596,48 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                     "entity_id": {
                         "type": "string",
                         "description": "Entity ID"
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "description": "Maximum number of hops to follow (1-5, default: 3)"
                     }
                 },
                 "required": ["entity_type", "entity_id"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "root": { "type": ["object", "null"] },
+                    "max_depth": schema::integer(),
+                    "total": schema::integer(),
+                    "tree": schema::string(),
+                    "by_label": { "type": "object", "additionalProperties": schema::strings() },
+                    "impacts": schema::array(schema::any_object())
+                }),
+                &["root", "max_depth", "total", "impacts"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_graph_sync".to_string(),
-            description: "Sync SQLite entities to Neo4j Knowledge Graph".to_string(),
+            description: "Sync Redis entities to Neo4j Knowledge Graph".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "incremental": {
+                        "type": "boolean",
+                        "description": "Only push entities changed since the last sync (default: false)"
+                    }
+                }
             }),
+            output_schema: schema::success(serde_json::json!({
+                "incremental": schema::boolean(),
+                "nodes_created": schema::integer(),
+                "nodes_updated": schema::integer(),
+                "nodes_unchanged": schema::integer(),
+                "nodes_deleted": schema::integer(),
+                "relationships_created": schema::integer()
+            })),
+            annotations: ToolAnnotations::DESTRUCTIVE,
         },
         // Embedding tools
         Tool {
@@ -335,6 +657,13 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["query"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "results": schema::array(schema::any_object())
+                }),
+                &["results"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_generate_tasks".to_string(),
@@ -348,11 +677,20 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                     },
                     "status": {
                         "type": "string",
-                        "description": "Initial task status (default: backlog)"
+                        "description": "Initial task status (default: the workflow's first column)"
                     }
                 },
                 "required": ["spec_identifier"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "created": schema::integer(),
+                "skipped": schema::integer(),
+                "tasks": schema::array(schema::object(
+                    serde_json::json!({ "id": schema::string(), "title": schema::string(), "status": schema::string() }),
+                    &["id", "title", "status"],
+                ))
+            })),
+            annotations: ToolAnnotations::WRITE_IDEMPOTENT,
         },
         Tool {
             name: "cwa_memory_add".to_string(),
@@ -375,6 +713,12 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["content", "entry_type"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string(),
+                "embedding_dim": schema::integer(),
+                "embedding_pending": schema::boolean()
+            })),
+            annotations: ToolAnnotations::WRITE,
         },
         // Observation tools (progressive disclosure)
         Tool {
@@ -413,6 +757,12 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["title", "obs_type"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string(),
+                "embedding_dim": schema::integer(),
+                "embedding_pending": schema::boolean()
+            })),
+            annotations: ToolAnnotations::WRITE,
         },
         Tool {
             name: "cwa_memory_timeline".to_string(),
@@ -430,6 +780,13 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                     }
                 }
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "observations": schema::array(schema::observation_index())
+                }),
+                &["observations"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_memory_get".to_string(),
@@ -445,6 +802,13 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["ids"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "observations": schema::array(schema::any_object())
+                }),
+                &["observations"],
+            ),
+            annotations: ToolAnnotations::WRITE,
         },
         // Creation tools
         Tool {
@@ -464,6 +828,11 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["name"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string(),
+                "name": schema::string()
+            })),
+            annotations: ToolAnnotations::WRITE,
         },
         Tool {
             name: "cwa_create_spec".to_string(),
@@ -491,6 +860,12 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["title"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string(),
+                "title": schema::string(),
+                "criteria_count": schema::integer()
+            })),
+            annotations: ToolAnnotations::WRITE,
         },
         Tool {
             name: "cwa_create_task".to_string(),
@@ -517,6 +892,95 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["title"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string(),
+                "title": schema::string()
+            })),
+            annotations: ToolAnnotations::WRITE,
+        },
+        Tool {
+            name: "cwa_update_task".to_string(),
+            description: "Edit a task's title, description, priority, assignee, labels, effort or blockers. Omitted fields are left unchanged; an empty string clears an optional field. Use cwa_update_task_status to change status.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {
+                        "type": "string",
+                        "description": "Task ID"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "New title"
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "New description"
+                    },
+                    "priority": {
+                        "type": "string",
+                        "description": "Priority: low, medium, high, critical"
+                    },
+                    "assignee": {
+                        "type": "string",
+                        "description": "Assignee name (empty string to unassign)"
+                    },
+                    "labels": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Replace all labels"
+                    },
+                    "add_labels": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Labels to add"
+                    },
+                    "remove_labels": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Labels to remove"
+                    },
+                    "estimated_effort": {
+                        "type": "string",
+                        "description": "Estimated effort, e.g. \"2h\" or \"3 points\""
+                    },
+                    "actual_effort": {
+                        "type": "string",
+                        "description": "Actual effort spent"
+                    },
+                    "blocked_by": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Replace the list of blocking task IDs (rejects cycles)"
+                    },
+                    "due_date": {
+                        "type": "string",
+                        "description": "Due date as YYYY-MM-DD (empty string to clear)"
+                    }
+                },
+                "required": ["task_id"]
+            }),
+            output_schema: schema::success(serde_json::json!({
+                "task": schema::task()
+            })),
+            annotations: ToolAnnotations::DESTRUCTIVE,
+        },
+        Tool {
+            name: "cwa_delete_task".to_string(),
+            description: "Permanently delete a task. Tasks it was blocking are unblocked.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {
+                        "type": "string",
+                        "description": "Task ID"
+                    }
+                },
+                "required": ["task_id"]
+            }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string()
+            })),
+            annotations: ToolAnnotations::DESTRUCTIVE,
         },
         Tool {
             name: "cwa_memory_search_all".to_string(),
@@ -535,6 +999,13 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["query"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "results": schema::array(schema::any_object())
+                }),
+                &["results"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         // New listing tools
         Tool {
@@ -549,6 +1020,8 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                     }
                 }
             }),
+            output_schema: schema::listing("specs", schema::spec_summary()),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_list_tasks".to_string(),
@@ -558,14 +1031,24 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "properties": {
                     "status": {
                         "type": "string",
-                        "description": "Filter by status: backlog, todo, in_progress, review, done (optional)"
+                        "description": "Filter by status, i.e. a workflow column name (optional)"
                     },
                     "spec_id": {
                         "type": "string",
                         "description": "Filter by spec ID (optional)"
+                    },
+                    "assignee": {
+                        "type": "string",
+                        "description": "Filter by assignee (optional)"
+                    },
+                    "label": {
+                        "type": "string",
+                        "description": "Filter by label (optional)"
                     }
                 }
             }),
+            output_schema: schema::listing("tasks", schema::task_summary()),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_update_spec_status".to_string(),
@@ -584,6 +1067,74 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["spec_id", "status"]
             }),
+            output_schema: schema::success(serde_json::json!({})),
+            annotations: ToolAnnotations::DESTRUCTIVE,
+        },
+        Tool {
+            name: "cwa_add_spec_dependency".to_string(),
+            description: "Declare that a specification depends on another one. A spec cannot become active until its dependencies are completed or archived. Rejects dependency cycles.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "spec_id": {
+                        "type": "string",
+                        "description": "Spec ID or title"
+                    },
+                    "depends_on": {
+                        "type": "string",
+                        "description": "Spec ID or title it depends on"
+                    }
+                },
+                "required": ["spec_id", "depends_on"]
+            }),
+            output_schema: schema::success(serde_json::json!({
+                "spec_id": schema::string(),
+                "title": schema::string(),
+                "dependencies": schema::strings()
+            })),
+            annotations: ToolAnnotations::WRITE_IDEMPOTENT,
+        },
+        Tool {
+            name: "cwa_remove_spec_dependency".to_string(),
+            description: "Remove a dependency between two specifications".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "spec_id": {
+                        "type": "string",
+                        "description": "Spec ID or title"
+                    },
+                    "depends_on": {
+                        "type": "string",
+                        "description": "Spec ID or title of the dependency to remove"
+                    }
+                },
+                "required": ["spec_id", "depends_on"]
+            }),
+            output_schema: schema::success(serde_json::json!({
+                "spec_id": schema::string(),
+                "title": schema::string(),
+                "dependencies": schema::strings()
+            })),
+            annotations: ToolAnnotations::DESTRUCTIVE,
+        },
+        Tool {
+            name: "cwa_spec_plan".to_string(),
+            description: "Order draft and active specifications into waves that can be worked on in parallel, following their dependencies".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "waves": schema::array(schema::array(schema::object(
+                    serde_json::json!({ "id": schema::string(), "title": schema::string(), "status": schema::string() }),
+                    &["id", "title"],
+                )))
+                }),
+                &["waves"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_get_glossary".to_string(),
@@ -592,6 +1143,8 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::listing("terms", schema::glossary_term()),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_add_glossary_term".to_string(),
@@ -614,6 +1167,8 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["term", "definition"]
             }),
+            output_schema: schema::success(serde_json::json!({})),
+            annotations: ToolAnnotations::WRITE,
         },
         Tool {
             name: "cwa_get_wip_status".to_string(),
@@ -622,6 +1177,23 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::wip_status(),
+            annotations: ToolAnnotations::READ_ONLY,
+        },
+        Tool {
+            name: "cwa_get_workflow".to_string(),
+            description: "Get the project's Kanban workflow: columns, allowed transitions, WIP limits and entry/exit policies".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "columns": schema::array(schema::any_object())
+                }),
+                &["columns"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_set_wip_limit".to_string(),
@@ -631,7 +1203,7 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "properties": {
                     "column": {
                         "type": "string",
-                        "description": "Column name: backlog, todo, in_progress, review, done"
+                        "description": "Column name of the project's workflow"
                     },
                     "limit": {
                         "type": "integer",
@@ -640,6 +1212,8 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["column"]
             }),
+            output_schema: schema::success(serde_json::json!({})),
+            annotations: ToolAnnotations::DESTRUCTIVE,
         },
         Tool {
             name: "cwa_validate_spec".to_string(),
@@ -654,6 +1228,15 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["spec_id"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "spec_id": schema::string(),
+                    "is_valid": schema::boolean(),
+                    "issues": schema::strings()
+                }),
+                &["spec_id", "is_valid", "issues"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_add_acceptance_criteria".to_string(),
@@ -673,6 +1256,12 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["spec_id", "criteria"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "spec_id": schema::string(),
+                "title": schema::string(),
+                "total_criteria": schema::integer()
+            })),
+            annotations: ToolAnnotations::WRITE,
         },
         Tool {
             name: "cwa_get_context_map".to_string(),
@@ -681,6 +1270,14 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "contexts": schema::strings(),
+                    "relationships": schema::array(schema::any_object())
+                }),
+                &["contexts", "relationships"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_create_domain_object".to_string(),
@@ -707,6 +1304,12 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["context_id", "name", "object_type"]
             }),
+            output_schema: schema::success(serde_json::json!({
+                "id": schema::string(),
+                "embedded": schema::boolean(),
+                "embedding_dim": schema::integer()
+            })),
+            annotations: ToolAnnotations::WRITE,
         },
         Tool {
             name: "cwa_list_decisions".to_string(),
@@ -715,6 +1318,8 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::listing("decisions", schema::decision()),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         // ── Phase 8: New tools ──────────────────────────────────────────────
         Tool {
@@ -724,6 +1329,15 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "tech_stack": schema::strings(),
+                    "count": schema::integer(),
+                    "hint": schema::string()
+                }),
+                &["tech_stack", "count"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_cache_status".to_string(),
@@ -732,6 +1346,15 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "total_keys": schema::integer(),
+                    "used_memory": schema::string(),
+                    "status": schema::string()
+                }),
+                &["total_keys", "used_memory", "status"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_graph_hyperedges".to_string(),
@@ -750,6 +1373,17 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 },
                 "required": ["entity_type", "entity_id"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "entity_type": schema::string(),
+                    "entity_id": schema::string(),
+                    "hyperedge_count": schema::integer(),
+                    "hyperedges": schema::array(schema::any_object()),
+                    "warning": schema::string()
+                }),
+                &["entity_type", "entity_id", "hyperedge_count", "hyperedges"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_codegen_agents".to_string(),
@@ -758,10 +1392,20 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                 "type": "object",
                 "properties": {}
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "tech_stack": schema::strings(),
+                    "agent_count": schema::integer(),
+                    "agents": schema::strings(),
+                    "hint": schema::string()
+                }),
+                &["tech_stack", "agent_count", "agents"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
         Tool {
             name: "cwa_hybrid_search".to_string(),
-            description: "Hybrid semantic search across all CWA vector collections (specs, tasks, domain, memory, files). Combines dense vector search with keyword filtering via RRF fusion.".to_string(),
+            description: "Hybrid search across the project's memories, observations, glossary terms, domain objects and files. Fuses dense vector search with a BM25 keyword index, so exact identifiers and error strings are found too; weight the two legs with dense_weight and lexical_weight.".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -776,19 +1420,103 @@ pub fn get_tools_list() -> Result<serde_json::Value, JsonRpcError> {
                     "collections": {
                         "type": "array",
                         "items": { "type": "string" },
-                        "description": "Collections to search: cwa_specs, cwa_tasks, cwa_domain, cwa_memory, cwa_files (default: all)"
+                        "description": "Collections to search: cwa_memories, cwa_observations, cwa_terms, cwa_domain_objects, cwa_files (default: all)"
+                    },
+                    "dense_weight": {
+                        "type": "number",
+                        "minimum": 0,
+                        "description": "Weight of the semantic (vector) leg; 0 disables it (default: 1.0)"
+                    },
+                    "lexical_weight": {
+                        "type": "number",
+                        "minimum": 0,
+                        "description": "Weight of the keyword (BM25) leg; 0 disables it (default: 1.0)"
+                    },
+                    "fusion": {
+                        "type": "string",
+                        "enum": ["rrf", "score_average"],
+                        "description": "How the legs are fused (default: rrf)"
                     }
                 },
                 "required": ["query"]
             }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "query": schema::string(),
+                    "count": schema::integer(),
+                    "results": schema::array(schema::any_object())
+                }),
+                &["query", "count", "results"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
+        },
+        Tool {
+            name: "cwa_code_search".to_string(),
+            description: "Search the project's source code by meaning and keywords. Returns matching chunks with their path and line range. The codebase must have been indexed with 'cwa index'.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "What the code does, or identifiers and strings it contains"
+                    },
+                    "top_k": {
+                        "type": "integer",
+                        "description": "Number of chunks to return (default: 5)"
+                    },
+                    "language": {
+                        "type": "string",
+                        "description": "Only return chunks in this language (e.g. rust, python, typescript)"
+                    },
+                    "path_prefix": {
+                        "type": "string",
+                        "description": "Only return chunks of files under this path (e.g. crates/cwa-core/)"
+                    }
+                },
+                "required": ["query"]
+            }),
+            output_schema: schema::object(
+                serde_json::json!({
+                    "query": schema::string(),
+                    "count": schema::integer(),
+                    "results": schema::array(schema::code_chunk())
+                }),
+                &["query", "count", "results"],
+            ),
+            annotations: ToolAnnotations::READ_ONLY,
         },
     ];
 
+    // Every tool except the listing can work in any registered project.
+    for tool in tools.iter_mut().filter(|t| t.name != "cwa_list_projects") {
+        tool.input_schema["properties"]["project"] = serde_json::json!({
+            "type": "string",
+            "description": "Project name, ID, or ID prefix (default: the server's project; see cwa_list_projects)"
+        });
+    }
+
     Ok(serde_json::json!({ "tools": tools }))
 }
 
 /// Build a tool error response with `isError: true` per MCP spec.
 /// Used for execution failures (DB, embedding, graph) — not protocol errors.
+/// Tell WebSocket clients and MCP subscribers in every process about a
+/// change in `project_id`.
+///
+/// Alongside the web server the broadcast channel is used directly; a
+/// standalone server notifies it over HTTP. Either way the event is also
+/// published on the project's Redis channel.
+async fn notify_change(pool: &DbPool, broadcast_tx: &Option<BroadcastSender>, project_id: &str, msg: WebSocketMessage) {
+    if let Some(tx) = broadcast_tx {
+        if let Err(e) = cwa_db::publish_event(pool, project_id, &msg).await {
+            tracing::debug!(error = %e, "Failed to publish change event");
+        }
+        let _ = tx.send(msg);
+    } else {
+        cwa_core::WebNotifier::new().with_pool(pool, project_id).notify(&msg).await;
+    }
+}
+
 fn tool_error(message: &str) -> serde_json::Value {
     serde_json::json!({
         "content": [{"type": "text", "text": format!("Error: {}", message)}],
@@ -796,14 +1524,89 @@ fn tool_error(message: &str) -> serde_json::Value {
     })
 }
 
-/// Call a tool by name (for reuse by planner).
+/// Handle `tools/call` for a session: check the tool against the session's
+/// profile, run it, and record the call in the project's audit log.
+///
+/// Per MCP spec, tool execution failures should be returned as successful
+/// JSON-RPC responses with isError: true, not as JSON-RPC errors. Only
+/// protocol errors (-32602, -32601) stay as JSON-RPC errors. A call the
+/// client cancels is dropped before it can be recorded.
+async fn call_tool(
+    pool: &DbPool,
+    broadcast_tx: &Option<BroadcastSender>,
+    session: &Session,
+    params: Option<serde_json::Value>,
+) -> Result<serde_json::Value, JsonRpcError> {
+    let started = std::time::Instant::now();
+    let name = params.as_ref().and_then(|p| p["name"].as_str()).unwrap_or_default().to_string();
+    let arguments = params.as_ref().and_then(|p| p.get("arguments")).cloned().unwrap_or(serde_json::Value::Null);
+
+    let allowed = name.is_empty() || session.profile().allows(&name);
+
+    let result = if !allowed {
+        Ok(tool_error(&format!(
+            "Tool '{}' is not allowed by the '{}' MCP profile. Restart the server with a profile that includes it (see 'cwa mcp profiles').",
+            name,
+            session.profile().name()
+        )))
+    } else {
+        match run_tool(pool, broadcast_tx, &session.progress(&params), session.default_project(), params).await {
+            Err(e) if e.code == -32603 => Ok(tool_error(&e.message)),
+            other => other,
+        }
+    };
+
+    if !name.is_empty() {
+        let (status, error) = match &result {
+            Ok(v) if v["isError"] == true => {
+                let message = v["content"][0]["text"].as_str().unwrap_or_default();
+                let status = if allowed { AuditStatus::Error } else { AuditStatus::Denied };
+                (status, Some(message.trim_start_matches("Error: ").to_string()))
+            }
+            Ok(_) => (AuditStatus::Ok, None),
+            Err(e) => (AuditStatus::Error, Some(e.message.clone())),
+        };
+        let entry = AuditEntry {
+            id: String::new(),
+            tool: name,
+            arguments: cwa_core::audit::truncate_arguments(&arguments),
+            status,
+            error,
+            duration_ms: started.elapsed().as_millis() as u64,
+            client: session.client(),
+            session_id: Some(session.id().to_string()),
+            at: chrono::Utc::now().to_rfc3339(),
+        };
+        audit(pool, arguments["project"].as_str(), session.default_project(), &entry).await;
+    }
+
+    result
+}
+
+/// Record a tool call in the audit log of the project it targeted.
+/// Failures are logged and never affect the call.
+async fn audit(pool: &DbPool, selector: Option<&str>, default_project: Option<&str>, entry: &AuditEntry) {
+    let Ok(project) = cwa_core::project::resolve_project(pool, selector, default_project).await else {
+        return;
+    };
+    if let Err(e) = cwa_core::audit::record(pool, &project.id, entry).await {
+        tracing::debug!(error = %e, tool = %entry.tool, "Failed to record MCP audit entry");
+    }
+}
+
+/// Run a tool by name (for reuse by planner).
 ///
 /// Execution errors (DB, embedding, graph failures) are returned as successful
 /// JSON-RPC responses with `isError: true` per the MCP spec.
 /// Protocol errors (missing params, unknown tool) remain as JSON-RPC errors.
-pub async fn call_tool(
+///
+/// The tool works in the project named by its `project` argument, else in
+/// `default_project` (a project ID), else in the first project found.
+pub async fn run_tool(
     pool: &DbPool,
     broadcast_tx: &Option<BroadcastSender>,
+    progress: &Progress,
+    default_project: Option<&str>,
     params: Option<serde_json::Value>,
 ) -> Result<serde_json::Value, JsonRpcError> {
     let params = params.ok_or_else(|| JsonRpcError {
@@ -818,11 +1621,15 @@ pub async fn call_tool(
 
     let args = params.get("arguments").cloned().unwrap_or(serde_json::json!({}));
 
-    // Get default project — execution failure returns isError
-    let project = match cwa_core::project::get_default_project(pool).await {
-        Ok(Some(p)) => p,
-        Ok(None) => return Ok(tool_error("No project found. Run 'cwa init' first.")),
-        Err(e) => return Ok(tool_error(&format!("Database error: {}", e))),
+    // Listing projects needs no project to work in
+    if name == "cwa_list_projects" {
+        return list_projects(pool, default_project).await;
+    }
+
+    // Resolve the target project — execution failure returns isError
+    let project = match cwa_core::project::resolve_project(pool, args["project"].as_str(), default_project).await {
+        Ok(p) => p,
+        Err(e) => return Ok(tool_error(&e.to_string())),
     };
 
     let result = match name {
@@ -918,22 +1725,44 @@ pub async fn call_tool(
                     message: e.to_string(),
                 })?;
 
-            // Broadcast to WebSocket clients (direct if available, HTTP fallback)
-            if let Some(tx) = broadcast_tx {
-                let _ = tx.send(WebSocketMessage::TaskUpdated {
-                    task_id: task_id.to_string(),
-                    status: status.to_string(),
-                });
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::TaskUpdated {
+                task_id: task_id.to_string(),
+                status: status.to_string(),
+            })
+            .await;
+
+            serde_json::json!({
+                "success": true,
+                "message": format!("Task {} moved to {}", task_id, status)
+            })
+        }
+
+        "cwa_add_task_blocker" | "cwa_remove_task_blocker" => {
+            let task_id = args["task_id"].as_str().ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "Missing task_id".to_string(),
+            })?;
+            let blocker_id = args["blocker_id"].as_str().ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "Missing blocker_id".to_string(),
+            })?;
+
+            let task = if name == "cwa_add_task_blocker" {
+                cwa_core::task::add_blocker(pool, &project.id, task_id, blocker_id).await
             } else {
-                // Fallback to HTTP notification when running standalone
-                // Use cwa_core notifier and await completion (no fire-and-forget)
-                let notifier = cwa_core::WebNotifier::new();
-                notifier.notify_task_updated(task_id, status).await;
+                cwa_core::task::remove_blocker(pool, &project.id, task_id, blocker_id).await
             }
+            .map_err(|e| JsonRpcError {
+                code: -32603,
+                message: e.to_string(),
+            })?;
+
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::BoardRefresh).await;
 
             serde_json::json!({
                 "success": true,
-                "message": format!("Task {} moved to {}", task_id, status)
+                "task_id": task.id,
+                "blocked_by": task.blocked_by
             })
         }
 
@@ -988,7 +1817,10 @@ pub async fn call_tool(
                     message: e.to_string(),
                 })?;
 
-            serde_json::to_value(&results).unwrap()
+            serde_json::json!({
+                "count": results.len(),
+                "results": results
+            })
         }
 
         "cwa_generate_tasks" => {
@@ -996,13 +1828,14 @@ pub async fn call_tool(
                 code: -32602,
                 message: "Missing spec_identifier".to_string(),
             })?;
-            let status = args.get("status").and_then(|v| v.as_str()).unwrap_or("backlog");
+            let status = args.get("status").and_then(|v| v.as_str());
 
             let result = cwa_core::task::generate_tasks_from_spec(pool, &project.id, spec_identifier, status).await
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: e.to_string(),
                 })?;
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::BoardRefresh).await;
 
             serde_json::json!({
                 "success": true,
@@ -1029,13 +1862,17 @@ pub async fn call_tool(
                     message: format!("Neo4j connection failed: {}", e),
                 })?;
 
-            let results = cwa_graph::queries::search::raw_query(&client, cypher).await
+            let result = cwa_graph::queries::search::raw_query(&client, cypher, !graph_writes_allowed()).await
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: e.to_string(),
                 })?;
 
-            serde_json::json!({ "results": results })
+            serde_json::json!({
+                "columns": result.columns,
+                "count": result.rows.len(),
+                "rows": result.rows
+            })
         }
 
         "cwa_graph_impact" => {
@@ -1054,13 +1891,31 @@ pub async fn call_tool(
                     message: format!("Neo4j connection failed: {}", e),
                 })?;
 
-            let nodes = cwa_graph::queries::impact::impact_analysis(&client, entity_type, entity_id, 3).await
+            let max_depth = args.get("max_depth").and_then(|v| v.as_u64()).unwrap_or(3) as u32;
+
+            let analysis = cwa_graph::queries::impact::impact_analysis(&client, entity_type, entity_id, max_depth).await
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: e.to_string(),
                 })?;
 
-            serde_json::json!({ "impacts": nodes })
+            let by_label: serde_json::Map<String, serde_json::Value> = analysis
+                .by_label()
+                .into_iter()
+                .map(|(label, nodes)| {
+                    let ids: Vec<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+                    (label.to_string(), serde_json::json!(ids))
+                })
+                .collect();
+
+            serde_json::json!({
+                "root": analysis.root,
+                "max_depth": analysis.max_depth,
+                "total": analysis.nodes.len(),
+                "tree": analysis.render_tree(),
+                "by_label": by_label,
+                "impacts": analysis.nodes
+            })
         }
 
         "cwa_graph_sync" => {
@@ -1076,7 +1931,11 @@ pub async fn call_tool(
                     message: e.to_string(),
                 })?;
 
-            let result = cwa_graph::run_full_sync(&client, pool, &project.id).await
+            let incremental = args.get("incremental").and_then(|v| v.as_bool()).unwrap_or(false);
+            let report = |done: usize, total: usize, stage: &str| {
+                progress.report(done, total, &format!("Synced {}", stage));
+            };
+            let result = cwa_graph::run_sync_with_progress(&client, pool, &project.id, incremental, &report).await
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: e.to_string(),
@@ -1084,8 +1943,11 @@ pub async fn call_tool(
 
             serde_json::json!({
                 "success": true,
+                "incremental": incremental,
                 "nodes_created": result.nodes_created,
                 "nodes_updated": result.nodes_updated,
+                "nodes_unchanged": result.nodes_unchanged,
+                "nodes_deleted": result.nodes_deleted,
                 "relationships_created": result.relationships_created
             })
         }
@@ -1098,7 +1960,7 @@ pub async fn call_tool(
             })?;
             let top_k = args.get("top_k").and_then(|v| v.as_u64()).unwrap_or(5);
 
-            let search = cwa_embedding::SemanticSearch::default_search()
+            let search = cwa_embedding::SemanticSearch::from_config(&embedding_config(&project)?)
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: format!("Search initialization failed: {}", e),
@@ -1137,7 +1999,7 @@ pub async fn call_tool(
                 .unwrap_or_default();
 
             // Try with embedding pipeline, fallback to DB-only
-            match cwa_embedding::ObservationPipeline::default_pipeline() {
+            match cwa_embedding::ObservationPipeline::from_config(&embedding_config(&project)?) {
                 Ok(pipeline) => {
                     let result = pipeline.add_observation(
                         pool, &project.id, obs_type, title, narrative,
@@ -1151,7 +2013,8 @@ pub async fn call_tool(
                     serde_json::json!({
                         "success": true,
                         "id": result.id,
-                        "embedding_dim": result.embedding_dim
+                        "embedding_dim": result.embedding_dim.unwrap_or(0),
+                        "embedding_pending": result.embedding_dim.is_none()
                     })
                 }
                 Err(_) => {
@@ -1167,7 +2030,8 @@ pub async fn call_tool(
                     serde_json::json!({
                         "success": true,
                         "id": obs.id,
-                        "embedding_dim": 0
+                        "embedding_dim": 0,
+                        "embedding_pending": true
                     })
                 }
             }
@@ -1197,7 +2061,7 @@ pub async fn call_tool(
                 .collect();
 
             let id_refs: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
-            let observations = cwa_core::memory::get_observations_batch(pool, &id_refs).await
+            let observations = cwa_core::memory::get_observations_batch(pool, &project.id, &id_refs).await
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: e.to_string(),
@@ -1219,7 +2083,7 @@ pub async fn call_tool(
             let top_k = args.get("top_k").and_then(|v| v.as_u64()).unwrap_or(10);
 
             // Try semantic search, fallback to timeline
-            match cwa_embedding::SemanticSearch::default_search() {
+            match cwa_embedding::SemanticSearch::from_config(&embedding_config(&project)?) {
                 Ok(search) => {
                     let results = search.search_all(query, &project.id, top_k).await
                         .map_err(|e| JsonRpcError {
@@ -1259,7 +2123,7 @@ pub async fn call_tool(
                     message: e.to_string(),
                 })?;
 
-            let pipeline = cwa_embedding::MemoryPipeline::default_pipeline()
+            let pipeline = cwa_embedding::MemoryPipeline::from_config(&embedding_config(&project)?)
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: format!("Pipeline initialization failed: {}", e),
@@ -1274,7 +2138,8 @@ pub async fn call_tool(
             serde_json::json!({
                 "success": true,
                 "id": result.id,
-                "embedding_dim": result.embedding_dim
+                "embedding_dim": result.embedding_dim.unwrap_or(0),
+                "embedding_pending": result.embedding_dim.is_none()
             })
         }
 
@@ -1299,7 +2164,8 @@ pub async fn call_tool(
                     "title": s.title,
                     "status": s.status.as_str(),
                     "priority": s.priority.as_str(),
-                    "criteria_count": s.acceptance_criteria.len()
+                    "criteria_count": s.acceptance_criteria.len(),
+                    "dependencies": s.dependencies
                 })).collect::<Vec<_>>()
             })
         }
@@ -1314,7 +2180,7 @@ pub async fn call_tool(
                         code: -32603,
                         message: e.to_string(),
                     })?;
-                cwa_core::task::list_tasks_by_spec(pool, &spec.id).await
+                cwa_core::task::list_tasks_by_spec(pool, &project.id, &spec.id).await
                     .map_err(|e| JsonRpcError {
                         code: -32603,
                         message: e.to_string(),
@@ -1327,9 +2193,15 @@ pub async fn call_tool(
                     })?
             };
 
+            let filter = cwa_core::task::model::TaskFilter {
+                status: status_filter.map(str::to_string),
+                spec_id: None,
+                assignee: args.get("assignee").and_then(|v| v.as_str()).map(str::to_string),
+                label: args.get("label").and_then(|v| v.as_str()).map(str::to_string),
+            };
             let filtered: Vec<_> = tasks
                 .into_iter()
-                .filter(|t| status_filter.map_or(true, |f| t.status.as_str() == f))
+                .filter(|t| filter.matches(t))
                 .collect();
 
             serde_json::json!({
@@ -1339,7 +2211,12 @@ pub async fn call_tool(
                     "title": t.title,
                     "status": t.status.as_str(),
                     "priority": t.priority.as_str(),
-                    "spec_id": t.spec_id
+                    "assignee": t.assignee,
+                    "labels": t.labels,
+                    "estimated_effort": t.estimated_effort,
+                    "actual_effort": t.actual_effort,
+                    "spec_id": t.spec_id,
+                    "blocked_by": t.blocked_by
                 })).collect::<Vec<_>>()
             })
         }
@@ -1365,6 +2242,7 @@ pub async fn call_tool(
                     code: -32603,
                     message: e.to_string(),
                 })?;
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::SpecUpdated { spec_id: spec.id.clone() }).await;
 
             serde_json::json!({
                 "success": true,
@@ -1372,6 +2250,45 @@ pub async fn call_tool(
             })
         }
 
+        "cwa_add_spec_dependency" | "cwa_remove_spec_dependency" => {
+            let spec_id = args["spec_id"].as_str().ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "Missing spec_id".to_string(),
+            })?;
+            let depends_on = args["depends_on"].as_str().ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "Missing depends_on".to_string(),
+            })?;
+
+            let spec = if name == "cwa_add_spec_dependency" {
+                cwa_core::spec::add_dependency(pool, &project.id, spec_id, depends_on).await
+            } else {
+                cwa_core::spec::remove_dependency(pool, &project.id, spec_id, depends_on).await
+            }
+            .map_err(|e| JsonRpcError {
+                code: -32603,
+                message: e.to_string(),
+            })?;
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::SpecUpdated { spec_id: spec.id.clone() }).await;
+
+            serde_json::json!({
+                "success": true,
+                "spec_id": spec.id,
+                "title": spec.title,
+                "dependencies": spec.dependencies
+            })
+        }
+
+        "cwa_spec_plan" => {
+            let plan = cwa_core::spec::plan_specs(pool, &project.id).await
+                .map_err(|e| JsonRpcError {
+                    code: -32603,
+                    message: e.to_string(),
+                })?;
+
+            serde_json::to_value(&plan).unwrap()
+        }
+
         "cwa_get_glossary" => {
             let terms = cwa_core::domain::list_glossary(pool, &project.id).await
                 .map_err(|e| JsonRpcError {
@@ -1418,6 +2335,16 @@ pub async fn call_tool(
             serde_json::to_value(&wip_status).unwrap()
         }
 
+        "cwa_get_workflow" => {
+            let workflow = cwa_core::task::get_workflow(pool, &project.id).await
+                .map_err(|e| JsonRpcError {
+                    code: -32603,
+                    message: e.to_string(),
+                })?;
+
+            serde_json::to_value(&workflow).unwrap()
+        }
+
         "cwa_set_wip_limit" => {
             let column = args["column"].as_str().ok_or_else(|| JsonRpcError {
                 code: -32602,
@@ -1430,6 +2357,7 @@ pub async fn call_tool(
                     code: -32603,
                     message: e.to_string(),
                 })?;
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::BoardRefresh).await;
 
             let msg = match limit {
                 Some(l) => format!("WIP limit for '{}' set to {}", column, l),
@@ -1482,6 +2410,7 @@ pub async fn call_tool(
                     code: -32603,
                     message: e.to_string(),
                 })?;
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::SpecUpdated { spec_id: spec.id.clone() }).await;
 
             serde_json::json!({
                 "success": true,
@@ -1516,7 +2445,7 @@ pub async fn call_tool(
             })?;
             let description = args.get("description").and_then(|v| v.as_str());
 
-            let obj_id = cwa_core::domain::create_domain_object(pool, context_id, name, object_type, description).await
+            let obj_id = cwa_core::domain::create_domain_object(pool, &project.id, context_id, name, object_type, description).await
                 .map_err(|e| JsonRpcError {
                     code: -32603,
                     message: e.to_string(),
@@ -1525,9 +2454,9 @@ pub async fn call_tool(
             // Try to embed (graceful failure if Qdrant/Ollama unavailable)
             let mut embedded = false;
             let mut embedding_dim = 0usize;
-            if let Ok(pipeline) = cwa_embedding::DomainObjectPipeline::default_pipeline() {
+            if let Ok(pipeline) = cwa_embedding::DomainObjectPipeline::from_config(&embedding_config(&project)?) {
                 // Look up context name for embedding text
-                let ctx_name = cwa_core::domain::get_context(pool, context_id).await
+                let ctx_name = cwa_core::domain::get_context(pool, &project.id, context_id).await
                     .map(|c| c.name)
                     .unwrap_or_else(|_| context_id.to_string());
                 if let Ok(dim) = pipeline.embed_domain_object(
@@ -1607,6 +2536,7 @@ pub async fn call_tool(
                     code: -32603,
                     message: e.to_string(),
                 })?;
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::SpecUpdated { spec_id: spec.id.clone() }).await;
 
             serde_json::json!({
                 "success": true,
@@ -1630,6 +2560,7 @@ pub async fn call_tool(
                     code: -32603,
                     message: e.to_string(),
                 })?;
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::BoardRefresh).await;
 
             serde_json::json!({
                 "success": true,
@@ -1638,6 +2569,52 @@ pub async fn call_tool(
             })
         }
 
+        "cwa_update_task" => {
+            let task_id = args["task_id"].as_str().ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "Missing task_id".to_string(),
+            })?;
+            let update: cwa_core::task::model::TaskUpdate = serde_json::from_value(args.clone())
+                .map_err(|e| JsonRpcError {
+                    code: -32602,
+                    message: format!("Invalid task fields: {}", e),
+                })?;
+
+            let task = cwa_core::task::update_task(pool, &project.id, task_id, &update).await
+                .map_err(|e| JsonRpcError {
+                    code: -32603,
+                    message: e.to_string(),
+                })?;
+
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::BoardRefresh).await;
+
+            serde_json::json!({
+                "success": true,
+                "task": task
+            })
+        }
+
+        "cwa_delete_task" => {
+            let task_id = args["task_id"].as_str().ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "Missing task_id".to_string(),
+            })?;
+
+            let task = cwa_core::task::delete_task(pool, &project.id, task_id).await
+                .map_err(|e| JsonRpcError {
+                    code: -32603,
+                    message: e.to_string(),
+                })?;
+
+            notify_change(pool, broadcast_tx, &project.id, WebSocketMessage::BoardRefresh).await;
+
+            serde_json::json!({
+                "success": true,
+                "message": format!("Deleted task '{}'", task.title),
+                "id": task.id
+            })
+        }
+
         // ── Phase 8: New tools ──────────────────────────────────────────────
         "cwa_get_tech_stack" => {
             let stack = cwa_db::queries::projects::get_tech_stack(pool, &project.id).await
@@ -1736,22 +2713,31 @@ pub async fn call_tool(
                 .map(|arr| arr.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
                 .unwrap_or_else(|| cwa_embedding::QdrantStore::all_collections().iter().map(|s| s.to_string()).collect());
 
-            let ollama_url = std::env::var("OLLAMA_URL").unwrap_or_else(|_| "http://localhost:11434".to_string());
+            let dense_weight = args.get("dense_weight").and_then(|v| v.as_f64()).unwrap_or(1.0);
+            let lexical_weight = args.get("lexical_weight").and_then(|v| v.as_f64()).unwrap_or(1.0);
+            let fusion = match args.get("fusion").and_then(|v| v.as_str()) {
+                Some(name) => name.parse().map_err(|e: String| JsonRpcError {
+                    code: -32602,
+                    message: e,
+                })?,
+                None => cwa_embedding::FusionAlgo::Rrf,
+            };
+
+            let embedding = embedding_config(&project)?;
             let qdrant_url = std::env::var("QDRANT_URL").unwrap_or_else(|_| "http://localhost:6333".to_string());
 
             match (
                 cwa_embedding::QdrantStore::new(&qdrant_url),
             ) {
                 (Ok(qdrant),) => {
-                    let ollama = cwa_embedding::OllamaClient::new(&ollama_url, "nomic-embed-text");
-                    let req = cwa_embedding::HybridSearchRequest {
-                        query,
-                        top_k,
-                        collections,
-                        project_id: Some(&project.id),
-                        fusion: cwa_embedding::FusionAlgo::Rrf,
-                    };
-                    match cwa_embedding::hybrid_search(&ollama, &qdrant, req).await {
+                    let qdrant = qdrant.with_dimension(embedding.dimension());
+                    let embedder = embedding.embedder();
+                    let req = cwa_embedding::HybridSearchRequest::new(query, top_k)
+                        .with_collections(collections)
+                        .with_project(&project.id)
+                        .with_fusion(fusion)
+                        .with_weights(dense_weight, lexical_weight);
+                    match cwa_embedding::hybrid_search(embedder.as_ref(), &qdrant, pool, req).await {
                         Ok(results) => serde_json::json!({
                             "query": query,
                             "count": results.len(),
@@ -1766,6 +2752,34 @@ pub async fn call_tool(
             }
         }
 
+        "cwa_code_search" => {
+            let query = args["query"].as_str().ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "Missing query".to_string(),
+            })?;
+            let top_k = args.get("top_k").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
+            let language = args.get("language").and_then(|v| v.as_str());
+            let path_prefix = args.get("path_prefix").and_then(|v| v.as_str());
+
+            let embedding = embedding_config(&project)?;
+            let qdrant_url = std::env::var("QDRANT_URL").unwrap_or_else(|_| "http://localhost:6333".to_string());
+            let qdrant = match cwa_embedding::QdrantStore::new(&qdrant_url) {
+                Ok(qdrant) => qdrant.with_dimension(embedding.dimension()),
+                Err(_) => {
+                    return Ok(tool_error("Qdrant not available — run 'cwa infra up' to start the vector database"));
+                }
+            };
+            let embedder = embedding.embedder();
+            match cwa_embedding::search_code(embedder.as_ref(), &qdrant, pool, &project.id, query, top_k, language, path_prefix).await {
+                Ok(results) => serde_json::json!({
+                    "query": query,
+                    "count": results.len(),
+                    "results": results
+                }),
+                Err(e) => return Ok(tool_error(&format!("Code search failed: {}", e))),
+            }
+        }
+
         _ => {
             return Err(JsonRpcError {
                 code: -32601,
@@ -1774,12 +2788,59 @@ pub async fn call_tool(
         }
     };
 
-    Ok(serde_json::json!({
+    Ok(structured_result(result))
+}
+
+/// The embedding configuration of `project`, or the defaults when its
+/// directory is not available to this server.
+fn embedding_config(project: &cwa_core::project::model::Project) -> Result<cwa_embedding::EmbeddingConfig, JsonRpcError> {
+    match project.root_dir() {
+        Some(dir) => cwa_embedding::EmbeddingConfig::load(&dir).map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("{:#}", e),
+        }),
+        None => Ok(cwa_embedding::EmbeddingConfig::default()),
+    }
+}
+
+/// Wrap a tool's result. The text block keeps older clients working; newer
+/// ones read the typed result matching the tool's outputSchema.
+fn structured_result(result: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
         "content": [{
             "type": "text",
             "text": serde_json::to_string_pretty(&result).unwrap()
-        }]
-    }))
+        }],
+        "structuredContent": result
+    })
+}
+
+/// The `cwa_list_projects` result, marking the project used by default.
+async fn list_projects(pool: &DbPool, default_project: Option<&str>) -> Result<serde_json::Value, JsonRpcError> {
+    let projects = match cwa_core::project::list_projects(pool).await {
+        Ok(projects) => projects,
+        Err(e) => return Ok(tool_error(&format!("Database error: {}", e))),
+    };
+    let default_id = cwa_core::project::resolve_project(pool, None, default_project)
+        .await
+        .ok()
+        .map(|p| p.id);
+
+    let summary: Vec<_> = projects
+        .iter()
+        .map(|p| serde_json::json!({
+            "id": p.id,
+            "name": p.name,
+            "description": p.description,
+            "path": p.root_dir().map(|d| d.display().to_string()),
+            "default": default_id.as_deref() == Some(p.id.as_str())
+        }))
+        .collect();
+
+    Ok(structured_result(serde_json::json!({
+        "count": projects.len(),
+        "projects": summary
+    })))
 }
 
 /// Get the list of all available resources (for reuse by planner).
@@ -1863,9 +2924,24 @@ pub fn get_resources_list() -> Result<serde_json::Value, JsonRpcError> {
     Ok(serde_json::json!({ "resources": resources }))
 }
 
+/// Split a project-scoped `cwa://{project}/{path}` URI into the project
+/// (percent-decoded) and the equivalent `project://{path}` URI.
+fn scoped_uri(uri: &str) -> Option<(String, String)> {
+    let (project, path) = uri.strip_prefix("cwa://")?.split_once('/')?;
+    if project.is_empty() || path.is_empty() {
+        return None;
+    }
+    let project = percent_encoding::percent_decode_str(project).decode_utf8().ok()?;
+    Some((project.into_owned(), format!("project://{}", path)))
+}
+
 /// Read a resource by URI (for reuse by planner).
+///
+/// `project://` URIs read `default_project` (a project ID, else the first
+/// project found); `cwa://{project}/...` URIs read the named project.
 pub async fn read_resource(
     pool: &DbPool,
+    default_project: Option<&str>,
     params: Option<serde_json::Value>,
 ) -> Result<serde_json::Value, JsonRpcError> {
     let params = params.ok_or_else(|| JsonRpcError {
@@ -1878,17 +2954,21 @@ pub async fn read_resource(
         message: "Missing uri".to_string(),
     })?;
 
-    let project = cwa_core::project::get_default_project(pool).await
+    let (selector, local_uri) = match scoped_uri(uri) {
+        Some((project, local)) => (Some(project), local),
+        None => (None, uri.to_string()),
+    };
+    let project = cwa_core::project::resolve_project(pool, selector.as_deref(), default_project)
+        .await
         .map_err(|e| JsonRpcError {
-            code: -32603,
+            code: match e {
+                cwa_core::CwaError::ProjectNotFound(_) if selector.is_some() => -32602,
+                _ => -32603,
+            },
             message: e.to_string(),
-        })?
-        .ok_or_else(|| JsonRpcError {
-            code: -32603,
-            message: "No project found".to_string(),
         })?;
 
-    let content = match uri {
+    let content = match local_uri.as_str() {
         "project://info" => {
             let info = cwa_core::project::get_project_info(pool, &project.id).await
                 .map_err(|e| JsonRpcError {
@@ -2042,12 +3122,15 @@ pub async fn read_resource(
             })).unwrap()
         }
 
-        _ => {
-            return Err(JsonRpcError {
-                code: -32602,
-                message: format!("Unknown resource: {}", uri),
-            })
-        }
+        _ => match templates::match_uri(&local_uri) {
+            Some((template, value)) => templates::read(pool, &project.id, template, &value).await?,
+            None => {
+                return Err(JsonRpcError {
+                    code: -32602,
+                    message: format!("Unknown resource: {}", uri),
+                })
+            }
+        },
     };
 
     Ok(serde_json::json!({
@@ -2058,3 +3141,32 @@ pub async fn read_resource(
         }]
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_uris_name_a_project() {
+        assert_eq!(
+            scoped_uri("cwa://billing-api/specs"),
+            Some(("billing-api".to_string(), "project://specs".to_string()))
+        );
+        assert_eq!(
+            scoped_uri("cwa://Order%20Service/spec/3f2a"),
+            Some(("Order Service".to_string(), "project://spec/3f2a".to_string()))
+        );
+        assert_eq!(scoped_uri("project://specs"), None);
+        assert_eq!(scoped_uri("cwa:///specs"), None);
+        assert_eq!(scoped_uri("cwa://billing-api"), None);
+    }
+
+    #[test]
+    fn every_tool_but_the_listing_takes_a_project() {
+        let tools = get_tools_list().unwrap();
+        for tool in tools["tools"].as_array().unwrap() {
+            let has_project = tool["inputSchema"]["properties"].get("project").is_some();
+            assert_eq!(has_project, tool["name"] != "cwa_list_projects", "{}", tool["name"]);
+        }
+    }
+}
@@ -0,0 +1,471 @@
+//! MCP client sessions: resource subscriptions and server-initiated
+//! notifications.
+//!
+//! Each connected client (the stdio peer, or one HTTP session) gets a
+//! [`Session`] with an outbox of JSON-RPC messages to deliver. Change events
+//! published on a project's Redis channel by any CWA process (the same
+//! events that refresh the web board) become
+//! `notifications/resources/updated` for the resources of that project the
+//! client subscribed to.
+//!
+//! Sessions also track their in-flight requests, so that a
+//! `notifications/cancelled` from the client can abort the matching request,
+//! and carry `notifications/progress` for requests that asked for it. The
+//! session's [`ToolProfile`] decides which tools the client may use.
+
+use cwa_db::{ProjectEvent, WebSocketMessage};
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::profile::ToolProfile;
+
+/// Messages a session's outbox holds before notifications are dropped (a
+/// client that never opens its stream must not grow it without bound).
+pub const OUTBOX_CAPACITY: usize = 1024;
+
+/// Resources derived from tasks and the workflow.
+const TASK_RESOURCES: &[&str] = &["project://kanban-board", "project://tasks", "project://wip-status"];
+
+/// Resources derived from specs.
+const SPEC_RESOURCES: &[&str] = &["project://specs", "project://current-spec"];
+
+/// Resources whose content may change with a change event.
+pub fn affected_resources(msg: &WebSocketMessage) -> &'static [&'static str] {
+    match msg {
+        WebSocketMessage::TaskUpdated { .. } | WebSocketMessage::BoardRefresh => TASK_RESOURCES,
+        WebSocketMessage::SpecUpdated { .. } => SPEC_RESOURCES,
+        WebSocketMessage::ToolsChanged => &[],
+    }
+}
+
+/// What a subscribed URI refers to.
+struct Subscription {
+    project_id: String,
+    /// The `project://` form of the URI.
+    resource: String,
+}
+
+/// A connected client.
+pub struct Session {
+    id: String,
+    /// Name and version the client gave in `initialize`.
+    client: Mutex<Option<String>>,
+    /// Subscribed URIs, with the project and `project://` resource each
+    /// one refers to.
+    subscriptions: Mutex<HashMap<String, Subscription>>,
+    /// Requests being handled, by serialized request ID. A client may reuse
+    /// an ID, so each entry is tagged with its registration number.
+    in_flight: Mutex<HashMap<String, Vec<(u64, AbortHandle)>>>,
+    next_request: AtomicU64,
+    outbox: mpsc::Sender<String>,
+    profile: ToolProfile,
+    /// Project used when a request names none.
+    default_project: Option<String>,
+}
+
+impl Session {
+    /// Create a session limited to the tools of `profile`, whose
+    /// server-initiated messages go to `outbox`. Requests that name no
+    /// project use `default_project` (a project ID), or else the first
+    /// project found.
+    pub fn new(
+        outbox: mpsc::Sender<String>,
+        profile: ToolProfile,
+        default_project: Option<String>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            client: Mutex::new(None),
+            subscriptions: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            next_request: AtomicU64::new(0),
+            outbox,
+            profile,
+            default_project,
+        })
+    }
+
+    /// Unique ID of the session (the `Mcp-Session-Id` over HTTP).
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Remember the client named in the `clientInfo` of `initialize` params.
+    pub fn set_client(&self, params: &Option<serde_json::Value>) {
+        let info = params.as_ref().and_then(|p| p.get("clientInfo"));
+        let Some(name) = info.and_then(|i| i["name"].as_str()) else {
+            return;
+        };
+        let client = match info.and_then(|i| i["version"].as_str()) {
+            Some(version) => format!("{} {}", name, version),
+            None => name.to_string(),
+        };
+        *self.client.lock().unwrap() = Some(client);
+    }
+
+    /// The client's name and version, once it has initialized.
+    pub fn client(&self) -> Option<String> {
+        self.client.lock().unwrap().clone()
+    }
+
+    /// The tools this client may list and call.
+    pub fn profile(&self) -> &ToolProfile {
+        &self.profile
+    }
+
+    /// ID of the project used when a request names none.
+    pub fn default_project(&self) -> Option<&str> {
+        self.default_project.as_deref()
+    }
+
+    /// Queue a response for the client, waiting for room in the outbox.
+    pub async fn send(&self, message: String) {
+        let _ = self.outbox.send(message).await;
+    }
+
+    /// Start sending `notifications/resources/updated` for `uri`, which
+    /// reads `resource` (a `project://` URI) of `project_id`.
+    pub fn subscribe(&self, uri: &str, project_id: &str, resource: &str) {
+        let subscription = Subscription {
+            project_id: project_id.to_string(),
+            resource: resource.to_string(),
+        };
+        self.subscriptions.lock().unwrap().insert(uri.to_string(), subscription);
+    }
+
+    /// Stop notifying about `uri`.
+    pub fn unsubscribe(&self, uri: &str) {
+        self.subscriptions.lock().unwrap().remove(uri);
+    }
+
+    /// Register request `id` as in flight, so that a cancellation arriving
+    /// from now on aborts it once it runs with [`Session::run`].
+    pub fn register(&self, id: &serde_json::Value) -> Registration {
+        let key = id.to_string();
+        let seq = self.next_request.fetch_add(1, Ordering::Relaxed);
+        let (handle, registration) = AbortHandle::new_pair();
+        self.in_flight.lock().unwrap().entry(key.clone()).or_default().push((seq, handle));
+        Registration { key, seq, registration }
+    }
+
+    /// Run a registered request with `handler`, unless the client cancels it
+    /// first.
+    ///
+    /// Returns `None` if the request was cancelled; per the MCP spec, a
+    /// cancelled request gets no response.
+    pub async fn run<F: Future>(&self, request: Registration, handler: F) -> Option<F::Output> {
+        let _done = InFlight { session: self, key: request.key, seq: request.seq };
+        Abortable::new(handler, request.registration).await.ok()
+    }
+
+    /// Handle request `id` with `handler`, unless the client cancels it first.
+    pub async fn run_cancellable<F: Future>(&self, id: &serde_json::Value, handler: F) -> Option<F::Output> {
+        self.run(self.register(id), handler).await
+    }
+
+    /// Abort every in-flight request with ID `id`.
+    pub fn cancel(&self, id: &serde_json::Value) {
+        if let Some(handles) = self.in_flight.lock().unwrap().remove(&id.to_string()) {
+            for (_, handle) in handles {
+                handle.abort();
+            }
+        }
+    }
+
+    /// Progress reporter for a request, live only if the client sent a
+    /// `progressToken` in the request's `_meta`.
+    pub fn progress(&self, params: &Option<serde_json::Value>) -> Progress {
+        Progress {
+            token: params
+                .as_ref()
+                .and_then(|p| p.pointer("/_meta/progressToken"))
+                .filter(|t| t.is_string() || t.is_number())
+                .cloned(),
+            outbox: self.outbox.clone(),
+        }
+    }
+
+    /// Notifications a change event produces for this client.
+    fn notifications_for(&self, event: &ProjectEvent) -> Vec<serde_json::Value> {
+        if let WebSocketMessage::ToolsChanged = event.message {
+            return vec![notification("notifications/tools/list_changed", None)];
+        }
+        let affected = affected_resources(&event.message);
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let mut uris: Vec<&String> = subscriptions
+            .iter()
+            .filter(|(_, s)| s.project_id == event.project_id && affected.contains(&s.resource.as_str()))
+            .map(|(uri, _)| uri)
+            .collect();
+        uris.sort();
+        uris.into_iter()
+            .map(|uri| notification("notifications/resources/updated", Some(serde_json::json!({ "uri": uri }))))
+            .collect()
+    }
+
+    /// Turn change events into notifications for this client until its
+    /// outbox is closed.
+    pub fn forward_events(self: &Arc<Self>, mut events: broadcast::Receiver<ProjectEvent>) {
+        let session = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = session.outbox.closed() => break,
+                    received = events.recv() => match received {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                };
+                for message in session.notifications_for(&event) {
+                    if !notify(&session.outbox, message.to_string()) {
+                        return;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// A request registered with [`Session::register`], not yet run.
+pub struct Registration {
+    key: String,
+    seq: u64,
+    registration: AbortRegistration,
+}
+
+/// Unregisters a request when its handler finishes or is dropped (e.g. when
+/// an HTTP client disconnects), leaving other requests with the same ID.
+struct InFlight<'a> {
+    session: &'a Session,
+    key: String,
+    seq: u64,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.session.in_flight.lock().unwrap();
+        if let Some(handles) = in_flight.get_mut(&self.key) {
+            handles.retain(|(seq, _)| *seq != self.seq);
+            if handles.is_empty() {
+                in_flight.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Sends `notifications/progress` for one request.
+#[derive(Clone)]
+pub struct Progress {
+    token: Option<serde_json::Value>,
+    outbox: mpsc::Sender<String>,
+}
+
+impl Progress {
+    /// Report `progress` out of `total` units of work.
+    pub fn report(&self, progress: usize, total: usize, message: &str) {
+        let Some(token) = &self.token else {
+            return;
+        };
+        let params = serde_json::json!({
+            "progressToken": token,
+            "progress": progress,
+            "total": total,
+            "message": message
+        });
+        notify(&self.outbox, notification("notifications/progress", Some(params)).to_string());
+    }
+}
+
+/// Queue a notification unless the outbox is full, in which case it is
+/// dropped. Returns `false` once the client is gone.
+fn notify(outbox: &mpsc::Sender<String>, message: String) -> bool {
+    match outbox.try_send(message) {
+        Ok(()) => true,
+        Err(mpsc::error::TrySendError::Full(_)) => {
+            tracing::debug!("MCP session outbox full, dropping notification");
+            true
+        }
+        Err(mpsc::error::TrySendError::Closed(_)) => false,
+    }
+}
+
+fn notification(method: &str, params: Option<serde_json::Value>) -> serde_json::Value {
+    let mut message = serde_json::json!({ "jsonrpc": "2.0", "method": method });
+    if let Some(params) = params {
+        message["params"] = params;
+    }
+    message
+}
+
+/// Relay change events published on Redis to in-process subscribers,
+/// reconnecting whenever the Pub/Sub connection drops.
+pub fn listen_for_changes() -> broadcast::Sender<ProjectEvent> {
+    let (tx, _) = broadcast::channel(256);
+    let sender = tx.clone();
+    tokio::spawn(async move {
+        let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+        loop {
+            match cwa_db::subscribe_events(&redis_url).await {
+                Ok(events) => {
+                    let mut events = std::pin::pin!(events);
+                    while let Some(event) = events.next().await {
+                        let _ = sender.send(event);
+                    }
+                    tracing::debug!("Change event subscription closed, reconnecting");
+                }
+                Err(e) => tracing::debug!(error = %e, "Change event subscription failed"),
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    });
+    tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(project_id: &str, message: WebSocketMessage) -> ProjectEvent {
+        ProjectEvent { project_id: project_id.into(), message }
+    }
+
+    #[test]
+    fn only_subscribed_resources_are_notified() {
+        let (outbox, _rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+        let moved = event("p1", WebSocketMessage::TaskUpdated { task_id: "t1".into(), status: "done".into() });
+        assert!(session.notifications_for(&moved).is_empty());
+
+        session.subscribe("project://kanban-board", "p1", "project://kanban-board");
+        session.subscribe("project://specs", "p1", "project://specs");
+        let sent = session.notifications_for(&moved);
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0]["method"], "notifications/resources/updated");
+        assert_eq!(sent[0]["params"]["uri"], "project://kanban-board");
+
+        let spec = event("p1", WebSocketMessage::SpecUpdated { spec_id: "s1".into() });
+        assert_eq!(session.notifications_for(&spec)[0]["params"]["uri"], "project://specs");
+
+        session.unsubscribe("project://kanban-board");
+        assert!(session.notifications_for(&moved).is_empty());
+    }
+
+    #[test]
+    fn changes_only_reach_subscribers_of_their_project() {
+        let (outbox, _rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), Some("p1".into()));
+        session.subscribe("project://tasks", "p1", "project://tasks");
+        session.subscribe("cwa://billing/tasks", "p2", "project://tasks");
+
+        let here = session.notifications_for(&event("p1", WebSocketMessage::BoardRefresh));
+        assert_eq!(here.len(), 1);
+        assert_eq!(here[0]["params"]["uri"], "project://tasks");
+
+        let there = session.notifications_for(&event("p2", WebSocketMessage::BoardRefresh));
+        assert_eq!(there.len(), 1);
+        assert_eq!(there[0]["params"]["uri"], "cwa://billing/tasks");
+
+        assert!(session.notifications_for(&event("p3", WebSocketMessage::BoardRefresh)).is_empty());
+    }
+
+    #[test]
+    fn client_is_named_by_initialize() {
+        let (outbox, _rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+        session.set_client(&Some(serde_json::json!({ "protocolVersion": "2025-06-18" })));
+        assert_eq!(session.client(), None);
+
+        session.set_client(&Some(serde_json::json!({ "clientInfo": { "name": "claude-code", "version": "1.0.3" } })));
+        assert_eq!(session.client().as_deref(), Some("claude-code 1.0.3"));
+        assert_ne!(session.id(), Session::new(mpsc::channel(OUTBOX_CAPACITY).0, ToolProfile::full(), None).id());
+    }
+
+    #[test]
+    fn tool_changes_reach_every_session() {
+        let (outbox, _rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+        let sent = session.notifications_for(&event("any", WebSocketMessage::ToolsChanged));
+        assert_eq!(sent[0]["method"], "notifications/tools/list_changed");
+        assert!(sent[0].get("params").is_none());
+    }
+
+    #[tokio::test]
+    async fn cancelled_requests_get_no_result() {
+        let (outbox, _rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+        let id = serde_json::json!(7);
+
+        let pending = session.run_cancellable(&id, std::future::pending::<()>());
+        let cancel = async {
+            tokio::task::yield_now().await;
+            session.cancel(&id);
+        };
+        let (result, ()) = tokio::join!(pending, cancel);
+        assert!(result.is_none());
+        assert!(session.in_flight.lock().unwrap().is_empty());
+
+        assert_eq!(session.run_cancellable(&id, async { 42 }).await, Some(42));
+        assert!(session.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_before_the_handler_runs_is_kept() {
+        let (outbox, _rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+        let id = serde_json::json!("early");
+
+        let request = session.register(&id);
+        session.cancel(&id);
+        assert!(session.run(request, async { 42 }).await.is_none());
+        assert!(session.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reused_ids_do_not_unregister_each_other() {
+        let (outbox, _rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+        let id = serde_json::json!(1);
+
+        let slow = session.register(&id);
+        assert_eq!(session.run_cancellable(&id, async { 42 }).await, Some(42));
+        assert_eq!(session.in_flight.lock().unwrap()["1"].len(), 1);
+
+        session.cancel(&id);
+        assert!(session.run(slow, std::future::pending::<()>()).await.is_none());
+        assert!(session.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn progress_is_sent_only_with_a_token() {
+        let (outbox, mut rx) = mpsc::channel(OUTBOX_CAPACITY);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+
+        session.progress(&Some(serde_json::json!({ "name": "cwa_graph_sync" }))).report(1, 8, "Synced specs");
+        assert!(rx.try_recv().is_err());
+
+        let params = Some(serde_json::json!({ "_meta": { "progressToken": "sync-1" } }));
+        session.progress(&params).report(1, 8, "Synced specs");
+        let sent: serde_json::Value = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(sent["method"], "notifications/progress");
+        assert_eq!(sent["params"]["progressToken"], "sync-1");
+        assert_eq!(sent["params"]["total"], 8);
+    }
+
+    #[test]
+    fn notifications_are_dropped_when_the_outbox_is_full() {
+        let (outbox, mut rx) = mpsc::channel(1);
+        let session = Session::new(outbox, ToolProfile::full(), None);
+        let progress = session.progress(&Some(serde_json::json!({ "_meta": { "progressToken": 1 } })));
+        progress.report(1, 2, "first");
+        progress.report(2, 2, "second");
+        assert!(rx.try_recv().unwrap().contains("first"));
+        assert!(rx.try_recv().is_err());
+    }
+}
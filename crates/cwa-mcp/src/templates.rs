@@ -0,0 +1,186 @@
+//! MCP resource templates: individual specs, tasks and bounded contexts.
+//!
+//! The fixed `project://` resources list whole collections; templates such
+//! as `project://spec/{id}` let a client read a single entity, and their
+//! parameters can be filled in with `completion/complete`. Each template
+//! also has a `cwa://{project}/...` form that reads another registered
+//! project.
+
+use cwa_db::DbPool;
+use percent_encoding::percent_decode_str;
+
+use crate::server::JsonRpcError;
+
+/// A parameterized resource.
+pub struct TemplateDef {
+    pub uri_template: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The URI up to the parameter.
+    prefix: &'static str,
+    /// Name of the single parameter.
+    pub parameter: &'static str,
+}
+
+pub const TEMPLATES: &[TemplateDef] = &[
+    TemplateDef {
+        uri_template: "project://spec/{id}",
+        name: "Specification",
+        description: "A specification with its acceptance criteria and dependencies, by ID, ID prefix, or title",
+        prefix: "project://spec/",
+        parameter: "id",
+    },
+    TemplateDef {
+        uri_template: "project://task/{id}",
+        name: "Task",
+        description: "A task with its status, blockers and spec",
+        prefix: "project://task/",
+        parameter: "id",
+    },
+    TemplateDef {
+        uri_template: "project://context/{name}",
+        name: "Bounded Context",
+        description: "A bounded context with its domain objects and glossary terms",
+        prefix: "project://context/",
+        parameter: "name",
+    },
+];
+
+/// Prefix of the project-scoped form of every resource and template.
+pub const SCOPED_PREFIX: &str = "cwa://{project}/";
+
+/// Get the list of resource templates.
+pub fn get_resource_templates_list() -> Result<serde_json::Value, JsonRpcError> {
+    let mut templates: Vec<_> = TEMPLATES
+        .iter()
+        .map(|t| serde_json::json!({
+            "uriTemplate": t.uri_template,
+            "name": t.name,
+            "description": t.description,
+            "mimeType": "application/json"
+        }))
+        .collect();
+    templates.push(serde_json::json!({
+        "uriTemplate": format!("{}{{resource}}", SCOPED_PREFIX),
+        "name": "Project Resource",
+        "description": "Any project:// resource of another registered project, e.g. cwa://billing-api/specs",
+        "mimeType": "application/json"
+    }));
+    templates.extend(TEMPLATES.iter().map(|t| serde_json::json!({
+        "uriTemplate": scoped(t.uri_template),
+        "name": format!("{} (any project)", t.name),
+        "description": format!("{}, in the named project", t.description),
+        "mimeType": "application/json"
+    })));
+
+    Ok(serde_json::json!({ "resourceTemplates": templates }))
+}
+
+/// The project-scoped form of a `project://` URI or template.
+fn scoped(uri: &str) -> String {
+    format!("{}{}", SCOPED_PREFIX, uri.trim_start_matches("project://"))
+}
+
+/// The template with this `uriTemplate`, in either form.
+pub fn find(uri_template: &str) -> Option<&'static TemplateDef> {
+    let local = match uri_template.strip_prefix(SCOPED_PREFIX) {
+        Some(path) => format!("project://{}", path),
+        None => uri_template.to_string(),
+    };
+    TEMPLATES.iter().find(|t| t.uri_template == local)
+}
+
+/// Match a concrete URI against the templates, returning the template and
+/// its percent-decoded parameter.
+pub fn match_uri(uri: &str) -> Option<(&'static TemplateDef, String)> {
+    TEMPLATES.iter().find_map(|t| {
+        let value = uri.strip_prefix(t.prefix)?;
+        if value.is_empty() || value.contains('/') {
+            return None;
+        }
+        let value = percent_decode_str(value).decode_utf8().ok()?;
+        Some((t, value.into_owned()))
+    })
+}
+
+/// Read the entity a template URI names, as pretty JSON.
+pub async fn read(
+    pool: &DbPool,
+    project_id: &str,
+    template: &TemplateDef,
+    value: &str,
+) -> Result<String, JsonRpcError> {
+    let content = match template.prefix {
+        "project://spec/" => {
+            let spec = cwa_core::spec::get_spec(pool, project_id, value).await.map_err(resource_error)?;
+            serde_json::to_value(&spec).unwrap()
+        }
+        "project://task/" => {
+            let task = cwa_core::task::get_task(pool, project_id, value).await.map_err(resource_error)?;
+            serde_json::to_value(&task).unwrap()
+        }
+        _ => {
+            let context = cwa_core::domain::get_context_by_name(pool, project_id, value)
+                .await
+                .map_err(resource_error)?
+                .ok_or_else(|| resource_error(cwa_core::CwaError::ContextNotFound(value.to_string())))?;
+            let objects = cwa_core::domain::list_domain_objects(pool, project_id, &context.id)
+                .await
+                .map_err(resource_error)?;
+            let glossary: Vec<_> = cwa_core::domain::list_glossary(pool, project_id)
+                .await
+                .map_err(resource_error)?
+                .into_iter()
+                .filter(|t| t.context_id.as_deref() == Some(context.id.as_str()))
+                .collect();
+            serde_json::json!({
+                "context": context,
+                "objects": objects,
+                "glossary": glossary
+            })
+        }
+    };
+
+    Ok(serde_json::to_string_pretty(&content).unwrap())
+}
+
+/// Missing entities are invalid params; anything else is an internal error.
+fn resource_error(e: cwa_core::CwaError) -> JsonRpcError {
+    let code = match e {
+        cwa_core::CwaError::SpecNotFound(_)
+        | cwa_core::CwaError::TaskNotFound(_)
+        | cwa_core::CwaError::ContextNotFound(_) => -32602,
+        _ => -32603,
+    };
+    JsonRpcError {
+        code,
+        message: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uris_match_their_template() {
+        let (template, value) = match_uri("project://spec/3f2a").unwrap();
+        assert_eq!(template.uri_template, "project://spec/{id}");
+        assert_eq!(value, "3f2a");
+
+        let (template, value) = match_uri("project://context/Order%20Management").unwrap();
+        assert_eq!(template.parameter, "name");
+        assert_eq!(value, "Order Management");
+
+        assert!(match_uri("project://spec/").is_none());
+        assert!(match_uri("project://specs").is_none());
+        assert!(match_uri("project://task/a/b").is_none());
+        assert!(find("project://task/{id}").is_some());
+        assert_eq!(find("cwa://{project}/context/{name}").unwrap().parameter, "name");
+
+        let listed = get_resource_templates_list().unwrap();
+        let uris: Vec<_> = listed["resourceTemplates"].as_array().unwrap().iter().map(|t| t["uriTemplate"].clone()).collect();
+        assert!(uris.contains(&serde_json::json!("cwa://{project}/spec/{id}")));
+        assert!(uris.contains(&serde_json::json!("cwa://{project}/{resource}")));
+    }
+}
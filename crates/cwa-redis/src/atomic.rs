@@ -0,0 +1,108 @@
+//! Atomic multi-key writes.
+//!
+//! Every mutation that touches an entity hash together with its index sets
+//! goes through a MULTI/EXEC transaction or a Lua script, so a crash or a
+//! concurrent writer can never leave the hash and its indexes disagreeing.
+
+use std::sync::LazyLock;
+
+use redis::{Pipeline, Script};
+
+use crate::client::{RedisPool, RedisResult};
+
+/// Moves an entity between `{prefix}{status}` index sets based on the status
+/// currently stored in its hash, then writes the new `data`/`status` fields.
+///
+/// When KEYS[2] is given and the status actually changed, a `from`/`to`/`at`
+/// entry is appended to that stream in the same step.
+///
+/// KEYS[1] = entity hash, KEYS[2] = optional event stream
+/// ARGV[1] = status index key prefix, ARGV[2] = entity ID,
+/// ARGV[3] = new status, ARGV[4] = serialized entity, ARGV[5] = timestamp
+///
+/// The old index key is derived inside the script, so this relies on a
+/// single Redis node (which is how CWA is deployed).
+static SET_STATUS_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        local old = redis.call('HGET', KEYS[1], 'status')
+        if old and old ~= ARGV[3] then
+            redis.call('SREM', ARGV[1] .. old, ARGV[2])
+        end
+        redis.call('HSET', KEYS[1], 'data', ARGV[4], 'status', ARGV[3])
+        redis.call('SADD', ARGV[1] .. ARGV[3], ARGV[2])
+        if KEYS[2] and old ~= ARGV[3] then
+            redis.call('XADD', KEYS[2], '*', 'from', old or '', 'to', ARGV[3], 'at', ARGV[5])
+        end
+        return old
+        "#,
+    )
+});
+
+/// Start a MULTI/EXEC pipeline.
+///
+/// Commands added to it should be `.ignore()`d; run it with [`commit`].
+pub(crate) fn transaction() -> Pipeline {
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    pipe
+}
+
+/// Execute a transaction built with [`transaction`].
+pub(crate) async fn commit(pool: &RedisPool, pipe: &Pipeline) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    pipe.query_async::<()>(&mut conn).await?;
+    Ok(())
+}
+
+/// Atomically store an entity's `data`/`status` fields and move it to the
+/// matching status index set.
+///
+/// Returns the status the entity had before, if any.
+pub(crate) async fn set_status(
+    pool: &RedisPool,
+    key: &str,
+    status_prefix: &str,
+    id: &str,
+    status: &str,
+    json: &str,
+) -> RedisResult<Option<String>> {
+    let mut conn = pool.clone();
+    let old: Option<String> = SET_STATUS_SCRIPT
+        .key(key)
+        .arg(status_prefix)
+        .arg(id)
+        .arg(status)
+        .arg(json)
+        .arg("")
+        .invoke_async(&mut conn)
+        .await?;
+    Ok(old)
+}
+
+/// Like [`set_status`], additionally appending the transition to the
+/// `events_key` stream when the status changed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn set_status_with_event(
+    pool: &RedisPool,
+    key: &str,
+    events_key: &str,
+    status_prefix: &str,
+    id: &str,
+    status: &str,
+    json: &str,
+    at: &str,
+) -> RedisResult<Option<String>> {
+    let mut conn = pool.clone();
+    let old: Option<String> = SET_STATUS_SCRIPT
+        .key(key)
+        .key(events_key)
+        .arg(status_prefix)
+        .arg(id)
+        .arg(status)
+        .arg(json)
+        .arg(at)
+        .invoke_async(&mut conn)
+        .await?;
+    Ok(old)
+}
@@ -1,8 +1,12 @@
 //! Broadcast channel for real-time WebSocket updates.
 //!
-//! Uses tokio broadcast channel for in-process communication.
-//! Redis PubSub publish is handled separately in queries/observations.rs.
+//! Uses tokio broadcast channel for in-process communication, and Redis
+//! Pub/Sub to carry the same events between processes (CLI, MCP servers and
+//! the web server).
 
+use crate::client::{RedisPool, RedisResult};
+use futures::{Stream, StreamExt};
+use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
@@ -16,6 +20,8 @@ pub enum WebSocketMessage {
     SpecUpdated { spec_id: String },
     /// Request a full board refresh.
     BoardRefresh,
+    /// The set of MCP tools offered to clients changed.
+    ToolsChanged,
 }
 
 /// Type alias for the broadcast sender.
@@ -29,3 +35,46 @@ pub fn create_broadcast_channel() -> BroadcastSender {
     let (tx, _rx) = broadcast::channel(100);
     tx
 }
+
+/// Redis Pub/Sub channel pattern matching the change events of every project.
+pub const EVENTS_PATTERN: &str = "cwa:*:events";
+
+/// Redis Pub/Sub channel carrying a project's change events between processes.
+pub fn events_channel(project_id: &str) -> String {
+    format!("cwa:{}:events", project_id)
+}
+
+/// A change event and the project it happened in.
+#[derive(Clone, Debug)]
+pub struct ProjectEvent {
+    pub project_id: String,
+    pub message: WebSocketMessage,
+}
+
+/// Publish a change event in `project_id` to every subscribed process.
+pub async fn publish_event(pool: &RedisPool, project_id: &str, msg: &WebSocketMessage) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    conn.publish::<_, _, ()>(events_channel(project_id), serde_json::to_string(msg)?).await?;
+    Ok(())
+}
+
+/// Subscribe to change events of every project, published by any process.
+///
+/// Pub/Sub needs a dedicated connection, so this opens one from `redis_url`
+/// rather than using the shared pool. The stream ends when the connection
+/// drops; malformed payloads are skipped.
+pub async fn subscribe_events(redis_url: &str) -> RedisResult<impl Stream<Item = ProjectEvent>> {
+    let client = redis::Client::open(redis_url)?;
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.psubscribe(EVENTS_PATTERN).await?;
+    Ok(pubsub.into_on_message().filter_map(|msg| async move {
+        let project_id = msg
+            .get_channel_name()
+            .strip_prefix("cwa:")?
+            .strip_suffix(":events")?
+            .to_string();
+        let payload: String = msg.get_payload().ok()?;
+        let message = serde_json::from_str(&payload).ok()?;
+        Some(ProjectEvent { project_id, message })
+    }))
+}
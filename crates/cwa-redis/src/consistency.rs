@@ -0,0 +1,377 @@
+//! Index consistency checker.
+//!
+//! Every entity hash (`cwa:{project}:task:{id}`, ...) is mirrored in one or
+//! more index keys (`tasks:all`, `tasks:status:{status}`, `tasks:spec:{id}`,
+//! ...). Writes made before they were atomic, or interrupted by a crash, can
+//! leave the two disagreeing. This module detects that drift and can repair
+//! it, treating the entity hashes as the source of truth.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use redis::AsyncCommands;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::atomic;
+use crate::client::{RedisPool, RedisResult};
+
+/// An entity hash listed by a single "all" index.
+struct Collection {
+    entity: &'static str,
+    /// Hash key segment: `cwa:{project}:{hash}:{id}`.
+    hash: &'static str,
+    /// Index key segment: `cwa:{project}:{index}`.
+    index: &'static str,
+    sorted: bool,
+}
+
+const COLLECTIONS: &[Collection] = &[
+    Collection { entity: "task", hash: "task", index: "tasks:all", sorted: true },
+    Collection { entity: "spec", hash: "spec", index: "specs:all", sorted: true },
+    Collection { entity: "decision", hash: "decision", index: "decisions:all", sorted: true },
+    Collection { entity: "observation", hash: "observation", index: "observations:all", sorted: true },
+    Collection { entity: "summary", hash: "summary", index: "summaries:all", sorted: true },
+    Collection { entity: "memory", hash: "memory", index: "memories:all", sorted: true },
+    Collection { entity: "context", hash: "context", index: "contexts:all", sorted: false },
+    Collection { entity: "term", hash: "term", index: "glossary:all", sorted: false },
+    Collection { entity: "board", hash: "board", index: "boards:all", sorted: false },
+];
+
+/// A set index keyed by one of the entity's fields, e.g. `tasks:status:{status}`.
+struct FieldIndex {
+    hash: &'static str,
+    /// Field of the serialized entity the index is keyed by.
+    field: &'static str,
+    /// Index key segment before the field value.
+    prefix: &'static str,
+    /// Whether the hash mirrors the field outside of `data` (e.g. `status`).
+    mirrored: bool,
+}
+
+const FIELD_INDEXES: &[FieldIndex] = &[
+    FieldIndex { hash: "task", field: "status", prefix: "tasks:status:", mirrored: true },
+    FieldIndex { hash: "task", field: "spec_id", prefix: "tasks:spec:", mirrored: false },
+    FieldIndex { hash: "spec", field: "status", prefix: "specs:status:", mirrored: true },
+    FieldIndex { hash: "domain", field: "context_id", prefix: "domains:ctx:", mirrored: true },
+    FieldIndex { hash: "column", field: "board_id", prefix: "columns:board:", mirrored: true },
+    FieldIndex { hash: "card", field: "column_id", prefix: "cards:column:", mirrored: true },
+];
+
+/// Kind of drift between an entity hash and an index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// The entity exists but the index does not list it.
+    MissingFromIndex,
+    /// The index lists an entity whose hash no longer exists.
+    DanglingEntry,
+    /// The index lists an entity whose field no longer matches the index.
+    StaleEntry,
+    /// The field mirrored on the hash disagrees with the serialized entity.
+    FieldMismatch,
+}
+
+/// A single inconsistency found by [`check_project`].
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexIssue {
+    pub entity: String,
+    pub id: String,
+    /// The index (or hash) key that needs fixing.
+    pub key: String,
+    pub kind: IssueKind,
+    #[serde(skip)]
+    fix: Fix,
+}
+
+impl fmt::Display for IndexIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            IssueKind::MissingFromIndex => "missing from",
+            IssueKind::DanglingEntry => "dangling entry in",
+            IssueKind::StaleEntry => "stale entry in",
+            IssueKind::FieldMismatch => "field out of date on",
+        };
+        write!(f, "{} {}: {} {}", self.entity, self.id, what, self.key)
+    }
+}
+
+/// How to bring the index back in line with the entity hash.
+#[derive(Debug, Clone, PartialEq)]
+enum Fix {
+    Add { member: String, score: Option<i64> },
+    Remove { member: String },
+    SetField { field: String, value: String },
+}
+
+/// Outcome of a consistency check.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsistencyReport {
+    pub project_id: String,
+    /// Number of entity hashes inspected.
+    pub entities_checked: usize,
+    pub issues: Vec<IndexIssue>,
+    /// Whether the issues were repaired.
+    pub repaired: bool,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A serialized entity and the hash fields stored besides `data`.
+type Entity = (Value, BTreeMap<String, String>);
+
+/// Entity hashes and index members of one project, as read from Redis.
+#[derive(Debug, Default)]
+struct Snapshot {
+    /// Hash segment → entity ID → entity.
+    entities: BTreeMap<&'static str, BTreeMap<String, Entity>>,
+    /// Full index key → members.
+    indexes: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Check every index of a project against its entity hashes.
+///
+/// With `repair`, all issues are fixed in a single transaction.
+pub async fn check_project(pool: &RedisPool, project_id: &str, repair: bool) -> RedisResult<ConsistencyReport> {
+    let snapshot = load_snapshot(pool, project_id).await?;
+    let issues = find_issues(project_id, &snapshot);
+
+    if repair && !issues.is_empty() {
+        let mut tx = atomic::transaction();
+        for issue in &issues {
+            match &issue.fix {
+                Fix::Add { member, score: Some(score) } => tx.zadd(&issue.key, member, *score).ignore(),
+                Fix::Add { member, score: None } => tx.sadd(&issue.key, member).ignore(),
+                Fix::Remove { member } if is_sorted_index(project_id, &issue.key) => {
+                    tx.zrem(&issue.key, member).ignore()
+                }
+                Fix::Remove { member } => tx.srem(&issue.key, member).ignore(),
+                Fix::SetField { field, value } => tx.hset(&issue.key, field, value).ignore(),
+            };
+        }
+        atomic::commit(pool, &tx).await?;
+    }
+
+    Ok(ConsistencyReport {
+        project_id: project_id.to_string(),
+        entities_checked: snapshot.entities.values().map(|e| e.len()).sum(),
+        issues,
+        repaired: repair,
+    })
+}
+
+fn is_sorted_index(project_id: &str, key: &str) -> bool {
+    COLLECTIONS
+        .iter()
+        .any(|c| c.sorted && key == format!("cwa:{}:{}", project_id, c.index))
+}
+
+async fn load_snapshot(pool: &RedisPool, project_id: &str) -> RedisResult<Snapshot> {
+    let mut snapshot = Snapshot::default();
+
+    let mut hashes: BTreeSet<&'static str> = COLLECTIONS.iter().map(|c| c.hash).collect();
+    hashes.extend(FIELD_INDEXES.iter().map(|i| i.hash));
+
+    for hash in hashes {
+        let prefix = format!("cwa:{}:{}:", project_id, hash);
+        let mut entities = BTreeMap::new();
+        for key in scan_keys(pool, &format!("{}*", prefix)).await? {
+            // Only `{hash}:{id}` keys; anything nested below an entity is not a hash
+            if key[prefix.len()..].contains(':') {
+                continue;
+            }
+            let mut conn = pool.clone();
+            let mut fields: BTreeMap<String, String> = conn.hgetall(&key).await?;
+            let Some(data) = fields.remove("data") else { continue };
+            let Ok(value) = serde_json::from_str::<Value>(&data) else { continue };
+            entities.insert(key[prefix.len()..].to_string(), (value, fields));
+        }
+        snapshot.entities.insert(hash, entities);
+    }
+
+    for collection in COLLECTIONS {
+        let key = format!("cwa:{}:{}", project_id, collection.index);
+        let mut conn = pool.clone();
+        let members: BTreeSet<String> = if collection.sorted {
+            conn.zrange(&key, 0, -1).await?
+        } else {
+            conn.smembers(&key).await?
+        };
+        snapshot.indexes.insert(key, members);
+    }
+
+    for index in FIELD_INDEXES {
+        for key in scan_keys(pool, &format!("cwa:{}:{}*", project_id, index.prefix)).await? {
+            let mut conn = pool.clone();
+            let members: BTreeSet<String> = conn.smembers(&key).await?;
+            snapshot.indexes.insert(key, members);
+        }
+    }
+
+    Ok(snapshot)
+}
+
+async fn scan_keys(pool: &RedisPool, pattern: &str) -> RedisResult<Vec<String>> {
+    let mut conn = pool.clone();
+    let mut scan: redis::AsyncIter<String> = conn.scan_match(pattern).await?;
+    let mut keys = Vec::new();
+    while let Some(key) = scan.next_item().await {
+        keys.push(key);
+    }
+    Ok(keys)
+}
+
+fn find_issues(project_id: &str, snapshot: &Snapshot) -> Vec<IndexIssue> {
+    let empty_entities = BTreeMap::new();
+    let empty_members = BTreeSet::new();
+    let mut issues = Vec::new();
+
+    for collection in COLLECTIONS {
+        let entities = snapshot.entities.get(collection.hash).unwrap_or(&empty_entities);
+        let key = format!("cwa:{}:{}", project_id, collection.index);
+        let members = snapshot.indexes.get(&key).unwrap_or(&empty_members);
+
+        for (id, (value, _)) in entities {
+            if !members.contains(id) {
+                let score = collection.sorted.then(|| created_at_score(value));
+                issues.push(issue(collection.entity, id, &key, IssueKind::MissingFromIndex, Fix::Add {
+                    member: id.clone(),
+                    score,
+                }));
+            }
+        }
+        for id in members.difference(&entities.keys().cloned().collect()) {
+            issues.push(issue(collection.entity, id, &key, IssueKind::DanglingEntry, Fix::Remove {
+                member: id.clone(),
+            }));
+        }
+    }
+
+    for index in FIELD_INDEXES {
+        let entities = snapshot.entities.get(index.hash).unwrap_or(&empty_entities);
+        let prefix = format!("cwa:{}:{}", project_id, index.prefix);
+
+        for (id, (value, fields)) in entities {
+            let Some(field_value) = value.get(index.field).and_then(Value::as_str) else { continue };
+            let key = format!("{}{}", prefix, field_value);
+            if !snapshot.indexes.get(&key).is_some_and(|m| m.contains(id)) {
+                issues.push(issue(index.hash, id, &key, IssueKind::MissingFromIndex, Fix::Add {
+                    member: id.clone(),
+                    score: None,
+                }));
+            }
+            if index.mirrored && fields.get(index.field).map(String::as_str) != Some(field_value) {
+                let hash_key = format!("cwa:{}:{}:{}", project_id, index.hash, id);
+                issues.push(issue(index.hash, id, &hash_key, IssueKind::FieldMismatch, Fix::SetField {
+                    field: index.field.to_string(),
+                    value: field_value.to_string(),
+                }));
+            }
+        }
+
+        for (key, members) in snapshot.indexes.range(prefix.clone()..) {
+            let Some(indexed_value) = key.strip_prefix(&prefix) else { break };
+            for id in members {
+                let kind = match entities.get(id) {
+                    None => IssueKind::DanglingEntry,
+                    Some((value, _)) if value.get(index.field).and_then(Value::as_str) != Some(indexed_value) => {
+                        IssueKind::StaleEntry
+                    }
+                    Some(_) => continue,
+                };
+                issues.push(issue(index.hash, id, key, kind, Fix::Remove { member: id.clone() }));
+            }
+        }
+    }
+
+    issues
+}
+
+fn issue(entity: &str, id: &str, key: &str, kind: IssueKind, fix: Fix) -> IndexIssue {
+    IndexIssue {
+        entity: entity.to_string(),
+        id: id.to_string(),
+        key: key.to_string(),
+        kind,
+        fix,
+    }
+}
+
+/// Sorted indexes are scored by creation time, in seconds.
+fn created_at_score(value: &Value) -> i64 {
+    value
+        .get("created_at")
+        .and_then(Value::as_str)
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.timestamp())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn task(id: &str, status: &str, spec_id: Option<&str>) -> (String, Entity) {
+        let value = json!({
+            "id": id,
+            "status": status,
+            "spec_id": spec_id,
+            "created_at": "2025-01-01T00:00:00+00:00",
+        });
+        let fields = BTreeMap::from([("status".to_string(), status.to_string())]);
+        (id.to_string(), (value, fields))
+    }
+
+    fn members(ids: &[&str]) -> BTreeSet<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_consistent_project_has_no_issues() {
+        let mut snapshot = Snapshot::default();
+        snapshot.entities.insert("task", BTreeMap::from([task("t1", "todo", Some("s1"))]));
+        snapshot.indexes.insert("cwa:p:tasks:all".into(), members(&["t1"]));
+        snapshot.indexes.insert("cwa:p:tasks:status:todo".into(), members(&["t1"]));
+        snapshot.indexes.insert("cwa:p:tasks:spec:s1".into(), members(&["t1"]));
+
+        assert!(find_issues("p", &snapshot).is_empty());
+    }
+
+    #[test]
+    fn test_detects_task_index_drift() {
+        let mut snapshot = Snapshot::default();
+        let (id, (value, mut fields)) = task("t1", "done", None);
+        fields.insert("status".into(), "in_progress".into());
+        snapshot.entities.insert("task", BTreeMap::from([(id, (value, fields))]));
+        snapshot.indexes.insert("cwa:p:tasks:all".into(), members(&["t1", "gone"]));
+        snapshot.indexes.insert("cwa:p:tasks:status:in_progress".into(), members(&["t1"]));
+
+        let found: Vec<(String, IssueKind)> = find_issues("p", &snapshot)
+            .into_iter()
+            .map(|i| (i.key, i.kind))
+            .collect();
+
+        assert!(found.contains(&("cwa:p:tasks:all".into(), IssueKind::DanglingEntry)));
+        assert!(found.contains(&("cwa:p:tasks:status:done".into(), IssueKind::MissingFromIndex)));
+        assert!(found.contains(&("cwa:p:tasks:status:in_progress".into(), IssueKind::StaleEntry)));
+        assert!(found.contains(&("cwa:p:task:t1".into(), IssueKind::FieldMismatch)));
+        assert_eq!(found.len(), 4);
+    }
+
+    #[test]
+    fn test_missing_sorted_entry_is_scored_by_creation_time() {
+        let mut snapshot = Snapshot::default();
+        snapshot.entities.insert("spec", BTreeMap::from([task("s1", "draft", None)]));
+        snapshot.indexes.insert("cwa:p:specs:status:draft".into(), members(&["s1"]));
+
+        let issues = find_issues("p", &snapshot);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].fix, Fix::Add { member: "s1".into(), score: Some(1735689600) });
+        assert!(is_sorted_index("p", &issues[0].key));
+    }
+}
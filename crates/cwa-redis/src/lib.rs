@@ -3,14 +3,18 @@
 //! Provides async Redis-based persistence for CWA projects.
 //! Replaces the SQLite-based cwa-db crate.
 
+mod atomic;
 pub mod broadcast;
 pub mod client;
+pub mod consistency;
 pub mod queries;
 
 pub use broadcast::{
-    BroadcastReceiver, BroadcastSender, WebSocketMessage, create_broadcast_channel,
+    BroadcastReceiver, BroadcastSender, ProjectEvent, WebSocketMessage, create_broadcast_channel,
+    publish_event, subscribe_events,
 };
 pub use client::{RedisError, RedisPool, RedisResult, init_pool};
+pub use queries::audit;
 pub use queries::boards;
 pub use queries::decisions;
 pub use queries::domains;
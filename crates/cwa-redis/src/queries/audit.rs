@@ -0,0 +1,109 @@
+//! MCP audit log queries — one Redis Stream per project.
+
+use crate::client::{RedisPool, RedisResult};
+use serde::{Deserialize, Serialize};
+
+/// Entries kept per project; older ones are trimmed as new ones arrive.
+const MAX_ENTRIES: usize = 10_000;
+
+/// A recorded MCP tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRow {
+    /// Stream entry ID (empty until recorded).
+    pub id: String,
+    pub tool: String,
+    /// Call arguments as JSON.
+    pub arguments: String,
+    pub status: String,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Client name (and version) from `initialize`.
+    pub client: Option<String>,
+    pub session_id: Option<String>,
+    pub at: String,
+}
+
+/// Key of the append-only stream holding a project's MCP audit log.
+fn audit_key(project_id: &str) -> String {
+    format!("cwa:{}:mcp:audit", project_id)
+}
+
+/// Append an entry to the project's audit log, returning its stream ID.
+pub async fn append_audit_entry(pool: &RedisPool, project_id: &str, row: &AuditRow) -> RedisResult<String> {
+    let mut conn = pool.clone();
+    let duration = row.duration_ms.to_string();
+    let fields = [
+        ("tool", row.tool.as_str()),
+        ("arguments", row.arguments.as_str()),
+        ("status", row.status.as_str()),
+        ("error", row.error.as_deref().unwrap_or("")),
+        ("duration_ms", duration.as_str()),
+        ("client", row.client.as_deref().unwrap_or("")),
+        ("session_id", row.session_id.as_deref().unwrap_or("")),
+        ("at", row.at.as_str()),
+    ];
+    let id: String = redis::cmd("XADD")
+        .arg(audit_key(project_id))
+        .arg("MAXLEN")
+        .arg("~")
+        .arg(MAX_ENTRIES)
+        .arg("*")
+        .arg(&fields)
+        .query_async(&mut conn)
+        .await?;
+    Ok(id)
+}
+
+/// List up to `count` audit entries, newest first, starting after the entry
+/// `before` (or at the newest entry).
+pub async fn list_audit_entries(
+    pool: &RedisPool,
+    project_id: &str,
+    before: Option<&str>,
+    count: usize,
+) -> RedisResult<Vec<AuditRow>> {
+    let mut conn = pool.clone();
+    let end = before.map_or_else(|| "+".to_string(), |id| format!("({}", id));
+    let entries: Vec<(String, Vec<String>)> = redis::cmd("XREVRANGE")
+        .arg(audit_key(project_id))
+        .arg(end)
+        .arg("-")
+        .arg("COUNT")
+        .arg(count)
+        .query_async(&mut conn)
+        .await?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|(id, fields)| parse_entry(id, fields))
+        .collect())
+}
+
+/// Build an entry from a stream entry's flat field/value list.
+fn parse_entry(id: String, fields: Vec<String>) -> Option<AuditRow> {
+    let mut row = AuditRow {
+        id,
+        tool: String::new(),
+        arguments: String::new(),
+        status: String::new(),
+        error: None,
+        duration_ms: 0,
+        client: None,
+        session_id: None,
+        at: String::new(),
+    };
+    for pair in fields.chunks_exact(2) {
+        let value = pair[1].clone();
+        match pair[0].as_str() {
+            "tool" => row.tool = value,
+            "arguments" => row.arguments = value,
+            "status" => row.status = value,
+            "error" => row.error = Some(value).filter(|s| !s.is_empty()),
+            "duration_ms" => row.duration_ms = value.parse().unwrap_or_default(),
+            "client" => row.client = Some(value).filter(|s| !s.is_empty()),
+            "session_id" => row.session_id = Some(value).filter(|s| !s.is_empty()),
+            "at" => row.at = value,
+            _ => {}
+        }
+    }
+    (!row.tool.is_empty()).then_some(row)
+}
@@ -1,6 +1,12 @@
 //! Kanban board queries — Redis implementation.
+//!
+//! Boards are saved views over the project's tasks. Before boards and tasks
+//! were unified, boards owned their own columns, cards and labels; those
+//! legacy keys are only read here so they can be imported as tasks.
 
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
+use crate::queries::tasks::{self, TaskEventRow, TaskRow};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
@@ -10,10 +16,20 @@ pub struct BoardRow {
     pub project_id: String,
     pub name: String,
     pub description: Option<String>,
+    /// Which tasks the board shows; empty shows every task.
+    #[serde(default)]
+    pub filter: BoardFilterRow,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardFilterRow {
+    pub label: Option<String>,
+    pub assignee: Option<String>,
+    pub spec_id: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnRow {
     pub id: String,
@@ -54,6 +70,7 @@ pub async fn create_board(
     project_id: &str,
     name: &str,
     description: Option<&str>,
+    filter: &BoardFilterRow,
 ) -> RedisResult<()> {
     let now = chrono::Utc::now().to_rfc3339();
     let row = BoardRow {
@@ -61,14 +78,20 @@ pub async fn create_board(
         project_id: project_id.to_string(),
         name: name.to_string(),
         description: description.map(str::to_string),
+        filter: filter.clone(),
         created_at: now.clone(),
         updated_at: now,
     };
-    let mut conn = pool.clone();
-    let key = format!("cwa:{}:board:{}", project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+    let mut tx = atomic::transaction();
+    queue_save_board(&mut tx, &row)?;
     let set_key = format!("cwa:{}:boards:all", project_id);
-    conn.sadd::<_, _, ()>(&set_key, id).await?;
+    tx.sadd(&set_key, id).ignore();
+    atomic::commit(pool, &tx).await
+}
+
+fn queue_save_board(tx: &mut redis::Pipeline, row: &BoardRow) -> RedisResult<()> {
+    let key = format!("cwa:{}:board:{}", row.project_id, row.id);
+    tx.hset(&key, "data", serde_json::to_string(row)?).ignore();
     Ok(())
 }
 
@@ -102,375 +125,126 @@ pub async fn list_boards(pool: &RedisPool, project_id: &str) -> RedisResult<Vec<
             }
         }
     }
+    boards.sort_by(|a, b| a.created_at.cmp(&b.created_at));
     Ok(boards)
 }
 
 pub async fn delete_board(pool: &RedisPool, board_id: &str) -> RedisResult<()> {
     let board = get_board(pool, board_id).await?;
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     let key = format!("cwa:{}:board:{}", board.project_id, board_id);
-    conn.del::<_, ()>(&key).await?;
+    tx.del(&key).ignore();
     let set_key = format!("cwa:{}:boards:all", board.project_id);
-    conn.srem::<_, _, ()>(&set_key, board_id).await?;
-    Ok(())
+    tx.srem(&set_key, board_id).ignore();
+    atomic::commit(pool, &tx).await
 }
 
-// ─────────────────────────────── COLUMNS ───────────────────────────────
+// ──────────────────────────── LEGACY CARDS ─────────────────────────────
 
-pub async fn create_column(
+/// Fetch the `data` field of every hash `cwa:{project}:{hash}:{id}` whose
+/// ID is a member of `set_key`.
+async fn load_members<T: for<'de> Deserialize<'de>>(
     pool: &RedisPool,
-    id: &str,
-    board_id: &str,
-    name: &str,
-    position: i32,
-    color: Option<&str>,
-    wip_limit: Option<i32>,
-) -> RedisResult<()> {
-    let board = get_board(pool, board_id).await?;
-    let row = ColumnRow {
-        id: id.to_string(),
-        board_id: board_id.to_string(),
-        name: name.to_string(),
-        position,
-        color: color.map(str::to_string),
-        wip_limit,
-    };
-    let mut conn = pool.clone();
-    let key = format!("cwa:{}:column:{}", board.project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "board_id", board_id).await?;
-    let set_key = format!("cwa:{}:columns:board:{}", board.project_id, board_id);
-    conn.sadd::<_, _, ()>(&set_key, id).await?;
-    Ok(())
-}
-
-pub async fn list_columns(pool: &RedisPool, board_id: &str) -> RedisResult<Vec<ColumnRow>> {
-    let board = get_board(pool, board_id).await?;
+    project_id: &str,
+    set_key: &str,
+    hash: &str,
+) -> RedisResult<Vec<T>> {
     let mut conn = pool.clone();
-    let set_key = format!("cwa:{}:columns:board:{}", board.project_id, board_id);
-    let ids: Vec<String> = conn.smembers(&set_key).await?;
-    let mut columns = Vec::new();
+    let ids: Vec<String> = conn.smembers(set_key).await?;
+    let mut rows = Vec::with_capacity(ids.len());
     for id in ids {
-        let key = format!("cwa:{}:column:{}", board.project_id, id);
-        let mut c = pool.clone();
-        let json: Option<String> = c.hget(&key, "data").await?;
-        if let Some(j) = json {
-            if let Ok(row) = serde_json::from_str::<ColumnRow>(&j) {
-                columns.push(row);
-            }
+        let key = format!("cwa:{}:{}:{}", project_id, hash, id);
+        let json: Option<String> = conn.hget(&key, "data").await?;
+        if let Some(row) = json.and_then(|j| serde_json::from_str(&j).ok()) {
+            rows.push(row);
         }
     }
-    columns.sort_by_key(|c| c.position);
-    Ok(columns)
-}
-
-pub async fn get_column(pool: &RedisPool, column_id: &str) -> RedisResult<ColumnRow> {
-    let mut conn = pool.clone();
-    let pattern = format!("cwa:*:column:{}", column_id);
-    let mut scan: redis::AsyncIter<String> = conn.scan_match(&pattern).await?;
-    if let Some(key) = scan.next_item().await {
-        drop(scan);
-        let mut c = pool.clone();
-        let json: Option<String> = c.hget(&key, "data").await?;
-        if let Some(j) = json {
-            return Ok(serde_json::from_str(&j)?);
-        }
-    }
-    Err(RedisError::NotFound(format!("Column not found: {}", column_id)))
-}
-
-pub async fn count_cards_in_column(pool: &RedisPool, column_id: &str) -> RedisResult<i32> {
-    let col = get_column(pool, column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-    let mut conn = pool.clone();
-    let set_key = format!("cwa:{}:cards:column:{}", board.project_id, column_id);
-    let count: i64 = conn.scard(&set_key).await?;
-    Ok(count as i32)
+    Ok(rows)
 }
 
-pub async fn delete_column(pool: &RedisPool, column_id: &str) -> RedisResult<()> {
-    let col = get_column(pool, column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-    let mut conn = pool.clone();
-    let key = format!("cwa:{}:column:{}", board.project_id, column_id);
-    conn.del::<_, ()>(&key).await?;
-    let set_key = format!("cwa:{}:columns:board:{}", board.project_id, col.board_id);
-    conn.srem::<_, _, ()>(&set_key, column_id).await?;
-    Ok(())
+/// Columns of a board created before boards were unified with tasks,
+/// ordered by position.
+pub async fn list_legacy_columns(
+    pool: &RedisPool,
+    project_id: &str,
+    board_id: &str,
+) -> RedisResult<Vec<ColumnRow>> {
+    let set_key = format!("cwa:{}:columns:board:{}", project_id, board_id);
+    let mut columns: Vec<ColumnRow> = load_members(pool, project_id, &set_key, "column").await?;
+    columns.sort_by_key(|c| c.position);
+    Ok(columns)
 }
 
-// ─────────────────────────────── CARDS ─────────────────────────────────
-
-pub async fn create_card(
+/// Cards of a legacy column, ordered by position.
+pub async fn list_legacy_cards(
     pool: &RedisPool,
-    id: &str,
+    project_id: &str,
     column_id: &str,
-    title: &str,
-    description: Option<&str>,
-    position: i32,
-    priority: Option<&str>,
-    due_date: Option<&str>,
-) -> RedisResult<()> {
-    let col = get_column(pool, column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-    let now = chrono::Utc::now().to_rfc3339();
-    let row = CardRow {
-        id: id.to_string(),
-        column_id: column_id.to_string(),
-        title: title.to_string(),
-        description: description.map(str::to_string),
-        position,
-        priority: priority.map(str::to_string),
-        due_date: due_date.map(str::to_string),
-        created_at: now.clone(),
-        updated_at: now,
-        completed_at: None,
-    };
-    let mut conn = pool.clone();
-    let key = format!("cwa:{}:card:{}", board.project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "column_id", column_id).await?;
-    let set_key = format!("cwa:{}:cards:column:{}", board.project_id, column_id);
-    conn.sadd::<_, _, ()>(&set_key, id).await?;
-    Ok(())
-}
-
-pub async fn get_card(pool: &RedisPool, card_id: &str) -> RedisResult<CardRow> {
-    let mut conn = pool.clone();
-    let pattern = format!("cwa:*:card:{}", card_id);
-    let mut scan: redis::AsyncIter<String> = conn.scan_match(&pattern).await?;
-    if let Some(key) = scan.next_item().await {
-        drop(scan);
-        let mut c = pool.clone();
-        let json: Option<String> = c.hget(&key, "data").await?;
-        if let Some(j) = json {
-            return Ok(serde_json::from_str(&j)?);
-        }
-    }
-    Err(RedisError::NotFound(format!("Card not found: {}", card_id)))
-}
-
-pub async fn list_cards_in_column(pool: &RedisPool, column_id: &str) -> RedisResult<Vec<CardRow>> {
-    let col = get_column(pool, column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-    let mut conn = pool.clone();
-    let set_key = format!("cwa:{}:cards:column:{}", board.project_id, column_id);
-    let ids: Vec<String> = conn.smembers(&set_key).await?;
-    let mut cards = Vec::new();
-    for id in ids {
-        let key = format!("cwa:{}:card:{}", board.project_id, id);
-        let mut c = pool.clone();
-        let json: Option<String> = c.hget(&key, "data").await?;
-        if let Some(j) = json {
-            if let Ok(row) = serde_json::from_str::<CardRow>(&j) {
-                cards.push(row);
-            }
-        }
-    }
+) -> RedisResult<Vec<CardRow>> {
+    let set_key = format!("cwa:{}:cards:column:{}", project_id, column_id);
+    let mut cards: Vec<CardRow> = load_members(pool, project_id, &set_key, "card").await?;
     cards.sort_by_key(|c| c.position);
     Ok(cards)
 }
 
-pub async fn move_card(
+/// Labels attached to a legacy card.
+pub async fn list_legacy_card_labels(
     pool: &RedisPool,
+    project_id: &str,
     card_id: &str,
-    new_column_id: &str,
-    new_position: i32,
-) -> RedisResult<String> {
-    let mut card = get_card(pool, card_id).await?;
-    let old_column_id = card.column_id.clone();
-
-    // Find project_id
-    let col = get_column(pool, new_column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-    let project_id = &board.project_id;
-
-    let old_set = format!("cwa:{}:cards:column:{}", project_id, old_column_id);
-    let new_set = format!("cwa:{}:cards:column:{}", project_id, new_column_id);
-
-    let mut conn = pool.clone();
-    conn.srem::<_, _, ()>(&old_set, card_id).await?;
-    conn.sadd::<_, _, ()>(&new_set, card_id).await?;
-
-    card.column_id = new_column_id.to_string();
-    card.position = new_position;
-    card.updated_at = chrono::Utc::now().to_rfc3339();
-
-    let key = format!("cwa:{}:card:{}", project_id, card_id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&card)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "column_id", new_column_id).await?;
-
-    Ok(old_column_id)
+) -> RedisResult<Vec<LabelRow>> {
+    let set_key = format!("cwa:{}:card_labels:{}", project_id, card_id);
+    load_members(pool, project_id, &set_key, "label").await
 }
 
-pub async fn update_card(
+/// Atomically replace a board's legacy columns, cards and labels with the
+/// given tasks and store the board's new definition.
+///
+/// `events` are appended to the tasks' histories in order; `tasks` and
+/// `events` are expected not to exist yet.
+pub async fn import_legacy_board(
     pool: &RedisPool,
-    card_id: &str,
-    title: &str,
-    description: Option<&str>,
-    priority: Option<&str>,
-    due_date: Option<&str>,
+    board: &BoardRow,
+    tasks: &[TaskRow],
+    events: &[TaskEventRow],
 ) -> RedisResult<()> {
-    let mut card = get_card(pool, card_id).await?;
-    let col = get_column(pool, &card.column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-
-    card.title = title.to_string();
-    card.description = description.map(str::to_string);
-    card.priority = priority.map(str::to_string);
-    card.due_date = due_date.map(str::to_string);
-    card.updated_at = chrono::Utc::now().to_rfc3339();
-
+    let project_id = board.project_id.as_str();
     let mut conn = pool.clone();
-    let key = format!("cwa:{}:card:{}", board.project_id, card_id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&card)?).await?;
-    Ok(())
-}
-
-pub async fn complete_card(pool: &RedisPool, card_id: &str) -> RedisResult<()> {
-    let mut card = get_card(pool, card_id).await?;
-    let col = get_column(pool, &card.column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-
-    let now = chrono::Utc::now().to_rfc3339();
-    card.completed_at = Some(now.clone());
-    card.updated_at = now;
-
-    let mut conn = pool.clone();
-    let key = format!("cwa:{}:card:{}", board.project_id, card_id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&card)?).await?;
-    Ok(())
-}
 
-pub async fn delete_card(pool: &RedisPool, card_id: &str) -> RedisResult<()> {
-    let card = get_card(pool, card_id).await?;
-    let col = get_column(pool, &card.column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
+    let columns_key = format!("cwa:{}:columns:board:{}", project_id, board.id);
+    let labels_key = format!("cwa:{}:labels:board:{}", project_id, board.id);
+    let column_ids: Vec<String> = conn.smembers(&columns_key).await?;
+    let label_ids: Vec<String> = conn.smembers(&labels_key).await?;
 
-    let mut conn = pool.clone();
-    let key = format!("cwa:{}:card:{}", board.project_id, card_id);
-    conn.del::<_, ()>(&key).await?;
-    let set_key = format!("cwa:{}:cards:column:{}", board.project_id, card.column_id);
-    conn.srem::<_, _, ()>(&set_key, card_id).await?;
-    Ok(())
-}
-
-pub async fn reorder_cards(
-    pool: &RedisPool,
-    column_id: &str,
-    card_ids: &[String],
-) -> RedisResult<()> {
-    let col = get_column(pool, column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-
-    for (pos, card_id) in card_ids.iter().enumerate() {
-        let key = format!("cwa:{}:card:{}", board.project_id, card_id);
-        let mut c = pool.clone();
-        let json: Option<String> = c.hget(&key, "data").await?;
-        if let Some(j) = json {
-            if let Ok(mut card) = serde_json::from_str::<CardRow>(&j) {
-                card.position = pos as i32;
-                c.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&card)?).await?;
-            }
+    let mut legacy_keys = vec![columns_key, labels_key];
+    for column_id in &column_ids {
+        let cards_key = format!("cwa:{}:cards:column:{}", project_id, column_id);
+        let card_ids: Vec<String> = conn.smembers(&cards_key).await?;
+        for card_id in card_ids {
+            legacy_keys.push(format!("cwa:{}:card:{}", project_id, card_id));
+            legacy_keys.push(format!("cwa:{}:card_labels:{}", project_id, card_id));
         }
+        legacy_keys.push(cards_key);
+        legacy_keys.push(format!("cwa:{}:column:{}", project_id, column_id));
     }
-    Ok(())
-}
-
-pub async fn next_card_position(pool: &RedisPool, column_id: &str) -> RedisResult<i32> {
-    let count = count_cards_in_column(pool, column_id).await?;
-    Ok(count)
-}
-
-// ─────────────────────────────── LABELS ────────────────────────────────
-
-pub async fn create_label(
-    pool: &RedisPool,
-    id: &str,
-    board_id: &str,
-    name: &str,
-    color: &str,
-) -> RedisResult<()> {
-    let board = get_board(pool, board_id).await?;
-    let row = LabelRow {
-        id: id.to_string(),
-        board_id: board_id.to_string(),
-        name: name.to_string(),
-        color: color.to_string(),
-    };
-    let mut conn = pool.clone();
-    let key = format!("cwa:{}:label:{}", board.project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
-    let set_key = format!("cwa:{}:labels:board:{}", board.project_id, board_id);
-    conn.sadd::<_, _, ()>(&set_key, id).await?;
-    Ok(())
-}
-
-pub async fn list_labels(pool: &RedisPool, board_id: &str) -> RedisResult<Vec<LabelRow>> {
-    let board = get_board(pool, board_id).await?;
-    let mut conn = pool.clone();
-    let set_key = format!("cwa:{}:labels:board:{}", board.project_id, board_id);
-    let ids: Vec<String> = conn.smembers(&set_key).await?;
-    let mut labels = Vec::new();
-    for id in ids {
-        let key = format!("cwa:{}:label:{}", board.project_id, id);
-        let mut c = pool.clone();
-        let json: Option<String> = c.hget(&key, "data").await?;
-        if let Some(j) = json {
-            if let Ok(row) = serde_json::from_str::<LabelRow>(&j) {
-                labels.push(row);
-            }
-        }
+    for label_id in &label_ids {
+        legacy_keys.push(format!("cwa:{}:label:{}", project_id, label_id));
     }
-    Ok(labels)
-}
-
-pub async fn get_card_labels(pool: &RedisPool, card_id: &str) -> RedisResult<Vec<LabelRow>> {
-    let card = get_card(pool, card_id).await?;
-    let col = get_column(pool, &card.column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
 
-    let mut conn = pool.clone();
-    let set_key = format!("cwa:{}:card_labels:{}", board.project_id, card_id);
-    let label_ids: Vec<String> = conn.smembers(&set_key).await?;
-    let mut labels = Vec::new();
-    for lid in label_ids {
-        let key = format!("cwa:{}:label:{}", board.project_id, lid);
-        let mut c = pool.clone();
-        let json: Option<String> = c.hget(&key, "data").await?;
-        if let Some(j) = json {
-            if let Ok(row) = serde_json::from_str::<LabelRow>(&j) {
-                labels.push(row);
-            }
+    let mut tx = atomic::transaction();
+    for row in tasks {
+        let score = chrono::DateTime::parse_from_rfc3339(&row.created_at)
+            .map_or_else(|_| chrono::Utc::now().timestamp(), |d| d.timestamp());
+        tasks::queue_save_task(&mut tx, row, score)?;
+    }
+    for event in events {
+        if let Some(row) = tasks.iter().find(|t| t.id == event.task_id) {
+            tasks::queue_task_event(&mut tx, row, event.from.as_deref(), &event.to, &event.at);
         }
     }
-    Ok(labels)
-}
-
-pub async fn add_label_to_card(
-    pool: &RedisPool,
-    card_id: &str,
-    label_id: &str,
-) -> RedisResult<()> {
-    let card = get_card(pool, card_id).await?;
-    let col = get_column(pool, &card.column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-    let mut conn = pool.clone();
-    let set_key = format!("cwa:{}:card_labels:{}", board.project_id, card_id);
-    conn.sadd::<_, _, ()>(&set_key, label_id).await?;
-    Ok(())
-}
-
-pub async fn remove_label_from_card(
-    pool: &RedisPool,
-    card_id: &str,
-    label_id: &str,
-) -> RedisResult<()> {
-    let card = get_card(pool, card_id).await?;
-    let col = get_column(pool, &card.column_id).await?;
-    let board = get_board(pool, &col.board_id).await?;
-    let mut conn = pool.clone();
-    let set_key = format!("cwa:{}:card_labels:{}", board.project_id, card_id);
-    conn.srem::<_, _, ()>(&set_key, label_id).await?;
-    Ok(())
+    for key in &legacy_keys {
+        tx.del(key).ignore();
+    }
+    queue_save_board(&mut tx, board)?;
+    atomic::commit(pool, &tx).await
 }
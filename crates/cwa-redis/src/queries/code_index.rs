@@ -0,0 +1,49 @@
+//! Codebase index state — Redis implementation.
+//!
+//! `cwa index` records every indexed file in `cwa:<project>:code:files`
+//! (path → [`IndexedFileRow`]), so later runs re-embed only files whose git
+//! blob hash changed and drop the chunks of deleted files.
+
+use std::collections::HashMap;
+
+use crate::client::{RedisPool, RedisResult};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedFileRow {
+    /// Git blob hash of the indexed content.
+    pub blob: String,
+    pub language: String,
+    /// Number of chunks stored for the file.
+    pub chunks: usize,
+    pub indexed_at: String,
+}
+
+fn files_key(project_id: &str) -> String {
+    format!("cwa:{}:code:files", project_id)
+}
+
+/// Indexed files of a project, by path relative to the project root.
+pub async fn list_indexed_files(pool: &RedisPool, project_id: &str) -> RedisResult<HashMap<String, IndexedFileRow>> {
+    let mut conn = pool.clone();
+    let entries: HashMap<String, String> = conn.hgetall(files_key(project_id)).await?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|(path, json)| serde_json::from_str(&json).ok().map(|row| (path, row)))
+        .collect())
+}
+
+/// Record a file as indexed.
+pub async fn set_indexed_file(pool: &RedisPool, project_id: &str, path: &str, row: &IndexedFileRow) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    conn.hset::<_, _, _, ()>(files_key(project_id), path, serde_json::to_string(row)?).await?;
+    Ok(())
+}
+
+/// Forget an indexed file.
+pub async fn remove_indexed_file(pool: &RedisPool, project_id: &str, path: &str) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    conn.hdel::<_, _, ()>(files_key(project_id), path).await?;
+    Ok(())
+}
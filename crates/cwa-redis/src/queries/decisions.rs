@@ -1,5 +1,6 @@
 //! Architectural Decision Record (ADR) queries — Redis implementation.
 
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
@@ -44,14 +45,14 @@ pub async fn create_decision(
         updated_at: now.to_rfc3339(),
     };
 
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     let key = format!("cwa:{}:decision:{}", project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+    tx.hset(&key, "data", serde_json::to_string(&row)?).ignore();
 
     let zkey = format!("cwa:{}:decisions:all", project_id);
-    conn.zadd::<_, _, _, ()>(&zkey, id, now.timestamp()).await?;
+    tx.zadd(&zkey, id, now.timestamp()).ignore();
 
-    Ok(())
+    atomic::commit(pool, &tx).await
 }
 
 pub async fn get_decision(pool: &RedisPool, decision_id: &str) -> RedisResult<DecisionRow> {
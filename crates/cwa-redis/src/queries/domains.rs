@@ -1,6 +1,8 @@
 //! Domain model queries (bounded contexts, domain objects) — Redis implementation.
 
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
+use crate::queries::lexical;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
@@ -52,12 +54,12 @@ pub async fn create_context(
         created_at: now.clone(),
         updated_at: now,
     };
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     let key = format!("cwa:{}:context:{}", project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+    tx.hset(&key, "data", serde_json::to_string(&row)?).ignore();
     let set_key = format!("cwa:{}:contexts:all", project_id);
-    conn.sadd::<_, _, ()>(&set_key, id).await?;
-    Ok(())
+    tx.sadd(&set_key, id).ignore();
+    atomic::commit(pool, &tx).await
 }
 
 pub async fn get_context(pool: &RedisPool, context_id: &str) -> RedisResult<BoundedContextRow> {
@@ -136,15 +138,16 @@ pub async fn create_domain_object(
         updated_at: now,
     };
 
-    let mut conn = pool.clone();
+    let json = serde_json::to_string(&row)?;
+    let mut tx = atomic::transaction();
     let key = format!("cwa:{}:domain:{}", project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "context_id", context_id).await?;
+    tx.hset_multiple(&key, &[("data", json.as_str()), ("context_id", context_id)]).ignore();
 
     let ctx_key = format!("cwa:{}:domains:ctx:{}", project_id, context_id);
-    conn.sadd::<_, _, ()>(&ctx_key, id).await?;
+    tx.sadd(&ctx_key, id).ignore();
 
-    Ok(())
+    atomic::commit(pool, &tx).await?;
+    lexical::index_domain_object(pool, &project_id, &row, &ctx.name).await
 }
 
 pub async fn list_domain_objects(
@@ -0,0 +1,80 @@
+//! Embedding queue — Redis implementation.
+//!
+//! Memories and observations are stored before they are embedded. Until
+//! their vector reaches Qdrant they are listed in a per-project
+//! `embedding_pending` set as `<kind>:<id>`.
+//!
+//! Computed vectors are cached under `cwa:embedding_cache:<model>:<hash>`,
+//! keyed by the SHA-256 of the embedded text, so unchanged content is never
+//! sent to the provider twice. The cache is shared by all projects.
+
+use crate::client::{RedisPool, RedisResult};
+use redis::AsyncCommands;
+
+/// Cached vectors expire after 90 days without being rewritten.
+const CACHE_TTL_SECS: u64 = 90 * 24 * 3600;
+
+fn cache_key(model: &str, hash: &str) -> String {
+    format!("cwa:embedding_cache:{}:{}", model, hash)
+}
+
+fn pending_key(project_id: &str) -> String {
+    format!("cwa:{}:embedding_pending", project_id)
+}
+
+/// Queue an entity (`memory` or `observation`) for embedding.
+pub async fn mark_pending(pool: &RedisPool, project_id: &str, kind: &str, id: &str) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    conn.sadd::<_, _, ()>(pending_key(project_id), format!("{}:{}", kind, id)).await?;
+    Ok(())
+}
+
+/// Remove an entity from the queue once embedded (or deleted).
+pub async fn clear_pending(pool: &RedisPool, project_id: &str, kind: &str, id: &str) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    conn.srem::<_, _, ()>(pending_key(project_id), format!("{}:{}", kind, id)).await?;
+    Ok(())
+}
+
+/// Queued entities as `(kind, id)` pairs.
+pub async fn list_pending(pool: &RedisPool, project_id: &str) -> RedisResult<Vec<(String, String)>> {
+    let mut conn = pool.clone();
+    let members: Vec<String> = conn.smembers(pending_key(project_id)).await?;
+    let mut pending: Vec<(String, String)> = members
+        .into_iter()
+        .filter_map(|m| m.split_once(':').map(|(k, id)| (k.to_string(), id.to_string())))
+        .collect();
+    pending.sort();
+    Ok(pending)
+}
+
+/// Number of queued entities.
+pub async fn count_pending(pool: &RedisPool, project_id: &str) -> RedisResult<usize> {
+    let mut conn = pool.clone();
+    Ok(conn.scard(pending_key(project_id)).await?)
+}
+
+/// Cached vectors of `model` for the given content hashes, in order.
+pub async fn get_cached(pool: &RedisPool, model: &str, hashes: &[String]) -> RedisResult<Vec<Option<Vec<u8>>>> {
+    if hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut conn = pool.clone();
+    let keys: Vec<String> = hashes.iter().map(|h| cache_key(model, h)).collect();
+    // `get` with a single key sends GET, so build the MGET explicitly.
+    Ok(redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?)
+}
+
+/// Cache vectors of `model` as `(content hash, bytes)` pairs.
+pub async fn put_cached(pool: &RedisPool, model: &str, entries: &[(String, Vec<u8>)]) -> RedisResult<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut conn = pool.clone();
+    let mut pipe = redis::pipe();
+    for (hash, bytes) in entries {
+        pipe.set_ex(cache_key(model, hash), bytes.as_slice(), CACHE_TTL_SECS).ignore();
+    }
+    pipe.query_async::<()>(&mut conn).await?;
+    Ok(())
+}
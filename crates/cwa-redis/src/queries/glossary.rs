@@ -1,6 +1,8 @@
 //! Glossary term queries — Redis implementation.
 
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
+use crate::queries::lexical;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
@@ -47,13 +49,22 @@ pub async fn create_glossary_term(
         created_at: now.clone(),
         updated_at: now,
     };
-    let mut conn = pool.clone();
+    let json = serde_json::to_string(&row)?;
     let key = format!("cwa:{}:term:{}", project_id, slug);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "id", id).await?;
+    // Redefining a term replaces it, so drop the old definition from the index.
+    let mut conn = pool.clone();
+    let old_id: Option<String> = conn.hget(&key, "id").await?;
+
+    let mut tx = atomic::transaction();
+    tx.hset_multiple(&key, &[("data", json.as_str()), ("id", id)]).ignore();
     let set_key = format!("cwa:{}:glossary:all", project_id);
-    conn.sadd::<_, _, ()>(&set_key, &slug).await?;
-    Ok(())
+    tx.sadd(&set_key, &slug).ignore();
+    atomic::commit(pool, &tx).await?;
+
+    if let Some(old_id) = old_id.filter(|old| old != id) {
+        lexical::remove_document(pool, project_id, lexical::TERMS, &old_id).await?;
+    }
+    lexical::index_term(pool, &row).await
 }
 
 pub async fn list_glossary(pool: &RedisPool, project_id: &str) -> RedisResult<Vec<GlossaryTermRow>> {
@@ -0,0 +1,395 @@
+//! Lexical (BM25) index — Redis implementation.
+//!
+//! Memories, observations, glossary terms and domain objects are tokenized
+//! when they are written (code chunks when `cwa index` stores them) and
+//! kept in a per-project inverted index, so
+//! hybrid search can match exact identifiers and error strings that dense
+//! vectors miss. Each collection (named like its Qdrant counterpart) uses:
+//!
+//! - `cwa:<project>:lexical:<collection>:term:<token>` — HASH document id → term frequency
+//! - `cwa:<project>:lexical:<collection>:doc:<id>` — HASH token → term frequency
+//! - `cwa:<project>:lexical:<collection>:len` — HASH document id → length in tokens
+//! - `cwa:<project>:lexical:<collection>:total` — total length of all documents
+//! - `cwa:<project>:lexical:<collection>:payload` — HASH document id → search payload
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use redis::{AsyncCommands, Script};
+
+use crate::client::{RedisPool, RedisResult};
+use crate::queries::domains::DomainObjectRow;
+use crate::queries::glossary::GlossaryTermRow;
+use crate::queries::memory::MemoryRow;
+use crate::queries::observations::ObservationRow;
+
+/// Index of memories (same name as the Qdrant collection).
+pub const MEMORIES: &str = "cwa_memories";
+
+/// Index of observations.
+pub const OBSERVATIONS: &str = "cwa_observations";
+
+/// Index of glossary terms.
+pub const TERMS: &str = "cwa_terms";
+
+/// Index of domain objects.
+pub const DOMAIN_OBJECTS: &str = "cwa_domain_objects";
+
+/// Index of source code chunks.
+pub const FILES: &str = "cwa_files";
+
+/// Every lexical index.
+pub const COLLECTIONS: &[&str] = &[MEMORIES, OBSERVATIONS, TERMS, DOMAIN_OBJECTS, FILES];
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+
+/// BM25 document-length normalization.
+const B: f64 = 0.75;
+
+/// Words too common to be worth indexing.
+const STOPWORDS: &[&str] = &[
+    "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "in", "is", "it", "of", "on",
+    "or", "that", "the", "this", "to", "was", "were", "will", "with",
+];
+
+/// Replaces a document's postings.
+///
+/// The old postings are read from the document's own hash and removed
+/// before the new ones are written, so updates never leave stale terms.
+///
+/// ARGV[1] = key prefix, ARGV[2] = document ID, ARGV[3] = payload ('' to
+/// remove the document), ARGV[4] = length, ARGV[5..] = token, frequency pairs
+static REPLACE_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        local prefix, id = ARGV[1], ARGV[2]
+        local doc = prefix .. 'doc:' .. id
+        local old = redis.call('HGETALL', doc)
+        for i = 1, #old, 2 do
+            redis.call('HDEL', prefix .. 'term:' .. old[i], id)
+        end
+        redis.call('DEL', doc)
+        local old_len = redis.call('HGET', prefix .. 'len', id)
+        if old_len then
+            redis.call('INCRBY', prefix .. 'total', -tonumber(old_len))
+            redis.call('HDEL', prefix .. 'len', id)
+            redis.call('HDEL', prefix .. 'payload', id)
+        end
+        if ARGV[3] ~= '' then
+            for i = 5, #ARGV, 2 do
+                redis.call('HSET', prefix .. 'term:' .. ARGV[i], id, ARGV[i + 1])
+                redis.call('HSET', doc, ARGV[i], ARGV[i + 1])
+            end
+            redis.call('HSET', prefix .. 'len', id, ARGV[4])
+            redis.call('INCRBY', prefix .. 'total', tonumber(ARGV[4]))
+            redis.call('HSET', prefix .. 'payload', id, ARGV[3])
+        end
+        return 0
+        "#,
+    )
+});
+
+/// A document matching a lexical query.
+#[derive(Debug, Clone)]
+pub struct LexicalHit {
+    pub id: String,
+    /// BM25 score (unbounded, higher is better).
+    pub score: f64,
+    pub payload: serde_json::Value,
+}
+
+fn prefix(project_id: &str, collection: &str) -> String {
+    format!("cwa:{}:lexical:{}:", project_id, collection)
+}
+
+/// Split text into lowercase tokens.
+///
+/// Identifiers survive whole (`get_user_by_id`, `E0382`), and snake_case
+/// identifiers are also indexed by their parts.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let keep = |t: &str| t.chars().count() > 1 && !STOPWORDS.contains(&t);
+    let mut tokens = Vec::new();
+    for word in text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map(|w| w.trim_matches('_').to_lowercase())
+        .filter(|w| !w.is_empty())
+    {
+        if word.contains('_') {
+            tokens.extend(word.split('_').filter(|p| keep(p)).map(str::to_string));
+        }
+        if keep(&word) {
+            tokens.push(word);
+        }
+    }
+    tokens
+}
+
+/// BM25 contribution of one query term to one document.
+pub fn bm25(term_freq: f64, doc_freq: usize, docs: usize, doc_len: f64, avg_len: f64) -> f64 {
+    let idf = (1.0 + (docs as f64 - doc_freq as f64 + 0.5) / (doc_freq as f64 + 0.5)).ln();
+    let norm = 1.0 - B + B * doc_len / avg_len.max(1.0);
+    idf * term_freq * (K1 + 1.0) / (term_freq + K1 * norm)
+}
+
+/// Add or replace a document in a project's lexical index.
+pub async fn index_document(
+    pool: &RedisPool,
+    project_id: &str,
+    collection: &str,
+    id: &str,
+    text: &str,
+    payload: &serde_json::Value,
+) -> RedisResult<()> {
+    let tokens = tokenize(text);
+    let mut freqs: HashMap<String, u32> = HashMap::new();
+    for token in &tokens {
+        *freqs.entry(token.clone()).or_default() += 1;
+    }
+
+    let mut invocation = REPLACE_SCRIPT.prepare_invoke();
+    invocation
+        .arg(prefix(project_id, collection))
+        .arg(id)
+        .arg(serde_json::to_string(payload)?)
+        .arg(tokens.len());
+    for (token, freq) in &freqs {
+        invocation.arg(token).arg(*freq);
+    }
+
+    let mut conn = pool.clone();
+    invocation.invoke_async::<()>(&mut conn).await?;
+    Ok(())
+}
+
+/// Remove a document from a project's lexical index.
+pub async fn remove_document(pool: &RedisPool, project_id: &str, collection: &str, id: &str) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    REPLACE_SCRIPT
+        .arg(prefix(project_id, collection))
+        .arg(id)
+        .arg("")
+        .arg(0)
+        .invoke_async::<()>(&mut conn)
+        .await?;
+    Ok(())
+}
+
+/// Drop a project's lexical index for `collection`, before rebuilding it.
+pub async fn clear_index(pool: &RedisPool, project_id: &str, collection: &str) -> RedisResult<usize> {
+    let mut conn = pool.clone();
+    let mut scan: redis::AsyncIter<String> = conn.scan_match(format!("{}*", prefix(project_id, collection))).await?;
+    let mut keys = Vec::new();
+    while let Some(key) = scan.next_item().await {
+        keys.push(key);
+    }
+    drop(scan);
+
+    for chunk in keys.chunks(500) {
+        conn.del::<_, ()>(chunk).await?;
+    }
+    Ok(keys.len())
+}
+
+/// Rank a project's documents in `collection` against `query` with BM25.
+pub async fn search(
+    pool: &RedisPool,
+    project_id: &str,
+    collection: &str,
+    query: &str,
+    limit: usize,
+) -> RedisResult<Vec<LexicalHit>> {
+    let mut terms = tokenize(query);
+    terms.sort();
+    terms.dedup();
+    if terms.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let prefix = prefix(project_id, collection);
+    let mut conn = pool.clone();
+    let (docs, total): (usize, Option<i64>) = redis::pipe()
+        .hlen(format!("{}len", prefix))
+        .get(format!("{}total", prefix))
+        .query_async(&mut conn)
+        .await?;
+    if docs == 0 {
+        return Ok(Vec::new());
+    }
+    let avg_len = total.unwrap_or(0).max(0) as f64 / docs as f64;
+
+    let mut pipe = redis::pipe();
+    for term in &terms {
+        pipe.hgetall(format!("{}term:{}", prefix, term));
+    }
+    let postings: Vec<HashMap<String, u32>> = pipe.query_async(&mut conn).await?;
+
+    let mut matches: HashMap<String, Vec<(u32, usize)>> = HashMap::new();
+    for posting in &postings {
+        for (id, freq) in posting {
+            matches.entry(id.clone()).or_default().push((*freq, posting.len()));
+        }
+    }
+    if matches.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids: Vec<String> = matches.keys().cloned().collect();
+    let lengths: Vec<Option<u32>> = redis::cmd("HMGET")
+        .arg(format!("{}len", prefix))
+        .arg(&ids)
+        .query_async(&mut conn)
+        .await?;
+
+    let mut scored: Vec<(String, f64)> = ids
+        .into_iter()
+        .zip(lengths)
+        .map(|(id, len)| {
+            let doc_len = len.unwrap_or(0) as f64;
+            let score = matches[&id]
+                .iter()
+                .map(|(freq, doc_freq)| bm25(*freq as f64, *doc_freq, docs, doc_len, avg_len))
+                .sum();
+            (id, score)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+    scored.truncate(limit);
+
+    let ids: Vec<&str> = scored.iter().map(|(id, _)| id.as_str()).collect();
+    let payloads: Vec<Option<String>> = redis::cmd("HMGET")
+        .arg(format!("{}payload", prefix))
+        .arg(&ids)
+        .query_async(&mut conn)
+        .await?;
+
+    Ok(scored
+        .into_iter()
+        .zip(payloads)
+        .map(|((id, score), payload)| LexicalHit {
+            id,
+            score,
+            payload: payload
+                .and_then(|p| serde_json::from_str(&p).ok())
+                .unwrap_or(serde_json::Value::Null),
+        })
+        .collect())
+}
+
+/// Index a memory by its content and tags.
+pub async fn index_memory(pool: &RedisPool, row: &MemoryRow) -> RedisResult<()> {
+    let text = format!("{} {}", row.content, row.tags.as_deref().unwrap_or(""));
+    index_document(pool, &row.project_id, MEMORIES, &row.id, &text, &memory_payload(row)).await
+}
+
+/// Index an observation by its title, narrative, facts and concepts.
+pub async fn index_observation(pool: &RedisPool, row: &ObservationRow) -> RedisResult<()> {
+    let text = [
+        Some(row.title.as_str()),
+        row.narrative.as_deref(),
+        row.facts.as_deref(),
+        row.concepts.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+    index_document(pool, &row.project_id, OBSERVATIONS, &row.id, &text, &observation_payload(row)).await
+}
+
+/// Index a glossary term by its name, aliases and definition.
+pub async fn index_term(pool: &RedisPool, row: &GlossaryTermRow) -> RedisResult<()> {
+    let text = format!("{} {} {}", row.term, row.aliases.as_deref().unwrap_or(""), row.definition);
+    index_document(pool, &row.project_id, TERMS, &row.id, &text, &term_payload(row)).await
+}
+
+/// Index a domain object by its name, type, context and description.
+pub async fn index_domain_object(
+    pool: &RedisPool,
+    project_id: &str,
+    row: &DomainObjectRow,
+    context_name: &str,
+) -> RedisResult<()> {
+    let text = format!(
+        "{} {} {} {}",
+        row.name,
+        row.object_type,
+        context_name,
+        row.description.as_deref().unwrap_or("")
+    );
+    let payload = domain_object_payload(project_id, row, context_name);
+    index_document(pool, project_id, DOMAIN_OBJECTS, &row.id, &text, &payload).await
+}
+
+/// Search payload of a memory, shared with its Qdrant point.
+pub fn memory_payload(row: &MemoryRow) -> serde_json::Value {
+    serde_json::json!({
+        "id": row.id,
+        "project_id": row.project_id,
+        "content": row.content,
+        "entry_type": row.entry_type,
+        "context": row.tags.as_deref().unwrap_or(""),
+        "created_at": row.created_at,
+    })
+}
+
+/// Search payload of an observation, shared with its Qdrant point.
+pub fn observation_payload(row: &ObservationRow) -> serde_json::Value {
+    serde_json::json!({
+        "id": row.id,
+        "project_id": row.project_id,
+        "obs_type": row.obs_type,
+        "title": row.title,
+        "narrative": row.narrative.as_deref().unwrap_or(""),
+        "created_at": row.created_at,
+    })
+}
+
+/// Search payload of a glossary term.
+pub fn term_payload(row: &GlossaryTermRow) -> serde_json::Value {
+    serde_json::json!({
+        "id": row.id,
+        "project_id": row.project_id,
+        "term": row.term,
+        "definition": row.definition,
+        "created_at": row.created_at,
+    })
+}
+
+/// Search payload of a domain object, shared with its Qdrant point.
+pub fn domain_object_payload(project_id: &str, row: &DomainObjectRow, context_name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": row.id,
+        "project_id": project_id,
+        "context_name": context_name,
+        "name": row.name,
+        "object_type": row.object_type,
+        "description": row.description.as_deref().unwrap_or(""),
+        "created_at": row.created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_keeps_identifiers_and_their_parts() {
+        assert_eq!(
+            tokenize("The `get_user_by_id` call failed with E0382: connection refused!"),
+            ["get", "user", "id", "get_user_by_id", "call", "failed", "e0382", "connection", "refused"]
+        );
+        assert!(tokenize("a of the").is_empty());
+    }
+
+    #[test]
+    fn bm25_favours_rare_terms_and_short_documents() {
+        // A term in 1 of 100 documents outweighs one in 50 of them.
+        assert!(bm25(1.0, 1, 100, 10.0, 10.0) > bm25(1.0, 50, 100, 10.0, 10.0));
+        // The same match counts for more in a shorter document.
+        assert!(bm25(1.0, 5, 100, 5.0, 10.0) > bm25(1.0, 5, 100, 40.0, 10.0));
+        // Repeats help, with diminishing returns.
+        let (one, two, three) = (bm25(1.0, 5, 100, 10.0, 10.0), bm25(2.0, 5, 100, 10.0, 10.0), bm25(3.0, 5, 100, 10.0, 10.0));
+        assert!(two > one && three - two < two - one);
+    }
+}
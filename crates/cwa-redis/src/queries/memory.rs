@@ -1,6 +1,8 @@
 //! Memory and session queries — Redis implementation.
 
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
+use crate::queries::lexical;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
@@ -54,15 +56,16 @@ pub async fn create_memory_entry(
         expires_at: None,
     };
 
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     let key = format!("cwa:{}:memory:{}", project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+    tx.hset(&key, "data", serde_json::to_string(&row)?).ignore();
 
     // Add to sorted set (score = timestamp for ordering)
     let zkey = format!("cwa:{}:memories:all", project_id);
-    conn.zadd::<_, _, _, ()>(&zkey, id, now.timestamp()).await?;
+    tx.zadd(&zkey, id, now.timestamp()).ignore();
 
-    Ok(())
+    atomic::commit(pool, &tx).await?;
+    lexical::index_memory(pool, &row).await
 }
 
 pub async fn list_memory(
@@ -89,6 +92,27 @@ pub async fn list_memory(
     Ok(memories)
 }
 
+pub async fn get_memory_entry(
+    pool: &RedisPool,
+    project_id: &str,
+    id: &str,
+) -> RedisResult<Option<MemoryRow>> {
+    let mut conn = pool.clone();
+    let key = format!("cwa:{}:memory:{}", project_id, id);
+    let json: Option<String> = conn.hget(&key, "data").await?;
+    match json {
+        Some(j) => Ok(Some(serde_json::from_str(&j)?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn list_memory_ids(pool: &RedisPool, project_id: &str) -> RedisResult<Vec<String>> {
+    let mut conn = pool.clone();
+    let zkey = format!("cwa:{}:memories:all", project_id);
+    let ids: Vec<String> = conn.zrange(&zkey, 0, -1).await?;
+    Ok(ids)
+}
+
 pub async fn search_memory(
     pool: &RedisPool,
     project_id: &str,
@@ -127,7 +151,12 @@ pub async fn cleanup_expired_memory(pool: &RedisPool) -> RedisResult<usize> {
             if let Ok(row) = serde_json::from_str::<MemoryRow>(&j) {
                 if let Some(ref expires) = row.expires_at {
                     if expires < &now {
-                        c.del::<_, ()>(&key).await?;
+                        let mut tx = atomic::transaction();
+                        tx.del(&key).ignore();
+                        let zkey = format!("cwa:{}:memories:all", row.project_id);
+                        tx.zrem(&zkey, &row.id).ignore();
+                        atomic::commit(pool, &tx).await?;
+                        lexical::remove_document(pool, &row.project_id, lexical::MEMORIES, &row.id).await?;
                         deleted += 1;
                     }
                 }
@@ -154,17 +183,17 @@ pub async fn create_session(
         accomplishments: None,
     };
 
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     // Session with 30-day TTL
     let key = format!("cwa:session:{}", id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
-    conn.expire::<_, ()>(&key, 30 * 24 * 3600).await?;
+    tx.hset(&key, "data", serde_json::to_string(&row)?).ignore();
+    tx.expire(&key, 30 * 24 * 3600).ignore();
 
     // Track active session per project
     let active_key = format!("cwa:{}:session:active", project_id);
-    conn.set::<_, _, ()>(&active_key, id).await?;
+    tx.set(&active_key, id).ignore();
 
-    Ok(())
+    atomic::commit(pool, &tx).await
 }
 
 pub async fn end_session(
@@ -1,9 +1,13 @@
 //! Query modules for all CWA domain entities stored in Redis.
 
+pub mod audit;
 pub mod boards;
+pub mod code_index;
 pub mod decisions;
 pub mod domains;
+pub mod embeddings;
 pub mod glossary;
+pub mod lexical;
 pub mod memory;
 pub mod observations;
 pub mod projects;
@@ -1,6 +1,8 @@
 //! Observation and summary queries — Redis implementation using Streams.
 
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
+use crate::queries::lexical;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 
@@ -83,15 +85,15 @@ pub async fn create_observation(
 
     let json = serde_json::to_string(&row)?;
 
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
 
     // Store as HASH
     let key = format!("cwa:{}:observation:{}", project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", &json).await?;
+    tx.hset(&key, "data", &json).ignore();
 
     // Add to sorted set (score = timestamp)
     let zkey = format!("cwa:{}:observations:all", project_id);
-    conn.zadd::<_, _, _, ()>(&zkey, id, now.timestamp()).await?;
+    tx.zadd(&zkey, id, now.timestamp()).ignore();
 
     // Also publish to stream for timeline
     let stream_key = format!("cwa:{}:observations", project_id);
@@ -101,9 +103,10 @@ pub async fn create_observation(
         ("title", title),
         ("data", &json),
     ];
-    conn.xadd::<_, _, _, _, ()>(&stream_key, "*", &fields).await?;
+    tx.xadd(&stream_key, "*", &fields).ignore();
 
-    Ok(())
+    atomic::commit(pool, &tx).await?;
+    lexical::index_observation(pool, &row).await
 }
 
 pub async fn get_observation(
@@ -167,6 +170,13 @@ pub async fn list_observations_compact(
     Ok(results)
 }
 
+pub async fn list_observation_ids(pool: &RedisPool, project_id: &str) -> RedisResult<Vec<String>> {
+    let mut conn = pool.clone();
+    let zkey = format!("cwa:{}:observations:all", project_id);
+    let ids: Vec<String> = conn.zrange(&zkey, 0, -1).await?;
+    Ok(ids)
+}
+
 pub async fn list_observations_timeline(
     pool: &RedisPool,
     project_id: &str,
@@ -241,6 +251,7 @@ pub async fn decay_all_confidence(
     let zkey = format!("cwa:{}:observations:all", project_id);
     let ids: Vec<String> = conn.zrange(&zkey, 0, -1).await?;
     let count = ids.len();
+    let mut tx = atomic::transaction();
     for id in ids {
         let key = format!("cwa:{}:observation:{}", project_id, id);
         let mut c = pool.clone();
@@ -248,10 +259,11 @@ pub async fn decay_all_confidence(
         if let Some(j) = json {
             if let Ok(mut row) = serde_json::from_str::<ObservationRow>(&j) {
                 row.confidence *= decay_factor;
-                c.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+                tx.hset(&key, "data", serde_json::to_string(&row)?).ignore();
             }
         }
     }
+    atomic::commit(pool, &tx).await?;
     Ok(count)
 }
 
@@ -264,6 +276,7 @@ pub async fn remove_low_confidence(
     let zkey = format!("cwa:{}:observations:all", project_id);
     let ids: Vec<String> = conn.zrange(&zkey, 0, -1).await?;
     let mut removed = Vec::new();
+    let mut tx = atomic::transaction();
     for id in ids {
         let key = format!("cwa:{}:observation:{}", project_id, id);
         let mut c = pool.clone();
@@ -271,13 +284,17 @@ pub async fn remove_low_confidence(
         if let Some(j) = json {
             if let Ok(row) = serde_json::from_str::<ObservationRow>(&j) {
                 if row.confidence < min_confidence {
-                    c.del::<_, ()>(&key).await?;
-                    conn.zrem::<_, _, ()>(&zkey, &id).await?;
+                    tx.del(&key).ignore();
+                    tx.zrem(&zkey, &id).ignore();
                     removed.push(id);
                 }
             }
         }
     }
+    atomic::commit(pool, &tx).await?;
+    for id in &removed {
+        lexical::remove_document(pool, project_id, lexical::OBSERVATIONS, id).await?;
+    }
     Ok(removed)
 }
 
@@ -305,14 +322,14 @@ pub async fn create_summary(
         created_at: now.to_rfc3339(),
     };
 
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     let key = format!("cwa:{}:summary:{}", project_id, id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+    tx.hset(&key, "data", serde_json::to_string(&row)?).ignore();
 
     let zkey = format!("cwa:{}:summaries:all", project_id);
-    conn.zadd::<_, _, _, ()>(&zkey, id, now.timestamp()).await?;
+    tx.zadd(&zkey, id, now.timestamp()).ignore();
 
-    Ok(())
+    atomic::commit(pool, &tx).await
 }
 
 pub async fn get_recent_summaries(
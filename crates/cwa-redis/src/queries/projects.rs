@@ -36,9 +36,7 @@ pub async fn create_project(
     };
     let json = serde_json::to_string(&row)?;
     let key = format!("cwa:{}:info", id);
-    conn.hset::<_, _, _, ()>(&key, "data", &json).await?;
-    conn.hset::<_, _, _, ()>(&key, "name", name).await?;
-    conn.hset::<_, _, _, ()>(&key, "status", "active").await?;
+    conn.hset_multiple::<_, _, _, ()>(&key, &[("data", json.as_str()), ("name", name), ("status", "active")]).await?;
     Ok(())
 }
 
@@ -114,8 +112,8 @@ pub async fn update_project(
     row.updated_at = chrono::Utc::now().to_rfc3339();
     let mut conn = pool.clone();
     let key = format!("cwa:{}:info", project_id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "name", name).await?;
+    let json = serde_json::to_string(&row)?;
+    conn.hset_multiple::<_, _, _, ()>(&key, &[("data", json.as_str()), ("name", name)]).await?;
     Ok(())
 }
 
@@ -1,5 +1,6 @@
 //! Specification queries — Redis implementation.
 
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
@@ -74,21 +75,25 @@ pub async fn create_spec_with_criteria(
 }
 
 async fn save_spec(pool: &RedisPool, row: &SpecRow, score: i64) -> RedisResult<()> {
-    let mut conn = pool.clone();
     let key = format!("cwa:{}:spec:{}", row.project_id, row.id);
     let json = serde_json::to_string(row)?;
-    conn.hset::<_, _, _, ()>(&key, "data", &json).await?;
-    conn.hset::<_, _, _, ()>(&key, "status", &row.status).await?;
+    let mut tx = atomic::transaction();
+    tx.hset_multiple(&key, &[("data", json.as_str()), ("status", row.status.as_str())]).ignore();
 
     // Add to all-specs sorted set
     let zkey = format!("cwa:{}:specs:all", row.project_id);
-    conn.zadd::<_, _, _, ()>(&zkey, &row.id, score).await?;
+    tx.zadd(&zkey, &row.id, score).ignore();
 
     // Add to status index
     let skey = format!("cwa:{}:specs:status:{}", row.project_id, row.status);
-    conn.sadd::<_, _, ()>(&skey, &row.id).await?;
+    tx.sadd(&skey, &row.id).ignore();
 
-    Ok(())
+    atomic::commit(pool, &tx).await
+}
+
+/// Status index key prefix for a project's specs.
+pub(crate) fn status_prefix(project_id: &str) -> String {
+    format!("cwa:{}:specs:status:", project_id)
 }
 
 pub async fn get_spec(pool: &RedisPool, spec_id: &str) -> RedisResult<SpecRow> {
@@ -167,6 +172,19 @@ pub async fn list_specs(pool: &RedisPool, project_id: &str) -> RedisResult<Vec<S
     Ok(specs)
 }
 
+/// IDs of the project's specs starting with `prefix`, oldest first, at most `limit`.
+pub async fn list_spec_ids_with_prefix(
+    pool: &RedisPool,
+    project_id: &str,
+    prefix: &str,
+    limit: usize,
+) -> RedisResult<Vec<String>> {
+    let mut conn = pool.clone();
+    let zkey = format!("cwa:{}:specs:all", project_id);
+    let ids: Vec<String> = conn.zrange(&zkey, 0, -1).await?;
+    Ok(ids.into_iter().filter(|id| id.starts_with(prefix)).take(limit).collect())
+}
+
 pub async fn get_active_spec(pool: &RedisPool, project_id: &str) -> RedisResult<Option<SpecRow>> {
     let mut conn = pool.clone();
     let skey = format!("cwa:{}:specs:status:active", project_id);
@@ -188,7 +206,6 @@ pub async fn update_spec_status(
     new_status: &str,
 ) -> RedisResult<()> {
     let row = get_spec(pool, spec_id).await?;
-    let old_status = row.status.clone();
 
     let mut updated = row.clone();
     updated.status = new_status.to_string();
@@ -197,16 +214,9 @@ pub async fn update_spec_status(
         updated.archived_at = Some(chrono::Utc::now().to_rfc3339());
     }
 
-    let mut conn = pool.clone();
     let key = format!("cwa:{}:spec:{}", row.project_id, spec_id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&updated)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "status", new_status).await?;
-
-    // Update status indexes
-    let old_skey = format!("cwa:{}:specs:status:{}", row.project_id, old_status);
-    conn.srem::<_, _, ()>(&old_skey, spec_id).await?;
-    let new_skey = format!("cwa:{}:specs:status:{}", row.project_id, new_status);
-    conn.sadd::<_, _, ()>(&new_skey, spec_id).await?;
+    let json = serde_json::to_string(&updated)?;
+    atomic::set_status(pool, &key, &status_prefix(&row.project_id), spec_id, new_status, &json).await?;
 
     Ok(())
 }
@@ -225,19 +235,34 @@ pub async fn update_acceptance_criteria(
     Ok(())
 }
 
+pub async fn update_spec_dependencies(
+    pool: &RedisPool,
+    spec_id: &str,
+    dependencies_json: Option<&str>,
+) -> RedisResult<()> {
+    let mut row = get_spec(pool, spec_id).await?;
+    row.dependencies = dependencies_json.map(str::to_string);
+    row.updated_at = chrono::Utc::now().to_rfc3339();
+    let mut conn = pool.clone();
+    let key = format!("cwa:{}:spec:{}", row.project_id, spec_id);
+    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+    Ok(())
+}
+
 pub async fn delete_all_specs(pool: &RedisPool, project_id: &str) -> RedisResult<usize> {
     let specs = list_specs(pool, project_id).await?;
     let count = specs.len();
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     for spec in &specs {
         let key = format!("cwa:{}:spec:{}", project_id, spec.id);
-        conn.del::<_, ()>(&key).await?;
+        tx.del(&key).ignore();
     }
     let zkey = format!("cwa:{}:specs:all", project_id);
-    conn.del::<_, ()>(&zkey).await?;
+    tx.del(&zkey).ignore();
     for status in &["draft", "active", "in_review", "accepted", "completed", "archived"] {
         let skey = format!("cwa:{}:specs:status:{}", project_id, status);
-        conn.del::<_, ()>(&skey).await?;
+        tx.del(&skey).ignore();
     }
+    atomic::commit(pool, &tx).await?;
     Ok(count)
 }
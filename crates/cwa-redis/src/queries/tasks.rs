@@ -1,7 +1,10 @@
 //! Task (Kanban) queries — Redis implementation.
 
+use std::sync::LazyLock;
+
+use crate::atomic;
 use crate::client::{RedisError, RedisPool, RedisResult};
-use redis::AsyncCommands;
+use redis::{AsyncCommands, Script};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,12 +21,15 @@ pub struct TaskRow {
     pub estimated_effort: Option<String>,
     pub actual_effort: Option<String>,
     pub blocked_by: Option<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
     pub created_at: String,
     pub updated_at: String,
     pub started_at: Option<String>,
     pub completed_at: Option<String>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_task(
     pool: &RedisPool,
     id: &str,
@@ -32,6 +38,7 @@ pub async fn create_task(
     description: Option<&str>,
     spec_id: Option<&str>,
     priority: &str,
+    status: &str,
 ) -> RedisResult<()> {
     let now = chrono::Utc::now();
     let row = TaskRow {
@@ -40,13 +47,14 @@ pub async fn create_task(
         spec_id: spec_id.map(str::to_string),
         title: title.to_string(),
         description: description.map(str::to_string),
-        status: "backlog".to_string(),
+        status: status.to_string(),
         priority: priority.to_string(),
         assignee: None,
         labels: None,
         estimated_effort: None,
         actual_effort: None,
         blocked_by: None,
+        due_date: None,
         created_at: now.to_rfc3339(),
         updated_at: now.to_rfc3339(),
         started_at: None,
@@ -56,29 +64,79 @@ pub async fn create_task(
 }
 
 async fn save_task(pool: &RedisPool, row: &TaskRow, score: i64) -> RedisResult<()> {
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
+    queue_save_task(&mut tx, row, score)?;
+
+    // Creation is the first entry of the task's history
+    queue_task_event(&mut tx, row, None, &row.status, &row.created_at);
+
+    atomic::commit(pool, &tx).await
+}
+
+/// Queue the writes storing a new task and its indexes on `tx`.
+pub(crate) fn queue_save_task(tx: &mut redis::Pipeline, row: &TaskRow, score: i64) -> RedisResult<()> {
     let key = format!("cwa:{}:task:{}", row.project_id, row.id);
     let json = serde_json::to_string(row)?;
-    conn.hset::<_, _, _, ()>(&key, "data", &json).await?;
-    conn.hset::<_, _, _, ()>(&key, "status", &row.status).await?;
+    tx.hset_multiple(&key, &[("data", json.as_str()), ("status", row.status.as_str())]).ignore();
 
     // Sorted set
     let zkey = format!("cwa:{}:tasks:all", row.project_id);
-    conn.zadd::<_, _, _, ()>(&zkey, &row.id, score).await?;
+    tx.zadd(&zkey, &row.id, score).ignore();
 
     // Status index
     let skey = format!("cwa:{}:tasks:status:{}", row.project_id, row.status);
-    conn.sadd::<_, _, ()>(&skey, &row.id).await?;
+    tx.sadd(&skey, &row.id).ignore();
 
     // Spec index
     if let Some(ref sid) = row.spec_id {
         let spec_key = format!("cwa:{}:tasks:spec:{}", row.project_id, sid);
-        conn.sadd::<_, _, ()>(&spec_key, &row.id).await?;
+        tx.sadd(&spec_key, &row.id).ignore();
     }
-
     Ok(())
 }
 
+/// Queue a status transition on the task's event stream.
+pub(crate) fn queue_task_event(
+    tx: &mut redis::Pipeline,
+    row: &TaskRow,
+    from: Option<&str>,
+    to: &str,
+    at: &str,
+) {
+    tx.cmd("XADD")
+        .arg(events_key(&row.project_id, &row.id))
+        .arg("*")
+        .arg(&[("from", from.unwrap_or("")), ("to", to), ("at", at)])
+        .ignore();
+}
+
+/// A single status transition from a task's event stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEventRow {
+    /// Stream entry ID.
+    pub id: String,
+    pub task_id: String,
+    /// Previous status; `None` for the creation event.
+    pub from: Option<String>,
+    pub to: String,
+    pub at: String,
+}
+
+/// Key of the append-only stream holding a task's status transitions.
+fn events_key(project_id: &str, task_id: &str) -> String {
+    format!("cwa:{}:task:{}:events", project_id, task_id)
+}
+
+/// Status index key prefix for a project's tasks.
+pub(crate) fn status_prefix(project_id: &str) -> String {
+    format!("cwa:{}:tasks:status:", project_id)
+}
+
+/// Spec index key prefix for a project's tasks.
+fn spec_prefix(project_id: &str) -> String {
+    format!("cwa:{}:tasks:spec:", project_id)
+}
+
 pub async fn get_task(pool: &RedisPool, task_id: &str) -> RedisResult<TaskRow> {
     let mut conn = pool.clone();
     let pattern = format!("cwa:*:task:{}", task_id);
@@ -131,6 +189,19 @@ pub async fn list_tasks(pool: &RedisPool, project_id: &str) -> RedisResult<Vec<T
     Ok(tasks)
 }
 
+/// IDs of the project's tasks starting with `prefix`, oldest first, at most `limit`.
+pub async fn list_task_ids_with_prefix(
+    pool: &RedisPool,
+    project_id: &str,
+    prefix: &str,
+    limit: usize,
+) -> RedisResult<Vec<String>> {
+    let mut conn = pool.clone();
+    let zkey = format!("cwa:{}:tasks:all", project_id);
+    let ids: Vec<String> = conn.zrange(&zkey, 0, -1).await?;
+    Ok(ids.into_iter().filter(|id| id.starts_with(prefix)).take(limit).collect())
+}
+
 pub async fn list_tasks_by_spec(pool: &RedisPool, spec_id: &str) -> RedisResult<Vec<TaskRow>> {
     // Need to scan since we don't know project_id
     let mut conn = pool.clone();
@@ -193,37 +264,267 @@ pub async fn list_tasks_by_status(
     Ok(tasks)
 }
 
+/// Move a task to `new_status`, recording the transition.
+///
+/// `starts` stamps `started_at` (if unset) and `completes` stamps
+/// `completed_at`; callers derive them from the column's category.
 pub async fn update_task_status(
     pool: &RedisPool,
+    project_id: &str,
     task_id: &str,
     new_status: &str,
+    starts: bool,
+    completes: bool,
 ) -> RedisResult<()> {
-    let row = get_task(pool, task_id).await?;
-    let old_status = row.status.clone();
-    let project_id = row.project_id.clone();
-
-    let mut updated = row;
+    let mut updated = get_task_in_project(pool, project_id, task_id).await?;
     let now = chrono::Utc::now().to_rfc3339();
     updated.status = new_status.to_string();
     updated.updated_at = now.clone();
-    if new_status == "in_progress" && updated.started_at.is_none() {
+    if starts && updated.started_at.is_none() {
         updated.started_at = Some(now.clone());
     }
-    if new_status == "done" {
+    if completes {
         updated.completed_at = Some(now);
     }
 
-    let mut conn = pool.clone();
+    // The script reads the stored status itself, so a concurrent transition
+    // can't leave the task in two status sets
     let key = format!("cwa:{}:task:{}", project_id, task_id);
-    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&updated)?).await?;
-    conn.hset::<_, _, _, ()>(&key, "status", new_status).await?;
+    let json = serde_json::to_string(&updated)?;
+    atomic::set_status_with_event(
+        pool,
+        &key,
+        &events_key(project_id, task_id),
+        &status_prefix(project_id),
+        task_id,
+        new_status,
+        &json,
+        &updated.updated_at,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// List a task's status transitions, oldest first.
+pub async fn list_task_events(
+    pool: &RedisPool,
+    project_id: &str,
+    task_id: &str,
+) -> RedisResult<Vec<TaskEventRow>> {
+    let mut conn = pool.clone();
+    let entries: Vec<(String, Vec<String>)> = redis::cmd("XRANGE")
+        .arg(events_key(project_id, task_id))
+        .arg("-")
+        .arg("+")
+        .query_async(&mut conn)
+        .await?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|(id, fields)| parse_event(task_id, id, fields))
+        .collect())
+}
+
+/// List the status transitions of every task in a project, grouped by task.
+pub async fn list_project_task_events(
+    pool: &RedisPool,
+    project_id: &str,
+) -> RedisResult<Vec<(String, Vec<TaskEventRow>)>> {
+    let mut conn = pool.clone();
+    let zkey = format!("cwa:{}:tasks:all", project_id);
+    let ids: Vec<String> = conn.zrange(&zkey, 0, -1).await?;
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    // Update status indexes
-    let old_skey = format!("cwa:{}:tasks:status:{}", project_id, old_status);
-    conn.srem::<_, _, ()>(&old_skey, task_id).await?;
-    let new_skey = format!("cwa:{}:tasks:status:{}", project_id, new_status);
-    conn.sadd::<_, _, ()>(&new_skey, task_id).await?;
+    let mut pipe = redis::pipe();
+    for id in &ids {
+        pipe.cmd("XRANGE").arg(events_key(project_id, id)).arg("-").arg("+");
+    }
+    let streams: Vec<Vec<(String, Vec<String>)>> = pipe.query_async(&mut conn).await?;
+
+    Ok(ids
+        .into_iter()
+        .zip(streams)
+        .map(|(task_id, entries)| {
+            let events = entries
+                .into_iter()
+                .filter_map(|(id, fields)| parse_event(&task_id, id, fields))
+                .collect();
+            (task_id, events)
+        })
+        .collect())
+}
+
+/// Build an event from a stream entry's flat field/value list.
+fn parse_event(task_id: &str, id: String, fields: Vec<String>) -> Option<TaskEventRow> {
+    let mut from = None;
+    let mut to = None;
+    let mut at = None;
+    for pair in fields.chunks_exact(2) {
+        match pair[0].as_str() {
+            "from" => from = Some(pair[1].clone()).filter(|s| !s.is_empty()),
+            "to" => to = Some(pair[1].clone()),
+            "at" => at = Some(pair[1].clone()),
+            _ => {}
+        }
+    }
+    Some(TaskEventRow {
+        id,
+        task_id: task_id.to_string(),
+        from,
+        to: to?,
+        at: at?,
+    })
+}
+
+pub async fn update_task_blocked_by(
+    pool: &RedisPool,
+    project_id: &str,
+    task_id: &str,
+    blocked_by_json: Option<&str>,
+) -> RedisResult<()> {
+    let mut row = get_task_in_project(pool, project_id, task_id).await?;
+    row.blocked_by = blocked_by_json.map(str::to_string);
+    row.updated_at = chrono::Utc::now().to_rfc3339();
+    let mut conn = pool.clone();
+    let key = format!("cwa:{}:task:{}", row.project_id, task_id);
+    conn.hset::<_, _, _, ()>(&key, "data", serde_json::to_string(&row)?).await?;
+    Ok(())
+}
+
+/// Overwrites a task and moves it between status and spec index sets, based
+/// on the status and spec it currently has in Redis rather than a copy read
+/// earlier, so a concurrent status move can't leave it in two sets.
+///
+/// KEYS[1] = task hash
+/// ARGV[1] = status index key prefix, ARGV[2] = spec index key prefix,
+/// ARGV[3] = task ID, ARGV[4] = new status, ARGV[5] = new spec ID ('' for none),
+/// ARGV[6] = serialized task
+///
+/// Returns 0 without writing if the task no longer exists.
+static UPDATE_TASK_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        local data = redis.call('HGET', KEYS[1], 'data')
+        if not data then
+            return 0
+        end
+        local old = cjson.decode(data)
+        local old_status = redis.call('HGET', KEYS[1], 'status') or old.status
+        if type(old_status) == 'string' and old_status ~= ARGV[4] then
+            redis.call('SREM', ARGV[1] .. old_status, ARGV[3])
+        end
+        redis.call('SADD', ARGV[1] .. ARGV[4], ARGV[3])
+        local old_spec = old.spec_id
+        if type(old_spec) == 'string' and old_spec ~= ARGV[5] then
+            redis.call('SREM', ARGV[2] .. old_spec, ARGV[3])
+        end
+        if ARGV[5] ~= '' then
+            redis.call('SADD', ARGV[2] .. ARGV[5], ARGV[3])
+        end
+        redis.call('HSET', KEYS[1], 'data', ARGV[6], 'status', ARGV[4])
+        return 1
+        "#,
+    )
+});
+
+/// Deletes a task, drops it from its indexes as currently stored, and removes
+/// it from the `blocked_by` list of every other task in the project.
+///
+/// KEYS[1] = task hash, KEYS[2] = event stream, KEYS[3] = `tasks:all` index
+/// ARGV[1] = status index key prefix, ARGV[2] = spec index key prefix,
+/// ARGV[3] = task hash key prefix, ARGV[4] = task ID
+///
+/// Returns 0 if the task does not exist.
+static DELETE_TASK_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+        local data = redis.call('HGET', KEYS[1], 'data')
+        if not data then
+            return 0
+        end
+        local task = cjson.decode(data)
+        local status = redis.call('HGET', KEYS[1], 'status') or task.status
+        if type(status) == 'string' then
+            redis.call('SREM', ARGV[1] .. status, ARGV[4])
+        end
+        if type(task.spec_id) == 'string' then
+            redis.call('SREM', ARGV[2] .. task.spec_id, ARGV[4])
+        end
+        redis.call('DEL', KEYS[1], KEYS[2])
+        redis.call('ZREM', KEYS[3], ARGV[4])
 
+        for _, other in ipairs(redis.call('ZRANGE', KEYS[3], 0, -1)) do
+            local key = ARGV[3] .. other
+            local other_data = redis.call('HGET', key, 'data')
+            if other_data then
+                local row = cjson.decode(other_data)
+                if type(row.blocked_by) == 'string' then
+                    local ok, ids = pcall(cjson.decode, row.blocked_by)
+                    if ok and type(ids) == 'table' then
+                        local kept, found = {}, false
+                        for _, id in ipairs(ids) do
+                            if id == ARGV[4] then
+                                found = true
+                            else
+                                table.insert(kept, id)
+                            end
+                        end
+                        if found then
+                            if #kept == 0 then
+                                row.blocked_by = cjson.null
+                            else
+                                row.blocked_by = cjson.encode(kept)
+                            end
+                            redis.call('HSET', key, 'data', cjson.encode(row))
+                        end
+                    end
+                end
+            end
+        end
+        return 1
+        "#,
+    )
+});
+
+/// Overwrite a task's fields, moving it between the status and spec index
+/// sets when those changed.
+pub async fn update_task(pool: &RedisPool, row: &TaskRow) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    let updated: i64 = UPDATE_TASK_SCRIPT
+        .key(format!("cwa:{}:task:{}", row.project_id, row.id))
+        .arg(status_prefix(&row.project_id))
+        .arg(spec_prefix(&row.project_id))
+        .arg(&row.id)
+        .arg(&row.status)
+        .arg(row.spec_id.as_deref().unwrap_or(""))
+        .arg(serde_json::to_string(row)?)
+        .invoke_async(&mut conn)
+        .await?;
+    if updated == 0 {
+        return Err(RedisError::NotFound(format!("Task not found: {}", row.id)));
+    }
+    Ok(())
+}
+
+/// Delete a single task and drop it from every index and from the
+/// `blocked_by` lists of the tasks it was blocking.
+pub async fn delete_task(pool: &RedisPool, project_id: &str, task_id: &str) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    let deleted: i64 = DELETE_TASK_SCRIPT
+        .key(format!("cwa:{}:task:{}", project_id, task_id))
+        .key(events_key(project_id, task_id))
+        .key(format!("cwa:{}:tasks:all", project_id))
+        .arg(status_prefix(project_id))
+        .arg(spec_prefix(project_id))
+        .arg(format!("cwa:{}:task:", project_id))
+        .arg(task_id)
+        .invoke_async(&mut conn)
+        .await?;
+    if deleted == 0 {
+        return Err(RedisError::NotFound(format!("Task not found: {}", task_id)));
+    }
     Ok(())
 }
 
@@ -276,35 +577,113 @@ pub async fn get_all_wip_limits(pool: &RedisPool, project_id: &str) -> RedisResu
     Ok(result)
 }
 
+/// Get the project's stored workflow definition (JSON), if any.
+pub async fn get_workflow(pool: &RedisPool, project_id: &str) -> RedisResult<Option<String>> {
+    let mut conn = pool.clone();
+    let key = format!("cwa:{}:workflow", project_id);
+    let json: Option<String> = conn.get(&key).await?;
+    Ok(json)
+}
+
+/// Store the project's workflow definition (JSON).
+pub async fn set_workflow(pool: &RedisPool, project_id: &str, json: &str) -> RedisResult<()> {
+    let mut conn = pool.clone();
+    let key = format!("cwa:{}:workflow", project_id);
+    conn.set::<_, _, ()>(&key, json).await?;
+    Ok(())
+}
+
 pub async fn delete_tasks_by_spec(pool: &RedisPool, spec_id: &str) -> RedisResult<usize> {
     let tasks = list_tasks_by_spec(pool, spec_id).await?;
     let count = tasks.len();
+    let mut tx = atomic::transaction();
     for task in &tasks {
-        let mut conn = pool.clone();
         let key = format!("cwa:{}:task:{}", task.project_id, task.id);
-        conn.del::<_, ()>(&key).await?;
+        tx.del(&key).ignore();
+        tx.del(events_key(&task.project_id, &task.id)).ignore();
         // Remove from all indexes
         let zkey = format!("cwa:{}:tasks:all", task.project_id);
-        conn.zrem::<_, _, ()>(&zkey, &task.id).await?;
+        tx.zrem(&zkey, &task.id).ignore();
         let skey = format!("cwa:{}:tasks:status:{}", task.project_id, task.status);
-        conn.srem::<_, _, ()>(&skey, &task.id).await?;
+        tx.srem(&skey, &task.id).ignore();
+        let spec_key = format!("cwa:{}:tasks:spec:{}", task.project_id, spec_id);
+        tx.srem(&spec_key, &task.id).ignore();
     }
+    atomic::commit(pool, &tx).await?;
     Ok(count)
 }
 
 pub async fn delete_all_tasks(pool: &RedisPool, project_id: &str) -> RedisResult<usize> {
     let tasks = list_tasks(pool, project_id).await?;
     let count = tasks.len();
-    let mut conn = pool.clone();
+    let mut tx = atomic::transaction();
     for task in &tasks {
         let key = format!("cwa:{}:task:{}", project_id, task.id);
-        conn.del::<_, ()>(&key).await?;
+        tx.del(&key).ignore();
+        tx.del(events_key(project_id, &task.id)).ignore();
+        if let Some(ref sid) = task.spec_id {
+            tx.del(format!("cwa:{}:tasks:spec:{}", project_id, sid)).ignore();
+        }
     }
     let zkey = format!("cwa:{}:tasks:all", project_id);
-    conn.del::<_, ()>(&zkey).await?;
-    for status in &["backlog", "todo", "in_progress", "review", "done"] {
-        let skey = format!("cwa:{}:tasks:status:{}", project_id, status);
-        conn.del::<_, ()>(&skey).await?;
+    tx.del(&zkey).ignore();
+    let mut statuses: Vec<&str> = tasks.iter().map(|t| t.status.as_str()).collect();
+    statuses.sort_unstable();
+    statuses.dedup();
+    for status in statuses {
+        tx.del(format!("{}{}", status_prefix(project_id), status)).ignore();
     }
+    atomic::commit(pool, &tx).await?;
     Ok(count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::init_pool;
+    use crate::consistency::check_project;
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server at REDIS_URL"]
+    async fn concurrent_update_and_move_keep_indexes_consistent() {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+        let pool = init_pool(&url).await.unwrap();
+        let project_id = format!("test-{}", uuid::Uuid::new_v4());
+
+        create_task(&pool, "t1", &project_id, "Task", None, Some("spec-a"), "medium", "todo").await.unwrap();
+        create_task(&pool, "t2", &project_id, "Blocked", None, None, "medium", "todo").await.unwrap();
+        update_task_blocked_by(&pool, &project_id, "t2", Some(r#"["t1"]"#)).await.unwrap();
+
+        for round in 0..50 {
+            // The edit was prepared from a copy read before the status move
+            let mut edited = get_task_in_project(&pool, &project_id, "t1").await.unwrap();
+            edited.title = format!("Task {round}");
+            edited.spec_id = Some(if round % 2 == 0 { "spec-b" } else { "spec-a" }.to_string());
+            let status = if round % 2 == 0 { "in_progress" } else { "review" };
+
+            let (updated, moved) = tokio::join!(
+                update_task(&pool, &edited),
+                update_task_status(&pool, &project_id, "t1", status, true, false),
+            );
+            updated.unwrap();
+            moved.unwrap();
+
+            let report = check_project(&pool, &project_id, false).await.unwrap();
+            assert!(report.is_consistent(), "round {round}: {:?}", report.issues);
+        }
+
+        let (deleted, moved) = tokio::join!(
+            delete_task(&pool, &project_id, "t1"),
+            update_task_status(&pool, &project_id, "t2", "in_progress", true, false),
+        );
+        deleted.unwrap();
+        moved.unwrap();
+
+        let report = check_project(&pool, &project_id, false).await.unwrap();
+        assert!(report.is_consistent(), "{:?}", report.issues);
+        let blocked = get_task_in_project(&pool, &project_id, "t2").await.unwrap();
+        assert_eq!(blocked.blocked_by, None);
+
+        delete_all_tasks(&pool, &project_id).await.unwrap();
+    }
+}
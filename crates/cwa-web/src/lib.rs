@@ -1,6 +1,6 @@
 //! CWA Web Server
 //!
-//! Axum-based web server for dashboard and REST API.
+//! Axum-based web server for dashboard, REST API and the MCP HTTP endpoint.
 
 pub mod routes;
 pub mod state;
@@ -11,6 +11,7 @@ use axum::{
     Router,
 };
 use cwa_db::{BroadcastSender, DbPool};
+use cwa_mcp::ToolProfile;
 use std::sync::Arc;
 use tower_http::{
     cors::{Any, CorsLayer},
@@ -20,7 +21,10 @@ use tower_http::{
 use state::AppState;
 
 /// Create the application router.
-pub fn create_router(state: AppState) -> Router {
+///
+/// MCP clients of the HTTP endpoint may only use the tools of `mcp_profile`,
+/// and work in `default_project` (a project ID) when a request names none.
+pub fn create_router(state: AppState, mcp_profile: ToolProfile, default_project: Option<String>) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
@@ -32,7 +36,11 @@ pub fn create_router(state: AppState) -> Router {
         .route("/tasks", post(routes::tasks::create_task))
         .route("/tasks/{id}", get(routes::tasks::get_task))
         .route("/tasks/{id}", put(routes::tasks::update_task))
+        .route("/tasks/{id}", delete(routes::tasks::delete_task))
+        .route("/tasks/{id}/history", get(routes::tasks::get_task_history))
         .route("/board", get(routes::tasks::get_board))
+        .route("/workflow", get(routes::tasks::get_workflow))
+        .route("/metrics", get(routes::tasks::get_metrics))
         // Specs
         .route("/specs", get(routes::specs::list_specs))
         .route("/specs", post(routes::specs::create_spec))
@@ -46,15 +54,19 @@ pub fn create_router(state: AppState) -> Router {
         .route("/decisions", post(routes::decisions::create_decision))
         // Context
         .route("/context/summary", get(routes::context::get_summary))
+        // MCP audit log
+        .route("/audit", get(routes::audit::list_audit))
         .with_state(state.clone());
 
     // HTMX-driven HTML routes for Kanban board
     let board_routes = Router::new()
         .route("/boards", get(routes::board_html::list_boards))
         .route("/boards/{id}", get(routes::board_html::get_board))
+        .route("/boards/{id}/columns", get(routes::board_html::get_board_columns))
         .route("/cards", post(routes::board_html::create_card))
         .route("/cards/{id}/move", patch(routes::board_html::move_card))
         .route("/cards/{id}", delete(routes::board_html::delete_card))
+        .route("/audit", get(routes::audit::audit_page))
         .with_state(state.clone());
 
     Router::new()
@@ -63,18 +75,27 @@ pub fn create_router(state: AppState) -> Router {
         .merge(board_routes)
         .route("/ws", get(websocket::ws_handler))
         .route("/internal/notify", post(routes::internal::notify))
+        // MCP Streamable HTTP transport, sharing the dashboard's broadcast channel
+        .merge(cwa_mcp::http_router(state.db.clone(), Some(state.tx.clone()), mcp_profile, default_project))
         .layer(TraceLayer::new_for_http())
         .layer(cors)
         .with_state(state)
 }
 
 /// Run the web server with a shared broadcast channel.
-pub async fn run_server(db: Arc<DbPool>, tx: BroadcastSender, port: u16) -> anyhow::Result<()> {
+pub async fn run_server(
+    db: Arc<DbPool>,
+    tx: BroadcastSender,
+    host: &str,
+    port: u16,
+    mcp_profile: ToolProfile,
+    default_project: Option<String>,
+) -> anyhow::Result<()> {
     let state = AppState::new(db, tx);
-    let app = create_router(state);
+    let app = create_router(state, mcp_profile, default_project);
 
-    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
-    tracing::info!("Web server listening on http://127.0.0.1:{}", port);
+    let listener = tokio::net::TcpListener::bind((host, port)).await?;
+    tracing::info!("Web server listening on http://{}:{}", host, port);
 
     axum::serve(listener, app).await?;
     Ok(())
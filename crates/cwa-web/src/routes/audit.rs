@@ -0,0 +1,135 @@
+//! MCP audit log route handlers.
+//!
+//! Lists the tool calls agents made through the MCP server, as JSON for the
+//! API and as a filterable HTML page.
+
+use askama::Template;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::state::AppState;
+use cwa_core::audit::model::{AuditEntry, AuditFilter, AuditStatus};
+
+/// Entries shown when the request doesn't set a limit.
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    pub tool: Option<String>,
+    pub client: Option<String>,
+    pub status: Option<String>,
+    pub days: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn filter(&self) -> AuditFilter {
+        let text = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        AuditFilter {
+            tool: text(&self.tool),
+            client: text(&self.client),
+            status: self.status.as_deref().and_then(AuditStatus::parse),
+            since: self.days.map(AuditFilter::days_ago),
+            limit: Some(self.limit.unwrap_or(DEFAULT_LIMIT)),
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "audit.html")]
+struct AuditTemplate {
+    tool: String,
+    client: String,
+    statuses: Vec<StatusOption>,
+    entries: Vec<EntryView>,
+}
+
+/// An option of the status filter.
+struct StatusOption {
+    name: &'static str,
+    selected: bool,
+}
+
+/// View model for an audit entry.
+struct EntryView {
+    at: String,
+    tool: String,
+    status: &'static str,
+    status_color: &'static str,
+    duration_ms: u64,
+    client: String,
+    session: String,
+    arguments: String,
+    error: Option<String>,
+}
+
+impl EntryView {
+    fn from_entry(entry: AuditEntry) -> Self {
+        Self {
+            at: entry.at.get(..19).unwrap_or(&entry.at).replace('T', " "),
+            tool: entry.tool,
+            status: entry.status.as_str(),
+            status_color: match entry.status {
+                AuditStatus::Ok => "text-green-700 bg-green-50",
+                AuditStatus::Error => "text-red-700 bg-red-50",
+                AuditStatus::Denied => "text-amber-700 bg-amber-50",
+            },
+            duration_ms: entry.duration_ms,
+            client: entry.client.unwrap_or_else(|| "-".to_string()),
+            session: entry.session_id.map(|id| id.chars().take(8).collect()).unwrap_or_default(),
+            arguments: entry.arguments.to_string(),
+            error: entry.error,
+        }
+    }
+}
+
+/// GET /api/audit - List MCP tool calls, newest first.
+pub async fn list_audit(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, String)> {
+    let project = cwa_core::project::get_default_project(&state.db).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
+
+    let entries = cwa_core::audit::list_entries(&state.db, &project.id, &query.filter()).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(entries))
+}
+
+/// GET /audit - Render the audit log page.
+pub async fn audit_page(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Response {
+    let project = match cwa_core::project::get_default_project(&state.db).await {
+        Ok(Some(p)) => p,
+        Ok(None) | Err(_) => return (StatusCode::NOT_FOUND, Html("No project found. Run 'cwa init' first.".to_string())).into_response(),
+    };
+
+    let entries = match cwa_core::audit::list_entries(&state.db, &project.id, &query.filter()).await {
+        Ok(entries) => entries,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Html(format!("Error: {}", e))).into_response(),
+    };
+
+    let template = AuditTemplate {
+        tool: query.tool.unwrap_or_default(),
+        client: query.client.unwrap_or_default(),
+        statuses: AuditStatus::all_variants()
+            .iter()
+            .map(|name| StatusOption { name, selected: query.status.as_deref() == Some(*name) })
+            .collect(),
+        entries: entries.into_iter().map(EntryView::from_entry).collect(),
+    };
+
+    match template.render() {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Html(format!("Template error: {}", e))).into_response(),
+    }
+}
@@ -1,10 +1,11 @@
 //! HTMX-driven Kanban board route handlers.
 //!
-//! Returns HTML fragments for HTMX partial page updates.
+//! Returns HTML fragments for HTMX partial page updates. Cards are views of
+//! tasks, so every change here goes through the task workflow.
 
 use askama::Template;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{Html, IntoResponse, Redirect, Response},
     Form,
@@ -12,7 +13,9 @@ use axum::{
 use serde::Deserialize;
 
 use crate::state::{AppState, WebSocketMessage};
-use cwa_core::board::{self, Card, Column, Priority};
+use cwa_core::board::{self, Board};
+use cwa_core::task::model::{BoardColumn, Task};
+use cwa_core::task::workflow::ColumnCategory;
 
 // ============================================================
 // TEMPLATES
@@ -34,22 +37,25 @@ struct ColumnTemplate {
 
 /// View model for a column (with computed fields).
 struct ColumnView {
+    /// Workflow column name.
     id: String,
+    board_id: String,
     name: String,
-    position: i32,
     color: Option<String>,
-    wip_limit: Option<i32>,
+    wip_limit: Option<i64>,
     wip_exceeded: bool,
     cards: Vec<CardView>,
 }
 
-/// View model for a card.
+/// View model for a card (a task).
 struct CardView {
     id: String,
     title: String,
     description: Option<String>,
-    priority: Option<Priority>,
+    priority: String,
+    assignee: Option<String>,
     due_date: Option<String>,
+    blocked: bool,
     labels: Vec<LabelView>,
 }
 
@@ -58,38 +64,67 @@ struct LabelView {
     color: String,
 }
 
+const LABEL_COLORS: &[&str] = &["#3b82f6", "#10b981", "#f59e0b", "#8b5cf6", "#ef4444", "#06b6d4", "#ec4899"];
+
+/// Stable color for a label name.
+fn label_color(name: &str) -> String {
+    let hash = name.bytes().fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+    LABEL_COLORS[hash % LABEL_COLORS.len()].to_string()
+}
+
+/// Header color of a column; custom columns are colored by category.
+fn column_color(column: &BoardColumn) -> &'static str {
+    match (column.name.as_str(), column.category) {
+        ("backlog", _) => "#6b7280",
+        ("todo", _) => "#3b82f6",
+        ("in_progress", _) => "#f59e0b",
+        ("review", _) => "#8b5cf6",
+        (_, ColumnCategory::Done) => "#10b981",
+        (_, ColumnCategory::Active) => "#f59e0b",
+        (_, ColumnCategory::Waiting) => "#6b7280",
+    }
+}
+
 impl CardView {
-    fn from_card(card: &Card) -> Self {
+    fn from_task(task: &Task, blocked: bool) -> Self {
         Self {
-            id: card.id.clone(),
-            title: card.title.clone(),
-            description: card.description.clone(),
-            priority: card.priority.clone(),
-            due_date: card.due_date.clone(),
-            labels: card.labels.iter().map(|l| LabelView {
-                name: l.name.clone(),
-                color: l.color.clone(),
+            id: task.id.clone(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            priority: task.priority.clone(),
+            assignee: task.assignee.clone(),
+            due_date: task.due_date.clone(),
+            blocked,
+            labels: task.labels.iter().map(|l| LabelView {
+                name: l.clone(),
+                color: label_color(l),
             }).collect(),
         }
     }
 }
 
 impl ColumnView {
-    fn from_column(col: &Column) -> Self {
-        let card_count = col.cards.len() as i32;
-        let wip_exceeded = col.wip_limit.map_or(false, |limit| card_count >= limit);
+    fn from_column(board: &Board, col: &BoardColumn) -> Self {
+        let card_count = col.tasks.len() as i64;
+        let wip_exceeded = col.wip_limit.map_or(false, |limit| card_count > limit);
         Self {
-            id: col.id.clone(),
-            name: col.name.clone(),
-            position: col.position,
-            color: col.color.clone(),
+            id: col.name.clone(),
+            board_id: board.id.clone(),
+            name: col.label.clone().unwrap_or_else(|| col.name.clone()),
+            color: Some(column_color(col).to_string()),
             wip_limit: col.wip_limit,
             wip_exceeded,
-            cards: col.cards.iter().map(CardView::from_card).collect(),
+            cards: col.tasks.iter()
+                .map(|t| CardView::from_task(t, board.blocked_task_ids.contains(&t.id)))
+                .collect(),
         }
     }
 }
 
+fn column_views(board: &Board) -> Vec<ColumnView> {
+    board.columns.iter().map(|c| ColumnView::from_column(board, c)).collect()
+}
+
 // ============================================================
 // REQUEST TYPES
 // ============================================================
@@ -97,6 +132,7 @@ impl ColumnView {
 #[derive(Deserialize)]
 pub struct CreateCardForm {
     pub board_id: String,
+    /// Workflow column name.
     pub column_id: String,
     pub title: String,
     pub description: Option<String>,
@@ -106,8 +142,14 @@ pub struct CreateCardForm {
 
 #[derive(Deserialize)]
 pub struct MoveCardForm {
+    pub board_id: String,
+    /// Workflow column name.
     pub target_column_id: String,
-    pub position: i32,
+}
+
+#[derive(Deserialize)]
+pub struct BoardQuery {
+    pub board_id: String,
 }
 
 // ============================================================
@@ -145,12 +187,10 @@ pub async fn get_board(
         Err(_) => return (StatusCode::NOT_FOUND, Html("Board not found".to_string())).into_response(),
     };
 
-    let columns: Vec<ColumnView> = board.columns.iter().map(ColumnView::from_column).collect();
-
     let template = BoardTemplate {
+        columns: column_views(&board),
         board_id: board.id,
         board_name: board.name,
-        columns,
     };
 
     match template.render() {
@@ -159,7 +199,15 @@ pub async fn get_board(
     }
 }
 
-/// POST /cards - Create a new card. Returns updated board columns.
+/// GET /boards/{id}/columns - Render just the board columns.
+pub async fn get_board_columns(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+) -> Response {
+    render_board_columns(&state, &board_id).await
+}
+
+/// POST /cards - Create a task from the board. Returns updated board columns.
 pub async fn create_card(
     State(state): State<AppState>,
     Form(form): Form<CreateCardForm>,
@@ -168,70 +216,70 @@ pub async fn create_card(
     let due_date = form.due_date.as_deref().filter(|s| !s.is_empty());
     let description = form.description.as_deref().filter(|s| !s.is_empty());
 
-    if let Err(e) = board::create_card(
+    let task = match board::create_board_task(
         &state.db,
+        &form.board_id,
         &form.column_id,
         &form.title,
         description,
         priority,
         due_date,
     ).await {
-        return (StatusCode::BAD_REQUEST, Html(format!("Error: {}", e))).into_response();
-    }
+        Ok(task) => task,
+        Err(e) => return (StatusCode::BAD_REQUEST, Html(format!("Error: {}", e))).into_response(),
+    };
 
-    state.broadcast(WebSocketMessage::BoardRefresh);
+    state.broadcast(&task.project_id, WebSocketMessage::BoardRefresh);
     render_board_columns(&state, &form.board_id).await
 }
 
-/// PATCH /cards/{id}/move - Move a card to a new column/position.
+/// Look up a task of the board's project.
+async fn board_task(state: &AppState, board_id: &str, card_id: &str) -> Result<Task, Response> {
+    let board = board::get_board(&state.db, board_id).await
+        .map_err(|_| (StatusCode::NOT_FOUND, Html("Board not found".to_string())).into_response())?;
+    cwa_core::task::get_task(&state.db, &board.project_id, card_id).await
+        .map_err(|_| (StatusCode::NOT_FOUND, Html("Task not found".to_string())).into_response())
+}
+
+/// PATCH /cards/{id}/move - Move a task to another workflow column.
 pub async fn move_card(
     State(state): State<AppState>,
     Path(card_id): Path<String>,
     Form(form): Form<MoveCardForm>,
 ) -> Response {
-    // Get the card to find its board
-    let card = match cwa_db::queries::boards::get_card(&state.db, &card_id).await {
-        Ok(c) => c,
-        Err(_) => return (StatusCode::NOT_FOUND, Html("Card not found".to_string())).into_response(),
+    let task = match board_task(&state, &form.board_id, &card_id).await {
+        Ok(t) => t,
+        Err(response) => return response,
     };
 
-    if let Err(e) = board::move_card(&state.db, &card_id, &form.target_column_id, form.position).await {
+    if let Err(e) = cwa_core::task::move_task(&state.db, &task.project_id, &card_id, &form.target_column_id).await {
         return (StatusCode::BAD_REQUEST, Html(format!("Error: {}", e))).into_response();
     }
 
-    state.broadcast(WebSocketMessage::BoardRefresh);
-
-    // Find board_id from column
-    let column = match cwa_db::queries::boards::get_column(&state.db, &card.column_id).await {
-        Ok(c) => c,
-        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Html("Column not found".to_string())).into_response(),
-    };
-
-    render_board_columns(&state, &column.board_id).await
+    state.broadcast(&task.project_id, WebSocketMessage::TaskUpdated {
+        task_id: card_id,
+        status: form.target_column_id,
+    });
+    render_board_columns(&state, &form.board_id).await
 }
 
-/// DELETE /cards/{id} - Delete a card.
+/// DELETE /cards/{id}?board_id= - Delete a task.
 pub async fn delete_card(
     State(state): State<AppState>,
     Path(card_id): Path<String>,
+    Query(query): Query<BoardQuery>,
 ) -> Response {
-    // Get card's column to find board
-    let card = match cwa_db::queries::boards::get_card(&state.db, &card_id).await {
-        Ok(c) => c,
-        Err(_) => return (StatusCode::NOT_FOUND, Html("Card not found".to_string())).into_response(),
+    let task = match board_task(&state, &query.board_id, &card_id).await {
+        Ok(t) => t,
+        Err(response) => return response,
     };
 
-    let column = match cwa_db::queries::boards::get_column(&state.db, &card.column_id).await {
-        Ok(c) => c,
-        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Html("Column not found".to_string())).into_response(),
-    };
-
-    if let Err(e) = board::delete_card(&state.db, &card_id).await {
+    if let Err(e) = cwa_core::task::delete_task(&state.db, &task.project_id, &card_id).await {
         return (StatusCode::INTERNAL_SERVER_ERROR, Html(format!("Error: {}", e))).into_response();
     }
 
-    state.broadcast(WebSocketMessage::BoardRefresh);
-    render_board_columns(&state, &column.board_id).await
+    state.broadcast(&task.project_id, WebSocketMessage::BoardRefresh);
+    render_board_columns(&state, &query.board_id).await
 }
 
 // ============================================================
@@ -245,11 +293,9 @@ async fn render_board_columns(state: &AppState, board_id: &str) -> Response {
         Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Html(format!("Error: {}", e))).into_response(),
     };
 
-    let columns: Vec<ColumnView> = board.columns.iter().map(ColumnView::from_column).collect();
-
     let mut html = String::new();
-    for column in &columns {
-        let tmpl = ColumnTemplate { column: column_view_ref(column) };
+    for column in column_views(&board) {
+        let tmpl = ColumnTemplate { column };
         match tmpl.render() {
             Ok(rendered) => html.push_str(&rendered),
             Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Html(format!("Template error: {}", e))).into_response(),
@@ -258,27 +304,3 @@ async fn render_board_columns(state: &AppState, board_id: &str) -> Response {
 
     Html(html).into_response()
 }
-
-/// Create a ColumnView reference for template rendering.
-/// Askama templates need owned data, so we clone for partial renders.
-fn column_view_ref(col: &ColumnView) -> ColumnView {
-    ColumnView {
-        id: col.id.clone(),
-        name: col.name.clone(),
-        position: col.position,
-        color: col.color.clone(),
-        wip_limit: col.wip_limit,
-        wip_exceeded: col.wip_exceeded,
-        cards: col.cards.iter().map(|c| CardView {
-            id: c.id.clone(),
-            title: c.title.clone(),
-            description: c.description.clone(),
-            priority: c.priority.clone(),
-            due_date: c.due_date.clone(),
-            labels: c.labels.iter().map(|l| LabelView {
-                name: l.name.clone(),
-                color: l.color.clone(),
-            }).collect(),
-        }).collect(),
-    }
-}
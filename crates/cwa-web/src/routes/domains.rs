@@ -25,7 +25,11 @@ pub async fn get_context(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<cwa_core::domain::model::BoundedContext>, (StatusCode, String)> {
-    let context = cwa_core::domain::get_context(&state.db, &id).await
+    let project = cwa_core::project::get_default_project(&state.db).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
+
+    let context = cwa_core::domain::get_context(&state.db, &project.id, &id).await
         .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
 
     Ok(Json(context))
@@ -13,6 +13,7 @@ pub async fn notify(
     info!(?msg, "Received internal notification, broadcasting to WebSocket clients");
     let receiver_count = state.tx.receiver_count();
     debug!(receiver_count, "Active WebSocket receivers");
-    state.broadcast(msg);
+    // The sender already published the event on Redis, so only relay it locally
+    let _ = state.tx.send(msg);
     StatusCode::OK
 }
@@ -1,5 +1,6 @@
 //! Route handlers.
 
+pub mod audit;
 pub mod board_html;
 pub mod context;
 pub mod dashboard;
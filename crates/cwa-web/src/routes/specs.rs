@@ -77,9 +77,7 @@ pub async fn generate_tasks(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
 
-    let status = body.as_ref()
-        .and_then(|b| b.status.as_deref())
-        .unwrap_or("backlog");
+    let status = body.as_ref().and_then(|b| b.status.as_deref());
 
     let result = cwa_core::task::generate_tasks_from_spec(&state.db, &project.id, &id, status).await
         .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
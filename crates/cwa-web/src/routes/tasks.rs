@@ -1,12 +1,16 @@
 //! Task route handlers.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use serde::Deserialize;
 
+use cwa_core::task::metrics::{TaskEvent, TaskMetrics};
+use cwa_core::task::model::{TaskFilter, TaskUpdate};
+use cwa_core::task::workflow::Workflow;
+
 use crate::state::{AppState, WebSocketMessage};
 
 #[derive(Deserialize)]
@@ -20,17 +24,20 @@ pub struct CreateTaskRequest {
 #[derive(Deserialize)]
 pub struct UpdateTaskRequest {
     pub status: Option<String>,
-    pub priority: Option<String>,
+    /// Field edits (title, priority, assignee, labels, effort, blocked_by...).
+    #[serde(flatten)]
+    pub fields: TaskUpdate,
 }
 
 pub async fn list_tasks(
     State(state): State<AppState>,
+    Query(filter): Query<TaskFilter>,
 ) -> Result<Json<Vec<cwa_core::task::model::Task>>, (StatusCode, String)> {
     let project = cwa_core::project::get_default_project(&state.db).await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
 
-    let tasks = cwa_core::task::list_tasks(&state.db, &project.id).await
+    let tasks = cwa_core::task::list_tasks_filtered(&state.db, &project.id, &filter).await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     Ok(Json(tasks))
@@ -40,7 +47,11 @@ pub async fn get_task(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<cwa_core::task::model::Task>, (StatusCode, String)> {
-    let task = cwa_core::task::get_task(&state.db, &id).await
+    let project = cwa_core::project::get_default_project(&state.db).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
+
+    let task = cwa_core::task::get_task(&state.db, &project.id, &id).await
         .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
 
     Ok(Json(task))
@@ -64,7 +75,7 @@ pub async fn create_task(
     ).await
     .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
 
-    state.broadcast(WebSocketMessage::BoardRefresh);
+    state.broadcast(&project.id, WebSocketMessage::BoardRefresh);
 
     Ok((StatusCode::CREATED, Json(task)))
 }
@@ -78,22 +89,85 @@ pub async fn update_task(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
 
+    if !req.fields.is_empty() {
+        cwa_core::task::update_task(&state.db, &project.id, &id, &req.fields).await
+            .map_err(|e| match e {
+                cwa_core::CwaError::TaskNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+                e => (StatusCode::BAD_REQUEST, e.to_string()),
+            })?;
+
+        state.broadcast(&project.id, WebSocketMessage::BoardRefresh);
+    }
+
     if let Some(status) = &req.status {
         cwa_core::task::move_task(&state.db, &project.id, &id, status).await
             .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
 
-        state.broadcast(WebSocketMessage::TaskUpdated {
+        state.broadcast(&project.id, WebSocketMessage::TaskUpdated {
             task_id: id.clone(),
             status: status.clone(),
         });
     }
 
-    let task = cwa_core::task::get_task(&state.db, &id).await
+    let task = cwa_core::task::get_task(&state.db, &project.id, &id).await
         .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
 
     Ok(Json(task))
 }
 
+pub async fn delete_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let project = cwa_core::project::get_default_project(&state.db).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
+
+    cwa_core::task::delete_task(&state.db, &project.id, &id).await
+        .map_err(|e| match e {
+            cwa_core::CwaError::TaskNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    state.broadcast(&project.id, WebSocketMessage::BoardRefresh);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn get_task_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<TaskEvent>>, (StatusCode, String)> {
+    let project = cwa_core::project::get_default_project(&state.db).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
+
+    let events = cwa_core::task::get_task_history(&state.db, &project.id, &id).await
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+
+    Ok(Json(events))
+}
+
+#[derive(Deserialize)]
+pub struct MetricsQuery {
+    /// Trailing window in days (default: 28).
+    pub days: Option<i64>,
+}
+
+pub async fn get_metrics(
+    State(state): State<AppState>,
+    Query(query): Query<MetricsQuery>,
+) -> Result<Json<TaskMetrics>, (StatusCode, String)> {
+    let project = cwa_core::project::get_default_project(&state.db).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
+
+    let metrics = cwa_core::task::get_task_metrics(&state.db, &project.id, query.days.unwrap_or(28)).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(metrics))
+}
+
 pub async fn get_board(
     State(state): State<AppState>,
 ) -> Result<Json<cwa_core::task::model::Board>, (StatusCode, String)> {
@@ -106,3 +180,16 @@ pub async fn get_board(
 
     Ok(Json(board))
 }
+
+pub async fn get_workflow(
+    State(state): State<AppState>,
+) -> Result<Json<Workflow>, (StatusCode, String)> {
+    let project = cwa_core::project::get_default_project(&state.db).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No project found".to_string()))?;
+
+    let workflow = cwa_core::task::get_workflow(&state.db, &project.id).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(workflow))
+}
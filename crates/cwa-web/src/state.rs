@@ -18,8 +18,17 @@ impl AppState {
         Self { db, tx }
     }
 
-    /// Broadcast a message to all WebSocket clients.
-    pub fn broadcast(&self, msg: WebSocketMessage) {
+    /// Broadcast a message to all WebSocket clients, and publish it on Redis
+    /// for MCP servers (in any process) following `project_id`.
+    pub fn broadcast(&self, project_id: &str, msg: WebSocketMessage) {
+        let db = self.db.clone();
+        let project_id = project_id.to_string();
+        let event = msg.clone();
+        tokio::spawn(async move {
+            if let Err(e) = cwa_db::publish_event(&db, &project_id, &event).await {
+                tracing::debug!(error = %e, "Failed to publish change event");
+            }
+        });
         let _ = self.tx.send(msg);
     }
 }